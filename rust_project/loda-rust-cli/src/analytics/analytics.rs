@@ -1,10 +1,12 @@
 use super::{AnalyticsMode, AnalyticsDirectory};
 use super::{AnalyzeDependencies, AnalyzeIndirectMemoryAccess, AnalyzeInstructionConstant, AnalyzeInstructionNgram, AnalyzeProgramModified};
-use super::{AnalyzeProgramComplexity, AnalyzeLineNgram, AnalyzeSourceNgram, AnalyzeTargetNgram, BatchProgramAnalyzer, BatchProgramAnalyzerPluginItem, DontMine, HistogramStrippedFile, AnalyticsTimestampFile, ValidatePrograms, compute_program_rank};
-use crate::config::Config;
-use crate::mine::PopulateBloomfilter;
+use super::{AnalyzeProgramComplexity, AnalyzeLineNgram, AnalyzeSourceNgram, AnalyzeTargetNgram, AnalyzeUnofficialFunction, BatchProgramAnalyzer, BatchProgramAnalyzerPluginItem, DontMine, HistogramStrippedFile, PriorityPrograms, AnalyticsTimestampFile, ValidatePrograms, compute_program_rank};
+use crate::config::{Config, NumberOfWorkers};
+use crate::mine::{FunnelConfig, PopulateBloomfilter};
+use crate::oeis::TermsToProgramIdFile;
 use crate::common::{find_asm_files_recursively, load_program_ids_csv_file, oeis_id_from_path, SimpleLog};
 use anyhow::Context;
+use num_bigint::{BigInt, ToBigInt};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -70,7 +72,12 @@ impl Analytics {
     fn run_if_expired(&self) -> anyhow::Result<()> {
         let timestamp_file_path: PathBuf = self.analytics_directory.last_analytics_timestamp_file();
         let expire_minutes = ANALYTICS_TIMESTAMP_FILE_EXPIRE_AFTER_MINUTES;
-        if !AnalyticsTimestampFile::is_expired(&timestamp_file_path, expire_minutes) {
+        // The "analytics" dir may have been generated by an older version, without the files that the miner needs.
+        let is_missing_files: bool = match self.analytics_mode {
+            AnalyticsMode::OEIS => !self.analytics_directory.terms_to_program_id_file().is_file(),
+            AnalyticsMode::ARC => false
+        };
+        if !is_missing_files && !AnalyticsTimestampFile::is_expired(&timestamp_file_path, expire_minutes) {
             println!("The \"analytics\" dir is newer than {} minutes. No need to regenerate analytics.", expire_minutes);
             return Ok(());
         }
@@ -148,9 +155,32 @@ impl Analytics {
         DontMine::run(self.analytics_directory.clone(), simple_log.clone())
             .map_err(|e| anyhow::anyhow!("Analytics.run_force. DontMine::run. error: {:?}", e))?;
 
+        PriorityPrograms::run(self.analytics_directory.clone(), simple_log.clone())
+            .map_err(|e| anyhow::anyhow!("Analytics.run_force. PriorityPrograms::run. error: {:?}", e))?;
+
         PopulateBloomfilter::run(self.analytics_directory.clone(), simple_log.clone())
             .map_err(|e| anyhow::anyhow!("Analytics.run_force. PopulateBloomfilter::run. error: {:?}", e))?;
 
+        self.create_terms_to_program_id_file(simple_log.clone())?;
+
+        Ok(())
+    }
+
+    /// Generate the `terms_to_program_id.bin` file, that the miner memory maps.
+    fn create_terms_to_program_id_file(&self, simple_log: SimpleLog) -> anyhow::Result<()> {
+        simple_log.println("\nTermsToProgramIdFile");
+        let start = Instant::now();
+        let padding_value: BigInt = FunnelConfig::WILDCARD_MAGIC_VALUE.to_bigint().unwrap();
+        let output_path: PathBuf = self.analytics_directory.terms_to_program_id_file();
+        TermsToProgramIdFile::create(
+            &self.config.oeis_stripped_file(),
+            &output_path,
+            FunnelConfig::MINIMUM_NUMBER_OF_REQUIRED_TERMS,
+            FunnelConfig::TERM_COUNT,
+            &padding_value
+        ).context("Analytics.run_force. TermsToProgramIdFile::create")?;
+        let content = format!("created 'terms_to_program_id.bin' in {:?}", start.elapsed());
+        simple_log.println(content);
         Ok(())
     }
 
@@ -176,6 +206,7 @@ impl Analytics {
         let plugin_target_ngram = Rc::new(RefCell::new(AnalyzeTargetNgram::new(self.analytics_directory.clone())));
         let plugin_program_complexity = Rc::new(RefCell::new(AnalyzeProgramComplexity::new(self.analytics_directory.clone())));
         let plugin_program_modified = Rc::new(RefCell::new(AnalyzeProgramModified::new(self.analytics_directory.clone())));
+        let plugin_unofficial_function = Rc::new(RefCell::new(AnalyzeUnofficialFunction::new(self.analytics_directory.clone())));
         let plugin_vec: Vec<BatchProgramAnalyzerPluginItem> = vec![
             plugin_dependencies,
             plugin_indirect_memory_access,
@@ -186,12 +217,14 @@ impl Analytics {
             plugin_target_ngram,
             plugin_program_complexity,
             plugin_program_modified,
+            plugin_unofficial_function,
         ];
         let mut analyzer = BatchProgramAnalyzer::new(
             self.analytics_mode, 
             plugin_vec, 
             simple_log,
-            program_paths
+            program_paths,
+            self.config.resolve_number_of_evaluation_threads(),
         );
         return analyzer.run();
     }
@@ -38,6 +38,14 @@ impl AnalyticsDirectory {
         self.analytics_directory.join("dont_mine.csv")
     }
 
+    pub fn priority_programs_file(&self) -> PathBuf {
+        self.analytics_directory.join("priority_programs.csv")
+    }
+
+    pub fn terms_to_program_id_file(&self) -> PathBuf {
+        self.analytics_directory.join("terms_to_program_id.bin")
+    }
+
     pub fn programs_valid_file(&self) -> PathBuf {
         self.analytics_directory.join("programs_valid.csv")
     }
@@ -78,6 +86,10 @@ impl AnalyticsDirectory {
         self.analytics_directory.join("histogram_instruction_constant.csv")
     }
 
+    pub fn histogram_unofficial_function_file(&self) -> PathBuf {
+        self.analytics_directory.join("histogram_unofficial_function.csv")
+    }
+
     pub fn histogram_instruction_unigram_file(&self) -> PathBuf {
         self.analytics_directory.join("histogram_instruction_unigram.csv")
     }
@@ -2,6 +2,7 @@ use super::{AnalyticsDirectory, BatchProgramAnalyzerPlugin, BatchProgramAnalyzer
 use crate::common::create_csv_file;
 use crate::common::ToOeisIdVec;
 use loda_rust_core;
+use loda_rust_core::analysis::RegisterUsage;
 use loda_rust_core::parser::ParsedProgram;
 use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
 use std::collections::HashSet;
@@ -47,7 +48,7 @@ impl AnalyzeIndirectMemoryAccess {
     }
 
     fn analyze_inner(&mut self, program_id: u32, parsed_program: &ParsedProgram) {
-        if parsed_program.contain_parameter_type_indirect() {
+        if RegisterUsage::analyze(parsed_program).indirect {
             self.programs_that_uses_indirect.insert(OeisId::from(program_id));
         }
     }
@@ -1,6 +1,7 @@
 use super::{AnalyticsDirectory, BatchProgramAnalyzerPlugin, BatchProgramAnalyzerContext};
 use crate::common::{create_csv_file, save_program_ids_csv_file};
 use loda_rust_core;
+use loda_rust_core::analysis::{ComplexityEstimate, Growth};
 use loda_rust_core::parser::{InstructionId, ParsedProgram};
 use std::path::PathBuf;
 use std::error::Error;
@@ -63,6 +64,7 @@ impl ProgramComplexityClassification {
 pub struct AnalyzeProgramComplexity {
     analytics_directory: AnalyticsDirectory,
     classifications: HashMap<u32, ProgramComplexityClassification>,
+    time_complexities: HashMap<u32, Growth>,
 }
 
 impl AnalyzeProgramComplexity {
@@ -70,6 +72,7 @@ impl AnalyzeProgramComplexity {
         Self {
             analytics_directory,
             classifications: HashMap::new(),
+            time_complexities: HashMap::new(),
         }
     }
 
@@ -108,9 +111,14 @@ impl AnalyzeProgramComplexity {
         // Convert from dictionary to array
         let mut records = Vec::<RecordProgram>::new();
         for (key, value) in &self.classifications {
+            let time_complexity: String = match self.time_complexities.get(key) {
+                Some(growth) => growth.to_string(),
+                None => Growth::Unknown.to_string(),
+            };
             let record = RecordProgram {
                 program_id: *key,
                 optimizable: value.optimizable_string(),
+                time_complexity: time_complexity,
                 comment: value.comment()
             };
             records.push(record);
@@ -152,6 +160,11 @@ impl BatchProgramAnalyzerPlugin for AnalyzeProgramComplexity {
     fn analyze(&mut self, context: &BatchProgramAnalyzerContext) -> Result<(), Box<dyn Error>> {
         let classification = Self::classify(&context.parsed_program);
         self.classifications.insert(context.program_id, classification);
+        let time_complexity: Growth = match ComplexityEstimate::analyze(&context.parsed_program) {
+            Ok(estimate) => estimate.time,
+            Err(_) => Growth::Unknown,
+        };
+        self.time_complexities.insert(context.program_id, time_complexity);
         Ok(())
     }
 
@@ -170,7 +183,8 @@ impl BatchProgramAnalyzerPlugin for AnalyzeProgramComplexity {
             optimize_count = total_count - dont_optimize_count;
         }
         let ratio = ((optimize_count * 100) as f32) / (total_count.max(1) as f32);
-        format!("optimize: {}, dontoptimize: {}, optimize/total: {:.1}%", optimize_count, dont_optimize_count, ratio)
+        let exponential_count: usize = self.time_complexities.values().filter(|growth| **growth == Growth::Exponential).count();
+        format!("optimize: {}, dontoptimize: {}, optimize/total: {:.1}%, exponential time: {}", optimize_count, dont_optimize_count, ratio, exponential_count)
     }
 }
 
@@ -260,6 +274,8 @@ struct RecordProgram {
     program_id: u32,
     #[serde(rename = "is optimizable")]
     optimizable: String,
+    #[serde(rename = "time complexity")]
+    time_complexity: String,
     comment: String,
 }
 
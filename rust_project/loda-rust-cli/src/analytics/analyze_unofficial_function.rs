@@ -0,0 +1,148 @@
+use super::{AnalyticsDirectory, BatchProgramAnalyzerPlugin, BatchProgramAnalyzerContext};
+use crate::common::create_csv_file;
+use loda_rust_core::parser::{InstructionId, InstructionParameter, ParameterType, ParsedProgram};
+use loda_rust_core::unofficial_function::UnofficialFunctionId;
+use std::path::PathBuf;
+use std::error::Error;
+use std::collections::HashMap;
+use serde::Serialize;
+
+/// Creates a histogram with how many times each unofficial function is used.
+///
+/// An unofficial function is invoked like this `f21 $0,1104`, where `2` is the number of inputs,
+/// `1` is the number of outputs and `1104` is the id of the function.
+///
+/// The official `loda-programs/oeis` programs doesn't use unofficial functions.
+/// The programs in the `loda-arc-challenge` repository makes heavy use of unofficial functions.
+///
+/// The miner uses the histogram for picking the most used functions more often.
+///
+/// This outputs a `histogram_unofficial_function.csv` file, with this format:
+///
+/// ```csv
+/// count;function_id;inputs;outputs
+/// 98;102120;1;1
+/// 47;101000;1;1
+/// 12;1104;2;1
+/// ```
+pub struct AnalyzeUnofficialFunction {
+    analytics_directory: AnalyticsDirectory,
+    histogram: HashMap<UnofficialFunctionId,u32>,
+}
+
+impl AnalyzeUnofficialFunction {
+    pub fn new(analytics_directory: AnalyticsDirectory) -> Self {
+        Self {
+            analytics_directory,
+            histogram: HashMap::new(),
+        }
+    }
+
+    fn analyze_inner(&mut self, parsed_program: &ParsedProgram) {
+        for instruction in &parsed_program.instruction_vec {
+            let (input_count, output_count) = match instruction.instruction_id {
+                InstructionId::UnofficialFunction { input_count, output_count } => (input_count, output_count),
+                _ => continue
+            };
+            let source_parameter: &InstructionParameter = match instruction.parameter_vec.get(1) {
+                Some(value) => value,
+                None => continue
+            };
+            if source_parameter.parameter_type != ParameterType::Constant {
+                continue;
+            }
+            if source_parameter.parameter_value < 0 || source_parameter.parameter_value > (u32::MAX as i64) {
+                continue;
+            }
+            let key = UnofficialFunctionId::InputOutput {
+                id: source_parameter.parameter_value as u32,
+                inputs: input_count,
+                outputs: output_count
+            };
+            let counter = self.histogram.entry(key).or_insert(0);
+            *counter += 1;
+        }
+    }
+}
+
+impl BatchProgramAnalyzerPlugin for AnalyzeUnofficialFunction {
+    fn plugin_name(&self) -> &'static str {
+        "AnalyzeUnofficialFunction"
+    }
+
+    fn analyze(&mut self, context: &BatchProgramAnalyzerContext) -> Result<(), Box<dyn Error>> {
+        self.analyze_inner(&context.parsed_program);
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), Box<dyn Error>> {
+        let records: Vec<Record> = Record::sorted_records_from_histogram(&self.histogram);
+        let output_path: PathBuf = self.analytics_directory.histogram_unofficial_function_file();
+        create_csv_file(&records, &output_path)
+    }
+
+    fn human_readable_summary(&self) -> String {
+        let count: u32 = self.histogram.values().sum();
+        let rows: Vec<String> = vec![
+            format!("number of unofficial function calls: {:?}", count),
+            format!("number of items in histogram: {:?}", self.histogram.len())
+        ];
+        rows.join("\n")
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Record {
+    count: u32,
+    function_id: u32,
+    inputs: u8,
+    outputs: u8,
+}
+
+impl Record {
+    fn sorted_records_from_histogram(histogram: &HashMap<UnofficialFunctionId,u32>) -> Vec<Record> {
+        let mut records = Vec::<Record>::new();
+        for (key, count) in histogram {
+            let record = match key {
+                UnofficialFunctionId::InputOutput { id, inputs, outputs } => Record {
+                    count: *count,
+                    function_id: *id,
+                    inputs: *inputs,
+                    outputs: *outputs,
+                }
+            };
+            records.push(record);
+        }
+
+        // Move the most frequently used functions to the top
+        records.sort_unstable_by_key(|item| (item.count, item.function_id, item.inputs, item.outputs));
+        records.reverse();
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_analyze_inner() {
+        // Arrange
+        let program = "f21 $0,1104\nadd $0,1\nf11 $1,1100\nf21 $2,1104\nseq $0,45";
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program(program).expect("program");
+        let tempdir = tempfile::tempdir().unwrap();
+        let analytics_directory = AnalyticsDirectory::new(PathBuf::from(tempdir.path())).expect("analytics_directory");
+        let mut instance = AnalyzeUnofficialFunction::new(analytics_directory);
+
+        // Act
+        instance.analyze_inner(&parsed_program);
+        let records: Vec<Record> = Record::sorted_records_from_histogram(&instance.histogram);
+
+        // Assert
+        let expected: Vec<Record> = vec![
+            Record { count: 2, function_id: 1104, inputs: 2, outputs: 1 },
+            Record { count: 1, function_id: 1100, inputs: 1, outputs: 1 },
+        ];
+        assert_eq!(records, expected);
+    }
+}
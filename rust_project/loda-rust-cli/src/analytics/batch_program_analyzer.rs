@@ -2,9 +2,10 @@ use crate::common::{oeis_id_from_path, SimpleLog};
 use loda_rust_core;
 use super::{AnalyticsError, AnalyticsMode};
 use loda_rust_core::parser::ParsedProgram;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::error::Error;
 use std::fs;
+use std::thread;
 use std::time::Instant;
 use std::rc::Rc;
 use core::cell::RefCell;
@@ -14,6 +15,9 @@ use indicatif::{HumanDuration, ProgressBar};
 #[cfg(feature = "loda-rust-arc")]
 use crate::arc::RunWithProgram;
 
+/// The number of program files that are loaded in parallel, before they are passed on to the plugins.
+const LOAD_BATCH_SIZE: usize = 1000;
+
 pub struct BatchProgramAnalyzerContext {
     pub program_path: PathBuf,
     pub program_id: u32,
@@ -40,23 +44,29 @@ pub struct BatchProgramAnalyzer {
     plugin_vec: Vec<BatchProgramAnalyzerPluginItem>,
     simple_log: SimpleLog,
     program_paths: Vec<PathBuf>,
+    number_of_threads: usize,
     number_of_program_files_that_could_not_be_loaded: usize,
     number_of_program_files_ignored: usize,
     number_of_program_files_successfully_analyzed: usize,
 }
 
 impl BatchProgramAnalyzer {
+    /// The program files are loaded and parsed with `number_of_threads` in parallel.
+    ///
+    /// The plugins are not thread safe, so they are invoked one program at a time, in the same order as the paths.
     pub fn new(
         analytics_mode: AnalyticsMode, 
         plugin_vec: Vec<BatchProgramAnalyzerPluginItem>, 
         simple_log: SimpleLog,
         program_paths: Vec<PathBuf>,
+        number_of_threads: usize,
     ) -> Self {
         Self {
             analytics_mode,
             plugin_vec,
             simple_log,
             program_paths,
+            number_of_threads: number_of_threads.max(1),
             number_of_program_files_that_could_not_be_loaded: 0,
             number_of_program_files_ignored: 0,
             number_of_program_files_successfully_analyzed: 0,
@@ -88,9 +98,15 @@ impl BatchProgramAnalyzer {
         let pb = ProgressBar::new(number_of_paths as u64);
         let start = Instant::now();
         let program_paths: Vec<PathBuf> = self.program_paths.clone();
-        for program_path in program_paths {
-            self.analyze_program_file(program_path)?;
-            pb.inc(1);
+        for batch in program_paths.chunks(LOAD_BATCH_SIZE) {
+            let contexts: Vec<Option<BatchProgramAnalyzerContext>> = self.load_program_files(batch);
+            for context in contexts {
+                match context {
+                    Some(context) => self.analyze_program(&context)?,
+                    None => self.number_of_program_files_that_could_not_be_loaded += 1
+                }
+                pb.inc(1);
+            }
         }
         pb.finish_and_clear();
 
@@ -111,40 +127,58 @@ impl BatchProgramAnalyzer {
         Ok(())
     }
 
-    fn analyze_program_file(
-        &mut self, 
-        program_path: PathBuf,
-    ) -> Result<(), Box<dyn Error>> {
-        let program_id: u32;
-        match self.analytics_mode {
+    /// Load the program files in parallel, and return them in the same order as the paths.
+    fn load_program_files(&self, program_paths: &[PathBuf]) -> Vec<Option<BatchProgramAnalyzerContext>> {
+        let analytics_mode: AnalyticsMode = self.analytics_mode;
+        let chunk_size: usize = program_paths.len().div_ceil(self.number_of_threads);
+        let mut contexts = Vec::<Option<BatchProgramAnalyzerContext>>::with_capacity(program_paths.len());
+        thread::scope(|scope| {
+            let mut handles = Vec::with_capacity(self.number_of_threads);
+            for chunk in program_paths.chunks(chunk_size.max(1)) {
+                let handle = scope.spawn(move || {
+                    chunk.iter().map(|program_path| Self::load_program_file(analytics_mode, program_path)).collect::<Vec<_>>()
+                });
+                handles.push(handle);
+            }
+            for handle in handles {
+                match handle.join() {
+                    Ok(results) => contexts.extend(results),
+                    Err(error) => std::panic::resume_unwind(error)
+                }
+            }
+        });
+        contexts
+    }
+
+    /// Returns `None` when the program file cannot be loaded.
+    fn load_program_file(analytics_mode: AnalyticsMode, program_path: &Path) -> Option<BatchProgramAnalyzerContext> {
+        let program_id: u32 = match analytics_mode {
             AnalyticsMode::OEIS => {
                 // Extract OEIS id from program path
-                program_id = match oeis_id_from_path(&program_path) {
+                match oeis_id_from_path(program_path) {
                     Some(oeis_id) => oeis_id.raw(),
                     None => {
                         debug!("Unable to extract program_id from {:?}", program_path);
-                        self.number_of_program_files_that_could_not_be_loaded += 1;
-                        return Ok(());
+                        return None;
                     }
-                };
+                }
             },
             AnalyticsMode::ARC => {
                 // ARC programs use a filename ala `39a8645d-1.asm`, so it doesn't work with an integer `program_id`.
-                program_id = 1;
+                1
             },
-        }
+        };
 
         #[allow(unused_mut)]
-        let mut contents: String = match fs::read_to_string(&program_path) {
+        let mut contents: String = match fs::read_to_string(program_path) {
             Ok(value) => value,
             Err(error) => {
                 debug!("loading program_id: {:?}, something went wrong reading the file: {:?}", program_id, error);
-                self.number_of_program_files_that_could_not_be_loaded += 1;
-                return Ok(());
+                return None;
             }
         };
         #[cfg(feature = "loda-rust-arc")]
-        if self.analytics_mode == AnalyticsMode::ARC {
+        if analytics_mode == AnalyticsMode::ARC {
             // detect if it's a "simple" program, and wrap it in the "advanced" template
             let is_simple: bool = contents.contains("Program Type: simple");
             if is_simple {
@@ -155,17 +189,20 @@ impl BatchProgramAnalyzer {
             Ok(value) => value,
             Err(error) => {
                 debug!("loading program_id: {:?}, something went wrong parsing the program: {:?}", program_id, error);
-                self.number_of_program_files_that_could_not_be_loaded += 1;
-                return Ok(());
+                return None;
             }
         };
         let context = BatchProgramAnalyzerContext {
-            program_path: program_path,
-            program_id: program_id,
-            parsed_program: parsed_program,
+            program_path: program_path.to_path_buf(),
+            program_id,
+            parsed_program,
         };
+        Some(context)
+    }
+
+    fn analyze_program(&mut self, context: &BatchProgramAnalyzerContext) -> Result<(), Box<dyn Error>> {
         for plugin in self.plugin_vec.iter() {
-            plugin.borrow_mut().analyze(&context)?;
+            plugin.borrow_mut().analyze(context)?;
         }
         self.number_of_program_files_successfully_analyzed += 1;
         Ok(())
@@ -0,0 +1,45 @@
+use super::AnalyticsDirectory;
+use crate::config::{Config, NumberOfWorkers};
+use crate::mine::PopularProgramContainer;
+use loda_rust_core::control::EvaluationPool;
+use loda_rust_core::execute::SharedProgramCache;
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+/// The number of values in the cache that is shared between the threads.
+const SHARED_CACHE_CAPACITY: usize = 100000;
+
+/// Programs with this popularity or higher, have their terms in the shared cache.
+const SHARED_CACHE_MIN_POPULARITY: usize = 2;
+
+/// Create a pool for evaluating programs in parallel, with the number of threads from the `miner_cpu_strategy`.
+///
+/// When the `program_popularity.csv` exist from a previous run of analytics,
+/// then only the terms of the popular programs are shared between the threads.
+/// Otherwise the terms of all programs are shared, until the shared cache is full.
+pub fn create_evaluation_pool(config: &Config, analytics_directory: &AnalyticsDirectory) -> EvaluationPool {
+    let number_of_threads: usize = config.resolve_number_of_evaluation_threads();
+    let mut shared_cache = SharedProgramCache::new(NonZeroUsize::new(SHARED_CACHE_CAPACITY).unwrap());
+    let popular_program_csv: PathBuf = analytics_directory.program_popularity_file();
+    if popular_program_csv.is_file() {
+        match PopularProgramContainer::load(&popular_program_csv) {
+            Ok(container) => {
+                let program_ids: HashSet<u64> = container.cluster_program_ids()
+                    .iter()
+                    .skip(SHARED_CACHE_MIN_POPULARITY)
+                    .flatten()
+                    .map(|program_id| *program_id as u64)
+                    .collect();
+                debug!("create_evaluation_pool: number of programs in shared cache: {}", program_ids.len());
+                shared_cache.set_program_ids(program_ids);
+            },
+            Err(error) => {
+                error!("create_evaluation_pool: Unable to load program popularity file. {:?}", error);
+            }
+        }
+    }
+    let mut pool = EvaluationPool::new(NonZeroUsize::new(number_of_threads).unwrap());
+    pool.set_shared_cache(shared_cache);
+    pool
+}
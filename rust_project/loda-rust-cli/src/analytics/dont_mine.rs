@@ -1,5 +1,6 @@
 use crate::common::SimpleLog;
 use crate::config::Config;
+use crate::oeis::{OeisKeyword, OeisKeywords};
 use std::error::Error;
 use std::path::PathBuf;
 use std::collections::HashSet;
@@ -28,6 +29,7 @@ impl DontMine {
         instance.extend_program_ids_with_loda_programs_deny_txt()?;
         instance.extend_program_ids_with_dont_optimize_csv()?;
         instance.remove_invalid_programs()?;
+        instance.extend_program_ids_with_oeis_keywords()?;
         instance.save()?;
         Ok(())
     }
@@ -70,6 +72,18 @@ impl DontMine {
         Ok(())
     }
 
+    /// Ignore sequences that have the `dead`, `dumb` or `fini` keyword.
+    /// There is no point in mining for these, even if the existing program is invalid.
+    fn extend_program_ids_with_oeis_keywords(&mut self) -> Result<(), Box<dyn Error>> {
+        let path: PathBuf = self.config.oeis_keywords_file();
+        let oeis_keywords: OeisKeywords = OeisKeywords::load_if_exists(&path)?;
+        let oeis_ids = oeis_keywords.oeis_ids_with_any_keyword(&OeisKeyword::DONT_MINE);
+        let content = format!("number of program ids with the dead/dumb/fini keywords: {:?}", oeis_ids.len());
+        self.simple_log.println(content);
+        self.program_ids.extend(oeis_ids.iter().map(|oeis_id| oeis_id.raw()));
+        Ok(())
+    }
+
     fn sorted_vec(program_ids: &HashSet<u32>) -> Vec<u32> {
         let mut program_ids_sorted: Vec<u32> = program_ids.clone().into_iter().collect();
         program_ids_sorted.sort();
@@ -13,10 +13,13 @@ mod analyze_source_ngram;
 mod analyze_program_complexity;
 mod analyze_program_modified;
 mod analyze_target_ngram;
+mod analyze_unofficial_function;
 mod batch_program_analyzer;
+mod create_evaluation_pool;
 mod deny_file;
 mod dont_mine;
 mod histogram_stripped_file;
+mod priority_programs;
 mod program_rank;
 mod validate_programs;
 
@@ -34,9 +37,12 @@ pub use analyze_program_modified::AnalyzeProgramModified;
 pub use analyze_line_ngram::AnalyzeLineNgram;
 pub use analyze_source_ngram::AnalyzeSourceNgram;
 pub use analyze_target_ngram::AnalyzeTargetNgram;
+pub use analyze_unofficial_function::AnalyzeUnofficialFunction;
 pub use batch_program_analyzer::{BatchProgramAnalyzer, BatchProgramAnalyzerContext, BatchProgramAnalyzerPlugin, BatchProgramAnalyzerPluginItem};
+pub use create_evaluation_pool::create_evaluation_pool;
 pub use deny_file::load_program_ids_from_deny_file;
 pub use dont_mine::DontMine;
 pub use histogram_stripped_file::HistogramStrippedFile;
+pub use priority_programs::PriorityPrograms;
 pub use program_rank::compute_program_rank;
 pub use validate_programs::ValidatePrograms;
@@ -0,0 +1,33 @@
+use crate::common::SimpleLog;
+use crate::config::Config;
+use crate::oeis::{OeisKeyword, OeisKeywords};
+use std::error::Error;
+use std::path::PathBuf;
+use super::AnalyticsDirectory;
+use crate::common::save_program_ids_csv_file;
+
+/// Generate the `priority_programs.csv` file.
+///
+/// These are the program ids for sequences with the `core` or `nice` keyword.
+/// The miner picks these programs more often, when initializing the genome.
+///
+/// When there is no OEIS keywords file, then the csv file is empty.
+pub struct PriorityPrograms;
+
+impl PriorityPrograms {
+    pub fn run(analytics_directory: AnalyticsDirectory, simple_log: SimpleLog) -> Result<(), Box<dyn Error>> {
+        simple_log.println("\nPriorityPrograms");
+        let config = Config::load();
+        let oeis_keywords: OeisKeywords = OeisKeywords::load_if_exists(&config.oeis_keywords_file())?;
+        let mut program_ids: Vec<u32> = oeis_keywords
+            .oeis_ids_with_any_keyword(&OeisKeyword::PRIORITY)
+            .iter()
+            .map(|oeis_id| oeis_id.raw())
+            .collect();
+        program_ids.sort();
+        let content = format!("number of program ids in the 'priority_programs.csv' file: {:?}", program_ids.len());
+        simple_log.println(content);
+        let output_path: PathBuf = analytics_directory.priority_programs_file();
+        save_program_ids_csv_file(&program_ids, &output_path)
+    }
+}
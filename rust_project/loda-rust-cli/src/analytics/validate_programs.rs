@@ -1,9 +1,10 @@
-use super::AnalyticsDirectory;
+use super::{AnalyticsDirectory, create_evaluation_pool};
 use crate::common::{find_asm_files_recursively, oeis_ids_from_paths, ToOeisIdVec, SimpleLog};
 use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
 use loda_rust_core;
-use crate::config::Config;
-use loda_rust_core::control::{DependencyManager,DependencyManagerFileSystemMode};
+use crate::config::{Config, PostmineBackend};
+use crate::mine::create_miner_unofficial_function_registry;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, EvaluationPool};
 use loda_rust_core::execute::{NodeLoopLimit, ProgramCache, ProgramRunner, RegisterValue, RunMode};
 use loda_rust_core::execute::NodeRegisterLimit;
 use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
@@ -51,6 +52,7 @@ If a program cannot execute or failes, then it goes to the invalid file.
 - programs that cannot parse.
 - programs with cyclic dependencies.
 - programs that fails to compute NUMBER_OF_TERMS_TO_VALIDATE terms.
+- programs that calls unofficial functions, unless the `lodarust` postmine backend is used.
 
 The outputted file: `programs_valid.csv` has this format:
 
@@ -81,6 +83,17 @@ The outputted file: `programs_invalid_verbose.csv` has this format:
 pub struct ValidatePrograms {}
 
 impl ValidatePrograms {
+    /// The miner and postmine uses the valid programs.
+    ///
+    /// The `lodacpp` postmine backend cannot evaluate unofficial functions, so the programs that uses them are invalid.
+    /// The `lodarust` postmine backend has the same unofficial functions as the miner.
+    fn create_unofficial_function_registry(config: &Config) -> anyhow::Result<UnofficialFunctionRegistry> {
+        match config.postmine_backend() {
+            PostmineBackend::LodaCpp => Ok(UnofficialFunctionRegistry::new()),
+            PostmineBackend::LodaRust => create_miner_unofficial_function_registry(config),
+        }
+    }
+
     pub fn run(analytics_directory: AnalyticsDirectory, simple_log: SimpleLog) -> anyhow::Result<()> {
         let start = Instant::now();
         simple_log.println("\nValidatePrograms");
@@ -118,58 +131,46 @@ impl ValidatePrograms {
         let mut programs_invalid_verbose_csv = LineWriter::new(file2);
         programs_invalid_verbose_csv.write_all(b"program id;error category;error message\n")?;
 
-        // Run all the programs.
+        // Run all the programs in parallel.
         // Reject the programs that is having difficulties running.
-        let mut dm = DependencyManager::new(
-            DependencyManagerFileSystemMode::System,
-            loda_programs_oeis_dir,
-            UnofficialFunctionRegistry::new(),
-        );
-        let mut cache = ProgramCache::new();
-        let oeis_ids_len: usize = oeis_ids.len();
+        let pool: EvaluationPool = create_evaluation_pool(&config, &analytics_directory);
+        let content = format!("number of threads: {}", pool.number_of_threads());
+        simple_log.println(content);
+        let registry: UnofficialFunctionRegistry = Self::create_unofficial_function_registry(&config)?;
+        let create_dependency_manager = || {
+            DependencyManager::new(
+                DependencyManagerFileSystemMode::System,
+                loda_programs_oeis_dir.clone(),
+                registry.clone(),
+            )
+        };
+        let pb = ProgressBar::new(oeis_ids.len() as u64);
+        let outcomes: Vec<ValidateOutcome> = pool.map(&oeis_ids, create_dependency_manager, |dm, cache, oeis_id| {
+            let outcome = ValidateOutcome::validate(dm, cache, *oeis_id);
+            pb.inc(1);
+            outcome
+        });
+        pb.finish_and_clear();
+
+        // Append status for programs to the csv files, in the same order as the program ids.
         let mut number_of_invalid_programs: u32 = 0;
         let mut valid_program_ids: OeisIdHashSet = HashSet::new();
-        let pb = ProgressBar::new(oeis_ids_len as u64);
-        for oeis_id in oeis_ids {
-            let program_id64 = oeis_id.raw() as u64;
-            let program_runner: Rc::<ProgramRunner> = match dm.load(program_id64) {
-                Ok(value) => value,
-                Err(error) => {
-                    // error!("Cannot load program {:?}: {:?}", oeis_id, error);
-                    let row_simple = format!("{:?}\n", oeis_id.raw());
+        for (oeis_id, outcome) in oeis_ids.iter().zip(outcomes) {
+            let row_simple = format!("{:?}\n", oeis_id.raw());
+            match outcome {
+                ValidateOutcome::Valid => {
+                    programs_valid_csv.write_all(row_simple.as_bytes())?;
+                    valid_program_ids.insert(*oeis_id);
+                },
+                ValidateOutcome::Invalid { category, error_message } => {
                     programs_invalid_csv.write_all(row_simple.as_bytes())?;
-                    let mut error_message = format!("{:?}", error);
-                    error_message = error_message.replace("\n", "<BR>");
-                    let row_verbose = format!("{:?};LOAD;{}\n", oeis_id.raw(), error_message);
+                    let error_message: String = error_message.replace("\n", "<BR>");
+                    let row_verbose = format!("{:?};{};{}\n", oeis_id.raw(), category, error_message);
                     programs_invalid_verbose_csv.write_all(row_verbose.as_bytes())?;
                     number_of_invalid_programs += 1;
-                    pb.inc(1);
-                    continue;
-                }
-            };
-            match program_runner.compute_terms(NUMBER_OF_TERMS_TO_VALIDATE, &mut cache) {
-                Ok(_) => {},
-                Err(error) => {
-                    // error!("Cannot run program {:?}: {:?}", oeis_id, error);
-                    let row_simple = format!("{:?}\n", oeis_id.raw());
-                    programs_invalid_csv.write_all(row_simple.as_bytes())?;
-                    let mut error_message = format!("{:?}", error);
-                    error_message = error_message.replace("\n", "<BR>");
-                    let row_verbose = format!("{:?};COMPUTE;{}\n", oeis_id.raw(), error_message);
-                    programs_invalid_verbose_csv.write_all(row_verbose.as_bytes())?;
-                    number_of_invalid_programs += 1;
-                    pb.inc(1);
-                    continue;
                 }
             }
-
-            // Append status for programs to the csv file.
-            let row = format!("{:?}\n", oeis_id.raw());
-            programs_valid_csv.write_all(row.as_bytes())?;
-            valid_program_ids.insert(oeis_id);
-            pb.inc(1);
         }
-        pb.finish_and_clear();
 
         let green_bold = Style::new().green().bold();        
         println!(
@@ -187,6 +188,29 @@ impl ValidatePrograms {
     }
 }
 
+enum ValidateOutcome {
+    Valid,
+    Invalid { category: &'static str, error_message: String },
+}
+
+impl ValidateOutcome {
+    fn validate(dm: &mut DependencyManager, cache: &mut ProgramCache, oeis_id: OeisId) -> Self {
+        let program_id64 = oeis_id.raw() as u64;
+        let program_runner: Rc::<ProgramRunner> = match dm.load(program_id64) {
+            Ok(value) => value,
+            Err(error) => {
+                return Self::Invalid { category: "LOAD", error_message: format!("{:?}", error) };
+            }
+        };
+        match program_runner.compute_terms(NUMBER_OF_TERMS_TO_VALIDATE, cache) {
+            Ok(_) => Self::Valid,
+            Err(error) => {
+                Self::Invalid { category: "COMPUTE", error_message: format!("{:?}", error) }
+            }
+        }
+    }
+}
+
 trait ComputeTerms {
     fn compute_terms(&self, count: u64, cache: &mut ProgramCache) -> anyhow::Result<()>;
 }
@@ -49,6 +49,10 @@ impl UnofficialFunction for ImageDebugFunction {
         // no output
         Ok(vec!())
     }
+
+    fn is_minable(&self) -> bool {
+        false
+    }
 }
 
 enum ImageOffsetFunctionMode {
@@ -229,10 +229,15 @@ impl RunWithProgram {
         }
     }
 
-    pub fn create_dependency_manager() -> DependencyManager {
+    pub fn create_unofficial_function_registry() -> UnofficialFunctionRegistry {
         let registry = UnofficialFunctionRegistry::new();
         register_common_functions(&registry);
         register_arc_functions(&registry);
+        registry
+    }
+
+    pub fn create_dependency_manager() -> DependencyManager {
+        let registry: UnofficialFunctionRegistry = Self::create_unofficial_function_registry();
         let dm = DependencyManager::new(
             DependencyManagerFileSystemMode::Virtual,
             PathBuf::from("non-existing-dir"),
@@ -16,6 +16,7 @@ use anyhow::{Context, bail};
 use loda_rust_core::control::DependencyManager;
 use loda_rust_core::execute::{ProgramSerializer, ProgramId, ProgramRunner};
 use loda_rust_core::parser::ParsedProgram;
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use std::fmt;
 use std::time::{Duration, Instant};
 use std::cell::RefCell;
@@ -1566,7 +1567,8 @@ impl TraverseProgramsAndModels {
             self.arc_config.path_analytics_arc_dir.clone()
         ).with_context(||"unable to create AnalyticsDirectory instance")?;    
 
-        let context: GenomeMutateContext = create_genome_mutate_context(CreateGenomeMutateContextMode::ARC, analytics_directory)?;
+        let registry: UnofficialFunctionRegistry = RunWithProgram::create_unofficial_function_registry();
+        let context: GenomeMutateContext = create_genome_mutate_context(CreateGenomeMutateContextMode::ARC, analytics_directory, &registry)?;
         self.context = context;
         println!("loaded genome mutate context. elapsed: {}", HumanDuration(start.elapsed()));
         Ok(())
@@ -0,0 +1,30 @@
+use crate::config::Config;
+use loda_rust_core::unofficial_function::{ScriptedFunctionManifest, UnofficialFunctionRegistry};
+use anyhow::Context;
+use std::path::PathBuf;
+
+/// The unofficial functions that are available when evaluating the programs in the `loda-programs` repo.
+///
+/// Only the functions in the `unofficial_function_manifest` are registered, when the config specifies a manifest.
+/// Otherwise the registry is empty.
+pub fn create_unofficial_function_registry(config: &Config) -> anyhow::Result<UnofficialFunctionRegistry> {
+    let registry = UnofficialFunctionRegistry::new();
+    register_unofficial_function_manifest(config, &registry)?;
+    Ok(registry)
+}
+
+/// Register the functions in the `unofficial_function_manifest`, when the config specifies a manifest.
+pub fn register_unofficial_function_manifest(config: &Config, registry: &UnofficialFunctionRegistry) -> anyhow::Result<()> {
+    let manifest_path: PathBuf = match config.unofficial_function_manifest() {
+        Some(value) => value,
+        None => {
+            return Ok(());
+        }
+    };
+    let manifest: ScriptedFunctionManifest = ScriptedFunctionManifest::load(&manifest_path)
+        .with_context(|| format!("Unable to load unofficial function manifest: {:?}", manifest_path))?;
+    let loda_programs_oeis_dir: PathBuf = config.loda_programs_oeis_dir();
+    manifest.register(registry, &loda_programs_oeis_dir)
+        .with_context(|| format!("Unable to register the functions in the manifest: {:?}", manifest_path))?;
+    Ok(())
+}
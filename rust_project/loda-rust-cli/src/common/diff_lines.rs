@@ -0,0 +1,97 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Same(line) => write!(f, " {}", line),
+            Self::Removed(line) => write!(f, "-{}", line),
+            Self::Added(line) => write!(f, "+{}", line),
+        }
+    }
+}
+
+/// Line based diff between two texts, using the longest common subsequence.
+///
+/// The lines that only exist in `old_text` are `Removed`.
+/// The lines that only exist in `new_text` are `Added`.
+pub fn diff_lines(old_text: &str, new_text: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old_text.lines().collect();
+    let new_lines: Vec<&str> = new_text.lines().collect();
+    let n: usize = old_lines.len();
+    let m: usize = new_lines.len();
+
+    // lcs[i][j] is the length of the longest common subsequence of old_lines[i..] and new_lines[j..]
+    let mut lcs: Vec<Vec<usize>> = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::<DiffLine>::with_capacity(n.max(m));
+    let mut i: usize = 0;
+    let mut j: usize = 0;
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            result.push(DiffLine::Same(old_lines[i].to_string()));
+            i += 1;
+            j += 1;
+            continue;
+        }
+        if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old_lines[i].to_string()));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    for line in &old_lines[i..] {
+        result.push(DiffLine::Removed(line.to_string()));
+    }
+    for line in &new_lines[j..] {
+        result.push(DiffLine::Added(line.to_string()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(old_text: &str, new_text: &str) -> String {
+        let lines: Vec<String> = diff_lines(old_text, new_text).iter().map(|line| line.to_string()).collect();
+        lines.join("|")
+    }
+
+    #[test]
+    fn test_10000_identical() {
+        assert_eq!(process("", ""), "");
+        assert_eq!(process("a\nb", "a\nb"), " a| b");
+    }
+
+    #[test]
+    fn test_10001_added_removed() {
+        assert_eq!(process("", "a"), "+a");
+        assert_eq!(process("a", ""), "-a");
+        assert_eq!(process("a\nb\nc", "a\nc"), " a|-b| c");
+        assert_eq!(process("a\nc", "a\nb\nc"), " a|+b| c");
+    }
+
+    #[test]
+    fn test_10002_replaced() {
+        assert_eq!(process("mov $1,2\npow $1,$0\nmov $0,$1", "mov $1,2\npow $1,$0\nadd $1,1\nmov $0,$1"), " mov $1,2| pow $1,$0|+add $1,1| mov $0,$1");
+        assert_eq!(process("add $0,1", "sub $0,1"), "-add $0,1|+sub $0,1");
+    }
+}
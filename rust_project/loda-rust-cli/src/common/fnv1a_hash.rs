@@ -0,0 +1,24 @@
+/// FNV-1a hash, so the hash is the same across Rust versions and platforms.
+///
+/// Usecase:
+/// Hashes that are saved to disk, such as in a file format or in a filename.
+pub fn fnv1a_hash(key: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_fnv1a_hash() {
+        assert_eq!(fnv1a_hash(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a_hash("foobar"), 0x85944171f73967e8);
+    }
+}
@@ -1,6 +1,9 @@
 //! Read/write CSV files. CSV row types. Obtain filenames. Logging.
 mod create_csv_file;
+mod create_unofficial_function_registry;
+mod diff_lines;
 mod find_files_recursively;
+mod fnv1a_hash;
 mod mine_event_directory_scan;
 mod oeis_ids_from_paths;
 mod oeis_ids_from_programs;
@@ -8,6 +11,7 @@ mod oeis_ids_sorted;
 mod oeis_id_string_map;
 mod parse_csv_data;
 mod parse_csv_file;
+mod parse_program_id;
 mod pending_programs_with_priority;
 mod record_bigram;
 mod record_program_id;
@@ -17,9 +21,12 @@ mod record_unigram;
 mod simple_log;
 
 pub use create_csv_file::create_csv_file;
+pub use create_unofficial_function_registry::{create_unofficial_function_registry, register_unofficial_function_manifest};
+pub use diff_lines::{diff_lines, DiffLine};
 
 #[allow(unused_imports)]
 pub use find_files_recursively::{find_asm_files_recursively, find_csv_files_recursively, find_json_files_recursively};
+pub use fnv1a_hash::fnv1a_hash;
 pub use mine_event_directory_scan::MineEventDirectoryScan;
 pub use oeis_ids_from_paths::{oeis_id_from_path, oeis_ids_from_paths};
 
@@ -29,6 +36,7 @@ pub use oeis_ids_sorted::ToOeisIdVec;
 pub use oeis_id_string_map::OeisIdStringMap;
 pub use parse_csv_data::parse_csv_data;
 pub use parse_csv_file::parse_csv_file;
+pub use parse_program_id::parse_program_id;
 pub use pending_programs_with_priority::PendingProgramsWithPriority;
 pub use record_bigram::RecordBigram;
 pub use record_program_id::{load_program_ids_csv_file, save_program_ids_csv_file};
@@ -0,0 +1,36 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Extract the sequence number "123456" from user input like this "A123456" or "123456".
+    static ref EXTRACT_PROGRAM_ID: Regex = Regex::new(
+        "^A?(\\d+)$"
+    ).unwrap();
+}
+
+/// Fuzzy convert from user input to a program id, allows the 'A' to be left out.
+///
+/// Returns `None` when the input isn't an A number, such as a path to a program.
+pub fn parse_program_id(input: &str) -> Option<u64> {
+    let captures = EXTRACT_PROGRAM_ID.captures(input)?;
+    let capture1: &str = captures.get(1).map_or("", |m| m.as_str());
+    capture1.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_parse_program_id() {
+        assert_eq!(parse_program_id("A000045"), Some(45));
+        assert_eq!(parse_program_id("A123456"), Some(123456));
+        assert_eq!(parse_program_id("45"), Some(45));
+        assert_eq!(parse_program_id("0"), Some(0));
+        assert_eq!(parse_program_id("A"), None);
+        assert_eq!(parse_program_id("x"), None);
+        assert_eq!(parse_program_id("A45x"), None);
+        assert_eq!(parse_program_id("dir/A000045.asm"), None);
+        assert_eq!(parse_program_id("A99999999999999999999999"), None);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::mine::{PerformanceClassifier, PerformanceClassifierResult};
+use loda_rust_core::execute::{ProgramCache, ProgramRunner, TermResult};
+use loda_rust_core::util::BigIntVec;
+use num_bigint::BigInt;
+use std::ops::Range;
+
+/// The terms and the number of steps used for computing each term.
+#[derive(Clone, Debug)]
+pub struct ProgramEvaluation {
+    pub terms: BigIntVec,
+    pub steps: Vec<u64>,
+
+    /// The error that stopped the evaluation early.
+    pub error: Option<String>,
+}
+
+impl ProgramEvaluation {
+    const STEP_COUNT_LIMIT: u64 = 1000000000;
+
+    /// Evaluate the program for all the indexes in the range, or until an error occurs.
+    ///
+    /// The indexes are in OEIS coordinates, so with `#offset 1` the first term is at index `1`.
+    pub fn evaluate(runner: &ProgramRunner, cache: &mut ProgramCache, range: Range<i64>) -> Self {
+        let capacity: usize = (range.end - range.start).max(0) as usize;
+        let mut terms = BigIntVec::with_capacity(capacity);
+        let mut steps: Vec<u64> = Vec::with_capacity(capacity);
+        let mut error: Option<String> = None;
+        let term_results = runner.eval_terms(range, cache).step_count_limit(Self::STEP_COUNT_LIMIT);
+        for term in term_results {
+            let TermResult { index, value, step_count } = term;
+            match value {
+                Ok(value) => {
+                    terms.push(value);
+                    steps.push(step_count);
+                },
+                Err(e) => {
+                    error = Some(format!("a({}) failed: {}", index, e));
+                    break;
+                }
+            }
+        }
+        Self { terms, steps, error }
+    }
+
+    pub fn total_steps(&self) -> u64 {
+        self.steps.iter().sum()
+    }
+}
+
+/// The first term where the two programs disagree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TermMismatch {
+    pub index: i64,
+    pub term0: Option<BigInt>,
+    pub term1: Option<BigInt>,
+}
+
+/// Evaluation of two programs over the same range of indexes, in OEIS coordinates.
+#[derive(Clone, Debug)]
+pub struct CompareTermsResult {
+    pub range: Range<i64>,
+    pub evaluation0: ProgramEvaluation,
+    pub evaluation1: ProgramEvaluation,
+}
+
+impl CompareTermsResult {
+    pub fn new(runner0: &ProgramRunner, runner1: &ProgramRunner, cache: &mut ProgramCache, range: Range<i64>) -> Self {
+        let evaluation0 = ProgramEvaluation::evaluate(runner0, cache, range.clone());
+        let evaluation1 = ProgramEvaluation::evaluate(runner1, cache, range.clone());
+        Self { range, evaluation0, evaluation1 }
+    }
+
+    /// Evaluate the first `term_count` terms.
+    ///
+    /// When the programs have different offsets, then the terms starts at the highest `#offset`,
+    /// so both programs are evaluated where they are defined.
+    pub fn with_term_count(runner0: &ProgramRunner, runner1: &ProgramRunner, cache: &mut ProgramCache, term_count: u64) -> Self {
+        let first_index: i64 = runner0.first_index().max(runner1.first_index());
+        let count: i64 = i64::try_from(term_count).unwrap_or(i64::MAX);
+        let range: Range<i64> = first_index..first_index.saturating_add(count);
+        Self::new(runner0, runner1, cache, range)
+    }
+
+    /// Returns `None` when both programs computes the same terms for the entire range.
+    ///
+    /// When a program stops early, the missing term is `None`.
+    pub fn first_mismatch(&self) -> Option<TermMismatch> {
+        let terms0: &BigIntVec = &self.evaluation0.terms;
+        let terms1: &BigIntVec = &self.evaluation1.terms;
+        let length: usize = terms0.len().max(terms1.len());
+        for i in 0..length {
+            let term0: Option<&BigInt> = terms0.get(i);
+            let term1: Option<&BigInt> = terms1.get(i);
+            if term0 != term1 {
+                return Some(TermMismatch {
+                    index: self.range.start + i as i64,
+                    term0: term0.cloned(),
+                    term1: term1.cloned(),
+                });
+            }
+        }
+        let expected_length: usize = (self.range.end - self.range.start).max(0) as usize;
+        if length < expected_length {
+            // Both programs stopped early at the same index
+            return Some(TermMismatch {
+                index: self.range.start + length as i64,
+                term0: None,
+                term1: None,
+            });
+        }
+        None
+    }
+
+    pub fn is_equivalent(&self) -> bool {
+        self.first_mismatch().is_none()
+    }
+
+    /// The number of steps used by program1 divided by the number of steps used by program0.
+    pub fn step_ratio(&self) -> Option<f64> {
+        let steps0: u64 = self.evaluation0.total_steps();
+        let steps1: u64 = self.evaluation1.total_steps();
+        if steps0 == 0 {
+            return None;
+        }
+        Some(steps1 as f64 / steps0 as f64)
+    }
+
+    /// How the performance of program1 compares to program0, where program1 is considered the new program.
+    ///
+    /// Only available when the terms are the same.
+    pub fn performance_verdict(&self) -> Option<PerformanceClassifierResult> {
+        if !self.is_equivalent() {
+            return None;
+        }
+        let classifier = PerformanceClassifier::new(10);
+        Some(classifier.analyze(&self.evaluation1.steps, &self.evaluation0.steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+    use loda_rust_core::execute::ProgramId;
+    use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+    use std::path::PathBuf;
+
+    fn runners(program0: &str, program1: &str) -> (ProgramRunner, ProgramRunner) {
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+        let runner0: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, program0).expect("program0");
+        let runner1: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, program1).expect("program1");
+        (runner0, runner1)
+    }
+
+    fn compare(program0: &str, program1: &str, range: Range<i64>) -> CompareTermsResult {
+        let (runner0, runner1) = runners(program0, program1);
+        let mut cache = ProgramCache::new();
+        CompareTermsResult::new(&runner0, &runner1, &mut cache, range)
+    }
+
+    #[test]
+    fn test_10000_equivalent_and_faster() {
+        // Arrange
+        let program0 = "mov $1,$0\nmov $0,0\nlpb $1\n  sub $1,1\n  add $0,2\nlpe";
+        let program1 = "mul $0,2";
+
+        // Act
+        let result = compare(program0, program1, 0..20);
+
+        // Assert
+        assert_eq!(result.first_mismatch(), None);
+        assert_eq!(result.is_equivalent(), true);
+        assert!(result.step_ratio().unwrap() < 1.0);
+        assert_eq!(result.performance_verdict(), Some(PerformanceClassifierResult::NewProgramIsAlwaysFaster));
+    }
+
+    #[test]
+    fn test_10001_first_mismatch() {
+        // Act
+        let result = compare("mul $0,2", "mul $0,2\nmin $0,10", 0..20);
+
+        // Assert
+        let mismatch: TermMismatch = result.first_mismatch().expect("mismatch");
+        assert_eq!(mismatch.index, 6);
+        assert_eq!(mismatch.term0, Some(BigInt::from(12)));
+        assert_eq!(mismatch.term1, Some(BigInt::from(10)));
+        assert_eq!(result.performance_verdict(), None);
+    }
+
+    #[test]
+    fn test_10002_error_stops_early() {
+        // Act
+        let result = compare("mov $1,5\nsub $1,$0\ndiv $0,$1", "mov $0,0", 0..10);
+
+        // Assert
+        assert_eq!(result.evaluation0.terms.len(), 5);
+        assert_eq!(result.evaluation0.error.is_some(), true);
+        let mismatch: TermMismatch = result.first_mismatch().expect("mismatch");
+        assert_eq!(mismatch.index, 3);
+    }
+
+    #[test]
+    fn test_10003_range_not_starting_at_zero() {
+        // Act
+        let result = compare("add $0,1", "add $0,2\nsub $0,1", 100..110);
+
+        // Assert
+        assert_eq!(result.evaluation0.terms.first(), Some(&BigInt::from(101)));
+        assert_eq!(result.is_equivalent(), true);
+    }
+
+    #[test]
+    fn test_10004_offset() {
+        // Arrange
+        let (runner0, runner1) = runners("#offset 1\nmul $0,2", "#offset 1\nmul $0,2\nmin $0,10");
+        let mut cache = ProgramCache::new();
+
+        // Act
+        let result = CompareTermsResult::with_term_count(&runner0, &runner1, &mut cache, 10);
+
+        // Assert
+        assert_eq!(result.range, 1..11);
+        assert_eq!(result.evaluation0.terms.first(), Some(&BigInt::from(2)));
+        let mismatch: TermMismatch = result.first_mismatch().expect("mismatch");
+        assert_eq!(mismatch.index, 6);
+        assert_eq!(mismatch.term0, Some(BigInt::from(12)));
+        assert_eq!(mismatch.term1, Some(BigInt::from(10)));
+    }
+
+    #[test]
+    fn test_10005_different_offsets() {
+        // Arrange
+        let (runner0, runner1) = runners("mul $0,2", "#offset 1\nmul $0,2");
+        let mut cache = ProgramCache::new();
+
+        // Act
+        let result = CompareTermsResult::with_term_count(&runner0, &runner1, &mut cache, 10);
+
+        // Assert
+        assert_eq!(result.range, 1..11);
+        assert_eq!(result.is_equivalent(), true);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::common::DiffLine;
+use loda_rust_core::parser::{Instruction, InstructionId};
+
+#[derive(Clone, Debug)]
+enum Item {
+    Instruction(String),
+    Loop {
+        begin: String,
+        body: Vec<Item>,
+        end: String,
+    },
+}
+
+impl Item {
+    fn parse(instructions: &[Instruction]) -> Vec<Item> {
+        let mut stack: Vec<(String, Vec<Item>)> = vec!();
+        let mut items: Vec<Item> = vec!();
+        for instruction in instructions {
+            match instruction.instruction_id {
+                InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract => {
+                    let parent_items: Vec<Item> = std::mem::take(&mut items);
+                    stack.push((instruction.to_string(), parent_items));
+                },
+                InstructionId::LoopEnd => {
+                    match stack.pop() {
+                        Some((begin, parent_items)) => {
+                            let body: Vec<Item> = std::mem::replace(&mut items, parent_items);
+                            items.push(Item::Loop { begin, body, end: instruction.to_string() });
+                        },
+                        None => {
+                            // Unbalanced loop end, treat it as an ordinary instruction
+                            items.push(Item::Instruction(instruction.to_string()));
+                        }
+                    }
+                },
+                _ => {
+                    items.push(Item::Instruction(instruction.to_string()));
+                }
+            }
+        }
+        // Unbalanced loop begin, treat the remaining instructions as belonging to the parent
+        while let Some((begin, mut parent_items)) = stack.pop() {
+            parent_items.push(Item::Instruction(begin));
+            parent_items.append(&mut items);
+            items = parent_items;
+        }
+        items
+    }
+
+    fn append_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent: String = "  ".repeat(depth);
+        match self {
+            Item::Instruction(text) => {
+                lines.push(format!("{}{}", indent, text));
+            },
+            Item::Loop { begin, body, end } => {
+                lines.push(format!("{}{}", indent, begin));
+                for item in body {
+                    item.append_lines(depth + 1, lines);
+                }
+                lines.push(format!("{}{}", indent, end));
+            }
+        }
+    }
+}
+
+/// Score for aligning the two items, or `None` when the items cannot be aligned.
+///
+/// Identical instructions can be aligned. Any two loops can be aligned, so that the diff is anchored on the loops.
+fn match_score(item0: &Item, item1: &Item) -> Option<usize> {
+    match (item0, item1) {
+        (Item::Instruction(text0), Item::Instruction(text1)) => {
+            if text0 == text1 {
+                Some(2)
+            } else {
+                None
+            }
+        },
+        (Item::Loop { begin: begin0, body: body0, .. }, Item::Loop { begin: begin1, body: body1, .. }) => {
+            let begin_score: usize = if begin0 == begin1 { 2 } else { 1 };
+            Some(begin_score + Alignment::new(body0, body1).score())
+        },
+        _ => None
+    }
+}
+
+/// Longest common subsequence, weighted by the `match_score`.
+struct Alignment {
+    scores: Vec<Vec<usize>>,
+    matches: Vec<Vec<Option<usize>>>,
+}
+
+impl Alignment {
+    fn new(items0: &[Item], items1: &[Item]) -> Self {
+        let n: usize = items0.len();
+        let m: usize = items1.len();
+        let mut matches: Vec<Vec<Option<usize>>> = vec![vec![None; m]; n];
+        for i in 0..n {
+            for j in 0..m {
+                matches[i][j] = match_score(&items0[i], &items1[j]);
+            }
+        }
+        let mut scores: Vec<Vec<usize>> = vec![vec![0; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                let mut score: usize = scores[i + 1][j].max(scores[i][j + 1]);
+                if let Some(match_score) = matches[i][j] {
+                    score = score.max(match_score + scores[i + 1][j + 1]);
+                }
+                scores[i][j] = score;
+            }
+        }
+        Self { scores, matches }
+    }
+
+    fn score(&self) -> usize {
+        self.scores[0][0]
+    }
+}
+
+fn diff_items(items0: &[Item], items1: &[Item], depth: usize, result: &mut Vec<DiffLine>) {
+    let alignment = Alignment::new(items0, items1);
+    let scores: &Vec<Vec<usize>> = &alignment.scores;
+    let matches: &Vec<Vec<Option<usize>>> = &alignment.matches;
+    let n: usize = items0.len();
+    let m: usize = items1.len();
+    let mut i: usize = 0;
+    let mut j: usize = 0;
+    while i < n || j < m {
+        if i < n && j < m {
+            if let Some(match_score) = matches[i][j] {
+                if scores[i][j] == match_score + scores[i + 1][j + 1] {
+                    diff_aligned_items(&items0[i], &items1[j], depth, result);
+                    i += 1;
+                    j += 1;
+                    continue;
+                }
+            }
+        }
+        if j >= m || (i < n && scores[i + 1][j] >= scores[i][j + 1]) {
+            let mut lines: Vec<String> = vec!();
+            items0[i].append_lines(depth, &mut lines);
+            result.extend(lines.into_iter().map(DiffLine::Removed));
+            i += 1;
+        } else {
+            let mut lines: Vec<String> = vec!();
+            items1[j].append_lines(depth, &mut lines);
+            result.extend(lines.into_iter().map(DiffLine::Added));
+            j += 1;
+        }
+    }
+}
+
+fn diff_aligned_items(item0: &Item, item1: &Item, depth: usize, result: &mut Vec<DiffLine>) {
+    let indent: String = "  ".repeat(depth);
+    match (item0, item1) {
+        (Item::Loop { begin: begin0, body: body0, end: end0 }, Item::Loop { begin: begin1, body: body1, end: end1 }) => {
+            if begin0 == begin1 {
+                result.push(DiffLine::Same(format!("{}{}", indent, begin0)));
+            } else {
+                result.push(DiffLine::Removed(format!("{}{}", indent, begin0)));
+                result.push(DiffLine::Added(format!("{}{}", indent, begin1)));
+            }
+            diff_items(body0, body1, depth + 1, result);
+            if end0 == end1 {
+                result.push(DiffLine::Same(format!("{}{}", indent, end0)));
+            } else {
+                result.push(DiffLine::Removed(format!("{}{}", indent, end0)));
+                result.push(DiffLine::Added(format!("{}{}", indent, end1)));
+            }
+        },
+        _ => {
+            let mut lines: Vec<String> = vec!();
+            item0.append_lines(depth, &mut lines);
+            result.extend(lines.into_iter().map(DiffLine::Same));
+        }
+    }
+}
+
+/// Instruction level diff between two programs, where the loops are aligned with each other.
+///
+/// Comments are ignored. The instructions inside loops are indented.
+pub fn loop_aligned_diff(instructions0: &[Instruction], instructions1: &[Instruction]) -> Vec<DiffLine> {
+    let items0: Vec<Item> = Item::parse(instructions0);
+    let items1: Vec<Item> = Item::parse(instructions1);
+    let mut result: Vec<DiffLine> = vec!();
+    diff_items(&items0, &items1, 0, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::parser::ParsedProgram;
+
+    fn process(program0: &str, program1: &str) -> String {
+        let parsed_program0: ParsedProgram = ParsedProgram::parse_program(program0).expect("program0");
+        let parsed_program1: ParsedProgram = ParsedProgram::parse_program(program1).expect("program1");
+        let lines: Vec<String> = loop_aligned_diff(&parsed_program0.instruction_vec, &parsed_program1.instruction_vec)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        lines.join("|")
+    }
+
+    #[test]
+    fn test_10000_identical() {
+        assert_eq!(process("mov $1,2\npow $1,$0", "mov $1,2 ; comment\npow $1,$0"), " mov $1,2| pow $1,$0");
+    }
+
+    #[test]
+    fn test_10001_instruction_changed() {
+        assert_eq!(process("add $0,1", "sub $0,1"), "-add $0,1|+sub $0,1");
+    }
+
+    #[test]
+    fn test_20000_loop_body_changed() {
+        let program0 = "mov $1,1\nlpb $0\n  sub $0,1\n  mul $1,2\nlpe\nmov $0,$1";
+        let program1 = "mov $1,1\nlpb $0\n  sub $0,1\n  mul $1,3\nlpe\nmov $0,$1";
+        assert_eq!(process(program0, program1), " mov $1,1| lpb $0|   sub $0,1|-  mul $1,2|+  mul $1,3| lpe| mov $0,$1");
+    }
+
+    #[test]
+    fn test_20001_loop_parameter_changed() {
+        let program0 = "lpb $0\n  sub $0,1\nlpe";
+        let program1 = "lpb $0,2\n  sub $0,1\nlpe";
+        assert_eq!(process(program0, program1), "-lpb $0|+lpb $0,2|   sub $0,1| lpe");
+    }
+
+    #[test]
+    fn test_20002_loops_aligned_despite_different_bodies() {
+        // The loops have nothing in common, but are still aligned with each other.
+        let program0 = "lpb $0\n  add $1,1\nlpe";
+        let program1 = "lpb $0\n  mul $1,2\nlpe";
+        assert_eq!(process(program0, program1), " lpb $0|-  add $1,1|+  mul $1,2| lpe");
+    }
+
+    #[test]
+    fn test_20003_loop_added() {
+        let program0 = "mov $1,2";
+        let program1 = "mov $1,2\nlpb $0\n  sub $0,1\nlpe";
+        assert_eq!(process(program0, program1), " mov $1,2|+lpb $0|+  sub $0,1|+lpe");
+    }
+
+    #[test]
+    fn test_20004_nested_loops() {
+        let program0 = "lpb $0\n  lpb $1\n    add $2,1\n  lpe\nlpe";
+        let program1 = "lpb $0\n  lpb $1\n    add $2,2\n  lpe\nlpe";
+        assert_eq!(process(program0, program1), " lpb $0|   lpb $1|-    add $2,1|+    add $2,2|   lpe| lpe");
+    }
+}
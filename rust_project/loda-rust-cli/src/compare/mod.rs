@@ -0,0 +1,6 @@
+//! Compare two programs, both the instructions and the computed terms.
+mod compare_programs;
+mod loop_aligned_diff;
+
+pub use compare_programs::{CompareTermsResult, ProgramEvaluation, TermMismatch};
+pub use loop_aligned_diff::loop_aligned_diff;
@@ -36,6 +36,41 @@ pub enum MinerFilterMode {
     All,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Eq)]
+#[serde(tag = "type", content = "content")]
+pub enum PostmineBackend {
+    /// Run the `loda-cpp` executable for evaluating, minimizing and checking the mined programs.
+    #[serde(rename = "lodacpp")]
+    LodaCpp,
+
+    /// Evaluate, minimize and check the mined programs with `loda-rust` itself.
+    /// 
+    /// Useful on machines where `loda-cpp` is not installed.
+    /// The check compares against the b-file, or the OEIS `stripped` file when the b-file is missing.
+    #[serde(rename = "lodarust")]
+    LodaRust,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Eq)]
+#[serde(tag = "type", content = "content")]
+pub enum MinerUnofficialFunctions {
+    /// The miner doesn't insert calls to unofficial functions.
+    #[serde(rename = "none")]
+    None,
+
+    /// The miner inserts calls to the common unofficial functions, such as number theory functions.
+    /// 
+    /// The official `loda-cpp` cannot evaluate these programs,
+    /// so the `lodarust` postmine backend is needed for these programs to pass the checks.
+    #[serde(rename = "common")]
+    Common,
+
+    /// The miner inserts calls to the common unofficial functions,
+    /// and the image functions that are used for solving `ARC` puzzles.
+    #[serde(rename = "common_and_arc")]
+    CommonAndArc,
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     basedir: PathBuf,
@@ -47,6 +82,9 @@ pub struct Config {
     loda_cpp_executable: PathBuf,
     oeis_stripped_file: PathBuf,
     oeis_names_file: PathBuf,
+    oeis_bfile_dir: PathBuf,
+    oeis_offsets_file: PathBuf,
+    oeis_keywords_file: PathBuf,
     loda_submitted_by: String,
     miner_metrics_listen_port: u16,
     loda_patterns_repository: PathBuf,
@@ -54,8 +92,11 @@ pub struct Config {
     miner_program_upload_endpoint: String,
     miner_filter_mode: MinerFilterMode,
     miner_cpu_strategy: MinerCPUStrategy,
+    postmine_backend: PostmineBackend,
+    miner_unofficial_functions: MinerUnofficialFunctions,
     arc_repository_data: PathBuf,
     loda_arc_challenge_repository: PathBuf,
+    unofficial_function_manifest: Option<PathBuf>,
 }
 
 impl Config {
@@ -97,6 +138,13 @@ impl Config {
         path
     }
 
+    pub fn fuzz_dir(&self) -> PathBuf {
+        let name = Path::new("fuzz");
+        let path = self.basedir.join(name);
+        assert!(path.is_absolute());
+        path
+    }
+
     pub fn loda_programs_repository(&self) -> PathBuf {
         let path = &self.loda_programs_repository;
         assert!(path.is_absolute());
@@ -132,6 +180,35 @@ impl Config {
         PathBuf::from(path)
     }
 
+    /// Dir with OEIS b-files, such as `b000045.txt`.
+    /// 
+    /// The b-files can be placed directly inside the dir, or in subdirs like `000/b000045.txt`.
+    /// 
+    /// The dir may not exist, since the b-files are only needed by a few subcommands.
+    pub fn oeis_bfile_dir(&self) -> PathBuf {
+        let path = &self.oeis_bfile_dir;
+        assert!(path.is_absolute());
+        PathBuf::from(path)
+    }
+
+    /// File with the offset of each OEIS sequence, with rows like `A000045 0,4`.
+    /// 
+    /// The file may not exist, in which case the offsets are obtained from the b-files.
+    pub fn oeis_offsets_file(&self) -> PathBuf {
+        let path = &self.oeis_offsets_file;
+        assert!(path.is_absolute());
+        PathBuf::from(path)
+    }
+
+    /// File with the keywords of each OEIS sequence, with rows like `A000045 core,nonn,nice,easy`.
+    /// 
+    /// The file may not exist, in which case the keywords are ignored.
+    pub fn oeis_keywords_file(&self) -> PathBuf {
+        let path = &self.oeis_keywords_file;
+        assert!(path.is_absolute());
+        PathBuf::from(path)
+    }
+
     #[allow(dead_code)]
     pub fn loda_rust_repository(&self) -> PathBuf {
         let path = &self.loda_rust_repository;
@@ -223,6 +300,21 @@ impl Config {
         self.miner_cpu_strategy
     }
 
+    /// What to use for evaluating, minimizing and checking the mined programs.
+    /// - Use the `loda-cpp` executable.
+    /// - Use `loda-rust` itself.
+    pub fn postmine_backend(&self) -> PostmineBackend {
+        self.postmine_backend
+    }
+
+    /// What unofficial functions the miner can insert into the programs.
+    /// - No unofficial functions.
+    /// - The common unofficial functions.
+    /// - The common unofficial functions and the `ARC` image functions.
+    pub fn miner_unofficial_functions(&self) -> MinerUnofficialFunctions {
+        self.miner_unofficial_functions
+    }
+
     #[allow(dead_code)]
     pub fn arc_repository_data(&self) -> PathBuf {
         let path = &self.arc_repository_data;
@@ -239,6 +331,15 @@ impl Config {
         PathBuf::from(path)
     }
 
+    /// Absolute path to the manifest file that lists the unofficial functions implemented as LODA programs.
+    /// 
+    /// Returns `None` when there is no such manifest file specified in the config.
+    pub fn unofficial_function_manifest(&self) -> Option<PathBuf> {
+        let path: &PathBuf = self.unofficial_function_manifest.as_ref()?;
+        assert!(path.is_absolute());
+        Some(path.clone())
+    }
+
     pub fn loda_arc_challenge_repository_programs(&self) -> PathBuf {
         let name = Path::new("programs");
         let path = self.loda_arc_challenge_repository().join(name);
@@ -257,6 +358,9 @@ struct ConfigFallback {
     miner_sync_executable: String,
     loda_cpp_executable: String,
     oeis_names_file: String,
+    oeis_bfile_dir: String,
+    oeis_offsets_file: String,
+    oeis_keywords_file: String,
     loda_submitted_by: String,
     miner_metrics_listen_port: u16,
     loda_patterns_repository: String,
@@ -264,6 +368,8 @@ struct ConfigFallback {
     miner_program_upload_endpoint: String,
     miner_filter_mode: MinerFilterMode,
     miner_cpu_strategy: MinerCPUStrategy,
+    postmine_backend: PostmineBackend,
+    miner_unofficial_functions: MinerUnofficialFunctions,
     arc_repository_data: String,
     loda_arc_challenge_repository: String,
 }
@@ -278,6 +384,9 @@ struct ConfigCustom {
     miner_sync_executable: Option<String>,
     loda_cpp_executable: Option<String>,
     oeis_names_file: Option<String>,
+    oeis_bfile_dir: Option<String>,
+    oeis_offsets_file: Option<String>,
+    oeis_keywords_file: Option<String>,
     loda_submitted_by: Option<String>,
     miner_metrics_listen_port: Option<u16>,
     loda_patterns_repository: Option<String>,
@@ -285,8 +394,11 @@ struct ConfigCustom {
     miner_program_upload_endpoint: Option<String>,
     miner_filter_mode: Option<MinerFilterMode>,
     miner_cpu_strategy: Option<MinerCPUStrategy>,
+    postmine_backend: Option<PostmineBackend>,
+    miner_unofficial_functions: Option<MinerUnofficialFunctions>,
     arc_repository_data: Option<String>,
     loda_arc_challenge_repository: Option<String>,
+    unofficial_function_manifest: Option<String>,
 }
 
 fn load_config_from_home_dir() -> Config {
@@ -350,6 +462,9 @@ pub fn config_from_toml_content(toml_content: String, basedir: PathBuf, homedir:
     let oeis_stripped_file: String = custom.oeis_stripped_file.unwrap_or(fallback.oeis_stripped_file);
     let loda_rust_repository: String = custom.loda_rust_repository.unwrap_or(fallback.loda_rust_repository);
     let oeis_names_file: String = custom.oeis_names_file.unwrap_or(fallback.oeis_names_file);
+    let oeis_bfile_dir: String = custom.oeis_bfile_dir.unwrap_or(fallback.oeis_bfile_dir);
+    let oeis_offsets_file: String = custom.oeis_offsets_file.unwrap_or(fallback.oeis_offsets_file);
+    let oeis_keywords_file: String = custom.oeis_keywords_file.unwrap_or(fallback.oeis_keywords_file);
     let loda_rust_executable: String = custom.loda_rust_executable.unwrap_or(fallback.loda_rust_executable);
     let miner_sync_executable_command_windows: String = custom.miner_sync_executable_command_windows.unwrap_or(fallback.miner_sync_executable_command_windows);
     let miner_sync_executable: String = custom.miner_sync_executable.unwrap_or(fallback.miner_sync_executable);
@@ -361,13 +476,19 @@ pub fn config_from_toml_content(toml_content: String, basedir: PathBuf, homedir:
     let miner_program_upload_endpoint: String = custom.miner_program_upload_endpoint.unwrap_or(fallback.miner_program_upload_endpoint);
     let miner_filter_mode: MinerFilterMode = custom.miner_filter_mode.unwrap_or(fallback.miner_filter_mode);
     let miner_cpu_strategy: MinerCPUStrategy = custom.miner_cpu_strategy.unwrap_or(fallback.miner_cpu_strategy);
+    let postmine_backend: PostmineBackend = custom.postmine_backend.unwrap_or(fallback.postmine_backend);
+    let miner_unofficial_functions: MinerUnofficialFunctions = custom.miner_unofficial_functions.unwrap_or(fallback.miner_unofficial_functions);
     let arc_repository_data: String = custom.arc_repository_data.unwrap_or(fallback.arc_repository_data);
     let loda_arc_challenge_repository: String = custom.loda_arc_challenge_repository.unwrap_or(fallback.loda_arc_challenge_repository);
+    let unofficial_function_manifest: Option<PathBuf> = custom.unofficial_function_manifest.map(|path| simpleenv.resolve_path(&path));
     Config {
         basedir: basedir,
         loda_programs_repository: simpleenv.resolve_path(&loda_programs_repository),
         oeis_stripped_file: simpleenv.resolve_path(&oeis_stripped_file),
         oeis_names_file: simpleenv.resolve_path(&oeis_names_file),
+        oeis_bfile_dir: simpleenv.resolve_path(&oeis_bfile_dir),
+        oeis_offsets_file: simpleenv.resolve_path(&oeis_offsets_file),
+        oeis_keywords_file: simpleenv.resolve_path(&oeis_keywords_file),
         loda_rust_repository: simpleenv.resolve_path(&loda_rust_repository),
         loda_rust_executable: simpleenv.resolve_path(&loda_rust_executable),
         miner_sync_executable_command_windows: miner_sync_executable_command_windows,
@@ -380,8 +501,11 @@ pub fn config_from_toml_content(toml_content: String, basedir: PathBuf, homedir:
         miner_program_upload_endpoint: miner_program_upload_endpoint,
         miner_filter_mode: miner_filter_mode,
         miner_cpu_strategy: miner_cpu_strategy,
+        postmine_backend: postmine_backend,
+        miner_unofficial_functions: miner_unofficial_functions,
         arc_repository_data: simpleenv.resolve_path(&arc_repository_data),
         loda_arc_challenge_repository: simpleenv.resolve_path(&loda_arc_challenge_repository),
+        unofficial_function_manifest: unofficial_function_manifest,
     }
 }
 
@@ -489,6 +613,9 @@ mod tests {
         assert_has_suffix(&config.loda_programs_repository, "/loda/programs")?;
         assert_has_suffix(&config.oeis_stripped_file, "/loda/oeis/stripped")?;
         assert_has_suffix(&config.oeis_names_file, "/loda/oeis/names")?;
+        assert_has_suffix(&config.oeis_bfile_dir, "/loda/oeis/b")?;
+        assert_has_suffix(&config.oeis_offsets_file, "/loda/oeis/offsets")?;
+        assert_has_suffix(&config.oeis_keywords_file, "/loda/oeis/keywords")?;
         assert_has_suffix(&config.loda_rust_repository, "/git/loda-rust")?;
         assert_has_suffix(&config.loda_rust_executable, "/git/loda-rust/rust_project/target/release/loda-rust")?;
         assert_eq!(config.miner_sync_executable_command_windows, "ruby");
@@ -503,6 +630,9 @@ mod tests {
         assert_has_suffix(&config.loda_arc_challenge_repository, "/git/loda-arc-challenge")?;
         assert_eq!(config.miner_filter_mode, MinerFilterMode::New);
         assert_eq!(config.miner_cpu_strategy, MinerCPUStrategy::Max);
+        assert_eq!(config.postmine_backend, PostmineBackend::LodaCpp);
+        assert_eq!(config.miner_unofficial_functions, MinerUnofficialFunctions::None);
+        assert_eq!(config.unofficial_function_manifest, None);
         Ok(())
     }
 
@@ -593,4 +723,109 @@ mod tests {
         assert_eq!(config.miner_cpu_strategy, MinerCPUStrategy::CPU {count: 8});
         Ok(())
     }
+
+    #[test]
+    fn test_40004_override_postmine_backend() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join("test_40004_override_postmine_backend");
+        fs::create_dir(&homedir)?;
+        let content = 
+        r#"
+        [postmine_backend]
+        type = "lodarust"
+        "#;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+
+        // Act
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+
+        // Assert
+        assert_eq!(config.postmine_backend, PostmineBackend::LodaRust);
+        Ok(())
+    }
+
+    #[test]
+    fn test_40005_override_unofficial_function_manifest() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join("test_40005_override_unofficial_function_manifest");
+        fs::create_dir(&homedir)?;
+        let content = 
+        r#"
+        unofficial_function_manifest = "$HOME/functions/manifest.toml"
+        "#;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+
+        // Act
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+
+        // Assert
+        let path: PathBuf = config.unofficial_function_manifest().expect("path");
+        assert_has_suffix(&path, "/functions/manifest.toml")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_40006_override_miner_unofficial_functions() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join("test_40006_override_miner_unofficial_functions");
+        fs::create_dir(&homedir)?;
+        let content = 
+        r#"
+        [miner_unofficial_functions]
+        type = "common_and_arc"
+        "#;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+
+        // Act
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+
+        // Assert
+        assert_eq!(config.miner_unofficial_functions, MinerUnofficialFunctions::CommonAndArc);
+        Ok(())
+    }
+
+    #[test]
+    fn test_40007_override_oeis_bfile_dir_and_offsets_file() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join("test_40007_override_oeis_bfile_dir_and_offsets_file");
+        fs::create_dir(&homedir)?;
+        let content = 
+        r#"
+        oeis_bfile_dir = "$HOME/bfiles"
+        oeis_offsets_file = "$HOME/bfiles/offsets.txt"
+        "#;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+
+        // Act
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+
+        // Assert
+        assert_has_suffix(&config.oeis_bfile_dir(), "/bfiles")?;
+        assert_has_suffix(&config.oeis_offsets_file(), "/bfiles/offsets.txt")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_40008_override_oeis_keywords_file() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join("test_40008_override_oeis_keywords_file");
+        fs::create_dir(&homedir)?;
+        let content = 
+        r#"
+        oeis_keywords_file = "$HOME/oeis/keywords.txt"
+        "#;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+
+        // Act
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+
+        // Assert
+        assert_has_suffix(&config.oeis_keywords_file(), "/oeis/keywords.txt")?;
+        Ok(())
+    }
 }
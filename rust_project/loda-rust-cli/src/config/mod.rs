@@ -4,7 +4,7 @@ mod number_of_workers;
 mod validate_config;
 
 #[allow(unused_imports)]
-pub use config::{config_from_toml_content, Config, MinerCPUStrategy, MinerFilterMode};
+pub use config::{config_from_toml_content, Config, MinerCPUStrategy, MinerFilterMode, MinerUnofficialFunctions, PostmineBackend};
 
 pub use number_of_workers::NumberOfWorkers;
 pub use validate_config::{ValidateConfigTask, ValidateConfig};
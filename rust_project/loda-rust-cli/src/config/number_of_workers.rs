@@ -4,6 +4,11 @@ extern crate num_cpus;
 
 pub trait NumberOfWorkers {
     fn resolve_number_of_miner_workers(&self) -> usize;
+
+    /// The number of threads for evaluating programs in parallel, such as in analytics.
+    ///
+    /// Uses the same `miner_cpu_strategy` as the miner, so the cpu preference is respected.
+    fn resolve_number_of_evaluation_threads(&self) -> usize;
 }
 
 impl NumberOfWorkers for Config {    
@@ -24,4 +29,8 @@ impl NumberOfWorkers for Config {
         // Ensures that zero is never returned
         number_of_threads.max(1)
     }
+
+    fn resolve_number_of_evaluation_threads(&self) -> usize {
+        self.resolve_number_of_miner_workers()
+    }
 }
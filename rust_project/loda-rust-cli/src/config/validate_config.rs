@@ -1,4 +1,4 @@
-use super::Config;
+use super::{Config, MinerUnofficialFunctions, PostmineBackend};
 use std::path::PathBuf;
 
 pub enum ValidateConfigTask {
@@ -36,6 +36,7 @@ impl ValidateConfigInner {
 
     fn validate_config_for_task_oeis_mine(&mut self) -> anyhow::Result<()> {
         self.miner_sync_executable()?;
+        self.miner_unofficial_functions();
         self.to_result()
     }
 
@@ -61,6 +62,18 @@ impl ValidateConfigInner {
         }
         Ok(())
     }
+
+    /// The `loda-cpp` executable cannot evaluate programs with unofficial functions,
+    /// so the mined programs would never pass the checks.
+    fn miner_unofficial_functions(&mut self) {
+        if self.config.miner_unofficial_functions() == MinerUnofficialFunctions::None {
+            return;
+        }
+        if self.config.postmine_backend() == PostmineBackend::LodaCpp {
+            let s = format!("The 'miner_unofficial_functions' is {:?}, this requires the 'postmine_backend' to be 'lodarust', since 'lodacpp' cannot evaluate unofficial functions.", self.config.miner_unofficial_functions());
+            self.messages.push(s);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -123,4 +136,45 @@ mod tests {
         instance.to_result().expect("no error");
         Ok(())
     }
+
+    fn validate_miner_unofficial_functions(content: &str) -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path());
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+        let config: Config = config_from_toml_content(content.to_string(), basedir, homedir);
+        let mut instance = ValidateConfigInner::new(config);
+        instance.miner_unofficial_functions();
+        instance.to_result()
+    }
+
+    #[test]
+    fn test_20000_miner_unofficial_functions_ok() {
+        validate_miner_unofficial_functions("").expect("ok");
+        let content = 
+        r#"
+        [miner_unofficial_functions]
+        type = "common"
+        [postmine_backend]
+        type = "lodarust"
+        "#;
+        validate_miner_unofficial_functions(content).expect("ok");
+    }
+
+    #[test]
+    fn test_20001_miner_unofficial_functions_with_lodacpp() {
+        // Arrange
+        let content = 
+        r#"
+        [miner_unofficial_functions]
+        type = "common"
+        [postmine_backend]
+        type = "lodacpp"
+        "#;
+
+        // Act
+        let error = validate_miner_unofficial_functions(content).expect_err("error");
+
+        // Assert
+        assert_eq!(error.to_string().contains("requires the 'postmine_backend' to be 'lodarust'"), true);
+    }
 }
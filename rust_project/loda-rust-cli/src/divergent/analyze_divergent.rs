@@ -0,0 +1,323 @@
+//! The `loda-rust divergent` subcommand, re-evaluates the programs in the `loda-outlier-programs/oeis_divergent` dir.
+use super::{DivergentFilename, DivergentStatus};
+use crate::common::{create_csv_file, find_asm_files_recursively};
+use crate::config::Config;
+use crate::mine::create_miner_unofficial_function_registry;
+use crate::oeis::{ProcessStrippedFile, StrippedRow};
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::execute::{CancelCheck, ProgramCache, ProgramId, ProgramRunner};
+use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
+use loda_rust_core::parser::ParsedProgram;
+use loda_rust_core::util::BigIntVec;
+use chrono::{DateTime, Utc};
+use console::Style;
+use indicatif::{HumanDuration, ProgressBar};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use anyhow::Context;
+
+#[derive(Clone, Debug)]
+struct DivergentProgram {
+    path: PathBuf,
+    filename: String,
+    divergent_filename: DivergentFilename,
+    status: DivergentStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct DivergentRecord {
+    #[serde(rename = "oeis id")]
+    oeis_id: String,
+    filename: String,
+    status: String,
+    #[serde(rename = "saved correct terms")]
+    saved_correct_term_count: usize,
+    #[serde(rename = "first mismatch index")]
+    first_mismatch_index: String,
+}
+
+/// Analyze the near-miss programs that postmine has saved in the `loda-outlier-programs/oeis_divergent` dir.
+///
+/// Each program is evaluated again and compared with the terms in the current OEIS `stripped` file.
+///
+/// The programs are grouped by the OEIS sequence they diverge from, and the index of the first mismatching term.
+/// The result is saved in the `~/.loda-rust/analytics-oeis/divergent_programs.csv` file.
+///
+/// When all the terms are correct, then the OEIS sequence has most likely been corrected since the program was saved.
+/// Such programs are moved to the `mine-event` dir, so that the next postmine run can check them against the b-file.
+pub struct AnalyzeDivergent {
+    config: Config,
+    dry_run: bool,
+    programs: Vec<DivergentProgram>,
+}
+
+impl AnalyzeDivergent {
+    const MAX_TERM_COUNT: usize = 100;
+    const MINIMUM_NUMBER_OF_REQUIRED_TERMS: usize = 10;
+    const STEP_COUNT_LIMIT: u64 = 1000000000;
+    const EVAL_TIME_LIMIT_IN_SECONDS: u64 = 5;
+
+    pub fn run(dry_run: bool) -> anyhow::Result<()> {
+        let config = Config::load();
+        let mut instance = Self {
+            config,
+            dry_run,
+            programs: vec!(),
+        };
+        instance.scan_divergent_dir()?;
+        let oeis_id_terms: HashMap<OeisId, BigIntVec> = instance.lookup_in_oeis_stripped_file()?;
+        instance.evaluate_programs(&oeis_id_terms)?;
+        instance.print_summary();
+        instance.save_csv()?;
+        instance.promote_corrected_programs()?;
+        Ok(())
+    }
+
+    fn scan_divergent_dir(&mut self) -> anyhow::Result<()> {
+        let divergent_dir: PathBuf = self.config.loda_outlier_programs_repository_oeis_divergent();
+        if !divergent_dir.is_dir() {
+            return Err(anyhow::anyhow!("Expected dir: {:?}, but it's missing.", divergent_dir));
+        }
+        let paths: Vec<PathBuf> = find_asm_files_recursively(&divergent_dir);
+        let mut count_ignored: usize = 0;
+        for path in paths {
+            let filename: String = match path.file_name() {
+                Some(value) => value.to_string_lossy().to_string(),
+                None => {
+                    count_ignored += 1;
+                    continue;
+                }
+            };
+            let divergent_filename: DivergentFilename = match DivergentFilename::parse(&filename) {
+                Some(value) => value,
+                None => {
+                    debug!("Ignoring file with unrecognized filename: {:?}", path);
+                    count_ignored += 1;
+                    continue;
+                }
+            };
+            self.programs.push(DivergentProgram {
+                path,
+                filename,
+                divergent_filename,
+                status: DivergentStatus::MissingSequence,
+            });
+        }
+        println!("Number of divergent programs: {}", self.programs.len());
+        if count_ignored > 0 {
+            println!("Ignored {} files with unrecognized filenames", count_ignored);
+        }
+        Ok(())
+    }
+
+    fn lookup_in_oeis_stripped_file(&self) -> anyhow::Result<HashMap<OeisId, BigIntVec>> {
+        let start = Instant::now();
+        println!("Looking up in the OEIS 'stripped' file");
+        let oeis_ids: OeisIdHashSet = self.programs.iter().map(|program| program.divergent_filename.oeis_id).collect();
+        let oeis_stripped_file: PathBuf = self.config.oeis_stripped_file();
+        let file = File::open(&oeis_stripped_file)
+            .with_context(|| format!("Failed to open OEIS 'stripped' file: {:?}", oeis_stripped_file))?;
+        let filesize: usize = file.metadata()
+            .with_context(|| format!("Failed to obtain metadata about the OEIS 'stripped' file: {:?}", oeis_stripped_file))?
+            .len() as usize;
+        let mut reader = BufReader::new(file);
+
+        let mut oeis_id_terms = HashMap::<OeisId, BigIntVec>::new();
+        let pb = ProgressBar::new(filesize as u64);
+        let callback = |row: &StrippedRow, count_bytes: usize| {
+            pb.set_position(count_bytes as u64);
+            if oeis_ids.contains(&row.oeis_id()) {
+                oeis_id_terms.insert(row.oeis_id(), row.terms().clone());
+            }
+        };
+        let oeis_ids_to_ignore = OeisIdHashSet::new();
+        let padding_value: BigInt = BigInt::zero();
+        let mut processor = ProcessStrippedFile::new();
+        processor.execute(
+            &mut reader,
+            1,
+            Self::MAX_TERM_COUNT,
+            &oeis_ids_to_ignore,
+            &padding_value,
+            false,
+            callback
+        );
+        pb.finish_and_clear();
+
+        let green_bold = Style::new().green().bold();
+        println!(
+            "{:>12} Lookups in the OEIS 'stripped' file, in {}",
+            green_bold.apply_to("Finished"),
+            HumanDuration(start.elapsed())
+        );
+        Ok(oeis_id_terms)
+    }
+
+    /// The programs are evaluated with the same unofficial functions as postmine used, when the programs were saved.
+    fn evaluate_programs(&mut self, oeis_id_terms: &HashMap<OeisId, BigIntVec>) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            self.config.loda_programs_oeis_dir(),
+            create_miner_unofficial_function_registry(&self.config)?,
+        );
+        let mut cache = ProgramCache::new();
+        let empty_terms = BigIntVec::new();
+        let pb = ProgressBar::new(self.programs.len() as u64);
+        for program in self.programs.iter_mut() {
+            pb.inc(1);
+            let expected_terms: &BigIntVec = oeis_id_terms.get(&program.divergent_filename.oeis_id).unwrap_or(&empty_terms);
+            if expected_terms.is_empty() {
+                program.status = DivergentStatus::MissingSequence;
+                continue;
+            }
+            let actual_terms: BigIntVec = match Self::compute_terms(&mut dm, &mut cache, &program.path, expected_terms.len()) {
+                Ok(value) => value,
+                Err(error) => {
+                    debug!("Unable to evaluate program: {:?} error: {:?}", program.path, error);
+                    BigIntVec::new()
+                }
+            };
+            program.status = DivergentStatus::classify(
+                expected_terms, 
+                &actual_terms, 
+                program.divergent_filename.correct_term_count, 
+                Self::MINIMUM_NUMBER_OF_REQUIRED_TERMS
+            );
+        }
+        pb.finish_and_clear();
+
+        let green_bold = Style::new().green().bold();
+        println!(
+            "{:>12} Evaluated divergent programs, in {}",
+            green_bold.apply_to("Finished"),
+            HumanDuration(start.elapsed())
+        );
+        Ok(())
+    }
+
+    /// Compute terms until reaching `term_count`, or until an error occurs, or the time limit is exceeded.
+    ///
+    /// The time limit is also enforced in the middle of a slow term.
+    fn compute_terms(dm: &mut DependencyManager, cache: &mut ProgramCache, path: &Path, term_count: usize) -> anyhow::Result<BigIntVec> {
+        let contents: String = fs::read_to_string(path)?;
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program(&contents)
+            .map_err(|e| anyhow::anyhow!("Unable to parse program. error: {:?}", e))?;
+        let runner: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, &parsed_program)
+            .map_err(|e| anyhow::anyhow!("Unable to create program. error: {:?}", e))?;
+        let time_limit = Duration::from_secs(Self::EVAL_TIME_LIMIT_IN_SECONDS);
+        let cancel_check = CancelCheck::deadline(Instant::now() + time_limit);
+        let indexes = runner.indexes_from_offset(term_count as u64);
+        let term_results = runner.eval_terms(indexes, cache)
+            .step_count_limit(Self::STEP_COUNT_LIMIT)
+            .cancel_check(cancel_check);
+        let mut terms = BigIntVec::with_capacity(term_count);
+        for term in term_results {
+            match term.value {
+                Ok(value) => terms.push(value),
+                Err(_) => break
+            }
+        }
+        Ok(terms)
+    }
+
+    fn print_summary(&self) {
+        let mut status_counts = BTreeMap::<&str, usize>::new();
+        let mut divergence_counts = BTreeMap::<(OeisId, usize), usize>::new();
+        for program in &self.programs {
+            *status_counts.entry(program.status.name()).or_insert(0) += 1;
+            if let DivergentStatus::Diverges { index } = program.status {
+                *divergence_counts.entry((program.divergent_filename.oeis_id, index)).or_insert(0) += 1;
+            }
+        }
+        for (name, count) in &status_counts {
+            println!("{}: {}", name, count);
+        }
+
+        // The sequences where many programs agree on the same wrong term, may indicate an error in the OEIS data.
+        let mut groups: Vec<((OeisId, usize), usize)> = divergence_counts.into_iter().collect();
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let number_of_groups: usize = groups.len();
+        if number_of_groups > 0 {
+            println!("Most common divergences, out of {} groups:", number_of_groups);
+        }
+        for ((oeis_id, index), count) in groups.iter().take(10) {
+            println!("{} diverges at index {}, number of programs: {}", oeis_id.a_number(), index, count);
+        }
+    }
+
+    fn save_csv(&self) -> anyhow::Result<()> {
+        let mut programs: Vec<&DivergentProgram> = self.programs.iter().collect();
+        programs.sort_by(|a, b| {
+            let index_a: Option<usize> = Self::first_mismatch_index(a.status);
+            let index_b: Option<usize> = Self::first_mismatch_index(b.status);
+            a.divergent_filename.oeis_id.cmp(&b.divergent_filename.oeis_id)
+                .then_with(|| index_a.cmp(&index_b))
+                .then_with(|| a.filename.cmp(&b.filename))
+        });
+        let records: Vec<DivergentRecord> = programs.iter().map(|program| {
+            let first_mismatch_index: String = match Self::first_mismatch_index(program.status) {
+                Some(index) => index.to_string(),
+                None => String::new()
+            };
+            DivergentRecord {
+                oeis_id: program.divergent_filename.oeis_id.a_number(),
+                filename: program.filename.clone(),
+                status: program.status.name().to_string(),
+                saved_correct_term_count: program.divergent_filename.correct_term_count,
+                first_mismatch_index,
+            }
+        }).collect();
+        let path: PathBuf = self.config.analytics_oeis_dir().join("divergent_programs.csv");
+        create_csv_file(&records, &path)
+            .map_err(|e| anyhow::anyhow!("Unable to save csv file at: {:?} error: {:?}", path, e))?;
+        println!("Saved: {:?}", path);
+        Ok(())
+    }
+
+    fn first_mismatch_index(status: DivergentStatus) -> Option<usize> {
+        match status {
+            DivergentStatus::Diverges { index } => Some(index),
+            _ => None
+        }
+    }
+
+    /// Move the corrected programs from the `oeis_divergent` dir to the `mine-event` dir.
+    fn promote_corrected_programs(&self) -> anyhow::Result<()> {
+        let mine_event_dir: PathBuf = self.config.mine_event_dir();
+        let now: DateTime<Utc> = Utc::now();
+        let timestamp: String = now.format("%Y%m%d-%H%M%S").to_string();
+        let mut count: usize = 0;
+        for program in &self.programs {
+            if program.status != DivergentStatus::Corrected {
+                continue;
+            }
+            // Format filename as "19841231-235959-divergent-A041009_30_5.asm"
+            let filename: String = format!("{}-divergent-{}", timestamp, program.filename);
+            let destination: PathBuf = mine_event_dir.join(&filename);
+            count += 1;
+            if self.dry_run {
+                println!("Dry run. Would promote {:?} to {:?}", program.path, destination);
+                continue;
+            }
+            fs::copy(&program.path, &destination)
+                .with_context(|| format!("Unable to copy program from: {:?} to: {:?}", program.path, destination))?;
+            fs::remove_file(&program.path)
+                .with_context(|| format!("Unable to remove program: {:?}", program.path))?;
+            println!("Promoted {} to the 'mine-event' dir", program.filename);
+        }
+        if count == 0 {
+            println!("None of the divergent programs matches the current OEIS data.");
+        } else {
+            println!("Number of corrected programs: {}. Run 'postmine' to check them.", count);
+        }
+        Ok(())
+    }
+}
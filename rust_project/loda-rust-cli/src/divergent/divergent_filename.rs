@@ -0,0 +1,71 @@
+use loda_rust_core::oeis::OeisId;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref EXTRACT_DIVERGENT_FILENAME: Regex = Regex::new(
+        "^A(\\d+)(_timeout)?_(\\d+)_(\\d+)[.]asm$"
+    ).unwrap();
+}
+
+/// The filename of a program inside the `loda-outlier-programs/oeis_divergent` dir.
+///
+/// Postmine saves the near-miss programs with filenames like these:
+/// ```
+/// OEIS ID _ NUMBER OF CORRECT TERMS _ VARIANT INDEX . asm
+/// A041009_30_5.asm
+/// OEIS ID _timeout _ NUMBER OF CORRECT TERMS _ VARIANT INDEX . asm
+/// A041009_timeout_33333_5.asm
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DivergentFilename {
+    pub oeis_id: OeisId,
+    pub is_timeout: bool,
+    pub correct_term_count: usize,
+    pub variant_index: usize,
+}
+
+impl DivergentFilename {
+    pub fn parse(filename: &str) -> Option<Self> {
+        let captures = EXTRACT_DIVERGENT_FILENAME.captures(filename)?;
+        let oeis_id_raw: u32 = captures.get(1)?.as_str().parse().ok()?;
+        let is_timeout: bool = captures.get(2).is_some();
+        let correct_term_count: usize = captures.get(3)?.as_str().parse().ok()?;
+        let variant_index: usize = captures.get(4)?.as_str().parse().ok()?;
+        let instance = Self {
+            oeis_id: OeisId::from(oeis_id_raw),
+            is_timeout,
+            correct_term_count,
+            variant_index,
+        };
+        Some(instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(filename: &str) -> String {
+        match DivergentFilename::parse(filename) {
+            Some(value) => format!("{} {} {} {}", value.oeis_id.a_number(), value.is_timeout, value.correct_term_count, value.variant_index),
+            None => "NONE".to_string()
+        }
+    }
+
+    #[test]
+    fn test_10000_parse_ok() {
+        assert_eq!(parse("A041009_30_5.asm"), "A041009 false 30 5");
+        assert_eq!(parse("A144414_32_1.asm"), "A144414 false 32 1");
+        assert_eq!(parse("A041009_timeout_33333_0.asm"), "A041009 true 33333 0");
+    }
+
+    #[test]
+    fn test_10001_parse_error() {
+        assert_eq!(parse(""), "NONE");
+        assert_eq!(parse("A041009.asm"), "NONE");
+        assert_eq!(parse("A041009_30.asm"), "NONE");
+        assert_eq!(parse("A041009_30_5.txt"), "NONE");
+        assert_eq!(parse("A041009_junk_30_5.asm"), "NONE");
+    }
+}
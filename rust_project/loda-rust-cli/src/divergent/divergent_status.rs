@@ -0,0 +1,127 @@
+use loda_rust_core::util::BigIntVec;
+use std::fmt;
+
+/// The outcome of re-evaluating a divergent program, and comparing with the current OEIS data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DivergentStatus {
+    /// All the terms from the OEIS `stripped` file are now correct.
+    ///
+    /// Most likely the OEIS sequence has been corrected, since the program was saved.
+    Corrected,
+
+    /// The terms are correct up to `index`, followed by a mismatch.
+    Diverges { index: usize },
+
+    /// The terms are correct, but the program stopped early, due to an error or the time limit.
+    Incomplete { term_count: usize },
+
+    /// The terms are correct, but the OEIS `stripped` file has too few terms to tell if the divergence is gone.
+    ///
+    /// The program was saved because it diverged at the index `saved_correct_term_count`,
+    /// so the expected terms must go beyond that index, and there must be at least `minimum_term_count` terms.
+    Inconclusive { term_count: usize },
+
+    /// The sequence cannot be found in the OEIS `stripped` file.
+    MissingSequence,
+}
+
+impl DivergentStatus {
+    /// Compare the computed terms with the expected terms from the OEIS `stripped` file.
+    ///
+    /// There must be at least `minimum_term_count` terms, before the program can be considered as `Corrected`.
+    ///
+    /// The `saved_correct_term_count` is the number of correct terms when the program was saved.
+    /// The program can only be considered as `Corrected` when the expected terms includes the term that used to mismatch.
+    pub fn classify(expected_terms: &BigIntVec, actual_terms: &BigIntVec, saved_correct_term_count: usize, minimum_term_count: usize) -> Self {
+        if expected_terms.is_empty() {
+            return Self::MissingSequence;
+        }
+        for (index, (expected, actual)) in expected_terms.iter().zip(actual_terms.iter()).enumerate() {
+            if expected != actual {
+                return Self::Diverges { index };
+            }
+        }
+        if actual_terms.len() < expected_terms.len() {
+            return Self::Incomplete { term_count: actual_terms.len() };
+        }
+        if expected_terms.len() <= saved_correct_term_count || expected_terms.len() < minimum_term_count {
+            return Self::Inconclusive { term_count: expected_terms.len() };
+        }
+        Self::Corrected
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Corrected => "corrected",
+            Self::Diverges { .. } => "diverges",
+            Self::Incomplete { .. } => "incomplete",
+            Self::Inconclusive { .. } => "inconclusive",
+            Self::MissingSequence => "missing_sequence",
+        }
+    }
+}
+
+impl fmt::Display for DivergentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Corrected => write!(f, "corrected"),
+            Self::Diverges { index } => write!(f, "diverges at index {}", index),
+            Self::Incomplete { term_count } => write!(f, "incomplete, {} terms", term_count),
+            Self::Inconclusive { term_count } => write!(f, "inconclusive, {} expected terms", term_count),
+            Self::MissingSequence => write!(f, "missing sequence"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::util::BigIntVecFromI64;
+
+    fn classify(expected: &[i64], actual: &[i64], minimum_term_count: usize) -> String {
+        classify_saved(expected, actual, 0, minimum_term_count)
+    }
+
+    fn classify_saved(expected: &[i64], actual: &[i64], saved_correct_term_count: usize, minimum_term_count: usize) -> String {
+        let expected_terms: BigIntVec = BigIntVec::from_i64array(expected);
+        let actual_terms: BigIntVec = BigIntVec::from_i64array(actual);
+        DivergentStatus::classify(&expected_terms, &actual_terms, saved_correct_term_count, minimum_term_count).to_string()
+    }
+
+    #[test]
+    fn test_10000_corrected() {
+        assert_eq!(classify(&[1, 2, 3], &[1, 2, 3], 3), "corrected");
+        assert_eq!(classify(&[1, 2, 3], &[1, 2, 3, 4, 5], 3), "corrected");
+    }
+
+    #[test]
+    fn test_10001_diverges() {
+        assert_eq!(classify(&[1, 2, 3], &[0, 2, 3], 3), "diverges at index 0");
+        assert_eq!(classify(&[1, 2, 3], &[1, 2, 4], 3), "diverges at index 2");
+        assert_eq!(classify(&[1, 2, 3, 4], &[1, 5], 3), "diverges at index 1");
+    }
+
+    #[test]
+    fn test_10002_incomplete() {
+        assert_eq!(classify(&[1, 2, 3], &[1, 2], 3), "incomplete, 2 terms");
+        assert_eq!(classify(&[1, 2, 3], &[], 3), "incomplete, 0 terms");
+    }
+
+    #[test]
+    fn test_10004_inconclusive() {
+        assert_eq!(classify(&[1, 2], &[1, 2], 3), "inconclusive, 2 expected terms");
+        assert_eq!(classify_saved(&[1, 2, 3], &[1, 2, 3, 4], 3, 3), "inconclusive, 3 expected terms");
+        assert_eq!(classify_saved(&[1, 2, 3, 4], &[1, 2, 3, 4], 3, 3), "corrected");
+    }
+
+    #[test]
+    fn test_10005_diverges_with_few_expected_terms() {
+        assert_eq!(classify(&[1, 2], &[1, 5], 3), "diverges at index 1");
+        assert_eq!(classify_saved(&[1, 2, 3], &[1, 2, 4], 5, 3), "diverges at index 2");
+    }
+
+    #[test]
+    fn test_10003_missing_sequence() {
+        assert_eq!(classify(&[], &[1, 2, 3], 3), "missing sequence");
+    }
+}
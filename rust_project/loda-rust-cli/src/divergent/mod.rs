@@ -0,0 +1,8 @@
+//! Analyze the near-miss programs in the `loda-outlier-programs/oeis_divergent` dir.
+mod analyze_divergent;
+mod divergent_filename;
+mod divergent_status;
+
+pub use analyze_divergent::AnalyzeDivergent;
+pub use divergent_filename::DivergentFilename;
+pub use divergent_status::DivergentStatus;
@@ -0,0 +1,156 @@
+use super::{DifferentialTest, Divergence, FuzzCorpus};
+use crate::mine::{Genome, GenomeMutateContext, ToGenomeItemVec};
+use loda_rust_core::control::DependencyManager;
+use loda_rust_core::execute::ProgramId;
+use loda_rust_core::parser::{InstructionId, ParsedProgram};
+use std::path::PathBuf;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use anyhow::Context;
+
+const REFERENCE_NAME: &str = "loda-cpp";
+const CANDIDATE_NAME: &str = "loda-rust";
+const MAX_MUTATIONS_PER_PROGRAM: usize = 5;
+const LOAD_TEMPLATE_PROGRAM_RETRIES: usize = 1000;
+const ITERATIONS_BETWEEN_PROGRESS: usize = 100;
+
+/// Generate random programs and look for divergences between `loda-cpp` and `loda-rust`.
+///
+/// A program is generated by picking an existing program from the `loda-programs` repository,
+/// and applying a few of the same mutations as the miner uses.
+///
+/// When the backends disagree, then the program is minimized and saved to the corpus.
+pub struct DifferentialFuzzer {
+    rng: StdRng,
+    dependency_manager: DependencyManager,
+    context: GenomeMutateContext,
+    differential_test: DifferentialTest,
+    corpus: FuzzCorpus,
+    number_of_rejected_programs: usize,
+    number_of_tested_programs: usize,
+    number_of_divergences: usize,
+    number_of_saved_reproducers: usize,
+}
+
+impl DifferentialFuzzer {
+    pub fn new(initial_random_seed: u64, dependency_manager: DependencyManager, context: GenomeMutateContext, differential_test: DifferentialTest, corpus: FuzzCorpus) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(initial_random_seed),
+            dependency_manager,
+            context,
+            differential_test,
+            corpus,
+            number_of_rejected_programs: 0,
+            number_of_tested_programs: 0,
+            number_of_divergences: 0,
+            number_of_saved_reproducers: 0,
+        }
+    }
+
+    pub fn run(&mut self, iterations: usize) -> anyhow::Result<()> {
+        for iteration in 0..iterations {
+            match self.generate_program()? {
+                Some(parsed_program) => {
+                    self.process_program(&parsed_program)?;
+                },
+                None => {
+                    self.number_of_rejected_programs += 1;
+                }
+            }
+            if (iteration + 1) % ITERATIONS_BETWEEN_PROGRESS == 0 {
+                println!("iteration: {} {}", iteration + 1, self.summary());
+            }
+        }
+        println!("fuzz completed. {}", self.summary());
+        Ok(())
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "tested: {} rejected: {} divergences: {} saved reproducers: {}",
+            self.number_of_tested_programs,
+            self.number_of_rejected_programs,
+            self.number_of_divergences,
+            self.number_of_saved_reproducers
+        )
+    }
+
+    fn load_template_program(&mut self) -> anyhow::Result<ParsedProgram> {
+        for _ in 0..LOAD_TEMPLATE_PROGRAM_RETRIES {
+            let program_id: u32 = self.context.choose_initial_genome_program(&mut self.rng)
+                .context("choose_initial_genome_program() returned None, seems like data model is empty")?;
+            match Genome::load_program_with_id(&self.dependency_manager, program_id as u64) {
+                Ok(value) => {
+                    return Ok(value);
+                },
+                Err(error) => {
+                    debug!("Unable to load template program. {:?}", error);
+                }
+            }
+        }
+        Err(anyhow::anyhow!("Unable to pick among available programs"))
+    }
+
+    /// Returns `None` when the mutated program cannot be evaluated by both backends.
+    fn generate_program(&mut self) -> anyhow::Result<Option<ParsedProgram>> {
+        let template_program: ParsedProgram = self.load_template_program()?;
+        let mut genome = Genome::new();
+        genome.set_genome_vec(template_program.to_genome_item_vec());
+        let number_of_mutations: usize = self.rng.gen_range(1..=MAX_MUTATIONS_PER_PROGRAM);
+        let mut did_mutate = false;
+        for _ in 0..number_of_mutations {
+            if genome.mutate(&mut self.rng, &self.context) {
+                did_mutate = true;
+            }
+        }
+        if !did_mutate {
+            return Ok(None);
+        }
+        let parsed_program: ParsedProgram = genome.to_parsed_program();
+
+        // The unofficial instructions are not supported by loda-cpp.
+        let has_unofficial_instruction: bool = parsed_program.instruction_vec.iter().any(|instruction| {
+            matches!(instruction.instruction_id, InstructionId::UnofficialFunction { .. } | InstructionId::UnofficialLoopBeginSubtract)
+        });
+        if has_unofficial_instruction {
+            return Ok(None);
+        }
+
+        // Reject programs that are malformed, such as unbalanced loops or missing dependencies.
+        if self.dependency_manager.parse_stage2(ProgramId::ProgramWithoutId, &parsed_program).is_err() {
+            return Ok(None);
+        }
+        Ok(Some(parsed_program))
+    }
+
+    fn process_program(&mut self, parsed_program: &ParsedProgram) -> anyhow::Result<()> {
+        self.number_of_tested_programs += 1;
+        let divergence: Divergence = match self.differential_test.compare(parsed_program)? {
+            Some(value) => value,
+            None => {
+                return Ok(());
+            }
+        };
+        self.number_of_divergences += 1;
+        debug!("divergence: {:?}\n{}", divergence.kind, parsed_program);
+
+        let minimized_program: ParsedProgram = self.differential_test.minimize(parsed_program, divergence.kind)?;
+
+        // The corpus is keyed by the minimized program, so different programs that minimize to the same program are only saved once.
+        if self.corpus.contains(&minimized_program, divergence.kind) {
+            return Ok(());
+        }
+
+        // Evaluate the minimized program again, so the comments in the reproducer matches the program.
+        let (program_to_save, divergence_to_save): (ParsedProgram, Divergence) = match self.differential_test.compare(&minimized_program)? {
+            Some(value) if value.kind == divergence.kind => (minimized_program, value),
+            _ => (parsed_program.clone(), divergence),
+        };
+        let optional_path: Option<PathBuf> = self.corpus.save(&program_to_save, &divergence_to_save, REFERENCE_NAME, CANDIDATE_NAME)?;
+        if let Some(path) = optional_path {
+            self.number_of_saved_reproducers += 1;
+            println!("divergence: {}, saved reproducer: {:?}", divergence_to_save.kind, path);
+        }
+        Ok(())
+    }
+}
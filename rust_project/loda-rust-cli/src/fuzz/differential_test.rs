@@ -0,0 +1,184 @@
+use super::{Divergence, DivergenceKind, EvalOutcome};
+use crate::postmine::PostMineBackend;
+use loda_rust_core::parser::{InstructionId, ParsedProgram};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::Context;
+
+/// Evaluate a program with two backends, and compare the outcome.
+///
+/// The `reference` backend is considered correct, typically `loda-cpp`.
+/// The `candidate` backend is the one being tested, typically `loda-rust`.
+pub struct DifferentialTest {
+    reference: Box<dyn PostMineBackend>,
+    candidate: Box<dyn PostMineBackend>,
+    work_file: PathBuf,
+    term_count: usize,
+    time_limit: Duration,
+    compare_steps: bool,
+}
+
+impl DifferentialTest {
+    /// The `work_file` is overwritten with the program to be evaluated, since the backends operate on files.
+    pub fn new(reference: Box<dyn PostMineBackend>, candidate: Box<dyn PostMineBackend>, work_file: PathBuf) -> Self {
+        assert!(work_file.is_absolute());
+        Self {
+            reference,
+            candidate,
+            work_file,
+            term_count: 20,
+            time_limit: Duration::from_secs(5),
+            compare_steps: false,
+        }
+    }
+
+    pub fn set_term_count(&mut self, term_count: usize) {
+        self.term_count = term_count;
+    }
+
+    /// When `true`, then programs that only differ in the number of steps are also considered divergent.
+    ///
+    /// By default only the terms and the errors are compared.
+    pub fn set_compare_steps(&mut self, compare_steps: bool) {
+        self.compare_steps = compare_steps;
+    }
+
+    /// Returns `None` when the backends agree.
+    pub fn compare(&self, parsed_program: &ParsedProgram) -> anyhow::Result<Option<Divergence>> {
+        let content: String = parsed_program.to_string() + "\n";
+        fs::write(&self.work_file, content)
+            .with_context(|| format!("Unable to write program to: {:?}", self.work_file))?;
+        let reference = EvalOutcome::evaluate(self.reference.as_ref(), self.term_count, &self.work_file, self.time_limit);
+        let candidate = EvalOutcome::evaluate(self.candidate.as_ref(), self.term_count, &self.work_file, self.time_limit);
+        Ok(Divergence::compare(reference, candidate, self.compare_steps))
+    }
+
+    /// Greedy removal of instructions, one at a time, starting from the bottom of the program.
+    ///
+    /// An instruction is removed if the program still diverges in the same way without it.
+    /// The `lpb` and `lpe` instructions are only removed as a pair when the loop is empty, so the loops stays balanced.
+    pub fn minimize(&self, parsed_program: &ParsedProgram, kind: DivergenceKind) -> anyhow::Result<ParsedProgram> {
+        let mut current: ParsedProgram = parsed_program.clone();
+        let mut index: usize = current.instruction_vec.len();
+        while index > 0 {
+            index -= 1;
+            let instruction_id: InstructionId = current.instruction_vec[index].instruction_id;
+            let mut candidate: ParsedProgram = current.clone();
+            match instruction_id {
+                InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract => {
+                    let is_empty_loop: bool = current.instruction_vec.get(index + 1)
+                        .is_some_and(|instruction| instruction.instruction_id == InstructionId::LoopEnd);
+                    if !is_empty_loop {
+                        continue;
+                    }
+                    candidate.instruction_vec.drain(index..index + 2);
+                },
+                InstructionId::LoopEnd => {
+                    continue;
+                },
+                _ => {
+                    candidate.instruction_vec.remove(index);
+                }
+            }
+            let divergence: Option<Divergence> = self.compare(&candidate)?;
+            if divergence.is_some_and(|divergence| divergence.kind == kind) {
+                current = candidate;
+            }
+        }
+        Ok(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lodacpp::{LodaCppCheckResult, LodaCppEvalSteps, LodaCppEvalTerms};
+    use crate::postmine::PostMineBackendNative;
+    use loda_rust_core::util::BigIntVec;
+    use num_bigint::BigInt;
+    use std::path::Path;
+
+    /// Same as the native backend, except that it computes the wrong result for `mul` with a negative constant.
+    struct BackendWithBug {
+        native: PostMineBackendNative,
+    }
+
+    impl BackendWithBug {
+        fn has_bug(loda_program_path: &Path) -> anyhow::Result<bool> {
+            let content: String = fs::read_to_string(loda_program_path)?;
+            Ok(content.contains("mul $0,-"))
+        }
+    }
+
+    impl PostMineBackend for BackendWithBug {
+        fn eval_terms(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalTerms> {
+            let evalterms: LodaCppEvalTerms = self.native.eval_terms(term_count, loda_program_path, time_limit)?;
+            if !Self::has_bug(loda_program_path)? {
+                return Ok(evalterms);
+            }
+            let terms: BigIntVec = evalterms.terms().iter().map(|term| term + BigInt::from(1)).collect();
+            Ok(LodaCppEvalTerms::new(terms))
+        }
+
+        fn minimize(&self, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<String> {
+            self.native.minimize(loda_program_path, time_limit)
+        }
+
+        fn perform_check_and_save_output(&self, loda_program_path: &Path, time_limit: Duration, save_output_to_path: &Path) -> anyhow::Result<LodaCppCheckResult> {
+            self.native.perform_check_and_save_output(loda_program_path, time_limit, save_output_to_path)
+        }
+
+        fn eval_steps(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalSteps> {
+            self.native.eval_steps(term_count, loda_program_path, time_limit)
+        }
+    }
+
+    fn create_differential_test(basedir: &Path) -> anyhow::Result<DifferentialTest> {
+        let oeis_dir: PathBuf = basedir.join("oeis");
+        fs::create_dir(&oeis_dir)?;
+        let reference = PostMineBackendNative::new(oeis_dir.clone());
+        let candidate = BackendWithBug { native: PostMineBackendNative::new(oeis_dir) };
+        let mut instance = DifferentialTest::new(Box::new(reference), Box::new(candidate), basedir.join("work.asm"));
+        instance.set_term_count(5);
+        Ok(instance)
+    }
+
+    #[test]
+    fn test_10000_compare() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_10000_compare");
+        fs::create_dir(&basedir)?;
+        let instance: DifferentialTest = create_differential_test(&basedir)?;
+        let program0: ParsedProgram = ParsedProgram::parse_program("mul $0,3\nadd $0,1").expect("program");
+        let program1: ParsedProgram = ParsedProgram::parse_program("mul $0,-3\nadd $0,1").expect("program");
+
+        // Act
+        let divergence0: Option<Divergence> = instance.compare(&program0)?;
+        let divergence1: Option<Divergence> = instance.compare(&program1)?;
+
+        // Assert
+        assert_eq!(divergence0, None);
+        assert_eq!(divergence1.map(|divergence| divergence.kind), Some(DivergenceKind::Terms));
+        Ok(())
+    }
+
+    #[test]
+    fn test_20000_minimize() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_20000_minimize");
+        fs::create_dir(&basedir)?;
+        let instance: DifferentialTest = create_differential_test(&basedir)?;
+        let source = "mov $1,$0\nlpb $1\n  sub $1,1\n  add $2,5\nlpe\nmul $0,-3\nadd $0,$2\nlpb $3\n  add $0,1\nlpe";
+        let program: ParsedProgram = ParsedProgram::parse_program(source).expect("program");
+
+        // Act
+        let minimized: ParsedProgram = instance.minimize(&program, DivergenceKind::Terms)?;
+
+        // Assert
+        assert_eq!(minimized.to_string(), "mul $0,-3");
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use crate::lodacpp::LodaCppError;
+use crate::postmine::PostMineBackend;
+use loda_rust_core::util::{BigIntVec, BigIntVecToString};
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+/// The result of evaluating a program with one of the backends.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalOutcome {
+    Terms { terms: BigIntVec, steps: Vec<u64> },
+
+    /// The program could not be evaluated, such as division by zero or a missing dependency.
+    Error { message: String },
+
+    /// Exceeded the time limit. This is inconclusive, since the backends have different performance.
+    Timeout,
+}
+
+impl EvalOutcome {
+    /// Compute the initial terms and the number of steps used for each term.
+    pub fn evaluate(backend: &dyn PostMineBackend, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> Self {
+        let terms: BigIntVec = match backend.eval_terms(term_count, loda_program_path, time_limit) {
+            Ok(value) => value.terms().clone(),
+            Err(error) => {
+                return Self::from_error(error);
+            }
+        };
+        let steps: Vec<u64> = match backend.eval_steps(term_count, loda_program_path, time_limit) {
+            Ok(value) => value.steps().clone(),
+            Err(error) => {
+                return Self::from_error(error);
+            }
+        };
+        Self::Terms { terms, steps }
+    }
+
+    fn from_error(error: anyhow::Error) -> Self {
+        if error.downcast_ref::<LodaCppError>() == Some(&LodaCppError::Timeout) {
+            return Self::Timeout;
+        }
+        // The error messages differs between the backends, so only the root cause is kept.
+        let message: String = error.root_cause().to_string().replace('\n', " ");
+        Self::Error { message }
+    }
+
+    fn comment_lines(&self, backend_name: &str) -> Vec<String> {
+        match self {
+            Self::Terms { terms, steps } => {
+                let steps_strings: Vec<String> = steps.iter().map(|step| step.to_string()).collect();
+                vec![
+                    format!("; {} terms: {}", backend_name, terms.to_compact_comma_string()),
+                    format!("; {} steps: {}", backend_name, steps_strings.join(",")),
+                ]
+            },
+            Self::Error { message } => {
+                vec![format!("; {} error: {}", backend_name, message)]
+            },
+            Self::Timeout => {
+                vec![format!("; {} timeout", backend_name)]
+            }
+        }
+    }
+}
+
+/// In what way the backends disagree about a program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Both backends computed the terms, but the terms are different.
+    Terms,
+
+    /// One of the backends computed the terms, the other backend failed.
+    Error,
+
+    /// The terms are the same, but the number of steps are different.
+    Steps,
+}
+
+impl DivergenceKind {
+    /// Determine the kind of divergence, or `None` when the backends agree.
+    ///
+    /// A timeout is not considered a divergence, and when both backends fails, then they agree.
+    pub fn compare(reference: &EvalOutcome, candidate: &EvalOutcome, compare_steps: bool) -> Option<DivergenceKind> {
+        match (reference, candidate) {
+            (EvalOutcome::Timeout, _) | (_, EvalOutcome::Timeout) => None,
+            (EvalOutcome::Error { .. }, EvalOutcome::Error { .. }) => None,
+            (EvalOutcome::Error { .. }, EvalOutcome::Terms { .. }) => Some(Self::Error),
+            (EvalOutcome::Terms { .. }, EvalOutcome::Error { .. }) => Some(Self::Error),
+            (EvalOutcome::Terms { terms: terms0, steps: steps0 }, EvalOutcome::Terms { terms: terms1, steps: steps1 }) => {
+                if terms0 != terms1 {
+                    return Some(Self::Terms);
+                }
+                if compare_steps && steps0 != steps1 {
+                    return Some(Self::Steps);
+                }
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for DivergenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Terms => write!(f, "terms"),
+            Self::Error => write!(f, "error"),
+            Self::Steps => write!(f, "steps"),
+        }
+    }
+}
+
+/// The backends disagree about a program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub reference: EvalOutcome,
+    pub candidate: EvalOutcome,
+}
+
+impl Divergence {
+    pub fn compare(reference: EvalOutcome, candidate: EvalOutcome, compare_steps: bool) -> Option<Divergence> {
+        let kind: DivergenceKind = DivergenceKind::compare(&reference, &candidate, compare_steps)?;
+        Some(Self { kind, reference, candidate })
+    }
+
+    /// Description of the divergence, that is inserted at the top of the reproducer program.
+    pub fn comment_lines(&self, reference_name: &str, candidate_name: &str) -> Vec<String> {
+        let mut lines: Vec<String> = vec![format!("; divergence: {}", self.kind)];
+        lines.extend(self.reference.comment_lines(reference_name));
+        lines.extend(self.candidate.comment_lines(candidate_name));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    fn terms(terms: &[i64], steps: &[u64]) -> EvalOutcome {
+        EvalOutcome::Terms {
+            terms: terms.iter().map(|term| BigInt::from(*term)).collect(),
+            steps: steps.to_vec(),
+        }
+    }
+
+    fn error() -> EvalOutcome {
+        EvalOutcome::Error { message: "Division by zero".to_string() }
+    }
+
+    #[test]
+    fn test_10000_compare_agree() {
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &terms(&[1, 2], &[1, 1]), true), None);
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &terms(&[1, 2], &[1, 5]), false), None);
+        assert_eq!(DivergenceKind::compare(&error(), &error(), true), None);
+        assert_eq!(DivergenceKind::compare(&EvalOutcome::Timeout, &terms(&[1, 2], &[1, 1]), true), None);
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &EvalOutcome::Timeout, true), None);
+    }
+
+    #[test]
+    fn test_10001_compare_diverge() {
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &terms(&[1, 3], &[1, 1]), true), Some(DivergenceKind::Terms));
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &terms(&[1, 2], &[1, 5]), true), Some(DivergenceKind::Steps));
+        assert_eq!(DivergenceKind::compare(&error(), &terms(&[1, 2], &[1, 1]), true), Some(DivergenceKind::Error));
+        assert_eq!(DivergenceKind::compare(&terms(&[1, 2], &[1, 1]), &error(), true), Some(DivergenceKind::Error));
+    }
+
+    #[test]
+    fn test_20000_comment_lines() {
+        // Arrange
+        let divergence: Divergence = Divergence::compare(error(), terms(&[1, -2], &[3, 4]), true).expect("divergence");
+
+        // Act
+        let lines: Vec<String> = divergence.comment_lines("loda-cpp", "loda-rust");
+
+        // Assert
+        let expected: Vec<&str> = vec![
+            "; divergence: error",
+            "; loda-cpp error: Division by zero",
+            "; loda-rust terms: 1,-2",
+            "; loda-rust steps: 3,4",
+        ];
+        assert_eq!(lines, expected);
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Divergence, DivergenceKind};
+use crate::common::fnv1a_hash;
+use loda_rust_core::parser::ParsedProgram;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// Directory with the programs where the backends disagree, one reproducer per file.
+///
+/// The filename is the kind of divergence and the hash of the canonical program,
+/// so that structurally identical programs are only saved once, also across runs.
+pub struct FuzzCorpus {
+    corpus_dir: PathBuf,
+}
+
+impl FuzzCorpus {
+    pub fn new(corpus_dir: PathBuf) -> Self {
+        Self {
+            corpus_dir,
+        }
+    }
+
+    pub fn corpus_dir(&self) -> &Path {
+        &self.corpus_dir
+    }
+
+    fn path_for_program(&self, parsed_program: &ParsedProgram, kind: DivergenceKind) -> PathBuf {
+        let hash: u64 = fnv1a_hash(&parsed_program.canonical_form().to_string());
+        let filename = format!("{}_{:016x}.asm", kind, hash);
+        self.corpus_dir.join(filename)
+    }
+
+    /// Returns `true` when a structurally identical program is already in the corpus.
+    pub fn contains(&self, parsed_program: &ParsedProgram, kind: DivergenceKind) -> bool {
+        self.path_for_program(parsed_program, kind).is_file()
+    }
+
+    /// Save the program with a description of the divergence at the top.
+    ///
+    /// Returns `None` when a structurally identical program is already in the corpus.
+    pub fn save(&self, parsed_program: &ParsedProgram, divergence: &Divergence, reference_name: &str, candidate_name: &str) -> anyhow::Result<Option<PathBuf>> {
+        let path: PathBuf = self.path_for_program(parsed_program, divergence.kind);
+        if path.is_file() {
+            return Ok(None);
+        }
+        if !self.corpus_dir.is_dir() {
+            fs::create_dir_all(&self.corpus_dir)
+                .with_context(|| format!("Unable to create corpus dir: {:?}", self.corpus_dir))?;
+        }
+        let mut content: String = divergence.comment_lines(reference_name, candidate_name).join("\n");
+        content += "\n\n";
+        content += &parsed_program.to_string();
+        content += "\n";
+        fs::write(&path, content)
+            .with_context(|| format!("Unable to save reproducer: {:?}", path))?;
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::EvalOutcome;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn test_10000_save() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let corpus_dir: PathBuf = PathBuf::from(&tempdir.path()).join("test_10000_save");
+        let corpus = FuzzCorpus::new(corpus_dir);
+        let program0: ParsedProgram = ParsedProgram::parse_program("mov $1,$0\nadd $1,2\nmov $0,$1").expect("program");
+        let program1: ParsedProgram = ParsedProgram::parse_program("mov $3,$0\nadd $3,2\nmov $0,$3").expect("program");
+        let divergence = Divergence {
+            kind: DivergenceKind::Terms,
+            reference: EvalOutcome::Terms { terms: vec![BigInt::from(2)], steps: vec![3] },
+            candidate: EvalOutcome::Terms { terms: vec![BigInt::from(3)], steps: vec![3] },
+        };
+
+        // Act
+        let path0: Option<PathBuf> = corpus.save(&program0, &divergence, "loda-cpp", "loda-rust")?;
+        let path1: Option<PathBuf> = corpus.save(&program1, &divergence, "loda-cpp", "loda-rust")?;
+
+        // Assert
+        let path0: PathBuf = path0.expect("saved");
+        assert_eq!(path1, None);
+        assert_eq!(corpus.contains(&program1, DivergenceKind::Terms), true);
+        assert_eq!(corpus.contains(&program1, DivergenceKind::Error), false);
+        let filename: String = path0.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(filename.starts_with("terms_"), true);
+        let content: String = fs::read_to_string(&path0)?;
+        let expected = "; divergence: terms\n; loda-cpp terms: 2\n; loda-cpp steps: 3\n; loda-rust terms: 3\n; loda-rust steps: 3\n\nmov $1,$0\nadd $1,2\nmov $0,$1\n";
+        assert_eq!(content, expected);
+        Ok(())
+    }
+}
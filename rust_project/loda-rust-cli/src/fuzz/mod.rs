@@ -0,0 +1,10 @@
+//! Differential testing of `loda-rust` against `loda-cpp`, with randomly mutated programs.
+mod differential_fuzzer;
+mod differential_test;
+mod divergence;
+mod fuzz_corpus;
+
+pub use differential_fuzzer::DifferentialFuzzer;
+pub use differential_test::DifferentialTest;
+pub use divergence::{Divergence, DivergenceKind, EvalOutcome};
+pub use fuzz_corpus::FuzzCorpus;
@@ -6,7 +6,7 @@ use std::io::BufRead;
 lazy_static! {
     // Extract the `term index` from `loda-cpp check` output.
     static ref EXTRACT_TERM_INDEX: Regex = Regex::new(
-        "^(\\d+) \\d+$"
+        "^(\\d+) -?\\d+$"
     ).unwrap();
 }
 
@@ -108,6 +108,7 @@ mod tests {
         assert_eq!(parse_line("0 0"), Some(0));
         assert_eq!(parse_line("42 100"), Some(42));
         assert_eq!(parse_line("10000 1"), Some(10000));
+        assert_eq!(parse_line("7 -3"), Some(7));
     }
 
     #[test]
@@ -116,6 +117,7 @@ mod tests {
         assert_eq!(parse_line("ok"), None);
         assert_eq!(parse_line("error"), None);
         assert_eq!(parse_line("123 456 -> expected 500"), None);
+        assert_eq!(parse_line("5 -"), None);
     }
 
     #[test]
@@ -6,7 +6,7 @@ pub struct LodaCppEvalSteps {
 }
 
 impl LodaCppEvalSteps {
-    fn new(steps: Vec<u64>) -> Self {
+    pub fn new(steps: Vec<u64>) -> Self {
         Self {
             steps: steps,
         }
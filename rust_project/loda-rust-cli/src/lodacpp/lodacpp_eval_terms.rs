@@ -8,7 +8,7 @@ pub struct LodaCppEvalTerms {
 }
 
 impl LodaCppEvalTerms {
-    fn new(terms: BigIntVec) -> Self {
+    pub fn new(terms: BigIntVec) -> Self {
         Self {
             terms: terms,
         }
@@ -9,8 +9,9 @@ extern crate env_logger;
 #[macro_use]
 extern crate assert_float_eq;
 
-use std::{str::FromStr, path::PathBuf};
+use std::{str::FromStr, path::PathBuf, num::NonZeroUsize};
 use regex::Regex;
+use common::parse_program_id;
 use loda_rust_core::control::*;
 
 #[cfg(feature = "loda-rust-arc")]
@@ -18,7 +19,10 @@ mod arc;
 
 mod analytics;
 mod common;
+mod compare;
 mod config;
+mod divergent;
+mod fuzz;
 mod lodacpp;
 mod mine;
 mod oeis;
@@ -27,25 +31,43 @@ mod postmine;
 mod similar;
 mod subcommand_analytics;
 mod subcommand_arc;
+mod subcommand_bundle_programs;
+mod subcommand_compare;
 mod subcommand_dependencies;
+mod subcommand_divergent;
 mod subcommand_evaluate;
 mod subcommand_export_dataset;
+mod subcommand_fmt;
+mod subcommand_fuzz;
+mod subcommand_inline;
 mod subcommand_install;
+mod subcommand_lint;
 mod subcommand_mine;
 mod subcommand_pattern;
+mod subcommand_postmine;
 mod subcommand_similar;
 mod subcommand_test;
+mod subcommand_validate_bfiles;
 
 use subcommand_analytics::SubcommandAnalytics;
 use subcommand_arc::{SubcommandARC, SubcommandARCMode};
+use subcommand_bundle_programs::SubcommandBundlePrograms;
+use subcommand_compare::SubcommandCompare;
 use subcommand_dependencies::subcommand_dependencies;
+use subcommand_divergent::SubcommandDivergent;
 use subcommand_evaluate::{subcommand_evaluate,SubcommandEvaluateMode};
-use subcommand_export_dataset::SubcommandExportDataset;
+use subcommand_export_dataset::{ExportDatasetFormat, ExportDatasetOptions, ExportDatasetSplit, SubcommandExportDataset};
+use subcommand_fmt::{SubcommandFmt, SubcommandFmtInput};
+use subcommand_fuzz::SubcommandFuzz;
+use subcommand_inline::{SubcommandInline, SubcommandInlineInput};
 use subcommand_install::subcommand_install;
+use subcommand_lint::{SubcommandLint, SubcommandLintInput};
 use subcommand_mine::{SubcommandMine,SubcommandMineMetricsMode};
 use subcommand_pattern::SubcommandPattern;
+use subcommand_postmine::SubcommandPostmine;
 use subcommand_similar::subcommand_similar;
 use subcommand_test::SubcommandTest;
+use subcommand_validate_bfiles::SubcommandValidateBFiles;
 
 extern crate clap;
 extern crate num_bigint;
@@ -79,6 +101,13 @@ async fn main() -> anyhow::Result<()> {
                         .short('t')
                         .long("terms")
                 )
+                .arg(
+                    Arg::new("start")
+                        .help("Index of the first term in OEIS coordinates, may be negative (default: the #offset of the program)")
+                        .takes_value(true)
+                        .allow_hyphen_values(true)
+                        .long("start")
+                )
                 .arg(
                     Arg::new("steps")
                         .help("Show the number of steps used for computing a term")
@@ -89,6 +118,12 @@ async fn main() -> anyhow::Result<()> {
                         .help("Inspect the internal state during execute")
                         .long("debug")
                 )
+                .arg(
+                    Arg::new("threads")
+                        .help("Evaluate the terms in parallel with this number of threads (default:1)")
+                        .takes_value(true)
+                        .long("threads")
+                )
         )
         .subcommand(
             Command::new("dependencies")
@@ -99,6 +134,111 @@ async fn main() -> anyhow::Result<()> {
                         .required(true)
                 )
         )
+        .subcommand(
+            Command::new("bundle-programs")
+                .about("Write programs and their dependencies into a single bundle file, that can be loaded by loda-rust-web.")
+                .arg(
+                    Arg::new("output")
+                        .help("Path to the bundle file")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("programids")
+                        .help("The programs to include, such as A000040 A000045. When omitted, all programs are included.")
+                        .multiple_values(true)
+                )
+        )
+        .subcommand(
+            Command::new("validate-bfiles")
+                .about("Check programs against the OEIS b-files. Reports offset mismatches and term mismatches beyond the stripped file.")
+                .arg(
+                    Arg::new("terms")
+                        .help("Max number of terms to read from each b-file (default:200)")
+                        .takes_value(true)
+                        .short('t')
+                        .long("terms")
+                )
+                .arg(
+                    Arg::new("programids")
+                        .help("The programs to check, such as A000040 A000045. When omitted, all programs are checked.")
+                        .multiple_values(true)
+                )
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Compare two programs. Shows a diff, the first differing term and the step counts.")
+                .arg(
+                    Arg::new("a")
+                        .help("Path to the first program")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("b")
+                        .help("Path to the second program")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("terms")
+                        .help("Number of sequence terms (default:40)")
+                        .takes_value(true)
+                        .short('t')
+                        .long("terms")
+                )
+                .arg(
+                    Arg::new("equivalence")
+                        .help("Check that both programs computes the same terms for every index in the range. Example: 0..1000")
+                        .takes_value(true)
+                        .long("equivalence")
+                )
+        )
+        .subcommand(
+            Command::new("inline")
+                .about("Replace the 'seq' instructions with the called programs, and verify the terms against the original program.")
+                .arg(
+                    Arg::new("program")
+                        .help("OEIS id such as A000040, or a path to a program")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("terms")
+                        .help("Number of sequence terms to verify (default:40)")
+                        .takes_value(true)
+                        .short('t')
+                        .long("terms")
+                )
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Static analysis of programs. Reports dead stores, instructions without effect on the output and unused registers.")
+                .arg(
+                    Arg::new("programs")
+                        .help("OEIS ids such as A000040, paths to programs or dirs with programs. When omitted, all programs are linted.")
+                        .multiple_values(true)
+                )
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Reformat programs with consistent indentation and spacing, while preserving the comments.")
+                .arg(
+                    Arg::new("programs")
+                        .help("OEIS ids such as A000040, paths to programs or dirs with programs.")
+                        .required(true)
+                        .multiple_values(true)
+                )
+                .arg(
+                    Arg::new("check")
+                        .help("Don't modify the files. Exit with an error when one or more files are not formatted.")
+                        .long("check")
+                )
+                .arg(
+                    Arg::new("names")
+                        .help("Update the sequence names in the header and after the seq instructions, from the OEIS 'names' file.")
+                        .long("names")
+                )
+        )
         .subcommand(
             Command::new("install")
                 .about("Create the $HOME/.loda-rust directory")
@@ -122,6 +262,24 @@ async fn main() -> anyhow::Result<()> {
                         .help("Run a metrics server on localhost:8090 (can be overwritten in the config file)")
                 )
         )
+        .subcommand(
+            Command::new("postmine")
+                .about("Check the pending programs in the 'mine-event' dir, and keep the best. Writes a report.html file.")
+                .arg(
+                    Arg::new("dry-run")
+                        .help("Perform all the checks, but don't modify the 'loda-programs' repo, the 'loda-outlier-programs' repo or the 'mine-event' dir.")
+                        .long("dry-run")
+                )
+        )
+        .subcommand(
+            Command::new("divergent")
+                .about("Re-evaluate the programs in 'loda-outlier-programs/oeis_divergent', and promote those that matches the current OEIS data.")
+                .arg(
+                    Arg::new("dry-run")
+                        .help("Analyze the programs, but don't move any files to the 'mine-event' dir.")
+                        .long("dry-run")
+                )
+        )
         .subcommand(
             Command::new("similar")
                 .about("Identify similar programs.")
@@ -139,12 +297,93 @@ async fn main() -> anyhow::Result<()> {
             Command::new("export-dataset")
                 .about("Generates a .csv file with terms and programs, for use as AI training data.")
                 .hide(true)
+                .arg(
+                    Arg::new("min-terms")
+                        .help("Ignore sequences with fewer terms (default:20)")
+                        .takes_value(true)
+                        .long("min-terms")
+                )
+                .arg(
+                    Arg::new("max-terms")
+                        .help("Max number of terms to export for each sequence (default:20)")
+                        .takes_value(true)
+                        .long("max-terms")
+                )
+                .arg(
+                    Arg::new("format")
+                        .help("The file format: csv, jsonl or columnar, where columnar is columnar JSON (default:csv)")
+                        .takes_value(true)
+                        .long("format")
+                )
+                .arg(
+                    Arg::new("split")
+                        .help("Split into train/validation/test files by OEIS id, with these percentages, such as 80,10,10")
+                        .takes_value(true)
+                        .long("split")
+                )
+                .arg(
+                    Arg::new("inline-dependencies")
+                        .help("Add a column with the source code of the programs that are invoked via 'seq'")
+                        .long("inline-dependencies")
+                )
+                .arg(
+                    Arg::new("raw")
+                        .help("Export the programs as they are, without removing comments and normalizing the formatting")
+                        .long("raw")
+                )
+                .arg(
+                    Arg::new("min-instructions")
+                        .help("Ignore programs with fewer instructions")
+                        .takes_value(true)
+                        .long("min-instructions")
+                )
+                .arg(
+                    Arg::new("max-instructions")
+                        .help("Ignore programs with more instructions")
+                        .takes_value(true)
+                        .long("max-instructions")
+                )
+                .arg(
+                    Arg::new("instructions")
+                        .help("Ignore programs that use other instructions than these, such as add,sub,mov,lpb,lpe")
+                        .takes_value(true)
+                        .long("instructions")
+                )
         )
         .subcommand(
             Command::new("test-integration-with-lodacpp")
                 .about("Verify that integration with the 'lodacpp' executable is working.")
                 .hide(true)
         )
+        .subcommand(
+            Command::new("fuzz")
+                .about("Differential testing, compare loda-rust against loda-cpp with randomly mutated programs.")
+                .hide(true)
+                .arg(
+                    Arg::new("iterations")
+                        .help("Number of programs to generate (default:1000)")
+                        .takes_value(true)
+                        .long("iterations")
+                )
+                .arg(
+                    Arg::new("seed")
+                        .help("Seed for the random number generator, for repeating a run (default: random)")
+                        .takes_value(true)
+                        .long("seed")
+                )
+                .arg(
+                    Arg::new("terms")
+                        .help("Number of sequence terms to compare (default:20)")
+                        .takes_value(true)
+                        .short('t')
+                        .long("terms")
+                )
+                .arg(
+                    Arg::new("compare-steps")
+                        .help("Also compare the number of steps, not only the terms and errors")
+                        .long("compare-steps")
+                )
+        )
         .subcommand(
             Command::new("arc-eval-task")
                 .about("ARC - Eval a single task with all the existing solutions.")
@@ -234,17 +473,8 @@ async fn main() -> anyhow::Result<()> {
     if let Some(sub_m) = matches.subcommand_matches("evaluate") {
         // Fuzzy convert from user input to OEIS id, allows the 'A' to be left out.
         let program_id_raw: &str = sub_m.value_of("programid").unwrap();
-        let re = Regex::new("^A?(\\d+)$").unwrap();
-        let captures = match re.captures(program_id_raw) {
-            Some(value) => value,
-            None => {
-                return Err(anyhow::anyhow!("Unable to extract OEIS id, expected A number such as A000040 or A123456."));
-            }
-        };
-        let capture1: &str = captures.get(1).map_or("", |m| m.as_str());
-        let program_id_string: String = capture1.to_string();
-        let program_id: u64 = program_id_string.parse()
-            .map_err(|e| anyhow::anyhow!("Unable to parse OEIS id as u64, expected A number such as A000040 or A123456. error: {:?}", e))?;
+        let program_id: u64 = parse_program_id(program_id_raw)
+            .ok_or_else(|| anyhow::anyhow!("Unable to extract OEIS id, expected A number such as A000040 or A123456."))?;
 
         // Number of terms
         let mut number_of_terms: u64 = 20;
@@ -253,6 +483,14 @@ async fn main() -> anyhow::Result<()> {
                 .expect("Unable to parse number of terms.");
         }
 
+        // Index of the first term
+        let mut start_index: Option<i64> = None;
+        if let Some(start_index_raw) = sub_m.value_of("start") {
+            let value: i64 = i64::from_str(start_index_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse start index as i64. error: {:?}", e))?;
+            start_index = Some(value);
+        }
+
         // Eval mode
         let show_steps: bool = sub_m.is_present("steps");
         let show_debug: bool = sub_m.is_present("debug");
@@ -264,7 +502,17 @@ async fn main() -> anyhow::Result<()> {
                 panic!("Invalid combo of parameters");
             }
         };
-        subcommand_evaluate(program_id, number_of_terms, mode);
+
+        // Number of threads
+        let mut number_of_threads: NonZeroUsize = NonZeroUsize::new(1).unwrap();
+        if let Some(number_of_threads_raw) = sub_m.value_of("threads") {
+            number_of_threads = NonZeroUsize::from_str(number_of_threads_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse threads, expected 1 or greater. error: {:?}", e))?;
+        }
+        if show_debug && number_of_threads.get() > 1 {
+            return Err(anyhow::anyhow!("The --debug flag cannot be combined with more than 1 thread."));
+        }
+        subcommand_evaluate(program_id, start_index, number_of_terms, mode, number_of_threads)?;
         return Ok(());
     }
 
@@ -272,7 +520,96 @@ async fn main() -> anyhow::Result<()> {
         let program_id_raw: &str = sub_m.value_of("programid").unwrap();
         let program_id: u64 = u64::from_str(program_id_raw)
             .expect("Unable to parse program_id.");
-        subcommand_dependencies(program_id);
+        subcommand_dependencies(program_id)?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("bundle-programs") {
+        let output_path = PathBuf::from(sub_m.value_of("output").unwrap());
+        let mut program_ids: Vec<u64> = vec!();
+        for program_id_raw in sub_m.values_of("programids").into_iter().flatten() {
+            let program_id: u64 = parse_program_id(program_id_raw)
+                .ok_or_else(|| anyhow::anyhow!("Unable to extract OEIS id, expected A number such as A000040 or A123456, but got {:?}", program_id_raw))?;
+            program_ids.push(program_id);
+        }
+        SubcommandBundlePrograms::new(output_path, program_ids).run()?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("validate-bfiles") {
+        let mut term_count: usize = 200;
+        if let Some(term_count_raw) = sub_m.value_of("terms") {
+            term_count = usize::from_str(term_count_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse number of terms. error: {:?}", e))?;
+        }
+        let mut program_ids: Vec<u32> = vec!();
+        for program_id_raw in sub_m.values_of("programids").into_iter().flatten() {
+            let program_id: u64 = parse_program_id(program_id_raw)
+                .ok_or_else(|| anyhow::anyhow!("Unable to extract OEIS id, expected A number such as A000040 or A123456, but got {:?}", program_id_raw))?;
+            let program_id: u32 = u32::try_from(program_id)
+                .map_err(|e| anyhow::anyhow!("Unable to convert OEIS id to u32. error: {:?}", e))?;
+            program_ids.push(program_id);
+        }
+        SubcommandValidateBFiles::new(term_count, program_ids).run()?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("inline") {
+        let program_raw: &str = sub_m.value_of("program").unwrap();
+        let input: SubcommandInlineInput = match parse_program_id(program_raw) {
+            Some(program_id) => SubcommandInlineInput::ProgramId(program_id),
+            None => SubcommandInlineInput::Path(PathBuf::from(program_raw))
+        };
+        let mut term_count: usize = 40;
+        if let Some(term_count_raw) = sub_m.value_of("terms") {
+            term_count = usize::from_str(term_count_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse number of terms. error: {:?}", e))?;
+        }
+        SubcommandInline::new(input, term_count).run()?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("lint") {
+        let mut inputs: Vec<SubcommandLintInput> = vec!();
+        for program_raw in sub_m.values_of("programs").into_iter().flatten() {
+            let input: SubcommandLintInput = match parse_program_id(program_raw) {
+                Some(program_id) => SubcommandLintInput::ProgramId(program_id),
+                None => SubcommandLintInput::Path(PathBuf::from(program_raw))
+            };
+            inputs.push(input);
+        }
+        SubcommandLint::new(inputs).run()?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("fmt") {
+        let mut inputs: Vec<SubcommandFmtInput> = vec!();
+        for program_raw in sub_m.values_of("programs").into_iter().flatten() {
+            let input: SubcommandFmtInput = match parse_program_id(program_raw) {
+                Some(program_id) => SubcommandFmtInput::ProgramId(program_id),
+                None => SubcommandFmtInput::Path(PathBuf::from(program_raw))
+            };
+            inputs.push(input);
+        }
+        let check: bool = sub_m.is_present("check");
+        let names: bool = sub_m.is_present("names");
+        SubcommandFmt::new(inputs, check, names).run()?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("compare") {
+        let path0 = PathBuf::from(sub_m.value_of("a").unwrap());
+        let path1 = PathBuf::from(sub_m.value_of("b").unwrap());
+        let mut term_count: usize = 40;
+        if let Some(term_count_raw) = sub_m.value_of("terms") {
+            term_count = usize::from_str(term_count_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse number of terms. error: {:?}", e))?;
+        }
+        let equivalence_range = match sub_m.value_of("equivalence") {
+            Some(value) => Some(SubcommandCompare::parse_range(value)?),
+            None => None
+        };
+        SubcommandCompare::new(path0, path1, term_count, equivalence_range).run()?;
         return Ok(());
     }
 
@@ -301,6 +638,18 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(sub_m) = matches.subcommand_matches("postmine") {
+        let dry_run: bool = sub_m.is_present("dry-run");
+        SubcommandPostmine::run(dry_run)?;
+        return Ok(());
+    }
+
+    if let Some(sub_m) = matches.subcommand_matches("divergent") {
+        let dry_run: bool = sub_m.is_present("dry-run");
+        SubcommandDivergent::run(dry_run)?;
+        return Ok(());
+    }
+
     if let Some(_sub_m) = matches.subcommand_matches("similar") {
         subcommand_similar()?;
         return Ok(());
@@ -312,8 +661,38 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    if let Some(_sub_m) = matches.subcommand_matches("export-dataset") {
-        SubcommandExportDataset::export_dataset()?;
+    if let Some(sub_m) = matches.subcommand_matches("export-dataset") {
+        let mut options = ExportDatasetOptions::default();
+        if let Some(raw) = sub_m.value_of("min-terms") {
+            options.min_term_count = usize::from_str(raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse min-terms. error: {:?}", e))?;
+        }
+        if let Some(raw) = sub_m.value_of("max-terms") {
+            options.max_term_count = usize::from_str(raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse max-terms. error: {:?}", e))?;
+        }
+        if let Some(raw) = sub_m.value_of("format") {
+            options.format = ExportDatasetFormat::from_str(raw)?;
+        }
+        if let Some(raw) = sub_m.value_of("split") {
+            options.split = Some(ExportDatasetSplit::from_str(raw)?);
+        }
+        options.inline_dependencies = sub_m.is_present("inline-dependencies");
+        options.normalize = !sub_m.is_present("raw");
+        if let Some(raw) = sub_m.value_of("min-instructions") {
+            let value: usize = usize::from_str(raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse min-instructions. error: {:?}", e))?;
+            options.min_instruction_count = Some(value);
+        }
+        if let Some(raw) = sub_m.value_of("max-instructions") {
+            let value: usize = usize::from_str(raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse max-instructions. error: {:?}", e))?;
+            options.max_instruction_count = Some(value);
+        }
+        if let Some(raw) = sub_m.value_of("instructions") {
+            options.instruction_set = Some(ExportDatasetOptions::parse_instruction_set(raw)?);
+        }
+        SubcommandExportDataset::export_dataset(options)?;
         return Ok(());
     }
 
@@ -322,6 +701,28 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(sub_m) = matches.subcommand_matches("fuzz") {
+        let mut iterations: usize = 1000;
+        if let Some(iterations_raw) = sub_m.value_of("iterations") {
+            iterations = usize::from_str(iterations_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse number of iterations. error: {:?}", e))?;
+        }
+        let mut seed: Option<u64> = None;
+        if let Some(seed_raw) = sub_m.value_of("seed") {
+            let value: u64 = u64::from_str(seed_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse seed as u64. error: {:?}", e))?;
+            seed = Some(value);
+        }
+        let mut term_count: usize = 20;
+        if let Some(term_count_raw) = sub_m.value_of("terms") {
+            term_count = usize::from_str(term_count_raw)
+                .map_err(|e| anyhow::anyhow!("Unable to parse number of terms. error: {:?}", e))?;
+        }
+        let compare_steps: bool = sub_m.is_present("compare-steps");
+        SubcommandFuzz::new(iterations, seed, term_count, compare_steps).run()?;
+        return Ok(());
+    }
+
     if let Some(sub_m) = matches.subcommand_matches("arc-eval-task") {
         let pattern_raw: &str = sub_m.value_of("pattern").expect("pattern");
         let re = Regex::new("^[a-fA-F0-9]+$").unwrap();
@@ -2,14 +2,14 @@ use crate::analytics::{Analytics, AnalyticsDirectory};
 use crate::common::PendingProgramsWithPriority;
 use crate::config::Config;
 use crate::mine::{CoordinatorWorkerMessage, MineEventDirectoryState};
-use crate::oeis::{load_terms_to_program_id_set, TermsToProgramIdSet};
-use super::{CreateFunnel, Funnel, FunnelConfig};
-use super::{CreateGenomeMutateContextMode, create_genome_mutate_context, GenomeMutateContext};
+use crate::oeis::TermsToProgramIdFile;
+use super::{CreateFunnel, Funnel};
+use super::{CreateGenomeMutateContextMode, create_genome_mutate_context, create_miner_unofficial_function_registry, GenomeMutateContext};
 use super::MinerWorkerMessageWithAnalytics;
 use super::{create_prevent_flooding, PreventFlooding};
 use super::{MinerSyncExecute, MinerSyncExecuteStatus};
 use bastion::prelude::*;
-use num_bigint::{BigInt, ToBigInt};
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
@@ -107,31 +107,27 @@ fn perform_sync_and_analytics(
     }
 
     println!("populating terms_to_program_id");
-    let oeis_stripped_file: PathBuf = config.oeis_stripped_file();
-    let padding_value: BigInt = FunnelConfig::WILDCARD_MAGIC_VALUE.to_bigint().unwrap();
-    let terms_to_program_id_result = load_terms_to_program_id_set(
-        &oeis_stripped_file, 
-        FunnelConfig::MINIMUM_NUMBER_OF_REQUIRED_TERMS, 
-        FunnelConfig::TERM_COUNT,
-        &padding_value
-    );
-    let terms_to_program_id: TermsToProgramIdSet = match terms_to_program_id_result {
+    let analytics_directory = AnalyticsDirectory::new(
+        config.analytics_oeis_dir()
+    ).expect("unable to create AnalyticsDirectory instance");
+    let terms_to_program_id_path: PathBuf = analytics_directory.terms_to_program_id_file();
+    let terms_to_program_id: TermsToProgramIdFile = match TermsToProgramIdFile::load(&terms_to_program_id_path) {
         Ok(value) => value,
         Err(error) => {
             Bastion::stop();
-            panic!("analytics_worker: Unable to load terms for program ids. error: {:?}", error);
+            panic!("analytics_worker: Unable to load terms for program ids, try run 'loda-rust analytics' to regenerate the file. error: {:?}", error);
         }
     };
-    let terms_to_program_id_arc: Arc<TermsToProgramIdSet> = Arc::new(terms_to_program_id);
+    debug!("terms_to_program_id: {} keys, {} bytes", terms_to_program_id.len(), terms_to_program_id.byte_count());
+    let terms_to_program_id_arc: Arc<TermsToProgramIdFile> = Arc::new(terms_to_program_id);
 
     println!("populating funnel");
     let funnel: Funnel = Funnel::create_funnel_with_file_data(&config);
 
     println!("populating genome_mutate_context");
-    let analytics_directory = AnalyticsDirectory::new(
-        config.analytics_oeis_dir()
-    ).expect("unable to create AnalyticsDirectory instance");
-    let genome_mutate_context: GenomeMutateContext = create_genome_mutate_context(CreateGenomeMutateContextMode::OEIS, analytics_directory)
+    let unofficial_function_registry: UnofficialFunctionRegistry = create_miner_unofficial_function_registry(&config)
+        .expect("analytics_worker couldn't create UnofficialFunctionRegistry");
+    let genome_mutate_context: GenomeMutateContext = create_genome_mutate_context(CreateGenomeMutateContextMode::OEIS, analytics_directory, &unofficial_function_registry)
         .expect("analytics_worker couldn't create GenomeMutateContext");
     
     // Pass on funnel+genome_mutate_context to miner_workers
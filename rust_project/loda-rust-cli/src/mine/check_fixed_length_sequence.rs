@@ -5,8 +5,9 @@ use super::{FunnelConfig, WildcardChecker};
 use crate::config::Config;
 use crate::analytics::AnalyticsDirectory;
 use crate::common::{load_program_ids_csv_file, SimpleLog};
-use crate::oeis::{ProcessStrippedFile, StrippedRow};
+use crate::oeis::{OeisKeyword, OeisKeywords, ProcessStrippedFile, StrippedRow};
 use num_bigint::{BigInt, ToBigInt};
+use num_traits::Signed;
 use serde::{Serialize, Deserialize};
 use bloomfilter::*;
 use std::error::Error;
@@ -139,12 +140,14 @@ impl CheckFixedLengthSequenceInternalRepresentation {
 
 struct SequenceProcessor {
     counter: usize,
+    count_signed: usize,
 }
 
 impl SequenceProcessor {
     fn new() -> Self {
         Self {
-            counter: 0
+            counter: 0,
+            count_signed: 0,
         }
     }
 }
@@ -158,6 +161,8 @@ pub enum NamedCacheFile {
     Funnel20New,
     Funnel30New,
     Funnel40New,
+    Funnel10SignedAll,
+    Funnel10SignedNew,
 }
 
 impl NamedCacheFile {
@@ -169,6 +174,16 @@ impl NamedCacheFile {
         [Self::Funnel10New, Self::Funnel20New, Self::Funnel30New, Self::Funnel40New]
     }
 
+    /// The first 10 terms of the signed sequences in `group_all()`.
+    pub fn signed_all() -> NamedCacheFile {
+        Self::Funnel10SignedAll
+    }
+
+    /// The first 10 terms of the signed sequences in `group_new()`.
+    pub fn signed_new() -> NamedCacheFile {
+        Self::Funnel10SignedNew
+    }
+
     pub fn resolve_path(&self, parent_dir: &Path) -> PathBuf {
         parent_dir.join(Path::new(self.filename()))
     }
@@ -183,6 +198,8 @@ impl NamedCacheFile {
             Self::Funnel20New => "funnel_20_new.json",
             Self::Funnel30New => "funnel_30_new.json",
             Self::Funnel40New => "funnel_40_new.json",
+            Self::Funnel10SignedAll => "funnel_10_signed_all.json",
+            Self::Funnel10SignedNew => "funnel_10_signed_new.json",
         }
     }
 }
@@ -193,10 +210,12 @@ fn create_cache_files(
     filesize: usize,
     bloom_items_count: usize,
     oeis_ids_to_ignore: &OeisIdHashSet,
+    oeis_keywords: &OeisKeywords,
     funnel10_path: &Path,
     funnel20_path: &Path,
     funnel30_path: &Path,
     funnel40_path: &Path,
+    funnel10_signed_path: &Path,
 ) -> usize {
     let start = Instant::now();
     let mut processor = SequenceProcessor::new();
@@ -207,10 +226,12 @@ fn create_cache_files(
     let mut bloom20 = Bloom::<BigIntVec>::new_for_fp_rate(bloom_items_count, false_positive_rate);
     let mut bloom30 = Bloom::<BigIntVec>::new_for_fp_rate(bloom_items_count, false_positive_rate);
     let mut bloom40 = Bloom::<BigIntVec>::new_for_fp_rate(bloom_items_count, false_positive_rate);
+    let mut bloom10_signed = Bloom::<BigIntVec>::new_for_fp_rate(bloom_items_count, false_positive_rate);
     let bloom10_ref = &mut bloom10;
     let bloom20_ref = &mut bloom20;
     let bloom30_ref = &mut bloom30;
     let bloom40_ref = &mut bloom40;
+    let bloom10_signed_ref = &mut bloom10_signed;
 
     simple_log.println(format!("oeis 'stripped' file size: {} bytes", filesize));
     let pb = ProgressBar::new(filesize as u64);
@@ -221,6 +242,14 @@ fn create_cache_files(
         {
             let vec: BigIntVec = all_vec[0..10].to_vec();
             (*bloom10_ref).set(&vec);
+            // The `sign` keyword, or a negative term in case the keywords are missing or outdated.
+            // Only the first 10 terms are inspected, since the padding value is negative.
+            let is_signed: bool = oeis_keywords.has_keyword(stripped_sequence.oeis_id(), OeisKeyword::Sign) || 
+                vec.iter().any(|term| term.is_negative());
+            if is_signed {
+                (*bloom10_signed_ref).set(&vec);
+                (*x).count_signed += 1;
+            }
         }
         {
             let vec: BigIntVec = all_vec[0..20].to_vec();
@@ -249,6 +278,7 @@ fn create_cache_files(
     );
     stripped_sequence_processor.print_summary(simple_log.clone());
     simple_log.println(format!("number of sequences stored in bloomfilter: {:?}", processor.counter));
+    simple_log.println(format!("number of signed sequences stored in bloomfilter: {:?}", processor.count_signed));
     pb.finish_and_clear();
 
     let green_bold = Style::new().green().bold();        
@@ -260,7 +290,7 @@ fn create_cache_files(
 
     println!("Saving bloomfilter data");
     let start2 = Instant::now();
-    let pb = ProgressBar::new(4);
+    let pb = ProgressBar::new(5);
     {
         let instance = CheckFixedLengthSequence::new(bloom10);
         instance.save(funnel10_path);
@@ -279,6 +309,11 @@ fn create_cache_files(
     {
         let instance = CheckFixedLengthSequence::new(bloom40);
         instance.save(funnel40_path);
+        pb.inc(1);
+    }
+    {
+        let instance = CheckFixedLengthSequence::new(bloom10_signed);
+        instance.save(funnel10_signed_path);
         pb.finish_and_clear();
     }
     println!(
@@ -315,7 +350,7 @@ impl PopulateBloomfilter {
         self.simple_log.println("\nPopulateBloomfilter - group all");
         let oeis_ids_to_ignore: OeisIdHashSet = self.obtain_dontmine_program_ids()?;
         self.simple_log.println(format!("ignore total: {}", oeis_ids_to_ignore.len()));
-        self.populate_bloomfilter(NamedCacheFile::group_all(), oeis_ids_to_ignore)?;
+        self.populate_bloomfilter(NamedCacheFile::group_all(), NamedCacheFile::signed_all(), oeis_ids_to_ignore)?;
         Ok(())
     }
 
@@ -329,20 +364,22 @@ impl PopulateBloomfilter {
         oeis_ids_to_ignore.extend(&oeis_ids_invalid);
         oeis_ids_to_ignore.extend(&oeis_ids_valid);
         self.simple_log.println(format!("ignore total: {} dontmine: {} valid: {} invalid: {}", oeis_ids_to_ignore.len(), oeis_ids_dontmine.len(), oeis_ids_valid.len(), oeis_ids_invalid.len()));
-        self.populate_bloomfilter(NamedCacheFile::group_new(), oeis_ids_to_ignore)?;
+        self.populate_bloomfilter(NamedCacheFile::group_new(), NamedCacheFile::signed_new(), oeis_ids_to_ignore)?;
         Ok(())
     }
 
-    fn populate_bloomfilter(&self, names: [NamedCacheFile; 4], oeis_ids_to_ignore: OeisIdHashSet) -> anyhow::Result<()> {
+    fn populate_bloomfilter(&self, names: [NamedCacheFile; 4], name_signed: NamedCacheFile, oeis_ids_to_ignore: OeisIdHashSet) -> anyhow::Result<()> {
         let oeis_stripped_file: PathBuf = self.config.oeis_stripped_file();
         assert!(oeis_stripped_file.is_absolute());
         assert!(oeis_stripped_file.is_file());
+        let oeis_keywords: OeisKeywords = OeisKeywords::load_if_exists(&self.config.oeis_keywords_file())?;
 
         let analytics_dir: PathBuf = self.config.analytics_oeis_dir();
         let funnel10_path: PathBuf = names[0].resolve_path(&analytics_dir);
         let funnel20_path: PathBuf = names[1].resolve_path(&analytics_dir);
         let funnel30_path: PathBuf = names[2].resolve_path(&analytics_dir);
         let funnel40_path: PathBuf = names[3].resolve_path(&analytics_dir);
+        let funnel10_signed_path: PathBuf = name_signed.resolve_path(&analytics_dir);
 
         let file = File::open(oeis_stripped_file).unwrap();
         let filesize: usize = file.metadata().unwrap().len() as usize;
@@ -353,10 +390,12 @@ impl PopulateBloomfilter {
             filesize,
             FunnelConfig::BLOOMFILTER_CAPACITY,
             &oeis_ids_to_ignore,
+            &oeis_keywords,
             &funnel10_path,
             &funnel20_path,
             &funnel30_path,
             &funnel40_path,
+            &funnel10_signed_path,
         );
         Ok(())
     }
@@ -447,6 +486,7 @@ mod tests {
 # OEIS End-User License: http://oeis.org/LICENSE
 A000040 ,2,3,5,7,11,13,17,19,23,29,31,37,41,43,47,53,59,61,67,71,73,79,83,89,97,101,103,107,109,113,127,131,137,139,149,151,157,163,167,173,179,181,191,193,197,199,211,223,227,229,233,239,241,251,257,263,269,271,
 A000045 ,0,1,1,2,3,5,8,13,21,34,55,89,144,233,377,610,987,1597,2584,4181,6765,10946,17711,28657,46368,75025,121393,196418,317811,514229,832040,1346269,2178309,3524578,5702887,9227465,14930352,24157817,39088169,63245986,102334155,
+A001057 ,0,1,-1,2,-2,3,-3,4,-4,5,-5,6,-6,7,-7,8,-8,9,-9,10,-10,11,-11,12,-12,13,-13,14,-14,15,-15,16,-16,17,-17,18,-18,19,-19,20,-20,21,
 "#;
 
     fn create_checkfixedlengthsequence_inner(
@@ -573,6 +613,7 @@ A000045 ,0,1,1,2,3,5,8,13,21,34,55,89,144,233,377,610,987,1597,2584,4181,6765,10
         let funnel20_path = cache_dir.join(Path::new(NamedCacheFile::Funnel20All.filename()));
         let funnel30_path = cache_dir.join(Path::new(NamedCacheFile::Funnel30All.filename()));
         let funnel40_path = cache_dir.join(Path::new(NamedCacheFile::Funnel40All.filename()));
+        let funnel10_signed_path = cache_dir.join(Path::new(NamedCacheFile::signed_all().filename()));
 
         // Act
         let number_of_sequences: usize = create_cache_files(
@@ -581,14 +622,16 @@ A000045 ,0,1,1,2,3,5,8,13,21,34,55,89,144,233,377,610,987,1597,2584,4181,6765,10
             filesize,
             10,
             &hashset,
+            &OeisKeywords::new(),
             &funnel10_path,
             &funnel20_path,
             &funnel30_path,
             &funnel40_path,
+            &funnel10_signed_path,
         );
 
         // Assert
-        assert_eq!(number_of_sequences, 2);
+        assert_eq!(number_of_sequences, 3);
         // Check that all the cache files can be loaded
         let mut file_count: usize = 0;
         for item in NamedCacheFile::group_all() {
@@ -597,5 +640,46 @@ A000045 ,0,1,1,2,3,5,8,13,21,34,55,89,144,233,377,610,987,1597,2584,4181,6765,10
             file_count += 1;
         }
         assert_eq!(file_count, 4);
+        let checker10: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel10_path);
+        assert_eq!(checker10.check_i64(&vec!(0,1,1,2,3,5,8,13,21,34)), true);
+        assert_eq!(checker10.check_i64(&vec!(0,1,-1,2,-2,3,-3,4,-4,5)), true);
+        let checker10_signed: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel10_signed_path);
+        assert_eq!(checker10_signed.check_i64(&vec!(0,1,-1,2,-2,3,-3,4,-4,5)), true);
+        assert_eq!(checker10_signed.check_i64(&vec!(0,1,1,2,3,5,8,13,21,34)), false);
+    }
+
+    #[test]
+    fn test_30001_create_cache_files_with_sign_keyword() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let cache_dir = PathBuf::from(&tempdir.path());
+        let simple_log = SimpleLog::sink();
+        let mut input: &[u8] = INPUT_STRIPPED_SEQUENCE_MOCKDATA.as_bytes();
+        let filesize: usize = input.len();
+        let hashset = HashSet::<OeisId>::new();
+        let mut keywords = OeisKeywords::new();
+        keywords.insert(OeisId::from(45), vec![OeisKeyword::Sign]);
+        let funnel10_signed_path = cache_dir.join(Path::new(NamedCacheFile::signed_all().filename()));
+
+        // Act
+        create_cache_files(
+            simple_log,
+            &mut input, 
+            filesize,
+            10,
+            &hashset,
+            &keywords,
+            &cache_dir.join("funnel10.json"),
+            &cache_dir.join("funnel20.json"),
+            &cache_dir.join("funnel30.json"),
+            &cache_dir.join("funnel40.json"),
+            &funnel10_signed_path,
+        );
+
+        // Assert
+        let checker10_signed: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel10_signed_path);
+        assert_eq!(checker10_signed.check_i64(&vec!(0,1,-1,2,-2,3,-3,4,-4,5)), true);
+        assert_eq!(checker10_signed.check_i64(&vec!(0,1,1,2,3,5,8,13,21,34)), true);
+        assert_eq!(checker10_signed.check_i64(&vec!(2,3,5,7,11,13,17,19,23,29)), false);
     }
 }
@@ -10,9 +10,9 @@ pub trait CreateFunnel {
 impl CreateFunnel for Funnel {
     fn create_funnel_with_file_data(config: &Config) -> Funnel {
         let analytics_dir: PathBuf = config.analytics_oeis_dir();
-        let names: [NamedCacheFile; 4] = match config.miner_filter_mode() {
-            MinerFilterMode::All => NamedCacheFile::group_all(),
-            MinerFilterMode::New => NamedCacheFile::group_new(),
+        let (names, name_signed): ([NamedCacheFile; 4], NamedCacheFile) = match config.miner_filter_mode() {
+            MinerFilterMode::All => (NamedCacheFile::group_all(), NamedCacheFile::signed_all()),
+            MinerFilterMode::New => (NamedCacheFile::group_new(), NamedCacheFile::signed_new()),
         };
         let funnel10_path: PathBuf = names[0].resolve_path(&analytics_dir);
         let funnel20_path: PathBuf = names[1].resolve_path(&analytics_dir);
@@ -22,11 +22,18 @@ impl CreateFunnel for Funnel {
         let checker20: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel20_path);
         let checker30: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel30_path);
         let checker40: CheckFixedLengthSequence = CheckFixedLengthSequence::load(&funnel40_path);
+        // Analytics from before the signed bloomfilter was introduced, doesn't have this file.
+        let funnel10_signed_path: PathBuf = name_signed.resolve_path(&analytics_dir);
+        let checker10_signed: Option<CheckFixedLengthSequence> = match funnel10_signed_path.is_file() {
+            true => Some(CheckFixedLengthSequence::load(&funnel10_signed_path)),
+            false => None
+        };
         Funnel::new(
             checker10,
             checker20,
             checker30,
             checker40,
+            checker10_signed,
         )
     }
 
@@ -40,6 +47,7 @@ impl CreateFunnel for Funnel {
             checker20,
             checker30,
             checker40,
+            None,
         )
     }
 }
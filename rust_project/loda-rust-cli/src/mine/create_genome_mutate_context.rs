@@ -1,5 +1,6 @@
 use crate::analytics::AnalyticsDirectory;
 use super::{GenomeMutateContext, GenomeMutateContextBuilder};
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use std::path::PathBuf;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -10,7 +11,7 @@ pub enum CreateGenomeMutateContextMode {
     ARC,
 }
 
-pub fn create_genome_mutate_context(mode: CreateGenomeMutateContextMode, analytics_directory: AnalyticsDirectory) -> anyhow::Result<GenomeMutateContext> {
+pub fn create_genome_mutate_context(mode: CreateGenomeMutateContextMode, analytics_directory: AnalyticsDirectory, unofficial_function_registry: &UnofficialFunctionRegistry) -> anyhow::Result<GenomeMutateContext> {
     let program_modified_csv: PathBuf = analytics_directory.program_modified_file();
     let instruction_trigram_csv: PathBuf = analytics_directory.histogram_instruction_trigram_file();
     let line_trigram_csv: PathBuf = analytics_directory.histogram_line_trigram_file();
@@ -20,7 +21,9 @@ pub fn create_genome_mutate_context(mode: CreateGenomeMutateContextMode, analyti
     let popular_program_csv: PathBuf = analytics_directory.program_popularity_file();
     let valid_program_csv: PathBuf = analytics_directory.programs_valid_file();
     let invalid_program_csv: PathBuf = analytics_directory.programs_invalid_file();
+    let priority_program_csv: PathBuf = analytics_directory.priority_programs_file();
     let indirect_memory_access_csv: PathBuf = analytics_directory.indirect_memory_access_file();
+    let histogram_unofficial_function_csv: PathBuf = analytics_directory.histogram_unofficial_function_file();
 
     let mut builder = GenomeMutateContextBuilder::new();
     builder.suggest_instruction(&instruction_trigram_csv)?;
@@ -28,12 +31,14 @@ pub fn create_genome_mutate_context(mode: CreateGenomeMutateContextMode, analyti
     builder.suggest_source(&source_trigram_csv)?;
     builder.suggest_target(&target_trigram_csv)?;
     builder.histogram_instruction_constant(&histogram_instruction_constant_csv)?;
+    builder.unofficial_functions(unofficial_function_registry, &histogram_unofficial_function_csv)?;
 
     if mode == CreateGenomeMutateContextMode::OEIS {
         builder.recent_programs(&program_modified_csv)?;
         builder.popular_programs(&popular_program_csv)?;
         builder.valid_programs(&valid_program_csv)?;
         builder.invalid_programs(&invalid_program_csv)?;
+        builder.priority_programs(&priority_program_csv)?;
         builder.indirect_memory_access_program_ids(&indirect_memory_access_csv)?;
     }
 
@@ -0,0 +1,88 @@
+use crate::common::register_unofficial_function_manifest;
+use crate::config::{Config, MinerUnofficialFunctions};
+use loda_rust_core::unofficial_function::{register_common_functions, UnofficialFunctionRegistry};
+
+#[cfg(feature = "loda-rust-arc")]
+use crate::arc::register_arc_functions;
+
+/// The unofficial functions that are available while mining, and when checking the mined programs.
+///
+/// The `miner_unofficial_functions` setting in the config decides what functions gets registered.
+/// The `ARC` image functions are only available when compiled with the `loda-rust-arc` feature.
+///
+/// The functions in the `unofficial_function_manifest` are registered too, when the config specifies a manifest.
+/// When the setting is `None`, then the manifest is ignored, so the miner doesn't insert calls to unofficial functions
+/// that the `lodacpp` postmine backend cannot evaluate.
+pub fn create_miner_unofficial_function_registry(config: &Config) -> anyhow::Result<UnofficialFunctionRegistry> {
+    let registry = UnofficialFunctionRegistry::new();
+    match config.miner_unofficial_functions() {
+        MinerUnofficialFunctions::None => {
+            return Ok(registry);
+        },
+        MinerUnofficialFunctions::Common => {
+            register_common_functions(&registry);
+        },
+        MinerUnofficialFunctions::CommonAndArc => {
+            register_common_functions(&registry);
+            #[cfg(feature = "loda-rust-arc")]
+            register_arc_functions(&registry);
+            #[cfg(not(feature = "loda-rust-arc"))]
+            return Err(anyhow::anyhow!("The ARC functions requires that loda-rust is compiled with the loda-rust-arc feature"));
+        }
+    }
+    register_unofficial_function_manifest(config, &registry)?;
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config_from_toml_content;
+    use std::path::{Path, PathBuf};
+    use std::fs;
+
+    fn create_config(name: &str, content: &str) -> anyhow::Result<Config> {
+        let tempdir = tempfile::tempdir().unwrap();
+        let homedir = PathBuf::from(&tempdir.path()).join(name);
+        fs::create_dir(&homedir)?;
+        let basedir = PathBuf::from(Path::new("non-existing-basedir"));
+        Ok(config_from_toml_content(content.to_string(), basedir, homedir))
+    }
+
+    #[test]
+    fn test_10000_none_ignores_the_manifest() -> anyhow::Result<()> {
+        // Arrange
+        let content = 
+        r#"
+        unofficial_function_manifest = "$HOME/non-existing-manifest.toml"
+        "#;
+        let config: Config = create_config("test_10000_none_ignores_the_manifest", content)?;
+
+        // Act
+        let registry: UnofficialFunctionRegistry = create_miner_unofficial_function_registry(&config)?;
+
+        // Assert
+        assert_eq!(registry.function_ids().is_empty(), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_10001_common_loads_the_manifest() -> anyhow::Result<()> {
+        // Arrange
+        let content = 
+        r#"
+        unofficial_function_manifest = "$HOME/non-existing-manifest.toml"
+
+        [miner_unofficial_functions]
+        type = "common"
+        "#;
+        let config: Config = create_config("test_10001_common_loads_the_manifest", content)?;
+
+        // Act
+        let result = create_miner_unofficial_function_registry(&config);
+
+        // Assert
+        assert_eq!(result.is_err(), true);
+        Ok(())
+    }
+}
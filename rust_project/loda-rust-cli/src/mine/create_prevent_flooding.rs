@@ -1,7 +1,8 @@
-use super::PreventFlooding;
+use super::{create_miner_unofficial_function_registry, PreventFlooding};
 use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, ExecuteProfile};
 use loda_rust_core::execute::{NodeLoopLimit, ProgramCache, ProgramId, ProgramRunner, RegisterValue, RunMode};
 use loda_rust_core::execute::NodeRegisterLimit;
+use loda_rust_core::parser::ParsedProgram;
 use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use loda_rust_core::util::BigIntVec;
 use crate::common::find_asm_files_recursively;
@@ -72,6 +73,10 @@ fn prevent_flooding_populate(prevent_flooding: &mut PreventFlooding, dependency_
                 continue;
             }
         };
+        if let Ok(parsed_program) = ParsedProgram::parse_program(&contents) {
+            // Ignore the result, since programs with different terms can be structurally identical, such as with different comments.
+            let _ = prevent_flooding.try_register_program(&parsed_program.canonical_form());
+        }
         let runner: ProgramRunner = match dependency_manager.parse(ProgramId::ProgramWithoutId, &contents) {
             Ok(value) => value,
             Err(error) => {
@@ -123,10 +128,11 @@ pub fn create_prevent_flooding(config: &Config) -> anyhow::Result<PreventFloodin
     }
     println!("PreventFlooding: number of .asm files in total: {:?}", paths.len());
 
+    let unofficial_function_registry: UnofficialFunctionRegistry = create_miner_unofficial_function_registry(config)?;
     let mut dependency_manager = DependencyManager::new(
         DependencyManagerFileSystemMode::System,
         loda_programs_oeis_dir,
-        UnofficialFunctionRegistry::new(),
+        unofficial_function_registry,
     );
     dependency_manager.set_execute_profile(ExecuteProfile::SmallLimits);
     let capacity = NonZeroUsize::new(PREVENT_FLOODING_CACHE_CAPACITY).unwrap();
@@ -2,6 +2,7 @@ use super::CheckFixedLengthSequence;
 use super::FunnelConfig;
 use super::WildcardChecker;
 use loda_rust_core::util::BigIntVec;
+use num_traits::Signed;
 
 /// Funnel of bloomfilters for use during mining.
 ///
@@ -30,9 +31,14 @@ use loda_rust_core::util::BigIntVec;
 /// Then replace the second last term with a wildcard symbol, and try again.
 /// Then replace the third last term with a wildcard symbol, and try again.
 /// After around 10 attempts it can be determined if it's in the bloomfilter or not.
+///
+/// Most OEIS sequences are nonnegative. A candidate with a negative term among the first 10 terms,
+/// can only match a sequence that is signed. These candidates are checked against a bloomfilter
+/// that contains only the signed sequences, so they are rejected with fewer false positives.
 #[derive(Clone, Debug)]
 pub struct Funnel {
     checker10: CheckFixedLengthSequence,
+    checker10_signed: Option<CheckFixedLengthSequence>,
     checker20: CheckFixedLengthSequence,
     checker30: CheckFixedLengthSequence,
     checker40: CheckFixedLengthSequence,
@@ -49,9 +55,11 @@ impl Funnel {
         checker20: CheckFixedLengthSequence,
         checker30: CheckFixedLengthSequence,
         checker40: CheckFixedLengthSequence,
+        checker10_signed: Option<CheckFixedLengthSequence>,
     ) -> Self {
         Self {
             checker10: checker10, 
+            checker10_signed: checker10_signed,
             checker20: checker20,
             checker30: checker30,
             checker40: checker40,
@@ -63,7 +71,11 @@ impl Funnel {
     }
 
     pub fn check10(&mut self, terms: &BigIntVec) -> bool {
-        if !self.checker10.check(terms) {
+        let checker: &CheckFixedLengthSequence = match &self.checker10_signed {
+            Some(checker10_signed) if terms.iter().any(|term| term.is_negative()) => checker10_signed,
+            _ => &self.checker10
+        };
+        if !checker.check(terms) {
             return false;
         }
         self.metric_number_of_candidates_with_10terms += 1;
@@ -1,8 +1,10 @@
 use super::{GenomeItem, GenomeMutateContext, LineValue, MutateEvalSequenceCategory, SourceValue, TargetValue, ToGenomeItem, ToGenomeItemVec};
+use loda_rust_core::analysis::RegisterUsage;
 use loda_rust_core::control::DependencyManager;
 use loda_rust_core::execute::RegisterType;
 use loda_rust_core::parser::{Instruction, InstructionId, InstructionParameter, ParameterType};
 use loda_rust_core::parser::ParsedProgram;
+use loda_rust_core::unofficial_function::UnofficialFunctionId;
 use std::collections::HashSet;
 use std::fmt;
 use rand::Rng;
@@ -41,6 +43,9 @@ pub enum MutateGenome {
     CallLeastPopularProgram,
     CallRecentProgram,
     CallProgramThatUsesIndirectMemoryAccess,
+    InsertUnofficialFunction,
+    ReplaceUnofficialFunction,
+    ReparameterizeUnofficialFunction,
 }
 
 pub struct Genome {
@@ -108,10 +113,15 @@ impl Genome {
     }
 
     pub fn to_parsed_program(&self) -> ParsedProgram {
-        let mut instruction_vec = Vec::<Instruction>::with_capacity(self.genome_vec.len());
+        Self::genome_vec_to_parsed_program(&self.genome_vec)
+    }
+
+    /// Convert the enabled genome items to a program.
+    fn genome_vec_to_parsed_program(genome_vec: &[GenomeItem]) -> ParsedProgram {
+        let mut instruction_vec = Vec::<Instruction>::with_capacity(genome_vec.len());
 
         let mut line_number: usize = 0;
-        for genome_item in self.genome_vec.iter() {
+        for genome_item in genome_vec.iter() {
             if !genome_item.is_enabled() {
                 continue;
             }
@@ -126,6 +136,7 @@ impl Genome {
                 instruction_id: instruction_id,
                 parameter_vec: parameter_vec,
                 line_number: line_number,
+                source: None,
             };
             instruction_vec.push(instruction);
             line_number += 1;
@@ -133,7 +144,9 @@ impl Genome {
 
         ParsedProgram {
             optional_offset: None,
-            instruction_vec: instruction_vec
+            instruction_vec: instruction_vec,
+            header_comments: vec!(),
+            trailing_comments: vec!(),
         }
     }
 
@@ -1460,25 +1473,8 @@ impl Genome {
         }
 
         // Determine how many registers are already used
-        let mut max_register: u32 = 0;
-        for genome_item in genome_vec.iter() {
-            if !genome_item.is_enabled() {
-                continue;
-            }
-            if genome_item.target_type() == RegisterType::Direct {
-                let index = genome_item.target_value();
-                if index >= 0 {
-                    max_register = max_register.max(index as u32);
-                }
-            }
-            if genome_item.source_type() == ParameterType::Direct {
-                let index = genome_item.source_value();
-                if index >= 0 {
-                    max_register = max_register.max(index as u32);
-                }
-            }
-        }
-        let offset_by: u32 = max_register;
+        let parsed_program: ParsedProgram = Self::genome_vec_to_parsed_program(genome_vec);
+        let offset_by: u32 = RegisterUsage::analyze(&parsed_program).highest_register().unwrap_or(0) as u32;
 
         // Mutate one of the `seq` instructions
         // let before_snapshot: String = Self::genome_vec_to_formatted_program(&genome_vec);
@@ -1567,6 +1563,161 @@ impl Genome {
         true
     }
 
+    /// Pick one of the registers that is used as target by the program.
+    /// 
+    /// Fallback to register `$0` when there are no such registers.
+    fn choose_target_register_in_use<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        let mut registers: Vec<i32> = vec!();
+        for genome_item in &self.genome_vec {
+            if !genome_item.is_enabled() {
+                continue;
+            }
+            if genome_item.target_type() != RegisterType::Direct {
+                continue;
+            }
+            if genome_item.instruction_id() == InstructionId::LoopEnd {
+                continue;
+            }
+            registers.push(genome_item.target_value());
+        }
+        match registers.choose(rng) {
+            Some(value) => *value,
+            None => 0
+        }
+    }
+
+    /// Indexes of the `fxx` instructions that can be mutated.
+    fn unofficial_function_indexes(&self) -> Vec<usize> {
+        let mut indexes: Vec<usize> = vec!();
+        for (index, genome_item) in self.genome_vec.iter().enumerate() {
+            if genome_item.is_mutation_locked() {
+                continue;
+            }
+            if genome_item.unofficial_function_id().is_none() {
+                continue;
+            }
+            indexes.push(index);
+        }
+        indexes
+    }
+
+    /// Insert a call to an unofficial function, such as `f21 $1,1104`.
+    /// 
+    /// The function is picked weighted by how often the functions are used.
+    /// 
+    /// The target register is one of the registers that is already used by the program.
+    /// 
+    /// Return `true` when the mutation was successful.
+    /// 
+    /// Return `false` in case of failure, such as no unofficial functions available.
+    pub fn insert_unofficial_function<R: Rng + ?Sized>(&mut self, rng: &mut R, context: &GenomeMutateContext) -> bool {
+        if !context.has_unofficial_functions() {
+            return false;
+        }
+        let function_id: UnofficialFunctionId = match context.choose_unofficial_function(rng) {
+            Some(value) => value,
+            None => {
+                return false;
+            }
+        };
+        let target_value: i32 = self.choose_target_register_in_use(rng);
+        let mut genome_item = GenomeItem::new(
+            InstructionId::UnofficialFunction { input_count: 0, output_count: 0 },
+            RegisterType::Direct,
+            target_value,
+            ParameterType::Constant,
+            -1
+        );
+        if !genome_item.set_unofficial_function(function_id) {
+            return false;
+        }
+        let index: usize = rng.gen_range(0..=self.genome_vec.len());
+        self.genome_vec.insert(index, genome_item);
+        true
+    }
+
+    /// Replace the function invoked by a `fxx` instruction with another function.
+    /// 
+    /// The function is picked weighted by how often the functions are used.
+    /// 
+    /// Return `true` when the mutation was successful.
+    /// 
+    /// Return `false` in case of failure, such as no `fxx` instructions in the program.
+    pub fn replace_unofficial_function<R: Rng + ?Sized>(&mut self, rng: &mut R, context: &GenomeMutateContext) -> bool {
+        if !context.has_unofficial_functions() {
+            return false;
+        }
+        let indexes: Vec<usize> = self.unofficial_function_indexes();
+        let index: usize = match indexes.choose(rng) {
+            Some(value) => *value,
+            None => {
+                return false;
+            }
+        };
+        let genome_item: &mut GenomeItem = &mut self.genome_vec[index];
+
+        // Try a few times
+        for _ in 0..Self::MUTATE_RETRIES {
+            let function_id: UnofficialFunctionId = match context.choose_unofficial_function(rng) {
+                Some(value) => value,
+                None => {
+                    return false;
+                }
+            };
+            if genome_item.set_unofficial_function(function_id) {
+                return true;
+            }
+            // Picked the same as the original, try pick a different function
+        }
+        // Too many tries, without picking a different function. No mutation happened.
+        false
+    }
+
+    /// Change the parameters of a `fxx` instruction, while keeping the function id.
+    /// 
+    /// Either change the target register, or pick a variant of the function
+    /// with a different number of inputs or outputs.
+    /// 
+    /// Return `true` when the mutation was successful.
+    /// 
+    /// Return `false` in case of failure, such as no `fxx` instructions in the program.
+    pub fn reparameterize_unofficial_function<R: Rng + ?Sized>(&mut self, rng: &mut R, context: &GenomeMutateContext) -> bool {
+        let indexes: Vec<usize> = self.unofficial_function_indexes();
+        let index: usize = match indexes.choose(rng) {
+            Some(value) => *value,
+            None => {
+                return false;
+            }
+        };
+        let function_id: UnofficialFunctionId = match self.genome_vec[index].unofficial_function_id() {
+            Some(value) => value,
+            None => {
+                return false;
+            }
+        };
+        let variants: Vec<UnofficialFunctionId> = context.unofficial_function_variants(function_id);
+        if !variants.is_empty() && rng.gen_bool(0.5) {
+            let new_function_id: UnofficialFunctionId = *variants.choose(rng).unwrap();
+            return self.genome_vec[index].set_unofficial_function(new_function_id);
+        }
+
+        // Change the target register
+        let genome_item: &GenomeItem = &self.genome_vec[index];
+        if genome_item.target_type() != RegisterType::Direct {
+            return false;
+        }
+        let current_value: i32 = genome_item.target_value();
+        let new_value: i32 = match rng.gen_range(0..3) {
+            0 => current_value - 1,
+            1 => current_value + 1,
+            _ => self.choose_target_register_in_use(rng)
+        };
+        if new_value < 0 || new_value == current_value {
+            return false;
+        }
+        self.genome_vec[index].set_target_value(new_value)
+    }
+
     /// Mutate the `seq` instruction, so it invokes a random program.
     /// 
     /// Only impact rows where source_type=Constant and instruct=seq
@@ -1645,6 +1796,9 @@ impl Genome {
             (MutateGenome::CallLeastPopularProgram, 50),
             (MutateGenome::CallRecentProgram, 300),
             (MutateGenome::CallProgramThatUsesIndirectMemoryAccess, 0),
+            (MutateGenome::InsertUnofficialFunction, 10),
+            (MutateGenome::ReplaceUnofficialFunction, 10),
+            (MutateGenome::ReparameterizeUnofficialFunction, 10),
         ];
         let mutation: &MutateGenome = &mutation_vec.choose_weighted(rng, |item| item.1).unwrap().0;
 
@@ -1732,6 +1886,15 @@ impl Genome {
             },
             MutateGenome::CallProgramThatUsesIndirectMemoryAccess => {
                 self.mutate_instruction_seq(rng, context, MutateEvalSequenceCategory::ProgramThatUsesIndirectMemoryAccess)
+            },
+            MutateGenome::InsertUnofficialFunction => {
+                self.insert_unofficial_function(rng, context)
+            },
+            MutateGenome::ReplaceUnofficialFunction => {
+                self.replace_unofficial_function(rng, context)
+            },
+            MutateGenome::ReparameterizeUnofficialFunction => {
+                self.reparameterize_unofficial_function(rng, context)
             }
         };
 
@@ -1758,3 +1921,103 @@ impl fmt::Display for Genome {
         write!(f, "{}", formatted_program)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mine::GenomeMutateContextBuilder;
+    use loda_rust_core::unofficial_function::{SumFunction, UnofficialFunctionRegistry};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn create_context() -> GenomeMutateContext {
+        let registry = UnofficialFunctionRegistry::new();
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 2))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 3))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1001, 2))));
+        let mut builder = GenomeMutateContextBuilder::new();
+        builder.unofficial_functions(&registry, Path::new("non-existing-histogram.csv")).expect("ok");
+        builder.build().expect("context")
+    }
+
+    fn create_genome(program: &str) -> Genome {
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program(program).expect("program");
+        let mut genome = Genome::new();
+        genome.set_genome_vec(parsed_program.to_genome_item_vec());
+        genome
+    }
+
+    #[test]
+    fn test_10000_insert_unofficial_function() {
+        // Arrange
+        let context: GenomeMutateContext = create_context();
+        let mut genome: Genome = create_genome("mov $1,$0\nadd $1,1");
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Act
+        let success: bool = genome.insert_unofficial_function(&mut rng, &context);
+
+        // Assert
+        assert_eq!(success, true);
+        let program: String = genome.to_string();
+        assert_eq!(program.lines().count(), 3);
+        let inserted: Vec<&str> = program.lines().filter(|line| line.starts_with("f")).collect();
+        let expected: [&str; 3] = ["f21 $1,1000", "f31 $1,1000", "f21 $1,1001"];
+        assert_eq!(inserted.len(), 1);
+        assert_eq!(expected.contains(&inserted[0]), true);
+    }
+
+    #[test]
+    fn test_10001_insert_unofficial_function_without_functions() {
+        // Arrange
+        let context = GenomeMutateContext::default();
+        let mut genome: Genome = create_genome("mov $1,$0");
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Act
+        let success: bool = genome.insert_unofficial_function(&mut rng, &context);
+
+        // Assert
+        assert_eq!(success, false);
+        assert_eq!(genome.to_string(), "mov $1,$0");
+    }
+
+    #[test]
+    fn test_20000_replace_unofficial_function() {
+        // Arrange
+        let context: GenomeMutateContext = create_context();
+        let mut genome: Genome = create_genome("f21 $0,1000");
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Act
+        let success: bool = genome.replace_unofficial_function(&mut rng, &context);
+
+        // Assert
+        assert_eq!(success, true);
+        assert_ne!(genome.to_string(), "f21 $0,1000");
+    }
+
+    #[test]
+    fn test_30000_reparameterize_unofficial_function() {
+        // Arrange
+        let context: GenomeMutateContext = create_context();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut programs = HashSet::<String>::new();
+
+        // Act
+        for _ in 0..50 {
+            let mut genome: Genome = create_genome("mov $2,1\nf21 $1,1000");
+            if genome.reparameterize_unofficial_function(&mut rng, &context) {
+                programs.insert(genome.to_string());
+            }
+        }
+
+        // Assert
+        assert_eq!(programs.contains("mov $2,1\nf31 $1,1000"), true);
+        assert_eq!(programs.contains("mov $2,1\nf21 $0,1000"), true);
+        assert_eq!(programs.contains("mov $2,1\nf21 $2,1000"), true);
+        assert_eq!(programs.len(), 3);
+    }
+}
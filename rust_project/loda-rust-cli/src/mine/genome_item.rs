@@ -1,6 +1,7 @@
 use loda_rust_core::execute::RegisterType;
 use loda_rust_core::parser::{Instruction, InstructionId, InstructionParameter, ParameterType};
 use loda_rust_core::parser::ParsedProgram;
+use loda_rust_core::unofficial_function::UnofficialFunctionId;
 use super::GenomeMutateContext;
 use rand::Rng;
 use rand::seq::SliceRandom;
@@ -167,6 +168,51 @@ impl GenomeItem {
         true
     }
 
+    /// The function that is invoked by a `fxx` instruction.
+    /// 
+    /// Returns `None` for all other instructions.
+    pub fn unofficial_function_id(&self) -> Option<UnofficialFunctionId> {
+        let (input_count, output_count) = match self.instruction_id {
+            InstructionId::UnofficialFunction { input_count, output_count } => (input_count, output_count),
+            _ => {
+                return None;
+            }
+        };
+        if self.source_type != ParameterType::Constant || self.source_value < 0 {
+            return None;
+        }
+        let function_id = UnofficialFunctionId::InputOutput {
+            id: self.source_value as u32,
+            inputs: input_count,
+            outputs: output_count,
+        };
+        Some(function_id)
+    }
+
+    /// Turn the instruction into a `fxx` instruction, that invokes the function.
+    /// 
+    /// Only a `fxx` instruction can be modified, or a newly created instruction with `instruction_id=UnofficialFunction`.
+    pub fn set_unofficial_function(&mut self, function_id: UnofficialFunctionId) -> bool {
+        let is_unofficial_function = matches!(self.instruction_id, InstructionId::UnofficialFunction { .. });
+        if !is_unofficial_function {
+            return false;
+        }
+        if self.unofficial_function_id() == Some(function_id) {
+            // No mutation happened
+            return false;
+        }
+        let (id, inputs, outputs) = match function_id {
+            UnofficialFunctionId::InputOutput { id, inputs, outputs } => (id, inputs, outputs)
+        };
+        if id > (i32::MAX as u32) {
+            return false;
+        }
+        self.instruction_id = InstructionId::UnofficialFunction { input_count: inputs, output_count: outputs };
+        self.source_type = ParameterType::Constant;
+        self.source_value = id as i32;
+        true
+    }
+
     pub fn mutate_swap_source_target_value(&mut self) -> bool {
         if self.target_value == self.source_value {
             // No mutation happened
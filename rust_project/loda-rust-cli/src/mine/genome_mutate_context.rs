@@ -3,19 +3,27 @@ use super::SuggestInstruction;
 use super::{SuggestLine, LineValue};
 use super::{SuggestSource, SourceValue};
 use super::{SuggestTarget, TargetValue};
+use super::UnofficialFunctionContainer;
 use loda_rust_core::parser::InstructionId;
+use loda_rust_core::unofficial_function::{UnofficialFunctionId, UnofficialFunctionRegistry};
 use crate::common::RecordTrigram;
 use crate::common::load_program_ids_csv_file;
 use std::path::Path;
 use std::collections::HashSet;
 use std::fmt;
 use rand::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::seq::SliceRandom;
 
+/// Programs for sequences with the `core` or `nice` keyword are picked twice as often as the other programs.
+const INITIAL_GENOME_PRIORITY_PROGRAM_WEIGHT: u32 = 2;
+const INITIAL_GENOME_OTHER_PROGRAM_WEIGHT: u32 = 1;
+
 #[derive(Clone)]
 pub struct GenomeMutateContext {
     valid_program_ids: Vec<u32>,
     initial_genome_program_ids: Vec<u32>, 
+    initial_genome_program_weights: Option<WeightedIndex<u32>>,
     indirect_memory_access_program_ids: Vec<u32>,
     invalid_program_ids: HashSet<u32>,
     popular_program_container: Option<PopularProgramContainer>,
@@ -25,6 +33,7 @@ pub struct GenomeMutateContext {
     suggest_line: Option<SuggestLine>,
     suggest_source: Option<SuggestSource>,
     suggest_target: Option<SuggestTarget>,
+    unofficial_function_container: Option<UnofficialFunctionContainer>,
 }
 
 impl GenomeMutateContext {
@@ -37,14 +46,15 @@ impl GenomeMutateContext {
     }
 
     pub fn choose_initial_genome_program<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<u32> {
-        let program_id: u32 = match self.initial_genome_program_ids.choose(rng) {
-            Some(program_id) => *program_id,
+        let weights: &WeightedIndex<u32> = match &self.initial_genome_program_weights {
+            Some(value) => value,
             None => {
                 // For a non-empty vector, this shouldn't happen.
                 return None;
             }
         };
-        Some(program_id)
+        let index: usize = weights.sample(rng);
+        self.initial_genome_program_ids.get(index).copied()
     }
 
     pub fn choose_indirect_memory_access_program_id<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<u32> {
@@ -177,6 +187,32 @@ impl GenomeMutateContext {
         };
         suggest_target.choose_weighted(rng, prev_word, next_word)
     }
+
+    pub fn has_unofficial_functions(&self) -> bool {
+        match &self.unofficial_function_container {
+            Some(value) => value.number_of_functions() > 0,
+            None => false
+        }
+    }
+
+    /// Pick an unofficial function, where the most used functions are picked more often.
+    pub fn choose_unofficial_function<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<UnofficialFunctionId> {
+        let unofficial_function_container: &UnofficialFunctionContainer = match &self.unofficial_function_container {
+            Some(value) => value,
+            None => {
+                return None;
+            }
+        };
+        unofficial_function_container.choose_weighted(rng)
+    }
+
+    /// The functions with the same id as the function, but with a different number of inputs or outputs.
+    pub fn unofficial_function_variants(&self, function_id: UnofficialFunctionId) -> Vec<UnofficialFunctionId> {
+        match &self.unofficial_function_container {
+            Some(value) => value.variants(function_id),
+            None => vec!()
+        }
+    }
 }
 
 impl Default for GenomeMutateContext {
@@ -184,6 +220,7 @@ impl Default for GenomeMutateContext {
         Self {
             valid_program_ids: vec!(),
             initial_genome_program_ids: vec!(),
+            initial_genome_program_weights: None,
             indirect_memory_access_program_ids: vec!(),
             invalid_program_ids: HashSet::<u32>::new(),
             popular_program_container: None,
@@ -193,6 +230,7 @@ impl Default for GenomeMutateContext {
             suggest_line: None,
             suggest_source: None,
             suggest_target: None,
+            unofficial_function_container: None,
         }
     }
 }
@@ -213,7 +251,9 @@ pub struct GenomeMutateContextBuilder {
     histogram_instruction_constant: Option<HistogramInstructionConstant>,
     valid_program_ids: Option<Vec<u32>>,
     invalid_program_ids_hashset: Option<HashSet<u32>>,
+    priority_program_ids_hashset: Option<HashSet<u32>>,
     indirect_memory_access_program_ids: Option<Vec<u32>>,
+    unofficial_function_container: Option<UnofficialFunctionContainer>,
 }
 
 impl GenomeMutateContextBuilder {
@@ -228,7 +268,9 @@ impl GenomeMutateContextBuilder {
             histogram_instruction_constant: None,
             valid_program_ids: None,
             invalid_program_ids_hashset: None,
+            priority_program_ids_hashset: None,
             indirect_memory_access_program_ids: None,
+            unofficial_function_container: None,
         }
     }
 
@@ -238,7 +280,9 @@ impl GenomeMutateContextBuilder {
         if let Some(hashset) = &self.invalid_program_ids_hashset {
             invalid_program_ids_hashset = hashset.clone();
         }
+        let priority_program_ids_hashset: HashSet<u32> = self.priority_program_ids_hashset.clone().unwrap_or_default();
         let mut initial_genome_program_ids = Vec::<u32>::new();
+        let mut initial_genome_program_weight_vec = Vec::<u32>::new();
         if let Some(valid_program_ids) = &self.valid_program_ids {
             for program_id in valid_program_ids {
                 if invalid_program_ids_hashset.contains(program_id) {
@@ -246,12 +290,21 @@ impl GenomeMutateContextBuilder {
                     continue;
                 }
                 initial_genome_program_ids.push(*program_id);
+                let weight: u32 = if priority_program_ids_hashset.contains(program_id) {
+                    INITIAL_GENOME_PRIORITY_PROGRAM_WEIGHT
+                } else {
+                    INITIAL_GENOME_OTHER_PROGRAM_WEIGHT
+                };
+                initial_genome_program_weight_vec.push(weight);
             }
         }
+        // Fails when there are no programs, in which case no initial genome can be chosen.
+        let initial_genome_program_weights: Option<WeightedIndex<u32>> = WeightedIndex::new(&initial_genome_program_weight_vec).ok();
 
         let instance = GenomeMutateContext {
             valid_program_ids: self.valid_program_ids.unwrap_or_default(),
             initial_genome_program_ids: initial_genome_program_ids,
+            initial_genome_program_weights,
             indirect_memory_access_program_ids: self.indirect_memory_access_program_ids.unwrap_or_default(),
             invalid_program_ids: self.invalid_program_ids_hashset.unwrap_or_default(),
             popular_program_container: self.popular_program_container,
@@ -261,6 +314,7 @@ impl GenomeMutateContextBuilder {
             suggest_line: self.suggest_line,
             suggest_source: self.suggest_source,
             suggest_target: self.suggest_target,
+            unofficial_function_container: self.unofficial_function_container,
         };
         Ok(instance)
     }
@@ -346,6 +400,22 @@ impl GenomeMutateContextBuilder {
         Ok(())
     }
 
+    /// The programs for sequences with the `core` or `nice` keyword.
+    /// 
+    /// The file is optional, when it's missing then all programs have the same weight.
+    pub fn priority_programs(&mut self, priority_program_ids_csv: &Path) -> anyhow::Result<()> {
+        if !priority_program_ids_csv.is_file() {
+            debug!("priority_program_ids. no file: {:?}", priority_program_ids_csv);
+            return Ok(());
+        }
+        let program_ids: Vec<u32> = load_program_ids_csv_file(priority_program_ids_csv)
+            .map_err(|e| anyhow::anyhow!("Unable to load priority_program_ids_csv error: {:?}", e))?;
+        debug!("priority_program_ids. number of program ids: {:?}", program_ids.len());
+        let program_ids_hashset: HashSet<u32> = program_ids.into_iter().collect();
+        self.priority_program_ids_hashset = Some(program_ids_hashset);
+        Ok(())
+    }
+
     /// The programs that makes use of indirect memory access.
     /// 
     /// These programs trend to have a big memory foot print.
@@ -356,4 +426,19 @@ impl GenomeMutateContextBuilder {
         self.indirect_memory_access_program_ids = Some(program_ids);
         Ok(())
     }
+    /// The unofficial functions that can be inserted into programs, weighted by how often they are used.
+    /// 
+    /// The histogram file is optional, when it's missing then all the functions have the same weight.
+    pub fn unofficial_functions(&mut self, registry: &UnofficialFunctionRegistry, histogram_unofficial_function_csv: &Path) -> anyhow::Result<()> {
+        let mut instance = UnofficialFunctionContainer::new(registry);
+        if histogram_unofficial_function_csv.is_file() {
+            instance.load_histogram_csv_file(histogram_unofficial_function_csv)
+                .map_err(|e| anyhow::anyhow!("Unable to load histogram_unofficial_function_csv error: {:?}", e))?;
+        } else {
+            debug!("unofficial_functions. no histogram file: {:?}", histogram_unofficial_function_csv);
+        }
+        debug!("unofficial_functions. number of functions: {:?}", instance.number_of_functions());
+        self.unofficial_function_container = Some(instance);
+        Ok(())
+    }
 }
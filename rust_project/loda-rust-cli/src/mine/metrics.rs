@@ -16,6 +16,7 @@ pub enum MetricEvent {
         cannot_load: u64,
         cannot_parse: u64,
         too_short: u64,
+        loop_without_progress: u64,
         no_output: u64,
         no_mutation: u64,
         compute_error: u64,
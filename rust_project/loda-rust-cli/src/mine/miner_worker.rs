@@ -1,8 +1,8 @@
 use crate::config::Config;
 use super::{ExecuteBatchResult, RunMinerLoop, MetricEvent};
 use super::{Funnel, GenomeMutateContext, PreventFlooding};
-use super::CoordinatorWorkerQuestion;
-use crate::oeis::TermsToProgramIdSet;
+use super::{create_miner_unofficial_function_registry, CoordinatorWorkerQuestion};
+use crate::oeis::TermsToProgramIdFile;
 use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, ExecuteProfile};
 use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use bastion::prelude::*;
@@ -20,11 +20,11 @@ pub enum MinerWorkerMessage {
 pub struct MinerWorkerMessageWithAnalytics {
     funnel: Funnel,
     genome_mutate_context: GenomeMutateContext,
-    terms_to_program_id_arc: Arc<TermsToProgramIdSet>,
+    terms_to_program_id_arc: Arc<TermsToProgramIdFile>,
 }
 
 impl MinerWorkerMessageWithAnalytics {
-    pub fn new(funnel: Funnel, genome_mutate_context: GenomeMutateContext, terms_to_program_id_arc: Arc<TermsToProgramIdSet>) -> Self {
+    pub fn new(funnel: Funnel, genome_mutate_context: GenomeMutateContext, terms_to_program_id_arc: Arc<TermsToProgramIdFile>) -> Self {
         Self {
             funnel,
             genome_mutate_context,
@@ -52,6 +52,14 @@ pub async fn miner_worker(
 ) -> Result<(), ()> {
     debug!("miner_worker - started, {:?}", ctx.current().id());
     let loda_programs_oeis_dir: PathBuf = config.loda_programs_oeis_dir();
+    let unofficial_function_registry: UnofficialFunctionRegistry = match create_miner_unofficial_function_registry(&config) {
+        Ok(value) => value,
+        Err(error) => {
+            error!("miner_worker {}, unable to create UnofficialFunctionRegistry: {:?}", ctx.current().id(), error);
+            Bastion::stop();
+            panic!("the miner_worker is in a broken state");
+        }
+    };
 
     let coordinator_worker_distributor = Distributor::named("coordinator_worker");
     let metrics_worker_distributor = Distributor::named("metrics_worker");
@@ -138,7 +146,7 @@ pub async fn miner_worker(
             let mut dependency_manager = DependencyManager::new(
                 DependencyManagerFileSystemMode::System,
                 loda_programs_oeis_dir.clone(),
-                UnofficialFunctionRegistry::new(),
+                unofficial_function_registry.clone(),
             );
             dependency_manager.set_execute_profile(ExecuteProfile::SmallLimits);
         
@@ -4,6 +4,7 @@ mod check_fixed_length_sequence;
 mod coordinator_worker;
 mod create_funnel;
 mod create_genome_mutate_context;
+mod create_miner_unofficial_function_registry;
 mod create_prevent_flooding;
 mod cronjob_worker;
 mod funnel;
@@ -33,6 +34,7 @@ mod suggest_line;
 mod suggest_source;
 mod suggest_target;
 mod term_computer;
+mod unofficial_function_container;
 mod upload_worker;
 mod wildcard_checker;
 
@@ -41,6 +43,7 @@ pub use check_fixed_length_sequence::{CheckFixedLengthSequence, NamedCacheFile,
 pub use coordinator_worker::{coordinator_worker, CoordinatorWorkerMessage, CoordinatorWorkerQuestion};
 pub use create_funnel::CreateFunnel;
 pub use create_genome_mutate_context::{CreateGenomeMutateContextMode, create_genome_mutate_context};
+pub use create_miner_unofficial_function_registry::create_miner_unofficial_function_registry;
 pub use create_prevent_flooding::create_prevent_flooding;
 
 #[allow(unused_imports)]
@@ -77,5 +80,6 @@ pub use suggest_line::{SuggestLine, LineValue};
 pub use suggest_source::{SuggestSource, SourceValue};
 pub use suggest_target::{SuggestTarget, TargetValue};
 pub use term_computer::TermComputer;
+pub use unofficial_function_container::UnofficialFunctionContainer;
 pub use upload_worker::{upload_worker, UploadWorkerItem};
 pub use wildcard_checker::WildcardChecker;
@@ -55,7 +55,6 @@ impl PopularProgramContainer {
         process_csv_into_clusters(&mut reader)
     }
 
-    #[allow(dead_code)]
     pub fn cluster_program_ids(&self) -> &Vec<Vec<u32>> {
         &self.cluster_program_ids
     }
@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use loda_rust_core::analysis::CanonicalForm;
 use loda_rust_core::util::{BigIntVec, BigIntVecToString};
 
 /// The `mine-event` dir can quickly get filled up with candidate programs
@@ -10,8 +11,13 @@ use loda_rust_core::util::{BigIntVec, BigIntVecToString};
 /// then prevent the new program from being written to the dir.
 ///
 /// On load, the `mine-event` dir is scanned for what programs are there.
+///
+/// Programs that are structurally identical to an already registered program,
+/// such as the same program with the registers renumbered, are also prevented.
+/// This check is cheaper, since it doesn't require computing the terms.
 pub struct PreventFlooding {
     hashset: HashSet<String>,
+    program_hashset: HashSet<u64>,
 }
 
 pub enum PreventFloodingError {
@@ -22,6 +28,7 @@ impl PreventFlooding {
     pub fn new() -> Self {
         Self {
             hashset: HashSet::<String>::new(),
+            program_hashset: HashSet::<u64>::new(),
         }
     }
 
@@ -46,6 +53,19 @@ impl PreventFlooding {
         Ok(())
     }
 
+    /// Determine if a structurally identical program has been registered.
+    pub fn contains_program(&self, canonical_form: &CanonicalForm) -> bool {
+        self.program_hashset.contains(&canonical_form.hash())
+    }
+
+    pub fn try_register_program(&mut self, canonical_form: &CanonicalForm) -> Result<(), PreventFloodingError> {
+        if !self.program_hashset.insert(canonical_form.hash()) {
+            // The `mine-event` dir already contains a structurally identical program.
+            return Err(PreventFloodingError::AlreadyRegistered);
+        }
+        Ok(())
+    }
+
     pub fn len(&self) -> usize {
         self.hashset.len()
     }
@@ -54,6 +74,7 @@ impl PreventFlooding {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use loda_rust_core::parser::ParsedProgram;
     use loda_rust_core::util::BigIntVecFromI64;
     
     #[test]
@@ -75,4 +96,23 @@ mod tests {
         assert_eq!(pf.contains(&BigIntVec::from_i64array(&[1, 2, 3, 4, 5])), true);
         assert_eq!(pf.contains(&BigIntVec::from_i64array(&[1984, 1984, 1984])), false);
     }
+
+    #[test]
+    fn test_20000_try_register_program() {
+        // Arrange
+        let mut pf = PreventFlooding::new();
+        let canonical_form = |source: &str| -> CanonicalForm {
+            ParsedProgram::parse_program(source).expect("program").canonical_form()
+        };
+
+        // Act
+        let result0 = pf.try_register_program(&canonical_form("mov $1,$0\nmul $1,2\nmov $0,$1"));
+        let result1 = pf.try_register_program(&canonical_form("mov $4,$0\nmul $4,2\nmov $0,$4"));
+
+        // Assert
+        assert_eq!(result0.is_ok(), true);
+        assert_eq!(result1.is_ok(), false);
+        assert_eq!(pf.contains_program(&canonical_form("; renumbered\nmov $7,$0\nmul $7,2\nmov $0,$7")), true);
+        assert_eq!(pf.contains_program(&canonical_form("mov $1,$0\nmul $1,3\nmov $0,$1")), false);
+    }
 }
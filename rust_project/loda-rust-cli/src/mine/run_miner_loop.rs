@@ -4,8 +4,9 @@ use super::{PreventFlooding, TermComputer};
 use super::{PerformanceClassifierResult, PerformanceClassifier};
 use super::MetricEvent;
 use super::metrics_run_miner_loop::MetricsRunMinerLoop;
-use crate::oeis::TermsToProgramIdSet;
+use crate::oeis::TermsToProgramIdFile;
 use crate::config::{Config, MinerFilterMode};
+use loda_rust_core::analysis::{CanonicalForm, ComplexityEstimate};
 use loda_rust_core::control::DependencyManager;
 use loda_rust_core::execute::{ProgramCache, ProgramId, ProgramRunner, ProgramSerializer};
 use loda_rust_core::util::{BigIntVec, BigIntVecToString};
@@ -76,7 +77,7 @@ pub struct RunMinerLoop {
     iteration: usize,
     reload: bool,
     term_computer: TermComputer,
-    terms_to_program_id: Arc<TermsToProgramIdSet>,
+    terms_to_program_id: Arc<TermsToProgramIdFile>,
     suppress_low_priority_programs: bool,
 }
 
@@ -112,7 +113,7 @@ impl RunMinerLoop {
             iteration: 0,
             reload: true,
             term_computer: TermComputer::new(),
-            terms_to_program_id: Arc::new(TermsToProgramIdSet::new()),
+            terms_to_program_id: Arc::new(TermsToProgramIdFile::empty()),
             suppress_low_priority_programs: suppress_low_priority_programs,
         }
     }
@@ -163,6 +164,7 @@ impl RunMinerLoop {
             cannot_load: self.metric.number_of_failed_genome_loads,
             cannot_parse: self.metric.number_of_programs_that_cannot_parse,
             too_short: self.metric.number_of_too_short_programs,
+            loop_without_progress: self.metric.number_of_loops_without_progress,
             no_output: self.metric.number_of_programs_without_output,
             no_mutation: self.metric.number_of_failed_mutations,
             compute_error: self.metric.number_of_compute_errors,
@@ -199,7 +201,7 @@ impl RunMinerLoop {
         self.context = genome_mutate_context;
     }
 
-    pub fn set_terms_to_program_id(&mut self, terms_to_program_id: Arc<TermsToProgramIdSet>) {
+    pub fn set_terms_to_program_id(&mut self, terms_to_program_id: Arc<TermsToProgramIdFile>) {
         self.terms_to_program_id = terms_to_program_id;
     }
 
@@ -297,6 +299,14 @@ impl RunMinerLoop {
             return;
         }
 
+        // Reject programs with a loop that has no effect, such as `lpb $0` without any writes to `$0`.
+        if let Ok(estimate) = ComplexityEstimate::analyze(&genome_parsed_program) {
+            if estimate.has_loop_without_progress() {
+                self.metric.number_of_loops_without_progress += 1;
+                return;
+            }
+        }
+
         // Create program from genome
         let result_parse = dependency_manager.parse_stage2(
             ProgramId::ProgramWithoutId, 
@@ -327,6 +337,17 @@ impl RunMinerLoop {
             return;
         }
 
+        // Reject programs that are structurally identical to an already mined program, before computing more terms.
+        let genome_canonical_form: CanonicalForm = genome_parsed_program.canonical_form();
+        {
+            let prevent_flooding = self.prevent_flooding.lock().unwrap();
+            if prevent_flooding.contains_program(&genome_canonical_form) {
+                self.metric.number_of_prevented_floodings += 1;
+                self.reload = true;
+                return;
+            }
+        }
+
         match self.term_computer.compute(&mut self.cache, &runner, 20) {
             Ok(_) => {},
             Err(_error) => {
@@ -435,7 +456,7 @@ impl RunMinerLoop {
 
         // lookup in stripped.zip and find the corresponding program_ids
         let key: String = terms40_wildcard.to_compact_comma_string();
-        let corresponding_program_id_set: &HashSet<u32> = match self.terms_to_program_id.get(&key) {
+        let corresponding_program_id_set: Vec<u32> = match self.terms_to_program_id.get(&key) {
             Some(value) => value,
             None => {
                 debug!("Ignoring false-positive in bloomfilter funnel. Could not find the candiate in the oeis stripped file. funnel20_number_of_wildcards: {:?} funnel30_number_of_wildcards: {:?} funnel40_number_of_wildcards: {:?} key: {:?}", funnel20_number_of_wildcards, funnel30_number_of_wildcards, funnel40_number_of_wildcards, key);
@@ -444,7 +465,7 @@ impl RunMinerLoop {
                 return
             }
         };
-        let intersection: HashSet<&u32> = depends_on_program_ids.intersection(corresponding_program_id_set).collect();
+        let intersection: Vec<&u32> = corresponding_program_id_set.iter().filter(|program_id| depends_on_program_ids.contains(program_id)).collect();
         if !intersection.is_empty() {
             debug!("Ignoring self-dependency. There is this intersection: {:?}", intersection);
             self.metric.number_of_self_dependencies += 1;
@@ -459,7 +480,7 @@ impl RunMinerLoop {
         let mut maybe_a_new_program = false;
         let mut is_existing_program_with_better_performance = false;
         let mut priority = ProgramCandidatePriority::Low;
-        for program_id in corresponding_program_id_set {
+        for program_id in &corresponding_program_id_set {
             if self.context.is_program_id_invalid(*program_id) {
                 debug!("Keep. Maybe a new program. The program id {} is contained in 'programs_invalid.csv'", program_id);
                 self.genome.append_message(format!("keep: maybe a new program. The program id {} is contained in 'programs_invalid.csv'", program_id));
@@ -562,6 +583,9 @@ impl RunMinerLoop {
             if prevent_flooding.try_register(&terms40_original).is_err() {
                 debug!("already contained in prevent flooding dictionary");
             }
+            if prevent_flooding.try_register_program(&genome_canonical_form).is_err() {
+                debug!("a structurally identical program is already contained in prevent flooding dictionary");
+            }
         }
 
         if self.suppress_low_priority_programs {
@@ -0,0 +1,236 @@
+use loda_rust_core::unofficial_function::{UnofficialFunction, UnofficialFunctionId, UnofficialFunctionRegistry};
+use crate::common::parse_csv_data;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use serde::Deserialize;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+type FunctionIdAndWeight = (UnofficialFunctionId,u32);
+
+/// The unofficial functions that the miner can insert into a program.
+///
+/// Functions that are not suitable for mining, such as debug and assert functions, are ignored.
+///
+/// Usecase:
+/// During mining, when inserting a `f21 $0,1104` instruction, or replacing the function in such an instruction.
+///
+/// Before mining: analytics builds a histogram with how many times each function is used.
+///
+/// During mining: make weighted choices, so that the most used functions are picked more often.
+/// Every function has a weight of at least 1, so that unused functions also gets picked.
+#[derive(Clone, Debug)]
+pub struct UnofficialFunctionContainer {
+    function_id_and_weight_vec: Vec<FunctionIdAndWeight>,
+}
+
+impl UnofficialFunctionContainer {
+    pub fn new(registry: &UnofficialFunctionRegistry) -> Self {
+        // Sorted, so that the weighted choices are deterministic for a given random seed,
+        // no matter in what order the functions were registered.
+        let mut function_ids: Vec<UnofficialFunctionId> = registry.function_ids();
+        function_ids.sort();
+        let mut function_id_and_weight_vec = Vec::<FunctionIdAndWeight>::new();
+        for function_id in function_ids {
+            let unofficial_function: Arc<Box<dyn UnofficialFunction>> = match registry.lookup(function_id) {
+                Some(value) => value,
+                None => continue
+            };
+            if !unofficial_function.is_minable() {
+                continue;
+            }
+            function_id_and_weight_vec.push((function_id, 1));
+        }
+        Self {
+            function_id_and_weight_vec,
+        }
+    }
+
+    pub fn number_of_functions(&self) -> usize {
+        self.function_id_and_weight_vec.len()
+    }
+
+    pub fn load_histogram_csv_file(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        self.apply_histogram(&mut reader)
+    }
+
+    /// Increase the weight of the functions that are used in the histogram.
+    ///
+    /// Functions in the histogram that are not available are ignored.
+    fn apply_histogram(&mut self, reader: &mut dyn BufRead) -> Result<(), Box<dyn Error>> {
+        let records: Vec<Record> = parse_csv_data::<Record>(reader)?;
+        let mut function_id_to_count = HashMap::<UnofficialFunctionId,u32>::new();
+        for record in records {
+            let function_id = UnofficialFunctionId::InputOutput {
+                id: record.function_id,
+                inputs: record.inputs,
+                outputs: record.outputs,
+            };
+            function_id_to_count.insert(function_id, record.count);
+        }
+        for (function_id, weight) in self.function_id_and_weight_vec.iter_mut() {
+            if let Some(count) = function_id_to_count.get(function_id) {
+                *weight = count.saturating_add(1);
+            }
+        }
+        Ok(())
+    }
+
+    /// The functions with the same id, but with a different number of inputs or outputs.
+    /// 
+    /// Such as the `sum` function that exist with 2 inputs, 3 inputs, 4 inputs, etc.
+    pub fn variants(&self, function_id: UnofficialFunctionId) -> Vec<UnofficialFunctionId> {
+        let needle: u32 = match function_id {
+            UnofficialFunctionId::InputOutput { id, .. } => id
+        };
+        let mut result = Vec::<UnofficialFunctionId>::new();
+        for (candidate, _weight) in &self.function_id_and_weight_vec {
+            if *candidate == function_id {
+                continue;
+            }
+            match candidate {
+                UnofficialFunctionId::InputOutput { id, .. } => {
+                    if *id == needle {
+                        result.push(*candidate);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn choose_weighted<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<UnofficialFunctionId> {
+        let item: &FunctionIdAndWeight = self.function_id_and_weight_vec.choose_weighted(rng, |item| item.1).ok()?;
+        Some(item.0)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Record {
+    count: u32,
+    function_id: u32,
+    inputs: u8,
+    outputs: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::unofficial_function::{DebugFunction, SumFunction};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn create_registry() -> UnofficialFunctionRegistry {
+        let registry = UnofficialFunctionRegistry::new();
+        registry.register(Arc::new(Box::new(DebugFunction::new(1))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 2))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 3))));
+        registry
+    }
+
+    #[test]
+    fn test_10000_new_ignores_functions_that_are_not_minable() {
+        // Act
+        let instance = UnofficialFunctionContainer::new(&create_registry());
+
+        // Assert
+        let expected: Vec<FunctionIdAndWeight> = vec![
+            (UnofficialFunctionId::InputOutput { id: 1000, inputs: 2, outputs: 1 }, 1),
+            (UnofficialFunctionId::InputOutput { id: 1000, inputs: 3, outputs: 1 }, 1),
+        ];
+        assert_eq!(instance.function_id_and_weight_vec, expected);
+    }
+
+    #[test]
+    fn test_10001_new_is_sorted() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 3))));
+        registry.register(Arc::new(Box::new(SumFunction::new(900, 2))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 2))));
+
+        // Act
+        let instance = UnofficialFunctionContainer::new(&registry);
+
+        // Assert
+        let function_ids: Vec<UnofficialFunctionId> = instance.function_id_and_weight_vec.iter().map(|item| item.0).collect();
+        let expected: Vec<UnofficialFunctionId> = vec![
+            UnofficialFunctionId::InputOutput { id: 900, inputs: 2, outputs: 1 },
+            UnofficialFunctionId::InputOutput { id: 1000, inputs: 2, outputs: 1 },
+            UnofficialFunctionId::InputOutput { id: 1000, inputs: 3, outputs: 1 },
+        ];
+        assert_eq!(function_ids, expected);
+    }
+
+    #[test]
+    fn test_20000_apply_histogram() {
+        // Arrange
+        let data = "\
+count;function_id;inputs;outputs
+500;1000;3;1
+42;1;1;0
+7;5555;1;1
+";
+        let mut input: &[u8] = data.as_bytes();
+        let mut instance = UnofficialFunctionContainer::new(&create_registry());
+
+        // Act
+        instance.apply_histogram(&mut input).expect("ok");
+
+        // Assert
+        let expected: Vec<FunctionIdAndWeight> = vec![
+            (UnofficialFunctionId::InputOutput { id: 1000, inputs: 2, outputs: 1 }, 1),
+            (UnofficialFunctionId::InputOutput { id: 1000, inputs: 3, outputs: 1 }, 501),
+        ];
+        assert_eq!(instance.function_id_and_weight_vec, expected);
+    }
+
+    #[test]
+    fn test_20001_variants() {
+        // Arrange
+        let instance = UnofficialFunctionContainer::new(&create_registry());
+        let function_id = UnofficialFunctionId::InputOutput { id: 1000, inputs: 2, outputs: 1 };
+
+        // Act
+        let variants: Vec<UnofficialFunctionId> = instance.variants(function_id);
+
+        // Assert
+        let expected: Vec<UnofficialFunctionId> = vec![
+            UnofficialFunctionId::InputOutput { id: 1000, inputs: 3, outputs: 1 },
+        ];
+        assert_eq!(variants, expected);
+    }
+
+    #[test]
+    fn test_30000_choose_weighted() {
+        // Arrange
+        let instance = UnofficialFunctionContainer::new(&create_registry());
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Act
+        let function_id: UnofficialFunctionId = instance.choose_weighted(&mut rng).expect("function_id");
+
+        // Assert
+        let available: Vec<UnofficialFunctionId> = instance.function_id_and_weight_vec.iter().map(|item| item.0).collect();
+        assert_eq!(available.contains(&function_id), true);
+    }
+
+    #[test]
+    fn test_30001_choose_weighted_empty() {
+        // Arrange
+        let instance = UnofficialFunctionContainer::new(&UnofficialFunctionRegistry::new());
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Act
+        let function_id: Option<UnofficialFunctionId> = instance.choose_weighted(&mut rng);
+
+        // Assert
+        assert_eq!(function_id, None);
+    }
+}
@@ -0,0 +1,207 @@
+use loda_rust_core::oeis::OeisId;
+use loda_rust_core::control::DependencyManager;
+use loda_rust_core::util::BigIntVec;
+use num_bigint::BigInt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// The terms of an OEIS sequence, read from a b-file, such as `b000045.txt`.
+///
+/// The OEIS `stripped` file has only the first terms of each sequence, typically around 40 terms.
+/// A b-file has many more terms, often 1000 terms or more, and the index of each term.
+///
+/// The b-file format has one term per line, with the index and the value separated by whitespace.
+/// Lines starting with `#` are comments.
+///
+/// ```text
+/// # A000045: Fibonacci numbers
+/// 0 0
+/// 1 1
+/// 2 1
+/// 3 2
+/// ```
+///
+/// The index of the first term is the offset of the sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BFile {
+    oeis_id: OeisId,
+    offset: i64,
+    terms: BigIntVec,
+}
+
+impl BFile {
+    /// The filename of a b-file, such as `b000045.txt`.
+    pub fn filename(oeis_id: OeisId) -> String {
+        format!("b{:0>6}.txt", oeis_id.raw())
+    }
+
+    /// The b-file is either placed directly in the dir, like `dir/b000045.txt`,
+    /// or in a subdir, like `dir/000/b000045.txt`, the same way as the `loda-programs` repo.
+    pub fn find_path(bfile_dir: &Path, oeis_id: OeisId) -> Option<PathBuf> {
+        let filename: String = Self::filename(oeis_id);
+        let path: PathBuf = bfile_dir.join(&filename);
+        if path.is_file() {
+            return Some(path);
+        }
+        let dir_index_string: String = DependencyManager::dir_index_string(oeis_id.raw() as u64);
+        let path: PathBuf = bfile_dir.join(dir_index_string).join(&filename);
+        if path.is_file() {
+            return Some(path);
+        }
+        None
+    }
+
+    /// Load the b-file for the sequence.
+    ///
+    /// Returns `Ok(None)` when there is no b-file for the sequence.
+    pub fn load(bfile_dir: &Path, oeis_id: OeisId, max_term_count: Option<usize>) -> anyhow::Result<Option<Self>> {
+        let path: PathBuf = match Self::find_path(bfile_dir, oeis_id) {
+            Some(value) => value,
+            None => {
+                return Ok(None);
+            }
+        };
+        let file = File::open(&path)
+            .with_context(|| format!("Unable to open b-file: {:?}", path))?;
+        let mut reader = BufReader::new(file);
+        let bfile: BFile = Self::parse(&mut reader, oeis_id, max_term_count)
+            .with_context(|| format!("Unable to parse b-file: {:?}", path))?;
+        Ok(Some(bfile))
+    }
+
+    /// Parse the content of a b-file.
+    ///
+    /// The indexes must be consecutive. Reading stops after `max_term_count` terms.
+    pub fn parse(reader: &mut dyn BufRead, oeis_id: OeisId, max_term_count: Option<usize>) -> anyhow::Result<Self> {
+        let max_term_count_inner: usize = max_term_count.unwrap_or(usize::MAX);
+        let mut offset: Option<i64> = None;
+        let mut terms = BigIntVec::new();
+        for (line_index, line) in reader.lines().enumerate() {
+            if terms.len() >= max_term_count_inner {
+                break;
+            }
+            let line: String = line?;
+            let line_trimmed: &str = line.trim();
+            if line_trimmed.is_empty() || line_trimmed.starts_with('#') {
+                continue;
+            }
+            let line_number: usize = line_index + 1;
+            let mut iter = line_trimmed.split_whitespace();
+            let index_raw: &str = iter.next().unwrap_or("");
+            let value_raw: &str = iter.next()
+                .ok_or_else(|| anyhow::anyhow!("Expected index and value in line {}", line_number))?;
+            let index: i64 = index_raw.parse()
+                .with_context(|| format!("Unable to parse index in line {}", line_number))?;
+            let value: BigInt = BigInt::parse_bytes(value_raw.as_bytes(), 10)
+                .ok_or_else(|| anyhow::anyhow!("Unable to parse value in line {}", line_number))?;
+            let expected_index: i64 = *offset.get_or_insert(index) + (terms.len() as i64);
+            if index != expected_index {
+                return Err(anyhow::anyhow!("Expected index {}, but got {} in line {}", expected_index, index, line_number));
+            }
+            terms.push(value);
+        }
+        let offset: i64 = offset.ok_or_else(|| anyhow::anyhow!("The b-file contains no terms"))?;
+        Ok(Self {
+            oeis_id,
+            offset,
+            terms,
+        })
+    }
+
+    pub fn oeis_id(&self) -> OeisId {
+        self.oeis_id
+    }
+
+    /// The index of the first term.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn terms(&self) -> &BigIntVec {
+        &self.terms
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// The index after the last term.
+    pub fn end_index(&self) -> i64 {
+        self.offset + (self.terms.len() as i64)
+    }
+
+    /// The term `a(index)`, if the index is inside the b-file.
+    pub fn term(&self, index: i64) -> Option<&BigInt> {
+        if index < self.offset {
+            return None;
+        }
+        self.terms.get((index - self.offset) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse(input: &str, max_term_count: Option<usize>) -> anyhow::Result<BFile> {
+        let mut reader: &[u8] = input.as_bytes();
+        BFile::parse(&mut reader, OeisId::from(45), max_term_count)
+    }
+
+    #[test]
+    fn test_10000_parse() {
+        // Arrange
+        let input = "# A000045: Fibonacci numbers\n# comment\n\n1 1\n2  1\n3\t2\n4 3\n5 5\n";
+
+        // Act
+        let bfile: BFile = parse(input, None).expect("bfile");
+
+        // Assert
+        assert_eq!(bfile.offset(), 1);
+        assert_eq!(bfile.len(), 5);
+        assert_eq!(bfile.end_index(), 6);
+        assert_eq!(bfile.term(0), None);
+        assert_eq!(bfile.term(1), Some(&BigInt::from(1)));
+        assert_eq!(bfile.term(5), Some(&BigInt::from(5)));
+        assert_eq!(bfile.term(6), None);
+    }
+
+    #[test]
+    fn test_10001_parse_negative_offset_and_limit() {
+        let bfile: BFile = parse("-2 7\n-1 -8\n0 9\n1 10\n", Some(3)).expect("bfile");
+        assert_eq!(bfile.offset(), -2);
+        assert_eq!(bfile.len(), 3);
+        assert_eq!(bfile.term(-1), Some(&BigInt::from(-8)));
+        assert_eq!(bfile.term(1), None);
+    }
+
+    #[test]
+    fn test_10002_parse_error() {
+        assert_eq!(parse("", None).is_err(), true);
+        assert_eq!(parse("# just a comment\n", None).is_err(), true);
+        assert_eq!(parse("0 1\n2 1\n", None).is_err(), true);
+        assert_eq!(parse("0\n", None).is_err(), true);
+        assert_eq!(parse("0 x\n", None).is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_load_from_subdir() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_20000_load_from_subdir");
+        let dir123: PathBuf = basedir.join("123");
+        fs::create_dir_all(&dir123).expect("ok");
+        fs::write(dir123.join("b123456.txt"), "0 42\n").expect("ok");
+
+        // Act
+        let bfile: Option<BFile> = BFile::load(&basedir, OeisId::from(123456), None).expect("ok");
+        let missing: Option<BFile> = BFile::load(&basedir, OeisId::from(45), None).expect("ok");
+
+        // Assert
+        assert_eq!(bfile.expect("bfile").term(0), Some(&BigInt::from(42)));
+        assert_eq!(missing, None);
+    }
+}
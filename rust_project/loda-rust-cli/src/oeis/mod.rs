@@ -1,12 +1,22 @@
-//! OEIS code for A-numbers, parsing of the `stripped` file and the `names` file.
+//! OEIS code for A-numbers, parsing of the `stripped` file, the `names` file, the b-files, the offsets and the keywords.
+mod bfile;
 mod name_row;
+mod oeis_keywords;
+mod oeis_offsets;
 mod process_names_file;
 mod process_stripped_file;
 mod stripped_row;
+#[cfg(test)]
 mod terms_to_program_id;
+mod terms_to_program_id_file;
 
+pub use bfile::BFile;
 pub use name_row::NameRow;
+pub use oeis_keywords::{OeisKeyword, OeisKeywords};
+pub use oeis_offsets::OeisOffsets;
 pub use process_names_file::ProcessNamesFile;
 pub use process_stripped_file::ProcessStrippedFile;
 pub use stripped_row::StrippedRow;
+#[cfg(test)]
 pub use terms_to_program_id::{TermsToProgramIdSet, load_terms_to_program_id_set};
+pub use terms_to_program_id_file::TermsToProgramIdFile;
@@ -0,0 +1,291 @@
+use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+use regex::Regex;
+use lazy_static::lazy_static;
+use anyhow::Context;
+
+lazy_static! {
+    /// Extract the sequence number and the keywords from a row like this "A000045 core,easy,nice,nonn".
+    static ref EXTRACT_KEYWORDS_ROW: Regex = Regex::new(
+        "^A(\\d+)\\s+([a-z,]+)\\s*$"
+    ).unwrap();
+}
+
+/// Keyword from the `%K` line of an OEIS sequence.
+///
+/// See <https://oeis.org/eishelp2.html#RK>
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OeisKeyword {
+    Base,
+    Bref,
+    Cofr,
+    Cons,
+    Core,
+    Dead,
+    Dumb,
+    Easy,
+    Eigen,
+    Fini,
+    Frac,
+    Full,
+    Hard,
+    Hear,
+    Less,
+    Look,
+    More,
+    Mult,
+    Nice,
+    Nonn,
+    Obsc,
+    Sign,
+    Tabf,
+    Tabl,
+    Uned,
+    Unkn,
+    Walk,
+    Word,
+}
+
+impl OeisKeyword {
+    /// Sequences that makes no sense mining for.
+    ///
+    /// - `dead` is an erroneous or duplicate sequence.
+    /// - `dumb` is an unimportant sequence.
+    /// - `fini` is a finite sequence, where a program rarely makes sense.
+    pub const DONT_MINE: [OeisKeyword; 3] = [OeisKeyword::Dead, OeisKeyword::Dumb, OeisKeyword::Fini];
+
+    /// Sequences that are more important than the rest, and should be mined more often.
+    pub const PRIORITY: [OeisKeyword; 2] = [OeisKeyword::Core, OeisKeyword::Nice];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Base => "base",
+            Self::Bref => "bref",
+            Self::Cofr => "cofr",
+            Self::Cons => "cons",
+            Self::Core => "core",
+            Self::Dead => "dead",
+            Self::Dumb => "dumb",
+            Self::Easy => "easy",
+            Self::Eigen => "eigen",
+            Self::Fini => "fini",
+            Self::Frac => "frac",
+            Self::Full => "full",
+            Self::Hard => "hard",
+            Self::Hear => "hear",
+            Self::Less => "less",
+            Self::Look => "look",
+            Self::More => "more",
+            Self::Mult => "mult",
+            Self::Nice => "nice",
+            Self::Nonn => "nonn",
+            Self::Obsc => "obsc",
+            Self::Sign => "sign",
+            Self::Tabf => "tabf",
+            Self::Tabl => "tabl",
+            Self::Uned => "uned",
+            Self::Unkn => "unkn",
+            Self::Walk => "walk",
+            Self::Word => "word",
+        }
+    }
+}
+
+impl FromStr for OeisKeyword {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keyword: OeisKeyword = match s {
+            "base" => Self::Base,
+            "bref" => Self::Bref,
+            "cofr" => Self::Cofr,
+            "cons" => Self::Cons,
+            "core" => Self::Core,
+            "dead" => Self::Dead,
+            "dumb" => Self::Dumb,
+            "easy" => Self::Easy,
+            "eigen" => Self::Eigen,
+            "fini" => Self::Fini,
+            "frac" => Self::Frac,
+            "full" => Self::Full,
+            "hard" => Self::Hard,
+            "hear" => Self::Hear,
+            "less" => Self::Less,
+            "look" => Self::Look,
+            "more" => Self::More,
+            "mult" => Self::Mult,
+            "nice" => Self::Nice,
+            "nonn" => Self::Nonn,
+            "obsc" => Self::Obsc,
+            "sign" => Self::Sign,
+            "tabf" => Self::Tabf,
+            "tabl" => Self::Tabl,
+            "uned" => Self::Uned,
+            "unkn" => Self::Unkn,
+            "walk" => Self::Walk,
+            "word" => Self::Word,
+            _ => {
+                return Err(anyhow::anyhow!("Unrecognized OEIS keyword: {:?}", s));
+            }
+        };
+        Ok(keyword)
+    }
+}
+
+/// The keywords of each OEIS sequence.
+///
+/// The file has one row per sequence, in the same format as the `%K` line of the OEIS internal format.
+/// Unrecognized keywords are ignored, since OEIS may introduce new keywords.
+///
+/// ```text
+/// # comment
+/// A000040 core,nonn,nice,easy
+/// A000045 core,nonn,nice,easy,hear,changed
+/// A001057 sign,easy
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OeisKeywords {
+    oeis_id_to_keywords: HashMap<OeisId, Vec<OeisKeyword>>,
+}
+
+impl OeisKeywords {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open keywords file: {:?}", path))?;
+        let mut reader = BufReader::new(file);
+        Self::parse(&mut reader)
+            .with_context(|| format!("Unable to parse keywords file: {:?}", path))
+    }
+
+    /// The keywords file is optional. When it doesn't exist, there are no keywords.
+    pub fn load_if_exists(path: &Path) -> anyhow::Result<Self> {
+        if !path.is_file() {
+            debug!("No keywords file at {:?}", path);
+            return Ok(Self::new());
+        }
+        Self::load(path)
+    }
+
+    pub fn parse(reader: &mut dyn BufRead) -> anyhow::Result<Self> {
+        let re = &EXTRACT_KEYWORDS_ROW;
+        let mut instance = Self::new();
+        for (line_index, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            let line_trimmed: &str = line.trim();
+            if line_trimmed.is_empty() || line_trimmed.starts_with('#') {
+                continue;
+            }
+            let line_number: usize = line_index + 1;
+            let captures = re.captures(line_trimmed)
+                .ok_or_else(|| anyhow::anyhow!("Expected a row like 'A000045 core,nice' in line {}", line_number))?;
+            let capture1: &str = captures.get(1).map_or("", |m| m.as_str());
+            let capture2: &str = captures.get(2).map_or("", |m| m.as_str());
+            let sequence_number: u32 = capture1.parse()
+                .with_context(|| format!("Unable to parse sequence number in line {}", line_number))?;
+            let keywords: Vec<OeisKeyword> = capture2
+                .split(',')
+                .filter_map(|keyword| OeisKeyword::from_str(keyword).ok())
+                .collect();
+            instance.insert(OeisId::from(sequence_number), keywords);
+        }
+        Ok(instance)
+    }
+
+    pub fn insert(&mut self, oeis_id: OeisId, keywords: Vec<OeisKeyword>) {
+        self.oeis_id_to_keywords.insert(oeis_id, keywords);
+    }
+
+    /// Returns `None` when the sequence isn't in the keywords file.
+    pub fn get(&self, oeis_id: OeisId) -> Option<&Vec<OeisKeyword>> {
+        self.oeis_id_to_keywords.get(&oeis_id)
+    }
+
+    pub fn has_keyword(&self, oeis_id: OeisId, keyword: OeisKeyword) -> bool {
+        match self.get(oeis_id) {
+            Some(keywords) => keywords.contains(&keyword),
+            None => false
+        }
+    }
+
+    /// The sequences that have one or more of the keywords.
+    pub fn oeis_ids_with_any_keyword(&self, keywords: &[OeisKeyword]) -> OeisIdHashSet {
+        let mut result = OeisIdHashSet::new();
+        for (oeis_id, sequence_keywords) in &self.oeis_id_to_keywords {
+            if sequence_keywords.iter().any(|keyword| keywords.contains(keyword)) {
+                result.insert(*oeis_id);
+            }
+        }
+        result
+    }
+
+    /// Comma separated keywords, such as `core,nonn,nice`, or an empty string when the sequence isn't in the keywords file.
+    pub fn keywords_string(&self, oeis_id: OeisId) -> String {
+        match self.get(oeis_id) {
+            Some(keywords) => keywords.iter().map(|keyword| keyword.as_str()).collect::<Vec<&str>>().join(","),
+            None => String::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.oeis_id_to_keywords.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> OeisKeywords {
+        let mut reader: &[u8] = input.as_bytes();
+        OeisKeywords::parse(&mut reader).expect("keywords")
+    }
+
+    #[test]
+    fn test_10000_parse() {
+        // Arrange
+        let input = "# comment\n\nA000040 core,nonn,nice,easy\nA000045 core,nonn,nice,easy,hear,changed\nA001057 sign,easy\n";
+
+        // Act
+        let keywords: OeisKeywords = parse(input);
+
+        // Assert
+        assert_eq!(keywords.len(), 3);
+        assert_eq!(keywords.has_keyword(OeisId::from(40), OeisKeyword::Core), true);
+        assert_eq!(keywords.has_keyword(OeisId::from(1057), OeisKeyword::Sign), true);
+        assert_eq!(keywords.has_keyword(OeisId::from(1057), OeisKeyword::Nonn), false);
+        assert_eq!(keywords.has_keyword(OeisId::from(1), OeisKeyword::Nonn), false);
+        assert_eq!(keywords.keywords_string(OeisId::from(45)), "core,nonn,nice,easy,hear");
+        assert_eq!(keywords.keywords_string(OeisId::from(1)), "");
+    }
+
+    #[test]
+    fn test_10001_parse_error() {
+        let mut reader: &[u8] = "A000045\n".as_bytes();
+        assert_eq!(OeisKeywords::parse(&mut reader).is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_oeis_ids_with_any_keyword() {
+        // Arrange
+        let keywords: OeisKeywords = parse("A000001 nonn,dead\nA000002 nonn,fini,full\nA000003 nonn,nice\nA000004 sign,dumb\n");
+
+        // Act
+        let dont_mine: OeisIdHashSet = keywords.oeis_ids_with_any_keyword(&OeisKeyword::DONT_MINE);
+        let priority: OeisIdHashSet = keywords.oeis_ids_with_any_keyword(&OeisKeyword::PRIORITY);
+
+        // Assert
+        let mut dont_mine_sorted: Vec<u32> = dont_mine.iter().map(|oeis_id| oeis_id.raw()).collect();
+        dont_mine_sorted.sort();
+        assert_eq!(dont_mine_sorted, vec![1, 2, 4]);
+        let priority_vec: Vec<u32> = priority.iter().map(|oeis_id| oeis_id.raw()).collect();
+        assert_eq!(priority_vec, vec![3]);
+    }
+}
@@ -0,0 +1,110 @@
+use loda_rust_core::oeis::OeisId;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use regex::Regex;
+use lazy_static::lazy_static;
+use anyhow::Context;
+
+lazy_static! {
+    /// Extract the sequence number and the offset from a row like this "%O A000045 0,4".
+    static ref EXTRACT_OFFSET_ROW: Regex = Regex::new(
+        "^%O\\s+A(\\d+)\\s+(-?\\d+)(?:,\\s*\\d+)?\\s*$"
+    ).unwrap();
+}
+
+/// The offset of each OEIS sequence, which is the index of the first term.
+///
+/// The file is in the OEIS internal format, where the `%O` line has the offset of the sequence.
+/// The second number, the position of the first term greater than 1 in absolute value, is optional and ignored.
+/// The other lines, such as `%N` with the name and `%S` with the terms, are ignored.
+///
+/// ```text
+/// %I A000040 M0652 N0241
+/// %S A000040 2,3,5,7,11,13,17,19,23,29,31,37,41,43,47,53,59,61,67,71,
+/// %N A000040 The prime numbers.
+/// %O A000040 1,1
+/// %O A000045 0,4
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OeisOffsets {
+    oeis_id_to_offset: HashMap<OeisId, i64>,
+}
+
+impl OeisOffsets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open offsets file: {:?}", path))?;
+        let mut reader = BufReader::new(file);
+        Self::parse(&mut reader)
+            .with_context(|| format!("Unable to parse offsets file: {:?}", path))
+    }
+
+    pub fn parse(reader: &mut dyn BufRead) -> anyhow::Result<Self> {
+        let re = &EXTRACT_OFFSET_ROW;
+        let mut instance = Self::new();
+        for (line_index, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            let line_trimmed: &str = line.trim();
+            if !line_trimmed.starts_with("%O") {
+                continue;
+            }
+            let line_number: usize = line_index + 1;
+            let captures = re.captures(line_trimmed)
+                .ok_or_else(|| anyhow::anyhow!("Expected a row like '%O A000045 0,4' in line {}", line_number))?;
+            let capture1: &str = captures.get(1).map_or("", |m| m.as_str());
+            let capture2: &str = captures.get(2).map_or("", |m| m.as_str());
+            let sequence_number: u32 = capture1.parse()
+                .with_context(|| format!("Unable to parse sequence number in line {}", line_number))?;
+            let offset: i64 = capture2.parse()
+                .with_context(|| format!("Unable to parse offset in line {}", line_number))?;
+            instance.insert(OeisId::from(sequence_number), offset);
+        }
+        Ok(instance)
+    }
+
+    pub fn insert(&mut self, oeis_id: OeisId, offset: i64) {
+        self.oeis_id_to_offset.insert(oeis_id, offset);
+    }
+
+    pub fn get(&self, oeis_id: OeisId) -> Option<i64> {
+        self.oeis_id_to_offset.get(&oeis_id).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.oeis_id_to_offset.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_parse() {
+        // Arrange
+        let input = "%I A000040 M0652 N0241\n%S A000040 2,3,5,7,11,13,17,19,23,29,\n%N A000040 The prime numbers.\n%O A000040 1,1\n\n%O A000045 0,4\n%O A123456 -3\n";
+        let mut reader: &[u8] = input.as_bytes();
+
+        // Act
+        let offsets: OeisOffsets = OeisOffsets::parse(&mut reader).expect("offsets");
+
+        // Assert
+        assert_eq!(offsets.len(), 3);
+        assert_eq!(offsets.get(OeisId::from(40)), Some(1));
+        assert_eq!(offsets.get(OeisId::from(45)), Some(0));
+        assert_eq!(offsets.get(OeisId::from(123456)), Some(-3));
+        assert_eq!(offsets.get(OeisId::from(1)), None);
+    }
+
+    #[test]
+    fn test_10001_parse_error() {
+        let mut reader: &[u8] = "%O A000045 zero\n".as_bytes();
+        assert_eq!(OeisOffsets::parse(&mut reader).is_err(), true);
+    }
+}
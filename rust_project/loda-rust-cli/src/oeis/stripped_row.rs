@@ -22,8 +22,12 @@ use loda_rust_core::util::BigIntVec;
 /// Half of the sequences are shorter than or equal to 38 terms.
 /// 
 /// The other half of the sequences are longer than 38 terms.
+///
+/// The `stripped` file doesn't contain the offset of the sequence, so the offset is `0`,
+/// unless it's assigned with `set_offset()`, such as from the `OeisOffsets`.
 pub struct StrippedRow {
     oeis_id: OeisId,
+    offset: i64,
     terms: BigIntVec,
 }
 
@@ -35,6 +39,7 @@ impl StrippedRow {
     pub fn new(oeis_id: OeisId, terms: BigIntVec) -> Self {
         Self {
             oeis_id: oeis_id,
+            offset: 0,
             terms: terms,
         }
     }
@@ -43,10 +48,22 @@ impl StrippedRow {
         self.oeis_id
     }
 
+    pub fn set_offset(&mut self, offset: i64) {
+        self.offset = offset;
+    }
+
     pub fn terms(&self) -> &BigIntVec {
         &self.terms
     }
 
+    /// The term `a(index)`, if the index is inside the row.
+    pub fn term(&self, index: i64) -> Option<&BigInt> {
+        if index < self.offset {
+            return None;
+        }
+        self.terms.get((index - self.offset) as usize)
+    }
+
     pub fn len(&self) -> usize {
         self.terms.len()
     }
@@ -225,4 +242,21 @@ A000040 ,2,3,5,7,11,13,17,19,23,29,31,37,41,43,47,53,59,61,67,
         // Assert
         assert_eq!(row.to_string(), "A000040 2,3,5,7,11,13,17,19,23,0,0,0,0,0,0,0,0,0,0,0");
     }
+
+    #[test]
+    fn test_10003_offset() {
+        // Arrange
+        let input = "A000040 ,2,3,5,7,11,";
+        let mut row: StrippedRow = StrippedRow::parse(&input.to_string(), None).unwrap();
+        assert_eq!(row.term(0), Some(&BigInt::from(2)));
+
+        // Act
+        row.set_offset(1);
+
+        // Assert
+        assert_eq!(row.term(0), None);
+        assert_eq!(row.term(1), Some(&BigInt::from(2)));
+        assert_eq!(row.term(5), Some(&BigInt::from(11)));
+        assert_eq!(row.term(6), None);
+    }
 }
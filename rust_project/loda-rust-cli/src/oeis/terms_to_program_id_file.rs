@@ -0,0 +1,391 @@
+use loda_rust_core::util::BigIntVecToString;
+use loda_rust_core::oeis::OeisIdHashSet;
+use super::{ProcessStrippedFile, StrippedRow};
+use crate::common::fnv1a_hash;
+use memmap2::Mmap;
+use num_bigint::BigInt;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::Path;
+use anyhow::Context;
+
+/// Compact alternative to `TermsToProgramIdSet`, that can be memory mapped.
+///
+/// The `TermsToProgramIdSet` is a `HashMap` with the comma separated terms of every sequence as key,
+/// which uses lots of memory. Instead this stores a 64 bit hash of the comma separated terms,
+/// and the program ids as a sorted array of `u32`.
+///
+/// The file is created during analytics, and the miner memory maps it, so the operating system
+/// can share the pages between the miner processes, and evict them when memory is scarce.
+///
+/// File format, all the integers are little endian:
+///
+/// ```text
+/// "LODATTPI"                         magic, 8 bytes
+/// version                            u32
+/// number of entries                  u32
+/// entries sorted by hash             (hash u64, index of first program id u32, number of program ids u32)
+/// program ids                        u32
+/// ```
+///
+/// Two different keys with the same hash, share their program ids.
+/// With around 400k keys, the probability of a collision is around 1 in 200 million.
+/// The caller verifies the program ids anyways, so a collision yields a false positive, not a wrong result.
+pub struct TermsToProgramIdFile {
+    storage: Storage,
+    entry_count: usize,
+}
+
+enum Storage {
+    Mmap(Mmap),
+    Bytes(Vec<u8>),
+}
+
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Mmap(mmap) => mmap,
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl TermsToProgramIdFile {
+    const MAGIC: &'static [u8; 8] = b"LODATTPI";
+    const VERSION: u32 = 1;
+    const HEADER_SIZE: usize = 16;
+    const ENTRY_SIZE: usize = 16;
+
+    /// Without any terms. All lookups returns `None`.
+    pub fn empty() -> Self {
+        Self::from_bytes(Self::serialize(vec!())).expect("empty file is valid")
+    }
+
+    /// Memory map the file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open terms_to_program_id file: {:?}", path))?;
+        // Safety: the file is only written by analytics, which writes to a temp file and renames it in place.
+        let mmap: Mmap = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("Unable to memory map terms_to_program_id file: {:?}", path))?;
+        Self::from_storage(Storage::Mmap(mmap))
+            .with_context(|| format!("Invalid terms_to_program_id file: {:?}", path))
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        Self::from_storage(Storage::Bytes(bytes))
+    }
+
+    fn from_storage(storage: Storage) -> anyhow::Result<Self> {
+        let bytes: &[u8] = storage.bytes();
+        if bytes.len() < Self::HEADER_SIZE || &bytes[0..8] != Self::MAGIC {
+            return Err(anyhow::anyhow!("Expected the file to start with {:?}", Self::MAGIC));
+        }
+        let version: u32 = read_u32(bytes, 8);
+        if version != Self::VERSION {
+            return Err(anyhow::anyhow!("Unsupported version {}, expected version {}", version, Self::VERSION));
+        }
+        let entry_count: usize = read_u32(bytes, 12) as usize;
+        let ids_offset: usize = Self::HEADER_SIZE + entry_count * Self::ENTRY_SIZE;
+        if bytes.len() < ids_offset || !(bytes.len() - ids_offset).is_multiple_of(4) {
+            return Err(anyhow::anyhow!("The file is truncated"));
+        }
+        let id_count: usize = (bytes.len() - ids_offset) / 4;
+        let instance = Self {
+            storage,
+            entry_count,
+        };
+        for index in 0..entry_count {
+            let (_hash, start, count) = instance.entry(index);
+            if start + count > id_count {
+                return Err(anyhow::anyhow!("Entry {} refers to program ids beyond the end of the file", index));
+            }
+        }
+        Ok(instance)
+    }
+
+    /// Number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Size of the file in bytes.
+    pub fn byte_count(&self) -> usize {
+        self.storage.bytes().len()
+    }
+
+    /// The program ids of the sequences that starts with these terms, sorted ascending.
+    ///
+    /// The `key` is the comma separated terms, the same key as used by `TermsToProgramIdSet`.
+    pub fn get(&self, key: &str) -> Option<Vec<u32>> {
+        let hash: u64 = fnv1a_hash(key);
+        let mut low: usize = 0;
+        let mut high: usize = self.entry_count;
+        while low < high {
+            let middle: usize = low + (high - low) / 2;
+            let (middle_hash, start, count) = self.entry(middle);
+            if middle_hash < hash {
+                low = middle + 1;
+            } else if middle_hash > hash {
+                high = middle;
+            } else {
+                return Some(self.program_ids(start, count));
+            }
+        }
+        None
+    }
+
+    fn entry(&self, index: usize) -> (u64, usize, usize) {
+        let bytes: &[u8] = self.storage.bytes();
+        let offset: usize = Self::HEADER_SIZE + index * Self::ENTRY_SIZE;
+        let hash: u64 = read_u64(bytes, offset);
+        let start: usize = read_u32(bytes, offset + 8) as usize;
+        let count: usize = read_u32(bytes, offset + 12) as usize;
+        (hash, start, count)
+    }
+
+    fn program_ids(&self, start: usize, count: usize) -> Vec<u32> {
+        let bytes: &[u8] = self.storage.bytes();
+        let offset: usize = Self::HEADER_SIZE + self.entry_count * Self::ENTRY_SIZE + start * 4;
+        (0..count).map(|index| read_u32(bytes, offset + index * 4)).collect()
+    }
+
+    /// Read the OEIS `stripped` file, one row at a time, and serialize the hashed keys and the program ids.
+    pub fn build(
+        oeis_stripped_file_reader: &mut dyn io::BufRead,
+        minimum_number_of_required_terms: usize,
+        term_count: usize,
+        padding_value: &BigInt,
+    ) -> Vec<u8> {
+        let mut hash_and_program_id = Vec::<(u64, u32)>::new();
+        let callback = |row: &StrippedRow, _| {
+            let key: String = row.terms().to_compact_comma_string();
+            hash_and_program_id.push((fnv1a_hash(&key), row.oeis_id().raw()));
+        };
+        let mut processor = ProcessStrippedFile::new();
+        let oeis_ids_to_ignore = OeisIdHashSet::new();
+        processor.execute(
+            oeis_stripped_file_reader,
+            minimum_number_of_required_terms,
+            term_count,
+            &oeis_ids_to_ignore,
+            padding_value,
+            true,
+            callback
+        );
+        Self::serialize(hash_and_program_id)
+    }
+
+    /// Create the file from the OEIS `stripped` file.
+    ///
+    /// The content is written to a temp file, that is renamed in place,
+    /// so that a miner that has the old file memory mapped, is not affected.
+    pub fn create(
+        oeis_stripped_file: &Path,
+        output_path: &Path,
+        minimum_number_of_required_terms: usize,
+        term_count: usize,
+        padding_value: &BigInt,
+    ) -> anyhow::Result<()> {
+        let file = File::open(oeis_stripped_file)
+            .with_context(|| format!("Unable to open the OEIS stripped file: {:?}", oeis_stripped_file))?;
+        let mut reader = BufReader::new(file);
+        let bytes: Vec<u8> = Self::build(&mut reader, minimum_number_of_required_terms, term_count, padding_value);
+        let temp_path = output_path.with_extension("tmp");
+        fs::write(&temp_path, &bytes)
+            .with_context(|| format!("Unable to write terms_to_program_id file: {:?}", temp_path))?;
+        fs::rename(&temp_path, output_path)
+            .with_context(|| format!("Unable to rename {:?} to {:?}", temp_path, output_path))?;
+        Ok(())
+    }
+
+    fn serialize(mut hash_and_program_id: Vec<(u64, u32)>) -> Vec<u8> {
+        hash_and_program_id.sort_unstable();
+        hash_and_program_id.dedup();
+
+        // Group the program ids by hash
+        let mut entries = Vec::<(u64, u32, u32)>::new();
+        let mut program_ids = Vec::<u32>::with_capacity(hash_and_program_id.len());
+        for (hash, program_id) in hash_and_program_id {
+            match entries.last_mut() {
+                Some(entry) if entry.0 == hash => {
+                    entry.2 += 1;
+                },
+                _ => {
+                    entries.push((hash, program_ids.len() as u32, 1));
+                }
+            }
+            program_ids.push(program_id);
+        }
+
+        let capacity: usize = Self::HEADER_SIZE + entries.len() * Self::ENTRY_SIZE + program_ids.len() * 4;
+        let mut bytes = Vec::<u8>::with_capacity(capacity);
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.extend_from_slice(&Self::VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (hash, start, count) in &entries {
+            bytes.extend_from_slice(&hash.to_le_bytes());
+            bytes.extend_from_slice(&start.to_le_bytes());
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+        for program_id in &program_ids {
+            bytes.extend_from_slice(&program_id.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oeis::{TermsToProgramIdSet, load_terms_to_program_id_set};
+    use num_traits::Zero;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    const INPUT_STRIPPED_SEQUENCE_MOCKDATA: &str = r#"
+# OEIS Sequence Data (http://oeis.org/stripped.gz)
+A000040 ,2,3,5,7,11,13,17,19,23,29,31,37,41,43,47,53,59,61,67,71,
+A000045 ,0,1,1,2,3,5,8,13,21,34,55,89,144,233,377,610,987,1597,2584,4181,6765,
+A112088 ,2,3,5,7,11,16,24,36,54,81,122,183,274,411,617,925,1388,2082,3123,4684,
+A117093 ,2,3,5,7,11,13,16,17,18,19,23,28,29,30,31,37,38,39,40,41,43,47,53,58,59,
+"#;
+
+    fn build(input: &str, term_count: usize) -> TermsToProgramIdFile {
+        let mut reader: &[u8] = input.as_bytes();
+        let bytes: Vec<u8> = TermsToProgramIdFile::build(&mut reader, 0, term_count, &BigInt::zero());
+        TermsToProgramIdFile::from_bytes(bytes).expect("valid")
+    }
+
+    #[test]
+    fn test_10000_get() {
+        // Act
+        let instance: TermsToProgramIdFile = build(INPUT_STRIPPED_SEQUENCE_MOCKDATA, 5);
+
+        // Assert
+        assert_eq!(instance.len(), 2);
+        assert_eq!(instance.get("2,3,5,7,11"), Some(vec![40, 112088, 117093]));
+        assert_eq!(instance.get("0,1,1,2,3"), Some(vec![45]));
+        assert_eq!(instance.get("non-existing"), None);
+    }
+
+    #[test]
+    fn test_10001_empty() {
+        let instance = TermsToProgramIdFile::empty();
+        assert_eq!(instance.len(), 0);
+        assert_eq!(instance.get("2,3,5,7,11"), None);
+    }
+
+    #[test]
+    fn test_10002_invalid_bytes() {
+        assert_eq!(TermsToProgramIdFile::from_bytes(vec!()).is_err(), true);
+        assert_eq!(TermsToProgramIdFile::from_bytes(b"LODATTPI\x02\x00\x00\x00\x00\x00\x00\x00".to_vec()).is_err(), true);
+        assert_eq!(TermsToProgramIdFile::from_bytes(b"LODATTPI\x01\x00\x00\x00\x01\x00\x00\x00".to_vec()).is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_create_and_load() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_20000_create_and_load");
+        fs::create_dir(&basedir)?;
+        let stripped_path: PathBuf = basedir.join("stripped");
+        fs::write(&stripped_path, INPUT_STRIPPED_SEQUENCE_MOCKDATA)?;
+        let output_path: PathBuf = basedir.join("terms_to_program_id.bin");
+
+        // Act
+        TermsToProgramIdFile::create(&stripped_path, &output_path, 0, 6, &BigInt::zero())?;
+        let instance: TermsToProgramIdFile = TermsToProgramIdFile::load(&output_path)?;
+
+        // Assert
+        assert_eq!(instance.len(), 3);
+        assert_eq!(instance.get("2,3,5,7,11,13"), Some(vec![40, 117093]));
+        assert_eq!(basedir.join("terms_to_program_id.tmp").exists(), false);
+        Ok(())
+    }
+
+    /// Approximate number of bytes used by the `HashMap`, including the keys and the `HashSet`'s.
+    fn approximate_heap_size(dict: &TermsToProgramIdSet) -> usize {
+        let mut size: usize = dict.capacity() * (std::mem::size_of::<String>() + std::mem::size_of::<HashSet<u32>>() + 1);
+        for (key, value) in dict {
+            size += key.capacity();
+            size += value.capacity() * (std::mem::size_of::<u32>() + 1);
+        }
+        size
+    }
+
+    /// Compare memory usage and lookup speed of `TermsToProgramIdSet` and `TermsToProgramIdFile`.
+    ///
+    /// Run it like this:
+    /// ```text
+    /// cargo test --release -p loda-rust-cli -- --ignored --nocapture test_90000_benchmark
+    /// ```
+    #[test]
+    #[ignore]
+    fn test_90000_benchmark() -> anyhow::Result<()> {
+        // Arrange
+        let sequence_count: usize = 300000;
+        let term_count: usize = 40;
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut content = String::new();
+        for index in 0..sequence_count {
+            content += &format!("A{:0>6} ,", index + 1);
+            for _ in 0..term_count {
+                content += &format!("{},", rng.gen_range(0..1000000u32));
+            }
+            content += "\n";
+        }
+        let tempdir = tempfile::tempdir().unwrap();
+        let stripped_path: PathBuf = PathBuf::from(&tempdir.path()).join("stripped");
+        fs::write(&stripped_path, &content)?;
+        let output_path: PathBuf = PathBuf::from(&tempdir.path()).join("terms_to_program_id.bin");
+        let keys: Vec<String> = content.lines().take(10000).map(|line| {
+            line.split_once(" ,").unwrap().1.trim_end_matches(',').to_string()
+        }).collect();
+        let padding_value = BigInt::zero();
+
+        // Act
+        let start = Instant::now();
+        let dict: TermsToProgramIdSet = load_terms_to_program_id_set(&stripped_path, 10, term_count, &padding_value)
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        let elapsed_hashmap_build = start.elapsed();
+
+        let start = Instant::now();
+        TermsToProgramIdFile::create(&stripped_path, &output_path, 10, term_count, &padding_value)?;
+        let compact: TermsToProgramIdFile = TermsToProgramIdFile::load(&output_path)?;
+        let elapsed_compact_build = start.elapsed();
+
+        let start = Instant::now();
+        let mut count_hashmap: usize = 0;
+        for key in &keys {
+            count_hashmap += dict.get(key).map_or(0, |ids| ids.len());
+        }
+        let elapsed_hashmap_lookup = start.elapsed();
+
+        let start = Instant::now();
+        let mut count_compact: usize = 0;
+        for key in &keys {
+            count_compact += compact.get(key).map_or(0, |ids| ids.len());
+        }
+        let elapsed_compact_lookup = start.elapsed();
+
+        // Assert
+        assert_eq!(count_hashmap, keys.len());
+        assert_eq!(count_compact, keys.len());
+        println!("sequences: {}", sequence_count);
+        println!("HashMap: approx {} bytes, build {:?}, {} lookups {:?}", approximate_heap_size(&dict), elapsed_hashmap_build, keys.len(), elapsed_hashmap_lookup);
+        println!("Compact: {} bytes, build {:?}, {} lookups {:?}", compact.byte_count(), elapsed_compact_build, keys.len(), elapsed_compact_lookup);
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Context;
 use loda_rust_core::util::BigIntVec;
 use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
-use crate::postmine::{PathUtil, PostMineError};
+use crate::postmine::{PathUtil, PostMineError, RejectReason, RejectReasonSidecar};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::fmt;
@@ -28,6 +28,11 @@ pub struct CandidateProgram {
     possible_ids: OeisIdHashSet,
     keep_ids: OeisIdHashSet,
     minimized_program: String,
+    reason: Option<String>,
+    reject_reason: Option<RejectReason>,
+    oeis_id_reject_reasons: Vec<(OeisId, RejectReason)>,
+    dontmine_ids: OeisIdHashSet,
+    dry_run: bool,
 }
 
 impl CandidateProgram {
@@ -48,6 +53,11 @@ impl CandidateProgram {
             possible_ids: HashSet::new(),
             keep_ids: HashSet::new(),
             minimized_program: String::new(),
+            reason: None,
+            reject_reason: None,
+            oeis_id_reject_reasons: vec!(),
+            dontmine_ids: HashSet::new(),
+            dry_run: false,
         };
         Ok(instance)
     }
@@ -61,6 +71,32 @@ impl CandidateProgram {
         self.state
     }
 
+    /// When `dry_run` is enabled, the keep/reject decision is only recorded in memory, 
+    /// and the file inside the `mine-event` dir is left untouched.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// The reason for why the candidate program was kept or rejected.
+    pub fn reason(&self) -> Option<&String> {
+        self.reason.as_ref()
+    }
+
+    /// The categorized reason for why the candidate program was rejected.
+    pub fn reject_reason(&self) -> Option<&RejectReason> {
+        self.reject_reason.as_ref()
+    }
+
+    /// Record why the candidate program isn't a match for a particular OEIS sequence.
+    pub fn oeis_id_reject_reason_insert(&mut self, id: OeisId, reason: RejectReason) {
+        self.oeis_id_reject_reasons.push((id, reason));
+    }
+
+    /// The terms matches a sequence that is listed in the `dont_mine.csv` file.
+    pub fn dontmine_id_insert(&mut self, id: OeisId) {
+        self.dontmine_ids.insert(id);
+    }
+
     pub fn update_lodacpp_terms(&mut self, terms: BigIntVec) {
         self.lodacpp_terms = terms;
     }
@@ -137,10 +173,21 @@ impl CandidateProgram {
         input.as_ref().replace("\n", "\n; ")
     }
 
-    pub fn perform_reject<I: AsRef<str>>(&mut self, reason_reject: I) -> anyhow::Result<()> {
+    /// Rename the file to `.reject.asm` and append the reason.
+    /// 
+    /// The categorized reason is saved in a `.reject.json` sidecar file.
+    pub fn perform_reject<I: Into<RejectReason>>(&mut self, reason_reject: I) -> anyhow::Result<()> {
         if self.state != State::PendingProcessing {
             return Err(anyhow::anyhow!("perform_reject of candidate program with already resolved state"));
         }
+        let reject_reason: RejectReason = reason_reject.into();
+        let reason_reject: String = reject_reason.to_string();
+        self.reason = Some(reason_reject.clone());
+        self.reject_reason = Some(reject_reason.clone());
+        if self.dry_run {
+            self.state = State::Reject;
+            return Ok(());
+        }
         fs::rename(&self.path_original, &self.path_reject)
             .with_context(|| format!("perform_reject: Unable to rename file from: {:?} to: {:?}", &self.path_original, &self.path_reject))?;
         let mut file = OpenOptions::new()
@@ -148,9 +195,13 @@ impl CandidateProgram {
             .append(true)
             .open(&self.path_reject)
             .with_context(|| format!("perform_reject: Unable to open in append-mode. path: {:?}", &self.path_reject))?;
-        let reason: String = Self::prefix_with_semicolon(reason_reject.as_ref());
+        let reason: String = Self::prefix_with_semicolon(&reason_reject);
         writeln!(file, "\n; reject-reason: {}", reason)
             .with_context(|| format!("perform_reject: Unable to append to rejection-reason to file: {:?}", &self.path_reject))?;
+        let sidecar = RejectReasonSidecar::new(self.filename_original.clone(), &reject_reason, &self.oeis_id_reject_reasons);
+        let sidecar_path: PathBuf = PathUtil::path_reject_sidecar(&self.path_original);
+        fs::write(&sidecar_path, sidecar.to_json()?)
+            .with_context(|| format!("perform_reject: Unable to save sidecar file: {:?}", &sidecar_path))?;
         self.state = State::Reject;
         Ok(())
     }
@@ -159,6 +210,11 @@ impl CandidateProgram {
         if self.state != State::PendingProcessing {
             return Err(anyhow::anyhow!("perform_keep of candidate program with already resolved state"));
         }
+        self.reason = Some(reason_keep.as_ref().to_string());
+        if self.dry_run {
+            self.state = State::Keep;
+            return Ok(());
+        }
         fs::rename(&self.path_original, &self.path_keep)
             .with_context(|| format!("perform_keep: Unable to rename file from: {:?} to: {:?}", &self.path_original, &self.path_keep))?;
         let mut file = OpenOptions::new()
@@ -173,16 +229,30 @@ impl CandidateProgram {
         Ok(())
     }
 
+    /// The terms cannot be found in the OEIS `stripped` file, except for sequences in the `dont_mine.csv` file.
+    pub fn reject_reason_for_no_possible_ids(&self) -> RejectReason {
+        if self.dontmine_ids.is_empty() {
+            return RejectReason::NoMatchingSequence;
+        }
+        let mut oeis_ids: Vec<OeisId> = self.dontmine_ids.iter().cloned().collect();
+        oeis_ids.sort();
+        RejectReason::DontMine { oeis_ids: oeis_ids.iter().map(|id| id.a_number()).collect() }
+    }
+
     pub fn perform_keep_or_reject_based_result(&mut self) -> Result<(), Box<dyn Error>> {
         if self.is_keep_ids_empty() {
             let oeis_ids: Vec<OeisId> = self.possible_id_vec();
             if oeis_ids.is_empty() {
-                self.perform_reject("Doesn't correspond to any known OEIS sequence")
+                let reason: RejectReason = self.reject_reason_for_no_possible_ids();
+                self.perform_reject(reason)
                     .context("perform_keep_or_reject_based_result doesn't correspond to any known OEIS sequence")?;
                 return Ok(());
             }
-            let message = format!("Worse than the existing programs: {:?}", oeis_ids);
-            self.perform_reject(message)
+            let reason: RejectReason = match RejectReason::most_relevant(&self.oeis_id_reject_reasons) {
+                Some(reason) => reason.clone(),
+                None => RejectReason::from(format!("Worse than the existing programs: {:?}", oeis_ids))
+            };
+            self.perform_reject(reason)
                 .context("perform_keep_or_reject_based_result worse than the existing program")?;
             return Ok(());
         }
@@ -302,4 +372,81 @@ add $0,$1
         assert_eq!(output_content.ends_with("\n; keep-reason: KEEP-REASON1\n; KEEP-REASON2\n; KEEP-REASON3\n"), true);
         Ok(())
     }
+
+    #[test]
+    fn test_30002_perform_reject_dry_run() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30002_perform_reject_dry_run");
+        fs::create_dir(&basedir)?;
+        let input_path: PathBuf = basedir.join("19840101-054915-1251916462.asm");
+        let input_content = "; A039207\nmov $1,1\n";
+        fs::write(&input_path, input_content)?;
+        let mut candidate_program: CandidateProgram = CandidateProgram::new(&input_path)?;
+        candidate_program.set_dry_run(true);
+
+        // Act
+        candidate_program.perform_reject("REJECT-REASON")?;
+
+        // Assert
+        assert_eq!(candidate_program.state(), State::Reject);
+        assert_eq!(candidate_program.reason(), Some(&"REJECT-REASON".to_string()));
+        assert_eq!(candidate_program.path_original().is_file(), true);
+        assert_eq!(candidate_program.path_reject().is_file(), false);
+        let output_content: String = fs::read_to_string(candidate_program.path_original())?;
+        assert_eq!(output_content, input_content);
+        Ok(())
+    }
+
+    #[test]
+    fn test_30003_perform_keep_or_reject_sidecar() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30003_perform_keep_or_reject_sidecar");
+        fs::create_dir(&basedir)?;
+        let input_path: PathBuf = basedir.join("19840101-054915-1251916462.asm");
+        fs::write(&input_path, "; A000045\nmov $1,1\n")?;
+        let mut candidate_program: CandidateProgram = CandidateProgram::new(&input_path)?;
+        candidate_program.possible_id_insert(OeisId::from(45));
+        candidate_program.possible_id_insert(OeisId::from(40));
+        candidate_program.oeis_id_reject_reason_insert(OeisId::from(40), RejectReason::Mismatch { index: 12 });
+        candidate_program.oeis_id_reject_reason_insert(OeisId::from(45), RejectReason::SlowerThanExisting);
+
+        // Act
+        candidate_program.perform_keep_or_reject_based_result()?;
+
+        // Assert
+        assert_eq!(candidate_program.state(), State::Reject);
+        assert_eq!(candidate_program.reject_reason(), Some(&RejectReason::SlowerThanExisting));
+        let output_content: String = fs::read_to_string(candidate_program.path_reject())?;
+        assert_eq!(output_content.ends_with("\n; reject-reason: Worse than the existing program\n"), true);
+        let sidecar_path: PathBuf = basedir.join("19840101-054915-1251916462.reject.json");
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&sidecar_path)?)?;
+        assert_eq!(json["category"], "slower_than_existing");
+        assert_eq!(json["comparisons"][0]["oeis_id"], "A000040");
+        assert_eq!(json["comparisons"][0]["category"], "mismatch");
+        assert_eq!(json["comparisons"][1]["oeis_id"], "A000045");
+        Ok(())
+    }
+
+    #[test]
+    fn test_30004_reject_reason_for_no_possible_ids() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30004_reject_reason_for_no_possible_ids");
+        fs::create_dir(&basedir)?;
+        let input_path: PathBuf = basedir.join("19840101-054915-1251916462.asm");
+        fs::write(&input_path, "mov $1,1\n")?;
+        let mut candidate_program: CandidateProgram = CandidateProgram::new(&input_path)?;
+        assert_eq!(candidate_program.reject_reason_for_no_possible_ids(), RejectReason::NoMatchingSequence);
+
+        // Act
+        candidate_program.dontmine_id_insert(OeisId::from(142));
+        candidate_program.dontmine_id_insert(OeisId::from(12));
+
+        // Assert
+        let expected = RejectReason::DontMine { oeis_ids: vec!["A000012".to_string(), "A000142".to_string()] };
+        assert_eq!(candidate_program.reject_reason_for_no_possible_ids(), expected);
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 use loda_rust_core::parser::ParsedProgram;
-use crate::lodacpp::{LodaCpp, LodaCppEvalStepsExecute, LodaCppEvalSteps};
+use crate::lodacpp::LodaCppEvalSteps;
+use super::PostMineBackend;
 use crate::common::SimpleLog;
 use std::path::Path;
 use std::time::Duration;
@@ -26,7 +27,7 @@ pub struct CompareTwoPrograms;
 impl CompareTwoPrograms {
     pub fn compare(
         simple_log: SimpleLog,
-        lodacpp: &LodaCpp, 
+        backend: &dyn PostMineBackend, 
         path_program0: &Path, 
         path_program1: &Path,
         status_of_existing_program: &StatusOfExistingProgram,
@@ -54,7 +55,7 @@ impl CompareTwoPrograms {
             StatusOfExistingProgram::CompareNewWithExisting => {
                 return Self::compare_new_with_existing(
                     simple_log,
-                    lodacpp, 
+                    backend, 
                     path_program0, 
                     path_program1, 
                     path_comparison, 
@@ -83,7 +84,7 @@ impl CompareTwoPrograms {
 
     fn compare_new_with_existing(
         simple_log: SimpleLog,
-        lodacpp: &LodaCpp, 
+        backend: &dyn PostMineBackend, 
         path_program0: &Path, 
         path_program1: &Path,
         path_comparison: &Path, 
@@ -101,7 +102,7 @@ impl CompareTwoPrograms {
         let mut file = File::create(path_comparison)?;
         writeln!(&mut file, "program0, measuring steps: {:?}", path_program0)?;
         let start0 = Instant::now();
-        let result0 = lodacpp.eval_steps(
+        let result0 = backend.eval_steps(
             term_count,
             &path_program0, 
             time_limit
@@ -127,7 +128,7 @@ impl CompareTwoPrograms {
         
         writeln!(&mut file, "\n\nprogram1, measuring steps: {:?}", path_program1)?;
         let start1 = Instant::now();
-        let result1 = lodacpp.eval_steps(
+        let result1 = backend.eval_steps(
             term_count,
             &path_program1, 
             time_limit
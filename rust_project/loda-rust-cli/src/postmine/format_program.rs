@@ -22,6 +22,7 @@ pub struct FormatProgram {
     program_path: Option<PathBuf>,
     loda_submitted_by: Option<String>,
     terms: Option<String>,
+    unofficial_function_registry: UnofficialFunctionRegistry,
 }
 
 impl FormatProgram {
@@ -33,6 +34,7 @@ impl FormatProgram {
             program_path: None,
             loda_submitted_by: None,
             terms: None,
+            unofficial_function_registry: UnofficialFunctionRegistry::new(),
         }
     }
 
@@ -61,6 +63,11 @@ impl FormatProgram {
         self
     }
 
+    pub fn unofficial_function_registry(&mut self, unofficial_function_registry: UnofficialFunctionRegistry) -> &mut FormatProgram {
+        self.unofficial_function_registry = unofficial_function_registry;
+        self
+    }
+
     pub fn build(&self) -> anyhow::Result<String> {
         let parsed_program: ParsedProgram = match ParsedProgram::parse_program(&self.program_content) {
             Ok(value) => value,
@@ -75,7 +82,7 @@ impl FormatProgram {
         let mut dm = DependencyManager::new(
             DependencyManagerFileSystemMode::Virtual,
             PathBuf::from("non-existing-dir"),
-            UnofficialFunctionRegistry::new(),
+            self.unofficial_function_registry.clone(),
         );
         for program_id in parsed_program.direct_dependencies() {
             dm.virtual_filesystem_insert_file(program_id, "".to_string());
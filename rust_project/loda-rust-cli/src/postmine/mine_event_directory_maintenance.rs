@@ -67,10 +67,18 @@ impl MineEventDirectoryMaintenance {
     }
 
     /// This function is destructive and erases the scheduled files from disk.
+    /// 
+    /// Also erases the `.reject.json` sidecar files.
     pub fn perform_removal_of_scheduled_files(&self) -> anyhow::Result<()> {
         for path in &self.paths_scheduled_for_removal {
             fs::remove_file(path)
                 .with_context(|| format!("perform_removal_of_scheduled_files: Unable to remove file: {:?}", path))?;
+            // Remove the `.reject.json` sidecar file, that is next to the `.reject.asm` file.
+            let sidecar_path: PathBuf = path.with_extension("json");
+            if sidecar_path.is_file() {
+                fs::remove_file(&sidecar_path)
+                    .with_context(|| format!("perform_removal_of_scheduled_files: Unable to remove sidecar file: {:?}", sidecar_path))?;
+            }
         }
         Ok(())
     }
@@ -12,9 +12,13 @@ mod parent_dir_and_child_file;
 mod path_for_oeis_program;
 mod path_util;
 mod postmine;
+mod postmine_backend;
+mod postmine_backend_native;
 mod postmine_directory_maintenance;
 mod postmine_error;
+mod postmine_report;
 mod program_serializer_context_with_sequence_name;
+mod reject_reason;
 mod terms_from_programs;
 mod validate_single_program;
 
@@ -40,9 +44,13 @@ pub use parent_dir_and_child_file::ParentDirAndChildFile;
 pub use path_for_oeis_program::path_for_oeis_program;
 pub use path_util::PathUtil;
 pub use postmine::PostMine;
+pub use postmine_backend::PostMineBackend;
+pub use postmine_backend_native::PostMineBackendNative;
 pub use postmine_directory_maintenance::PostmineDirectoryMaintenance;
 pub use postmine_error::PostMineError;
+pub use postmine_report::{PostMineReport, PostMineReportItem};
 pub use program_serializer_context_with_sequence_name::ProgramSerializerContextWithSequenceName;
+pub use reject_reason::{RejectReason, RejectReasonCounts, RejectReasonSidecar};
 
 #[allow(unused_imports)]
 pub use terms_from_programs::{PathTermsMap, terms_from_program, terms_from_programs};
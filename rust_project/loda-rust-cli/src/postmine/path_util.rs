@@ -20,6 +20,17 @@ impl PathUtil {
     pub fn path_keep(path: &Path) -> PathBuf {
         Self::path_with_status_extension(path, "keep")
     }
+
+    /// The `.reject.json` file with the categorized reject reason.
+    pub fn path_reject_sidecar(path: &Path) -> PathBuf {
+        let mut result: PathBuf = Self::path_with_status_extension(path, "reject");
+        if path.extension().is_some() {
+            result.set_extension("json");
+        } else {
+            result.set_extension("reject.json");
+        }
+        result
+    }
 }
 
 #[cfg(test)]
@@ -56,4 +67,12 @@ mod tests {
         assert_eq!(process_reject("mine-event/20220710-054915-1251916462.keep.keep.asm"), "mine-event/20220710-054915-1251916462.keep.keep.reject.asm");
         assert_eq!(process_reject("readme.txt"), "readme.reject.txt");
     }
+
+    #[test]
+    fn test_10002_reject_sidecar() {
+        let process = |input: &str| PathUtil::path_reject_sidecar(&PathBuf::from(input)).to_string_lossy().to_string();
+        assert_eq!(process("mine-event/20220710-054915-1251916462"), "mine-event/20220710-054915-1251916462.reject.json");
+        assert_eq!(process("mine-event/20220710-054915-1251916462.asm"), "mine-event/20220710-054915-1251916462.reject.json");
+        assert_eq!(process("mine-event/20220710-054915-1251916462.keep.asm"), "mine-event/20220710-054915-1251916462.keep.reject.json");
+    }
 }
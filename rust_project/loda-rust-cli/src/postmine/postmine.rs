@@ -1,19 +1,23 @@
 //! The `postmine` worker, checks the candidate programs for correctness and performance, and keeps the best.
-use crate::config::{Config, MinerFilterMode};
+use crate::config::{Config, MinerFilterMode, PostmineBackend};
 use crate::common::{oeis_ids_from_program_string, OeisIdStringMap};
 use crate::common::{load_program_ids_csv_file, PendingProgramsWithPriority, SimpleLog};
-use crate::oeis::{ProcessStrippedFile, StrippedRow};
-use crate::lodacpp::{LodaCpp, LodaCppCheck, LodaCppCheckResult, LodaCppCheckStatus, LodaCppEvalTermsExecute, LodaCppEvalTerms, LodaCppMinimize};
+use crate::oeis::{OeisKeyword, OeisKeywords, ProcessStrippedFile, StrippedRow};
+use crate::lodacpp::{LodaCpp, LodaCppCheckResult, LodaCppCheckStatus, LodaCppEvalTerms};
 use crate::analytics::AnalyticsDirectory;
+use crate::mine::create_miner_unofficial_function_registry;
 use super::{batch_lookup_names, terms_from_program, FormatProgram, path_for_oeis_program};
 use super::{CandidateProgram, CompareTwoPrograms, CompareTwoProgramsResult, ParentDirAndChildFile, State, StatusOfExistingProgram, ValidateSingleProgram};
-use super::{MineEventDirectoryMaintenance, PostmineDirectoryMaintenance};
+use super::{MineEventDirectoryMaintenance, PostmineDirectoryMaintenance, PostMineBackend, PostMineBackendNative};
+use super::{PostMineReport, PostMineReportItem, RejectReason, RejectReasonCounts};
 use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
+use loda_rust_core::parser::ParsedProgram;
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use loda_rust_core::util::BigIntVec;
 use loda_rust_core::util::BigIntVecToString;
 use num_bigint::{BigInt, ToBigInt};
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::{File, Metadata};
 use std::io::prelude::*;
@@ -44,11 +48,19 @@ type CandidateProgramItem = Rc<RefCell<CandidateProgram>>;
 /// 
 /// Rejection, there are lots of ways the mined program is not a keeper.
 /// then the input file gets renamed to `20220826-210851-140750305.reject.asm`
+/// and the categorized reason is saved in `20220826-210851-140750305.reject.json`.
+/// The number of rejections per category are accumulated in the `postmine/reject_reasons.csv` file.
+/// 
+/// In `dry_run` mode all the checks are performed, but nothing gets written to the 
+/// `loda-programs` repo, the `loda-outlier-programs` repo or the `mine-event` dir.
+/// 
+/// Every run generates a `report.html` file inside the timestamped postmine dir,
+/// so a human can audit what has been kept or rejected.
 pub struct PostMine {
     analytics_directory: AnalyticsDirectory,
     config: Config,
     loda_submitted_by: String,
-    lodacpp: LodaCpp,
+    backend: Box<dyn PostMineBackend>,
     path_timestamped_postmine_dir: PathBuf,
     paths_for_processing: Vec<PathBuf>,
     candidate_programs: Vec<CandidateProgramItem>,
@@ -57,12 +69,16 @@ pub struct PostMine {
     valid_program_ids_hashset: OeisIdHashSet,
     oeis_id_name_map: OeisIdStringMap,
     oeis_id_terms_map: OeisIdStringMap,
+    oeis_keywords: OeisKeywords,
     loda_programs_oeis_dir: PathBuf,
     loda_outlier_programs_repository_oeis_divergent: PathBuf,
     validate_single_program: ValidateSingleProgram,
+    unofficial_function_registry: UnofficialFunctionRegistry,
     iteration: usize,
     focus_only_on_new_programs: bool,
     found_program_callback: Option<Box<dyn Fn(String, OeisId)>>,
+    dry_run: bool,
+    report: PostMineReport,
 }
 
 impl PostMine {
@@ -97,7 +113,6 @@ impl PostMine {
     /// ```
     const MAX_NUMBER_OF_OUTLIER_VARIANTS: usize = 10;
 
-    #[allow(dead_code)]
     pub fn new() -> anyhow::Result<Self> {
         let config = Config::load();
         Self::new_with_config(config)
@@ -105,7 +120,9 @@ impl PostMine {
 
     pub fn new_with_config(config: Config) -> anyhow::Result<Self> {
         let loda_programs_oeis_dir = config.loda_programs_oeis_dir();
-        let validate_single_program = ValidateSingleProgram::new(loda_programs_oeis_dir.clone());
+        let unofficial_function_registry: UnofficialFunctionRegistry = create_miner_unofficial_function_registry(&config)?;
+        let mut validate_single_program = ValidateSingleProgram::new(loda_programs_oeis_dir.clone());
+        validate_single_program.set_unofficial_function_registry(unofficial_function_registry.clone());
 
         let focus_only_on_new_programs: bool = match config.miner_filter_mode() {
             MinerFilterMode::All => false,
@@ -121,12 +138,25 @@ impl PostMine {
 
         // Create dir in which the postmine can store its temp files
         let dirname: String = Self::format_timestamped_postmine_dirname();
-        let path_timestamped_postmine_dir: PathBuf = postmine_dir_path.join(dirname);
+        let path_timestamped_postmine_dir: PathBuf = postmine_dir_path.join(&dirname);
         fs::create_dir(&path_timestamped_postmine_dir)?;
         assert!(path_timestamped_postmine_dir.is_dir());
 
-        let loda_cpp_executable: PathBuf = config.loda_cpp_executable();
-        let lodacpp = LodaCpp::new(loda_cpp_executable);
+        let backend: Box<dyn PostMineBackend> = match config.postmine_backend() {
+            PostmineBackend::LodaCpp => {
+                let loda_cpp_executable: PathBuf = config.loda_cpp_executable();
+                if !loda_cpp_executable.is_file() {
+                    return Err(anyhow::anyhow!("The 'loda_cpp_executable' cannot be found at path: {:?}. Either install loda-cpp, or use the 'lodarust' postmine_backend in the config file.", loda_cpp_executable));
+                }
+                Box::new(LodaCpp::new(loda_cpp_executable))
+            },
+            PostmineBackend::LodaRust => {
+                let mut backend = PostMineBackendNative::new(loda_programs_oeis_dir.clone());
+                backend.set_unofficial_function_registry(unofficial_function_registry.clone());
+                backend.set_reference_terms(config.oeis_bfile_dir(), config.oeis_stripped_file());
+                Box::new(backend)
+            }
+        };
 
         let loda_outlier_programs_repository_oeis_divergent: PathBuf = config.loda_outlier_programs_repository_oeis_divergent();
         assert!(loda_outlier_programs_repository_oeis_divergent.is_absolute());
@@ -142,7 +172,7 @@ impl PostMine {
             analytics_directory,
             config,
             loda_submitted_by,
-            lodacpp,
+            backend,
             path_timestamped_postmine_dir,
             paths_for_processing: vec!(),
             candidate_programs: vec!(),
@@ -151,12 +181,16 @@ impl PostMine {
             valid_program_ids_hashset: HashSet::new(),
             oeis_id_name_map: OeisIdStringMap::new(),
             oeis_id_terms_map: OeisIdStringMap::new(),
+            oeis_keywords: OeisKeywords::new(),
             loda_programs_oeis_dir,
             loda_outlier_programs_repository_oeis_divergent,
             validate_single_program,
+            unofficial_function_registry,
             iteration: 0,
             focus_only_on_new_programs,
             found_program_callback: None,
+            dry_run: false,
+            report: PostMineReport::new(dirname, false),
         };
         Ok(instance)
     }
@@ -165,17 +199,31 @@ impl PostMine {
         self.found_program_callback = Some(Box::new(c));
     }
 
+    /// Perform all the checks, but don't modify any files outside the timestamped postmine dir.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+        self.report.set_dry_run(dry_run);
+    }
+
     pub fn run(&mut self) -> anyhow::Result<()> {
         self.obtain_paths_for_processing()?;    
         self.populate_candidate_programs()?;
         self.obtain_dontmine_program_ids()?;
         self.obtain_invalid_program_ids()?;
         self.obtain_valid_program_ids()?;
-        self.eval_using_loda_cpp()?;
+        self.eval_candidate_programs()?;
         self.lookup_in_oeis_stripped_file()?;
         self.minimize_candidate_programs()?;
+        self.reject_duplicate_candidate_programs()?;
         self.obtain_sequence_names()?;
+        self.obtain_sequence_keywords()?;
         self.process_candidate_programs()?;
+        self.save_report()?;
+        self.save_reject_reason_counts()?;
+        if self.dry_run {
+            println!("Dry run. Skipping maintenance of the 'mine-event' dir and the 'postmine' dir.");
+            return Ok(());
+        }
         self.maintenance_of_mineevent_dir()?;
         self.maintenance_of_postmine_dir()?;
         Ok(())
@@ -223,8 +271,9 @@ impl PostMine {
     fn populate_candidate_programs(&mut self) -> anyhow::Result<()> {
         let mut candidate_programs = Vec::<CandidateProgramItem>::with_capacity(self.paths_for_processing.len());
         for path in &self.paths_for_processing {
-            let candidate_program = CandidateProgram::new(path)
+            let mut candidate_program = CandidateProgram::new(path)
                 .map_err(|e| anyhow::anyhow!("Unable to create CandidateProgram. error: {:?}", e))?;
+            candidate_program.set_dry_run(self.dry_run);
 
             let candidate_program_item = Rc::new(RefCell::new(candidate_program));
             candidate_programs.push(candidate_program_item);
@@ -273,7 +322,7 @@ impl PostMine {
         Ok(())
     }
 
-    fn eval_using_loda_cpp(&mut self) -> anyhow::Result<()> {
+    fn eval_candidate_programs(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let time_limit = Duration::from_secs(Self::LODACPP_EVAL_TIME_LIMIT_IN_SECONDS);
 
@@ -284,7 +333,7 @@ impl PostMine {
         let mut count_failure: usize = 0;
         for candidate_program in self.candidate_programs.iter_mut() {
             let path_original = PathBuf::from(candidate_program.borrow().path_original());
-            let result = self.lodacpp.eval_terms(
+            let result = self.backend.eval_terms(
                 Self::EVAL_TERM_COUNT, 
                 &path_original,
                 time_limit
@@ -292,9 +341,9 @@ impl PostMine {
             let evalterms: LodaCppEvalTerms = match result {
                 Ok(value) => value,
                 Err(error) => {
-                    let reason = format!("Couldn't eval program with loda-cpp, {:?}", error);
+                    let reason: RejectReason = RejectReason::eval_error(&error);
                     candidate_program.borrow_mut().perform_reject(reason)
-                        .map_err(|e| anyhow::anyhow!("eval_using_loda_cpp -> perform_reject. path_original: {:?} error: {:?}", path_original, e))?;
+                        .map_err(|e| anyhow::anyhow!("eval_candidate_programs -> perform_reject. path_original: {:?} error: {:?}", path_original, e))?;
                     count_failure += 1;
                     pb.inc(1);
                     continue;
@@ -309,7 +358,7 @@ impl PostMine {
     
         let green_bold = Style::new().green().bold();        
         println!(
-            "{:>12} Ran loda-cpp with pending programs, in {}",
+            "{:>12} Evaluated pending programs, in {}",
             green_bold.apply_to("Finished"),
             HumanDuration(start.elapsed())
         );
@@ -326,7 +375,9 @@ impl PostMine {
         let start = Instant::now();
         println!("Looking up in the OEIS 'stripped' file");
 
-        let mut oeis_ids_to_ignore: OeisIdHashSet = self.dontmine_hashset.clone();
+        // The sequences in the `dont_mine.csv` file are not ignored here. 
+        // so that it's possible to tell if the candidate program got rejected because of the `dont_mine.csv` file.
+        let mut oeis_ids_to_ignore = OeisIdHashSet::new();
         if self.focus_only_on_new_programs {
             oeis_ids_to_ignore.extend(&self.valid_program_ids_hashset);
         }
@@ -351,11 +402,16 @@ impl PostMine {
             pb.set_position(count_bytes as u64);
             let mut stripped_terms: BigIntVec = row.terms().clone();
             stripped_terms.truncate(Self::EVAL_TERM_COUNT);
+            let is_dontmine: bool = self.dontmine_hashset.contains(&row.oeis_id());
             let mut is_possible_match = false;
             for candidate_program in self.candidate_programs.iter_mut() {
                 let mut candidate_program_mut = candidate_program.borrow_mut();
                 let terms: &BigIntVec = candidate_program_mut.lodacpp_terms();
                 if terms.starts_with(&stripped_terms) {
+                    if is_dontmine {
+                        candidate_program_mut.dontmine_id_insert(row.oeis_id());
+                        continue;
+                    }
                     // let s = format!("program: {} is possible match with A{}  number of identical terms: {}", candidate_program, row.oeis_id, stripped_terms.len());
                     // pb.println(s);
                     candidate_program_mut.possible_id_insert(row.oeis_id());
@@ -406,7 +462,8 @@ impl PostMine {
                 continue;
             }
             debug!("Rejected {}, where terms cannot be found in OEIS 'stripped' file", candidate_program.borrow());
-            candidate_program.borrow_mut().perform_reject("lookup_in_oeis_stripped_file, Terms cannot be found in OEIS 'stripped' file")
+            let reason: RejectReason = candidate_program.borrow().reject_reason_for_no_possible_ids();
+            candidate_program.borrow_mut().perform_reject(reason)
                 .map_err(|e| anyhow::anyhow!("lookup_in_oeis_stripped_file -> perform_reject. error: {:?}", e))?;
         }
 
@@ -459,22 +516,49 @@ impl PostMine {
     fn minimize_candidate_program(&mut self, candidate_program: CandidateProgramItem) -> anyhow::Result<()> {
         let time_limit = Duration::from_secs(Self::LODACPP_MINIMIZE_TIME_LIMIT_IN_SECONDS);
         let path_original = PathBuf::from(candidate_program.borrow().path_original());
-        let result = self.lodacpp.minimize(&path_original, time_limit);
+        let result = self.backend.minimize(&path_original, time_limit);
         match result {
             Ok(value) => {
                 // debug!("minimized program successfully:\n{}", value);
                 candidate_program.borrow_mut().assign_minimized_program(value);
             },
             Err(error) => {
-                let reason = format!("Unable to minimize program: {:?}", error);
+                let reason = RejectReason::MinimizeError { message: format!("{:?}", error) };
                 // debug!("program: {:?}, rejection reason {}", candidate_program.borrow().path_original(), reason);
-                candidate_program.borrow_mut().perform_reject(&reason)
+                candidate_program.borrow_mut().perform_reject(reason.clone())
                     .map_err(|e| anyhow::anyhow!("minimize_candidate_program -> perform_reject. path_original: {:?} reason: {:?} error: {:?}", path_original, reason, e))?;
             }
         }
         Ok(())
     }
 
+    /// The miner sometimes finds the same program several times.
+    /// 
+    /// Only the first of the identical minimized programs gets processed.
+    fn reject_duplicate_candidate_programs(&mut self) -> anyhow::Result<()> {
+        let mut program_to_filename = HashMap::<String, String>::new();
+        for candidate_program in self.pending_candidate_programs() {
+            let minimized_program: String = candidate_program.borrow().minimized_program().clone();
+            // Programs that only differ in register numbering, instruction order or idioms, are also duplicates.
+            let program: String = match ParsedProgram::parse_program(&minimized_program) {
+                Ok(parsed_program) => parsed_program.canonical_form().to_string(),
+                Err(_) => minimized_program
+            };
+            let filename: String = candidate_program.borrow().filename_original().clone();
+            let duplicate_of: String = match program_to_filename.get(&program) {
+                Some(value) => value.clone(),
+                None => {
+                    program_to_filename.insert(program, filename);
+                    continue;
+                }
+            };
+            debug!("Rejected {}, duplicate of {}", candidate_program.borrow(), duplicate_of);
+            candidate_program.borrow_mut().perform_reject(RejectReason::Duplicate { duplicate_of })
+                .map_err(|e| anyhow::anyhow!("reject_duplicate_candidate_programs -> perform_reject. error: {:?}", e))?;
+        }
+        Ok(())
+    }
+
     fn obtain_sequence_names(&mut self) -> anyhow::Result<()> {
         if self.candidate_programs.is_empty() {
             return Ok(());
@@ -528,6 +612,17 @@ impl PostMine {
         Ok(())
     }
 
+    /// The keywords, such as `core` or `sign`, are shown in the report.
+    fn obtain_sequence_keywords(&mut self) -> anyhow::Result<()> {
+        if self.candidate_programs.is_empty() {
+            return Ok(());
+        }
+        let oeis_keywords_file: PathBuf = self.config.oeis_keywords_file();
+        self.oeis_keywords = OeisKeywords::load_if_exists(&oeis_keywords_file)?;
+        debug!("obtained keywords for {} sequences", self.oeis_keywords.len());
+        Ok(())
+    }
+
     fn process_candidate_programs(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
 
@@ -569,6 +664,44 @@ impl PostMine {
         Ok(())
     }
 
+    fn save_report(&self) -> anyhow::Result<()> {
+        let candidate_programs: Vec<std::cell::Ref<CandidateProgram>> = self.candidate_programs
+            .iter()
+            .map(|candidate_program| candidate_program.borrow())
+            .collect();
+        let candidate_program_refs: Vec<&CandidateProgram> = candidate_programs.iter().map(|x| &**x).collect();
+        let report_path: PathBuf = self.path_timestamped_postmine_dir.join("report.html");
+        self.report.save(&candidate_program_refs, &report_path)
+            .with_context(|| format!("Unable to save postmine report at path: {:?}", report_path))?;
+        println!("Postmine report: {:?}", report_path);
+        Ok(())
+    }
+
+    /// Count the rejected candidate programs per category.
+    /// 
+    /// Saves the counts of this postmine run in the timestamped postmine dir.
+    /// Unless it's a dry run, the counts are accumulated in the `postmine/reject_reasons.csv` file.
+    fn save_reject_reason_counts(&self) -> anyhow::Result<()> {
+        let mut counts = RejectReasonCounts::new();
+        for candidate_program in &self.candidate_programs {
+            if let Some(reason) = candidate_program.borrow().reject_reason() {
+                counts.increment(reason);
+            }
+        }
+        if counts.is_empty() {
+            return Ok(());
+        }
+        counts.save(&self.path_timestamped_postmine_dir.join("reject_reasons.csv"))?;
+        if self.dry_run {
+            return Ok(());
+        }
+        let path: PathBuf = self.config.postmine_dir().join("reject_reasons.csv");
+        let mut accumulated_counts = RejectReasonCounts::load(&path)?;
+        accumulated_counts.append(&counts);
+        accumulated_counts.save(&path)?;
+        Ok(())
+    }
+
     fn maintenance_of_mineevent_dir(&self) -> anyhow::Result<()> {
         let mineevent_dir: PathBuf = self.config.mine_event_dir();
         let instance = MineEventDirectoryMaintenance::scan(
@@ -658,6 +791,8 @@ impl PostMine {
     /// Determines if the candidate program is correct.
     /// 
     /// Determines if the candidate program is an improvement over an eventual existing program.
+    /// 
+    /// The outcome gets appended to the report.
     fn analyze_candidate(
         &mut self, 
         candidate_program: CandidateProgramItem, 
@@ -665,6 +800,26 @@ impl PostMine {
         progressbar: ProgressBar
     ) -> anyhow::Result<()> {
         self.iteration += 1;
+        let oeis_name: String = self.oeis_id_name_map.get(&possible_id).cloned().unwrap_or_default();
+        let mut report_item = PostMineReportItem::new(
+            self.iteration, 
+            candidate_program.borrow().filename_original().clone(), 
+            possible_id.a_number(), 
+            oeis_name
+        );
+        report_item.set_oeis_keywords(self.oeis_keywords.keywords_string(possible_id));
+        let result = self.analyze_candidate_inner(candidate_program, possible_id, progressbar, &mut report_item);
+        self.report.push(report_item);
+        result
+    }
+
+    fn analyze_candidate_inner(
+        &self, 
+        candidate_program: CandidateProgramItem, 
+        possible_id: OeisId, 
+        progressbar: ProgressBar,
+        report_item: &mut PostMineReportItem
+    ) -> anyhow::Result<()> {
 
         let log_filename = format!("iteration{}_log.txt", self.iteration);
         let log_path: PathBuf = self.path_timestamped_postmine_dir.join(log_filename);
@@ -681,6 +836,14 @@ impl PostMine {
             simple_log.println(message);
         }
     
+        let keywords: String = self.oeis_keywords.keywords_string(possible_id);
+        if !keywords.is_empty() {
+            simple_log.println(format!("Keywords of {}: {}", possible_id, keywords));
+        }
+        if self.oeis_keywords.has_keyword(possible_id, OeisKeyword::Dead) {
+            simple_log.println(format!("Sequence {} is dead. Analyzing it anyways.", possible_id));
+        }
+
         if self.invalid_program_ids_hashset.contains(&possible_id) {
             let message = format!("Program {} is listed in the 'programs_invalid.csv'", possible_id);
             // progressbar.println(message.clone());
@@ -742,7 +905,14 @@ impl PostMine {
         fp.oeis_id_name_map(self.oeis_id_name_map.clone());
         fp.program_path(oeis_program_path.child_file());
         fp.terms(resolved_terms.clone());
+        fp.unofficial_function_registry(self.unofficial_function_registry.clone());
         let file_content: String = fp.build()?;
+
+        let existing_program: Option<String> = match status_of_existing_program {
+            StatusOfExistingProgram::NoExistingProgram => None,
+            _ => fs::read_to_string(oeis_program_path.child_file()).ok()
+        };
+        report_item.set_programs(&file_content, existing_program);
         
         // Save the program to disk
         let mut check_program_file = File::create(&check_program_path)?;
@@ -750,10 +920,10 @@ impl PostMine {
         check_program_file.sync_all()?;
         // debug!("Created program file: {:?}", check_program_path);
     
-        // Execute `loda-check check <PATH> -b`
+        // Execute `loda-check check <PATH> -b`, or the native equivalent
         let time_limit = Duration::from_secs(Self::LODACPP_CHECK_TIME_LIMIT_IN_SECONDS);
         let check_start = Instant::now();
-        let ok_error = self.lodacpp.perform_check_and_save_output(&check_program_path, time_limit, &check_output_path);
+        let ok_error = self.backend.perform_check_and_save_output(&check_program_path, time_limit, &check_output_path);
         simple_log.println(format!("check - elapsed {}", HumanDuration(check_start.elapsed())));
         let check_result: LodaCppCheckResult = match ok_error {
             Ok(value) => {
@@ -761,13 +931,18 @@ impl PostMine {
                 let message = format!("check success: {:?}", value);
                 // progressbar.println(message.clone());
                 simple_log.println(message);
+                report_item.set_check(format!("{:?}, correct terms: {}", value.status, value.number_of_correct_terms));
                 value
             },
             Err(error) => {
                 debug!("Unable to check program: {:?} at path: {:?}", error, &check_program_path);
                 let message = format!("check error: {:?}", error);
                 // progressbar.println(message.clone());
-                simple_log.println(message);
+                simple_log.println(message.clone());
+                let reason: RejectReason = RejectReason::check_error(&error);
+                report_item.set_check(&message);
+                report_item.reject(reason.to_string());
+                candidate_program.borrow_mut().oeis_id_reject_reason_insert(possible_id, reason);
                 return Ok(());
             }
         };
@@ -784,6 +959,7 @@ impl PostMine {
                     &compare_output_path,
                     check_result.number_of_correct_terms as usize,
                     progressbar.clone(),
+                    report_item,
                 )?;
             },
             LodaCppCheckStatus::PartialMatch => {
@@ -792,7 +968,8 @@ impl PostMine {
                     candidate_program,
                     &check_program_path,
                     possible_id,
-                    check_result.number_of_correct_terms
+                    check_result.number_of_correct_terms,
+                    report_item
                 )?;
             },
             LodaCppCheckStatus::Timeout => {
@@ -801,7 +978,8 @@ impl PostMine {
                     candidate_program,
                     &check_program_path,
                     possible_id,
-                    check_result.number_of_correct_terms
+                    check_result.number_of_correct_terms,
+                    report_item
                 )?;
             },
         }
@@ -820,11 +998,14 @@ impl PostMine {
         status_of_existing_program: StatusOfExistingProgram,
         path_comparison: &Path,
         number_of_correct_terms: usize,
-        progressbar: ProgressBar
+        progressbar: ProgressBar,
+        report_item: &mut PostMineReportItem
     ) -> anyhow::Result<()> {
         if number_of_correct_terms < Self::MINIMUM_NUMBER_OF_REQUIRED_TERMS {
             let message = format!("process_full_match: Rejecting program with too few terms. Expected {} or more terms, but got {} terms.", Self::MINIMUM_NUMBER_OF_REQUIRED_TERMS, number_of_correct_terms);
-            simple_log.println(message);
+            simple_log.println(message.clone());
+            report_item.reject(message);
+            candidate_program.borrow_mut().oeis_id_reject_reason_insert(oeis_id, RejectReason::TooFewTerms { number_of_correct_terms });
             return Ok(());
         }
 
@@ -837,7 +1018,7 @@ impl PostMine {
         let time_limit = Duration::from_secs(Self::LODACPP_STEPS_TIME_LIMIT_IN_SECONDS);
         let ok_error = CompareTwoPrograms::compare(
             simple_log.clone(),
-            &self.lodacpp,    
+            self.backend.as_ref(),
             path_program0, 
             path_program1.child_file(),
             &status_of_existing_program,
@@ -845,6 +1026,7 @@ impl PostMine {
             time_limit,
             term_count
         );
+        report_item.set_step_comparison(fs::read_to_string(path_comparison).ok());
 
         let result: CompareTwoProgramsResult = match ok_error {
             Ok(value) => {
@@ -854,7 +1036,10 @@ impl PostMine {
             },
             Err(error) => {
                 let message = format!("process_full_match: compare result error: {:?}", error);
-                simple_log.println(message);
+                simple_log.println(message.clone());
+                report_item.reject(message);
+                let reason = RejectReason::CompareError { message: format!("{:?}", error) };
+                candidate_program.borrow_mut().oeis_id_reject_reason_insert(oeis_id, reason);
                 return Ok(());
             }
         };
@@ -863,9 +1048,16 @@ impl PostMine {
         match result {
             CompareTwoProgramsResult::Program0 => {
                 simple_log.println("Keeping. The new program is an improvement.");
+                report_item.keep("The new program is an improvement.");
             },
             CompareTwoProgramsResult::Program1 => {
                 simple_log.println("Rejecting. The new program isn't better than the existing program.");
+                report_item.reject("The new program isn't better than the existing program.");
+                let reason: RejectReason = match Self::is_identical_program(file_content, path_program1.child_file()) {
+                    true => RejectReason::Duplicate { duplicate_of: oeis_id.a_number() },
+                    false => RejectReason::SlowerThanExisting
+                };
+                candidate_program.borrow_mut().oeis_id_reject_reason_insert(oeis_id, reason);
                 return Ok(());
             }
         }
@@ -886,6 +1078,12 @@ impl PostMine {
             }
         }
 
+        if self.dry_run {
+            simple_log.println(format!("Dry run. Would save program at: {:?}", path_program1.child_file()));
+            candidate_program.borrow_mut().keep_id_insert(oeis_id);
+            return Ok(());
+        }
+
         // Save program to disk
         path_program1.create_parent_dir()
             .map_err(|e| anyhow::anyhow!("Unable to create parent dir for matching program. program_id: {:?} error: {:?}", oeis_id, e))?;
@@ -899,22 +1097,48 @@ impl PostMine {
         Ok(())
     }
 
+    /// Determine if the programs are structurally identical, ignoring comments, formatting, register numbering and the order of independent instructions.
+    fn is_identical_program(file_content: &str, path_existing_program: &Path) -> bool {
+        let existing_content: String = match fs::read_to_string(path_existing_program) {
+            Ok(value) => value,
+            Err(_) => {
+                return false;
+            }
+        };
+        match (ParsedProgram::parse_program(file_content), ParsedProgram::parse_program(&existing_content)) {
+            (Ok(program0), Ok(program1)) => program0.canonical_form() == program1.canonical_form(),
+            _ => false
+        }
+    }
+
     fn process_partial_match(
         &self, 
         simple_log: SimpleLog, 
         candidate_program: CandidateProgramItem, 
         path_program0: &Path, 
         oeis_id: OeisId, 
-        number_of_correct_terms: u32
+        number_of_correct_terms: u32,
+        report_item: &mut PostMineReportItem
     ) -> anyhow::Result<()> {
+        // Record the mismatch right away, so it shows up in the sidecar in case the candidate program gets rejected,
+        // regardless of whether the program gets saved into the outlier repo.
+        let reason = RejectReason::Mismatch { index: number_of_correct_terms as usize };
+        candidate_program.borrow_mut().oeis_id_reject_reason_insert(oeis_id, reason);
         let destination_path: ParentDirAndChildFile = match self.path_to_mismatch(oeis_id, number_of_correct_terms as usize) {
             Ok(value) => value,
             Err(error) => {
                 let message = format!("process_partial_match: discarding program. path_to_mismatch. oeis_id: {} error: {:?}", oeis_id, error);
-                simple_log.println(message);
+                simple_log.println(message.clone());
+                report_item.reject(message);
                 return Ok(());
             }
         };
+        report_item.keep(format!("This program is a mismatch, it has correct {} terms, followed by mismatch. Saving at: {:?}", number_of_correct_terms, destination_path.child_file()));
+        if self.dry_run {
+            simple_log.println(format!("Dry run. Would save mismatch at: {:?}", destination_path.child_file()));
+            candidate_program.borrow_mut().keep_id_insert(oeis_id);
+            return Ok(());
+        }
         destination_path.create_parent_dir()
             .map_err(|e| anyhow::anyhow!("process_partial_match: Unable to create parent dir. oeis_id: {:?} error: {:?}", oeis_id, e))?;
 
@@ -931,16 +1155,25 @@ impl PostMine {
         candidate_program: CandidateProgramItem, 
         path_program0: &Path, 
         oeis_id: OeisId, 
-        number_of_correct_terms: u32
+        number_of_correct_terms: u32,
+        report_item: &mut PostMineReportItem
     ) -> anyhow::Result<()> {
         let destination_path: ParentDirAndChildFile = match self.path_to_timeout(oeis_id, number_of_correct_terms as usize) {
             Ok(value) => value,
             Err(error) => {
                 let message = format!("process_timeout: discarding program. path_to_timeout. error: {:?}", error);
-                simple_log.println(message);
+                simple_log.println(message.clone());
+                report_item.reject(message);
+                candidate_program.borrow_mut().oeis_id_reject_reason_insert(oeis_id, RejectReason::CheckTimeout);
                 return Ok(());
             }
         };
+        report_item.keep(format!("Timeout while checking this program. Undecided if this is a full match or a partial match. It has correct {} terms. Saving at: {:?}", number_of_correct_terms, destination_path.child_file()));
+        if self.dry_run {
+            simple_log.println(format!("Dry run. Would save timeout program at: {:?}", destination_path.child_file()));
+            candidate_program.borrow_mut().keep_id_insert(oeis_id);
+            return Ok(());
+        }
         destination_path.create_parent_dir()
             .map_err(|e| anyhow::anyhow!("process_timeout: Unable to create parent dir. oeis_id: {:?} error: {:?}", oeis_id, e))?;
 
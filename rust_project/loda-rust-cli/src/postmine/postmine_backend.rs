@@ -0,0 +1,53 @@
+use crate::lodacpp::{LodaCpp, LodaCppCheck, LodaCppCheckResult, LodaCppError, LodaCppEvalSteps, LodaCppEvalStepsExecute, LodaCppEvalTerms, LodaCppEvalTermsExecute, LodaCppMinimize};
+use std::path::Path;
+use std::time::Duration;
+
+/// The operations that `postmine` needs for processing a candidate program.
+///
+/// There are two implementations:
+/// - `LodaCpp` runs the `loda-cpp` executable as a subprocess.
+/// - `PostMineBackendNative` does everything in-process with `loda-rust-core`,
+///   so that postmine can run on machines where `loda-cpp` isn't installed.
+///
+/// Which one gets used, is determined by the `postmine_backend` setting in the `~/.loda-rust/config.toml` file.
+pub trait PostMineBackend {
+    /// Compute the initial terms of a program.
+    fn eval_terms(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalTerms>;
+
+    /// Remove redundant instructions from a program, without changing its terms.
+    fn minimize(&self, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<String>;
+
+    /// Check a program against the known terms of the sequence.
+    ///
+    /// The output is saved in the same format as `loda-cpp check`, so it can be inspected afterwards.
+    fn perform_check_and_save_output(&self, loda_program_path: &Path, time_limit: Duration, save_output_to_path: &Path) -> anyhow::Result<LodaCppCheckResult>;
+
+    /// Measure the number of steps used for computing each of the initial terms.
+    fn eval_steps(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalSteps>;
+}
+
+impl PostMineBackend for LodaCpp {
+    fn eval_terms(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalTerms> {
+        LodaCppEvalTermsExecute::eval_terms(self, term_count, loda_program_path, time_limit)
+    }
+
+    fn minimize(&self, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<String> {
+        LodaCppMinimize::minimize(self, loda_program_path, time_limit)
+    }
+
+    fn perform_check_and_save_output(&self, loda_program_path: &Path, time_limit: Duration, save_output_to_path: &Path) -> anyhow::Result<LodaCppCheckResult> {
+        LodaCppCheck::perform_check_and_save_output(self, loda_program_path, time_limit, save_output_to_path)
+    }
+
+    fn eval_steps(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalSteps> {
+        LodaCppEvalStepsExecute::eval_steps(self, term_count, loda_program_path, time_limit)
+            .map_err(|e| {
+                let message: String = format!("LodaCppEvalStepsExecute::eval_steps returned error: {:?}", e);
+                // Keep the `LodaCppError`, so the caller can distinguish a timeout from other errors.
+                match e.downcast::<LodaCppError>() {
+                    Ok(lodacpp_error) => anyhow::Error::new(*lodacpp_error).context(message),
+                    Err(_) => anyhow::anyhow!(message),
+                }
+            })
+    }
+}
@@ -0,0 +1,574 @@
+use super::PostMineBackend;
+use crate::lodacpp::{LodaCppCheckResult, LodaCppError, LodaCppEvalSteps, LodaCppEvalTerms};
+use crate::oeis::{BFile, StrippedRow};
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::execute::{CancelCheck, EvalError, NodeLoopLimit, NodeRegisterLimit, ProgramCache, ProgramId, ProgramRunner, ProgramState, RegisterValue, RunMode};
+use loda_rust_core::oeis::OeisId;
+use loda_rust_core::parser::{InstructionId, ParsedProgram};
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+use loda_rust_core::util::BigIntVec;
+use num_bigint::BigInt;
+use regex::Regex;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use anyhow::Context;
+
+lazy_static! {
+    // Extract the OEIS id from the first line of a formatted program, such as `; A000045: Fibonacci numbers`.
+    static ref EXTRACT_OEIS_ID_FROM_PROGRAM: Regex = Regex::new(
+        "^; A(\\d+)"
+    ).unwrap();
+}
+
+/// Pure `loda-rust` implementation of the postmine operations, without using the `loda-cpp` executable.
+///
+/// Dependencies are loaded from the `loda-programs/oeis` dir.
+///
+/// The `check` operation compares against the b-file, the same way as `loda-cpp check`.
+/// When there is no b-file, then it compares against the OEIS `stripped` file.
+/// The OEIS id is extracted from the first line of the program, such as `; A000045: Fibonacci numbers`.
+pub struct PostMineBackendNative {
+    loda_programs_oeis_dir: PathBuf,
+    unofficial_function_registry: UnofficialFunctionRegistry,
+    oeis_bfile_dir: Option<PathBuf>,
+    oeis_stripped_file: Option<PathBuf>,
+
+    /// The byte position of each row in the OEIS `stripped` file, built the first time a sequence has no b-file.
+    stripped_file_index: OnceLock<HashMap<OeisId, u64>>,
+}
+
+impl PostMineBackendNative {
+    const STEP_COUNT_LIMIT: u64 = 1000000000;
+
+    /// The max number of terms to compare with, during check and during minimize.
+    const MAX_TERM_COUNT: usize = 1000;
+
+    pub fn new(loda_programs_oeis_dir: PathBuf) -> Self {
+        Self {
+            loda_programs_oeis_dir,
+            unofficial_function_registry: UnofficialFunctionRegistry::new(),
+            oeis_bfile_dir: None,
+            oeis_stripped_file: None,
+            stripped_file_index: OnceLock::new(),
+        }
+    }
+
+    /// Where the `check` operation looks for the known terms of a sequence.
+    pub fn set_reference_terms(&mut self, oeis_bfile_dir: PathBuf, oeis_stripped_file: PathBuf) {
+        self.oeis_bfile_dir = Some(oeis_bfile_dir);
+        self.oeis_stripped_file = Some(oeis_stripped_file);
+    }
+
+    /// The unofficial functions that the mined programs are allowed to use.
+    pub fn set_unofficial_function_registry(&mut self, unofficial_function_registry: UnofficialFunctionRegistry) {
+        self.unofficial_function_registry = unofficial_function_registry;
+    }
+
+    fn create_dependency_manager(&self) -> DependencyManager {
+        DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            self.loda_programs_oeis_dir.clone(),
+            self.unofficial_function_registry.clone(),
+        )
+    }
+
+    fn load_program(loda_program_path: &Path) -> anyhow::Result<ParsedProgram> {
+        let contents: String = Self::read_program(loda_program_path)?;
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program(&contents)
+            .map_err(|e| anyhow::anyhow!("Unable to parse program. path: {:?} error: {:?}", loda_program_path, e))?;
+        Ok(parsed_program)
+    }
+
+    /// Same as `load_program()`, and the comments are preserved.
+    fn load_program_with_source(loda_program_path: &Path) -> anyhow::Result<ParsedProgram> {
+        let contents: String = Self::read_program(loda_program_path)?;
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source(&contents)
+            .map_err(|e| anyhow::anyhow!("Unable to parse program. path: {:?} error: {:?}", loda_program_path, e))?;
+        Ok(parsed_program)
+    }
+
+    fn read_program(loda_program_path: &Path) -> anyhow::Result<String> {
+        if !loda_program_path.is_absolute() {
+            return Err(anyhow::anyhow!("Expected path to be absolute, but it's not. path: {:?}", loda_program_path));
+        }
+        fs::read_to_string(loda_program_path)
+            .with_context(|| format!("Unable to read program. path: {:?}", loda_program_path))
+    }
+
+    fn create_runner(dm: &mut DependencyManager, parsed_program: &ParsedProgram) -> anyhow::Result<ProgramRunner> {
+        dm.parse_stage2(ProgramId::ProgramWithoutId, parsed_program)
+            .map_err(|e| anyhow::anyhow!("Unable to create program. error: {:?}", e))
+    }
+
+    fn load_runner(&self, loda_program_path: &Path) -> anyhow::Result<ProgramRunner> {
+        let parsed_program: ParsedProgram = Self::load_program(loda_program_path)?;
+        let mut dm: DependencyManager = self.create_dependency_manager();
+        Self::create_runner(&mut dm, &parsed_program)
+            .with_context(|| format!("path: {:?}", loda_program_path))
+    }
+
+    /// Evaluate the program, and invoke the callback with each term and the number of steps used for that term.
+    ///
+    /// The terms are evaluated in OEIS coordinates, starting at `first_index`.
+    /// The callback is invoked with the position of the term, starting from 0.
+    ///
+    /// Returns `LodaCppError::Timeout` when the time limit is exceeded, also in the middle of a slow term.
+    fn eval_with_callback<F>(runner: &ProgramRunner, first_index: i64, term_count: usize, time_limit: Duration, mut callback: F) -> anyhow::Result<()>
+        where F: FnMut(usize, BigInt, u64) -> anyhow::Result<()>
+    {
+        let start = Instant::now();
+        let cancel_check = CancelCheck::deadline(start + time_limit);
+        let mut cache = ProgramCache::new();
+        for position in 0..term_count {
+            if start.elapsed() > time_limit {
+                let error = Err(LodaCppError::Timeout);
+                return error.with_context(|| format!("Exceeded time limit: {:?}, computed {} of {} terms", time_limit, position, term_count));
+            }
+            let index: i64 = first_index + (position as i64);
+            let input = RegisterValue::from_i64(index);
+            let mut step_count: u64 = 0;
+            let mut state = ProgramState::new(
+                RunMode::Silent,
+                Self::STEP_COUNT_LIMIT,
+                NodeRegisterLimit::Unlimited,
+                NodeLoopLimit::Unlimited,
+            );
+            state.set_cancel_check(Some(cancel_check.clone()));
+            let result_run = runner.run_without_offset_with_state(
+                input,
+                state,
+                &mut step_count,
+                &mut cache,
+            );
+            let output: RegisterValue = match result_run {
+                Ok(value) => value,
+                Err(error) => {
+                    if matches!(error.root_cause().downcast_ref::<EvalError>(), Some(EvalError::Cancelled)) {
+                        let error = Err(LodaCppError::Timeout);
+                        return error.with_context(|| format!("Exceeded time limit: {:?}, while computing term {}", time_limit, index));
+                    }
+                    return Err(error).with_context(|| format!("Failure while computing term {}", index));
+                }
+            };
+            callback(position, output.0, step_count)?;
+        }
+        Ok(())
+    }
+
+    /// Extract the OEIS id from the first line of the program, such as `; A000045: Fibonacci numbers`.
+    fn oeis_id_from_program(loda_program_path: &Path) -> anyhow::Result<Option<OeisId>> {
+        let contents: String = fs::read_to_string(loda_program_path)
+            .with_context(|| format!("Unable to read program. path: {:?}", loda_program_path))?;
+        let first_line: &str = contents.lines().next().unwrap_or("");
+        let captures = match EXTRACT_OEIS_ID_FROM_PROGRAM.captures(first_line) {
+            Some(value) => value,
+            None => {
+                return Ok(None);
+            }
+        };
+        let raw: &str = captures.get(1).map_or("", |m| m.as_str());
+        let value: u32 = raw.parse()
+            .with_context(|| format!("Unable to parse OEIS id. path: {:?}", loda_program_path))?;
+        Ok(Some(OeisId::from(value)))
+    }
+
+    /// The known terms of the sequence, and the index of the first term.
+    ///
+    /// The b-file is preferred. When there is no b-file, the terms in the OEIS `stripped` file are used,
+    /// and since the `stripped` file has no offsets, the first index is the `#offset` of the program.
+    fn reference_terms(&self, oeis_id: OeisId, runner: &ProgramRunner) -> anyhow::Result<Option<(i64, BigIntVec)>> {
+        if let Some(oeis_bfile_dir) = &self.oeis_bfile_dir {
+            if let Some(bfile) = BFile::load(oeis_bfile_dir, oeis_id, Some(Self::MAX_TERM_COUNT))? {
+                return Ok(Some((bfile.offset(), bfile.terms().clone())));
+            }
+        }
+        let oeis_stripped_file: &Path = match &self.oeis_stripped_file {
+            Some(value) => value,
+            None => {
+                return Ok(None);
+            }
+        };
+        let position: u64 = match self.stripped_file_index(oeis_stripped_file)?.get(&oeis_id) {
+            Some(value) => *value,
+            None => {
+                return Ok(None);
+            }
+        };
+        let mut file = File::open(oeis_stripped_file)
+            .with_context(|| format!("Unable to open the OEIS 'stripped' file: {:?}", oeis_stripped_file))?;
+        file.seek(SeekFrom::Start(position))?;
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line)?;
+        let line: String = line.trim_end().to_string();
+        let row: StrippedRow = match StrippedRow::parse(&line, Some(Self::MAX_TERM_COUNT)) {
+            Some(value) => value,
+            None => {
+                return Err(anyhow::anyhow!("Unable to parse the terms of {} in the OEIS 'stripped' file", oeis_id.a_number()));
+            }
+        };
+        Ok(Some((runner.first_index(), row.terms().clone())))
+    }
+
+    /// The OEIS `stripped` file is scanned only once, so each `check` can read its row directly.
+    fn stripped_file_index(&self, oeis_stripped_file: &Path) -> anyhow::Result<&HashMap<OeisId, u64>> {
+        if let Some(index) = self.stripped_file_index.get() {
+            return Ok(index);
+        }
+        let index: HashMap<OeisId, u64> = Self::create_stripped_file_index(oeis_stripped_file)?;
+        Ok(self.stripped_file_index.get_or_init(|| index))
+    }
+
+    fn create_stripped_file_index(oeis_stripped_file: &Path) -> anyhow::Result<HashMap<OeisId, u64>> {
+        let file = File::open(oeis_stripped_file)
+            .with_context(|| format!("Unable to open the OEIS 'stripped' file: {:?}", oeis_stripped_file))?;
+        let mut reader = BufReader::new(file);
+        let mut index = HashMap::<OeisId, u64>::new();
+        let mut position: u64 = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let byte_count: usize = reader.read_line(&mut line)?;
+            if byte_count == 0 {
+                break;
+            }
+            // Rows looks like `A000045 ,0,1,1,2,3,5,8,13,21,34,`, and the comments starts with `#`.
+            let oeis_id: Option<u32> = line.strip_prefix('A')
+                .and_then(|rest| rest.split(' ').next())
+                .and_then(|digits| digits.parse::<u32>().ok());
+            if let Some(oeis_id) = oeis_id {
+                index.insert(OeisId::from(oeis_id), position);
+            }
+            position += byte_count as u64;
+        }
+        Ok(index)
+    }
+
+    fn compute_terms(runner: &ProgramRunner, term_count: usize, time_limit: Duration) -> anyhow::Result<BigIntVec> {
+        let mut terms = BigIntVec::with_capacity(term_count);
+        Self::eval_with_callback(runner, runner.first_index(), term_count, time_limit, |_position, term, _step_count| {
+            terms.push(term);
+            Ok(())
+        })?;
+        Ok(terms)
+    }
+
+    /// Compute as many terms as possible within the time limit, up to `term_count` terms.
+    fn compute_terms_within_time_limit(runner: &ProgramRunner, term_count: usize, time_limit: Duration) -> anyhow::Result<BigIntVec> {
+        let mut terms = BigIntVec::with_capacity(term_count);
+        let result = Self::eval_with_callback(runner, runner.first_index(), term_count, time_limit, |_position, term, _step_count| {
+            terms.push(term);
+            Ok(())
+        });
+        if let Err(error) = result {
+            let is_timeout: bool = error.downcast_ref::<LodaCppError>() == Some(&LodaCppError::Timeout);
+            if !is_timeout || terms.is_empty() {
+                return Err(error);
+            }
+        }
+        Ok(terms)
+    }
+
+    /// Greedy removal of instructions, one at a time, starting from the bottom of the program.
+    ///
+    /// The `lpb` and `lpe` instructions are kept, so that the loops stays balanced.
+    ///
+    /// The minimized program must have the same terms as the original program, for as many terms
+    /// as the original program can compute in half the time limit, up to the same number of terms as `check` uses.
+    ///
+    /// If the time limit is reached, then the partially minimized program is returned.
+    fn minimize_parsed_program(&self, parsed_program: &ParsedProgram, time_limit: Duration) -> anyhow::Result<ParsedProgram> {
+        let start = Instant::now();
+        let mut dm: DependencyManager = self.create_dependency_manager();
+        let runner: ProgramRunner = Self::create_runner(&mut dm, parsed_program)?;
+        let expected_terms: BigIntVec = Self::compute_terms_within_time_limit(&runner, Self::MAX_TERM_COUNT, time_limit / 2)
+            .context("Unable to compute terms for the original program")?;
+
+        let mut current: ParsedProgram = parsed_program.clone();
+        let mut index: usize = current.instruction_vec.len();
+        while index > 0 {
+            index -= 1;
+            let elapsed: Duration = start.elapsed();
+            if elapsed > time_limit {
+                debug!("minimize reached the time limit: {:?}, stopping early", time_limit);
+                break;
+            }
+            let instruction_id: InstructionId = current.instruction_vec[index].instruction_id;
+            if instruction_id == InstructionId::LoopBegin || instruction_id == InstructionId::LoopEnd || instruction_id == InstructionId::UnofficialLoopBeginSubtract {
+                continue;
+            }
+            let mut candidate: ParsedProgram = current.clone();
+            candidate.instruction_vec.remove(index);
+            let candidate_runner: ProgramRunner = match Self::create_runner(&mut dm, &candidate) {
+                Ok(value) => value,
+                Err(_) => continue
+            };
+            let remaining_time: Duration = time_limit.saturating_sub(elapsed);
+            let terms: BigIntVec = match Self::compute_terms(&candidate_runner, expected_terms.len(), remaining_time) {
+                Ok(value) => value,
+                Err(_) => continue
+            };
+            if terms == expected_terms {
+                current = candidate;
+            }
+        }
+        Ok(current)
+    }
+}
+
+impl PostMineBackend for PostMineBackendNative {
+    fn eval_terms(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalTerms> {
+        let runner: ProgramRunner = self.load_runner(loda_program_path)?;
+        let terms: BigIntVec = Self::compute_terms(&runner, term_count, time_limit)
+            .with_context(|| format!("eval terms. path: {:?}", loda_program_path))?;
+        Ok(LodaCppEvalTerms::new(terms))
+    }
+
+    /// The comments are preserved, except for the comments that belong to a removed instruction.
+    fn minimize(&self, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<String> {
+        let parsed_program: ParsedProgram = Self::load_program_with_source(loda_program_path)?;
+        let minimized: ParsedProgram = self.minimize_parsed_program(&parsed_program, time_limit)
+            .with_context(|| format!("minimize program. path: {:?}", loda_program_path))?;
+        Ok(minimized.to_string_with_comments())
+    }
+
+    fn perform_check_and_save_output(&self, loda_program_path: &Path, time_limit: Duration, save_output_to_path: &Path) -> anyhow::Result<LodaCppCheckResult> {
+        debug!("will perform native check of {:?}, time_limit: {:?}", loda_program_path, time_limit);
+        let oeis_id: OeisId = match Self::oeis_id_from_program(loda_program_path)? {
+            Some(value) => value,
+            None => {
+                return Err(anyhow::anyhow!("The program has no OEIS id in its first line. path: {:?}", loda_program_path));
+            }
+        };
+        let runner: ProgramRunner = self.load_runner(loda_program_path)?;
+        let (first_index, expected_terms): (i64, BigIntVec) = match self.reference_terms(oeis_id, &runner)? {
+            Some(value) => value,
+            None => {
+                return Err(anyhow::anyhow!("There are no known terms to check {} against. path: {:?}", oeis_id.a_number(), loda_program_path));
+            }
+        };
+
+        // Mimic the output format of `loda-cpp check`, so it can be parsed by `LodaCppCheckResult::parse()`.
+        let mut output = String::new();
+        let result = Self::eval_with_callback(&runner, first_index, expected_terms.len(), time_limit, |position, term, _step_count| {
+            let index: i64 = first_index + (position as i64);
+            let expected_term: &BigInt = &expected_terms[position];
+            if term != *expected_term {
+                output += &format!("{} {} -> expected {}\n", index, term, expected_term);
+                return Err(anyhow::anyhow!("mismatch"));
+            }
+            output += &format!("{} {}\n", index, term);
+            Ok(())
+        });
+        let mut did_timeout = false;
+        match result {
+            Ok(()) => {
+                output += "ok\n";
+            },
+            Err(error) => {
+                if error.downcast_ref::<LodaCppError>() == Some(&LodaCppError::Timeout) {
+                    did_timeout = true;
+                } else {
+                    output += "error\n";
+                }
+            }
+        }
+
+        let mut file = File::create(save_output_to_path)?;
+        file.write_all(output.as_bytes())?;
+        LodaCppCheckResult::parse(&output, did_timeout)
+    }
+
+    fn eval_steps(&self, term_count: usize, loda_program_path: &Path, time_limit: Duration) -> anyhow::Result<LodaCppEvalSteps> {
+        let runner: ProgramRunner = self.load_runner(loda_program_path)?;
+        let mut steps = Vec::<u64>::with_capacity(term_count);
+        Self::eval_with_callback(&runner, runner.first_index(), term_count, time_limit, |_position, _term, step_count| {
+            steps.push(step_count);
+            Ok(())
+        }).with_context(|| format!("eval steps. path: {:?}", loda_program_path))?;
+        Ok(LodaCppEvalSteps::new(steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lodacpp::LodaCppCheckStatus;
+    use loda_rust_core::util::BigIntVecToString;
+
+    fn create_backend(basedir: &Path) -> anyhow::Result<PostMineBackendNative> {
+        let oeis_dir: PathBuf = basedir.join("oeis");
+        fs::create_dir(&oeis_dir)?;
+        let bfile_dir: PathBuf = basedir.join("b");
+        fs::create_dir(&bfile_dir)?;
+        let stripped_file: PathBuf = basedir.join("stripped");
+        fs::write(&stripped_file, b"A000040 ,2,3,5,7,11,\nA000079 ,1,2,4,8,16,32,64,128,256,500,\n")?;
+        let mut backend = PostMineBackendNative::new(oeis_dir);
+        backend.set_reference_terms(bfile_dir, stripped_file);
+        Ok(backend)
+    }
+
+    #[test]
+    fn test_10000_eval_terms() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_10000_eval_terms");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"; powers of 2\nmov $1,2\npow $1,$0\nmov $0,$1\n")?;
+
+        // Act
+        let evalterms: LodaCppEvalTerms = backend.eval_terms(10, &path, Duration::from_secs(5))?;
+
+        // Assert
+        assert_eq!(evalterms.terms().to_compact_comma_string(), "1,2,4,8,16,32,64,128,256,512");
+        Ok(())
+    }
+
+    #[test]
+    fn test_20000_minimize() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_20000_minimize");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"; A000079: Powers of 2\n\nmov $1,2\nmov $2,7\npow $1,$0 ; power\nadd $3,1 ; unused\nmov $0,$1\nadd $0,0\n")?;
+
+        // Act
+        let minimized: String = backend.minimize(&path, Duration::from_secs(5))?;
+
+        // Assert
+        assert_eq!(minimized, "; A000079: Powers of 2\n\nmov $1,2\npow $1,$0 ; power\nmov $0,$1\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_30000_check_full_match() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30000_check_full_match");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"; A000079: Powers of 2\n; 1,2,4,8,16,32,64,128,256,500\n\nmov $1,2\npow $1,$0\nmov $0,$1\n")?;
+        let bfile_content: String = (0..11).map(|index| format!("{} {}\n", index, 1 << index)).collect();
+        fs::write(basedir.join("b").join("b000079.txt"), bfile_content)?;
+        let output_path: PathBuf = basedir.join("check.txt");
+
+        // Act
+        let result: LodaCppCheckResult = backend.perform_check_and_save_output(&path, Duration::from_secs(5), &output_path)?;
+
+        // Assert
+        assert_eq!(result.status, LodaCppCheckStatus::FullMatch);
+        assert_eq!(result.number_of_correct_terms, 11);
+        let output: String = fs::read_to_string(&output_path)?;
+        assert_eq!(output.ends_with("10 1024\nok\n"), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_30001_check_partial_match() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30001_check_partial_match");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        // The terms-comment is ignored, the terms in the OEIS 'stripped' file are used.
+        fs::write(&path, b"; A000079: Powers of 2\n; 1,2,4,8,16,32,64,128,256,512\n\nmov $1,2\npow $1,$0\nmov $0,$1\n")?;
+        let output_path: PathBuf = basedir.join("check.txt");
+
+        // Act
+        let result: LodaCppCheckResult = backend.perform_check_and_save_output(&path, Duration::from_secs(5), &output_path)?;
+
+        // Assert
+        assert_eq!(result.status, LodaCppCheckStatus::PartialMatch);
+        assert_eq!(result.number_of_correct_terms, 9);
+        let output: String = fs::read_to_string(&output_path)?;
+        assert_eq!(output.ends_with("9 512 -> expected 500\nerror\n"), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_30002_check_without_oeis_id() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30002_check_without_oeis_id");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"; 1,2,4,8,16,32,64,128,256,512\n\nmov $1,2\npow $1,$0\nmov $0,$1\n")?;
+        let output_path: PathBuf = basedir.join("check.txt");
+
+        // Act
+        let result = backend.perform_check_and_save_output(&path, Duration::from_secs(5), &output_path);
+
+        // Assert
+        assert_eq!(result.is_err(), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_40000_eval_steps() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_40000_eval_steps");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"mov $1,$0\nlpb $1\n  add $0,1\n  sub $1,1\nlpe\n")?;
+
+        // Act
+        let evalsteps: LodaCppEvalSteps = backend.eval_steps(4, &path, Duration::from_secs(5))?;
+
+        // Assert
+        assert_eq!(evalsteps.steps().len(), 4);
+        assert_eq!(evalsteps.steps()[0] < evalsteps.steps()[3], true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_50000_timeout_inside_slow_term() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_50000_timeout_inside_slow_term");
+        fs::create_dir(&basedir)?;
+        let backend = create_backend(&basedir)?;
+        let path: PathBuf = basedir.join("program.asm");
+        fs::write(&path, b"mov $1,$0\nmul $1,100000000\nlpb $1\n  sub $1,1\nlpe\n")?;
+        let start = Instant::now();
+
+        // Act
+        let result = backend.eval_terms(2, &path, Duration::from_millis(50));
+
+        // Assert
+        let error: anyhow::Error = result.err().expect("timeout");
+        assert_eq!(error.downcast_ref::<LodaCppError>(), Some(&LodaCppError::Timeout));
+        assert_eq!(start.elapsed() < Duration::from_secs(5), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_60000_create_stripped_file_index() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path: PathBuf = PathBuf::from(&tempdir.path()).join("stripped");
+        fs::write(&path, b"# OEIS Sequence Data\nA000040 ,2,3,5,7,11,\nA000079 ,1,2,4,8,\n")?;
+
+        // Act
+        let index: HashMap<OeisId, u64> = PostMineBackendNative::create_stripped_file_index(&path)?;
+
+        // Assert
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(&OeisId::from(40)), Some(&21));
+        assert_eq!(index.get(&OeisId::from(79)), Some(&42));
+        Ok(())
+    }
+}
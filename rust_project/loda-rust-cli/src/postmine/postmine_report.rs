@@ -0,0 +1,219 @@
+use super::{CandidateProgram, State};
+use crate::common::{diff_lines, DiffLine};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tera::Tera;
+
+const TEMPLATE_REPORT: &str = include_str!("../../web/templates/postmine/report.html");
+
+#[derive(Clone, Debug, Serialize)]
+struct ReportDiffLine {
+    kind: String,
+    text: String,
+}
+
+impl ReportDiffLine {
+    fn from_diff_line(diff_line: &DiffLine) -> Self {
+        let kind: &str = match diff_line {
+            DiffLine::Same(_) => "same",
+            DiffLine::Removed(_) => "removed",
+            DiffLine::Added(_) => "added",
+        };
+        Self {
+            kind: kind.to_string(),
+            text: diff_line.to_string(),
+        }
+    }
+}
+
+/// The outcome of comparing a candidate program with a single OEIS sequence.
+#[derive(Clone, Debug, Serialize)]
+pub struct PostMineReportItem {
+    iteration: usize,
+    candidate: String,
+    oeis_id: String,
+    oeis_name: String,
+    oeis_keywords: String,
+    program: String,
+    existing_program: Option<String>,
+    diff: Vec<ReportDiffLine>,
+    check: String,
+    step_comparison: Option<String>,
+    outcome: String,
+    keep: bool,
+}
+
+impl PostMineReportItem {
+    pub fn new(iteration: usize, candidate: String, oeis_id: String, oeis_name: String) -> Self {
+        Self {
+            iteration,
+            candidate,
+            oeis_id,
+            oeis_name,
+            oeis_keywords: String::new(),
+            program: String::new(),
+            existing_program: None,
+            diff: vec!(),
+            check: "not checked".to_string(),
+            step_comparison: None,
+            outcome: "undecided".to_string(),
+            keep: false,
+        }
+    }
+
+    /// Comma separated OEIS keywords, such as `core,nonn,nice`.
+    pub fn set_oeis_keywords(&mut self, oeis_keywords: String) {
+        self.oeis_keywords = oeis_keywords;
+    }
+
+    /// The formatted candidate program, and the existing program from the `loda-programs` repo, if any.
+    pub fn set_programs(&mut self, program: &str, existing_program: Option<String>) {
+        self.program = program.to_string();
+        if let Some(existing_program) = &existing_program {
+            self.diff = diff_lines(existing_program, program).iter().map(ReportDiffLine::from_diff_line).collect();
+        }
+        self.existing_program = existing_program;
+    }
+
+    pub fn set_check<S: AsRef<str>>(&mut self, check: S) {
+        self.check = check.as_ref().to_string();
+    }
+
+    /// The content of the `iterationN_compare.txt` file, with the steps of the candidate program vs the existing program.
+    pub fn set_step_comparison(&mut self, step_comparison: Option<String>) {
+        self.step_comparison = step_comparison;
+    }
+
+    pub fn keep<S: AsRef<str>>(&mut self, outcome: S) {
+        self.keep = true;
+        self.outcome = outcome.as_ref().to_string();
+    }
+
+    pub fn reject<S: AsRef<str>>(&mut self, outcome: S) {
+        self.keep = false;
+        self.outcome = outcome.as_ref().to_string();
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ReportCandidate {
+    filename: String,
+    state: String,
+    reason: String,
+}
+
+/// HTML report of a postmine run, so a human can audit what has been kept or rejected.
+pub struct PostMineReport {
+    created_at: String,
+    dry_run: bool,
+    items: Vec<PostMineReportItem>,
+}
+
+impl PostMineReport {
+    pub fn new(created_at: String, dry_run: bool) -> Self {
+        Self {
+            created_at,
+            dry_run,
+            items: vec!(),
+        }
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn push(&mut self, item: PostMineReportItem) {
+        self.items.push(item);
+    }
+
+    pub fn render(&self, candidate_programs: &Vec<&CandidateProgram>) -> anyhow::Result<String> {
+        let candidates: Vec<ReportCandidate> = candidate_programs.iter().map(|candidate_program| {
+            let state: &str = match candidate_program.state() {
+                State::PendingProcessing => "pending",
+                State::Keep => "keep",
+                State::Reject => "reject",
+            };
+            ReportCandidate {
+                filename: candidate_program.filename_original().clone(),
+                state: state.to_string(),
+                reason: candidate_program.reason().cloned().unwrap_or_default(),
+            }
+        }).collect();
+
+        let mut tera = Tera::default();
+        tera.add_raw_template("report.html", TEMPLATE_REPORT)?;
+        tera.autoescape_on(vec![".html"]);
+        let mut context = tera::Context::new();
+        context.insert("created_at", &self.created_at);
+        context.insert("dry_run", &self.dry_run);
+        context.insert("candidates", &candidates);
+        context.insert("items", &self.items);
+        let html: String = tera.render("report.html", &context)?;
+        Ok(html)
+    }
+
+    pub fn save(&self, candidate_programs: &Vec<&CandidateProgram>, path: &Path) -> anyhow::Result<()> {
+        let html: String = self.render(candidate_programs)?;
+        fs::write(path, html)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_10000_render() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_10000_render");
+        fs::create_dir(&basedir)?;
+        let input_path: PathBuf = basedir.join("19840101-054915-1251916462.asm");
+        fs::write(&input_path, "mul $0,2\n")?;
+        let mut candidate_program = CandidateProgram::new(&input_path)
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        candidate_program.set_dry_run(true);
+        candidate_program.perform_keep("Corresponds to: A005843")?;
+
+        let mut report = PostMineReport::new("19840101-054915".to_string(), true);
+        let mut item = PostMineReportItem::new(1, "19840101-054915-1251916462.asm".to_string(), "A005843".to_string(), "The nonnegative even numbers: a(n) = 2n.".to_string());
+        item.set_programs("mul $0,2\n", Some("add $0,$0\n".to_string()));
+        item.set_check("FullMatch, correct terms: 100");
+        item.set_oeis_keywords("core,nonn,easy".to_string());
+        item.keep("The new program is an improvement.");
+        report.push(item);
+
+        // Act
+        let html: String = report.render(&vec![&candidate_program])?;
+
+        // Assert
+        assert_eq!(html.contains("<b>Dry run.</b>"), true);
+        assert_eq!(html.contains("Corresponds to: A005843"), true);
+        assert_eq!(html.contains("<span class=\"diff-removed\">-add $0,$0</span>"), true);
+        assert_eq!(html.contains("<span class=\"diff-added\">+mul $0,2</span>"), true);
+        assert_eq!(html.contains("FullMatch, correct terms: 100"), true);
+        assert_eq!(html.contains("Keywords: core,nonn,easy"), true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_10001_render_escape_html() -> anyhow::Result<()> {
+        // Arrange
+        let mut report = PostMineReport::new("19840101-054915".to_string(), false);
+        let mut item = PostMineReportItem::new(1, "candidate.asm".to_string(), "A000001".to_string(), "a(n) < 5 & b(n) > 3".to_string());
+        item.reject("rejected");
+        report.push(item);
+
+        // Act
+        let html: String = report.render(&vec![])?;
+
+        // Assert
+        assert_eq!(html.contains("<b>Dry run.</b>"), false);
+        assert_eq!(html.contains("a(n) &lt; 5 &amp; b(n) &gt; 3"), true);
+        assert_eq!(html.contains("Keywords:"), false);
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use crate::common::{create_csv_file, parse_csv_file};
+use crate::lodacpp::LodaCppError;
+use loda_rust_core::oeis::OeisId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Why a candidate program got rejected by `postmine`.
+///
+/// The `category` is a short stable name, used for aggregating statistics about mining yield.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "category", rename_all = "snake_case")]
+pub enum RejectReason {
+    /// Unable to compute the initial terms of the candidate program.
+    EvalError { message: String },
+
+    /// Computing the initial terms exceeded the time limit.
+    EvalTimeout,
+
+    /// The initial terms cannot be found in the OEIS `stripped` file.
+    NoMatchingSequence,
+
+    /// The initial terms only matches sequences listed in the `dont_mine.csv` file.
+    DontMine { oeis_ids: Vec<String> },
+
+    /// Unable to remove redundant instructions from the candidate program.
+    MinimizeError { message: String },
+
+    /// The candidate program is identical to another candidate program or to the existing program.
+    Duplicate { duplicate_of: String },
+
+    /// Unable to check the candidate program against the b-file.
+    CheckError { message: String },
+
+    /// Checking the candidate program against the b-file exceeded the time limit.
+    CheckTimeout,
+
+    /// The candidate program is correct, but the b-file has too few terms for it to be trusted.
+    TooFewTerms { number_of_correct_terms: usize },
+
+    /// The terms are correct up to `index`, followed by a mismatch.
+    Mismatch { index: usize },
+
+    /// Unable to compare the number of steps used by the candidate program and the existing program.
+    CompareError { message: String },
+
+    /// The candidate program isn't faster than the existing program.
+    SlowerThanExisting,
+
+    /// Uncategorized.
+    Other { message: String },
+}
+
+impl RejectReason {
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::EvalError { .. } => "eval_error",
+            Self::EvalTimeout => "eval_timeout",
+            Self::NoMatchingSequence => "no_matching_sequence",
+            Self::DontMine { .. } => "dont_mine",
+            Self::MinimizeError { .. } => "minimize_error",
+            Self::Duplicate { .. } => "duplicate",
+            Self::CheckError { .. } => "check_error",
+            Self::CheckTimeout => "check_timeout",
+            Self::TooFewTerms { .. } => "too_few_terms",
+            Self::Mismatch { .. } => "mismatch",
+            Self::CompareError { .. } => "compare_error",
+            Self::SlowerThanExisting => "slower_than_existing",
+            Self::Other { .. } => "other",
+        }
+    }
+
+    /// Distinguish between timeouts and other kinds of errors.
+    pub fn eval_error(error: &anyhow::Error) -> Self {
+        if Self::is_timeout(error) {
+            return Self::EvalTimeout;
+        }
+        Self::EvalError { message: format!("{:?}", error) }
+    }
+
+    /// Distinguish between timeouts and other kinds of errors.
+    pub fn check_error(error: &anyhow::Error) -> Self {
+        if Self::is_timeout(error) {
+            return Self::CheckTimeout;
+        }
+        Self::CheckError { message: format!("{:?}", error) }
+    }
+
+    fn is_timeout(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<LodaCppError>() == Some(&LodaCppError::Timeout)
+    }
+
+    /// How far the candidate program got in the postmine funnel, before it was rejected.
+    fn stage(&self) -> u8 {
+        match self {
+            Self::Other { .. } => 0,
+            Self::EvalError { .. } | Self::EvalTimeout => 1,
+            Self::NoMatchingSequence | Self::DontMine { .. } => 2,
+            Self::MinimizeError { .. } => 3,
+            Self::CheckError { .. } | Self::CheckTimeout => 4,
+            Self::TooFewTerms { .. } | Self::Mismatch { .. } => 5,
+            Self::CompareError { .. } => 6,
+            Self::Duplicate { .. } | Self::SlowerThanExisting => 7,
+        }
+    }
+
+    /// A candidate program is compared with several OEIS sequences, and each comparison may fail for a different reason.
+    ///
+    /// Picks the reason of the comparison that got furthest in the postmine funnel.
+    /// When there are several with the same stage, then the first one is picked.
+    pub fn most_relevant(items: &[(OeisId, RejectReason)]) -> Option<&RejectReason> {
+        let mut result: Option<&RejectReason> = None;
+        for (_oeis_id, reason) in items {
+            match result {
+                Some(current) if current.stage() >= reason.stage() => {},
+                _ => {
+                    result = Some(reason);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EvalError { message } => write!(f, "Couldn't eval program, {}", message),
+            Self::EvalTimeout => write!(f, "Couldn't eval program, timeout"),
+            Self::NoMatchingSequence => write!(f, "Terms cannot be found in OEIS 'stripped' file"),
+            Self::DontMine { oeis_ids } => write!(f, "Terms only matches sequences in the 'dont_mine.csv' file: {}", oeis_ids.join(",")),
+            Self::MinimizeError { message } => write!(f, "Unable to minimize program: {}", message),
+            Self::Duplicate { duplicate_of } => write!(f, "Duplicate of {}", duplicate_of),
+            Self::CheckError { message } => write!(f, "Unable to check program: {}", message),
+            Self::CheckTimeout => write!(f, "Timeout while checking program"),
+            Self::TooFewTerms { number_of_correct_terms } => write!(f, "Too few terms, got {} correct terms", number_of_correct_terms),
+            Self::Mismatch { index } => write!(f, "Mismatch at index {}", index),
+            Self::CompareError { message } => write!(f, "Unable to compare with the existing program: {}", message),
+            Self::SlowerThanExisting => write!(f, "Worse than the existing program"),
+            Self::Other { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<&str> for RejectReason {
+    fn from(message: &str) -> Self {
+        Self::Other { message: message.to_string() }
+    }
+}
+
+impl From<String> for RejectReason {
+    fn from(message: String) -> Self {
+        Self::Other { message }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RejectReasonSidecarItem {
+    oeis_id: String,
+    message: String,
+    #[serde(flatten)]
+    reason: RejectReason,
+}
+
+/// The content of the `.reject.json` file, that is saved next to the `.reject.asm` file.
+#[derive(Clone, Debug, Serialize)]
+pub struct RejectReasonSidecar {
+    filename: String,
+    message: String,
+    #[serde(flatten)]
+    reason: RejectReason,
+    comparisons: Vec<RejectReasonSidecarItem>,
+}
+
+impl RejectReasonSidecar {
+    pub fn new(filename: String, reason: &RejectReason, oeis_id_reject_reasons: &[(OeisId, RejectReason)]) -> Self {
+        let comparisons: Vec<RejectReasonSidecarItem> = oeis_id_reject_reasons.iter().map(|(oeis_id, reason)| {
+            RejectReasonSidecarItem {
+                oeis_id: oeis_id.a_number(),
+                message: reason.to_string(),
+                reason: reason.clone(),
+            }
+        }).collect();
+        Self {
+            filename,
+            message: reason.to_string(),
+            reason: reason.clone(),
+            comparisons,
+        }
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let json: String = serde_json::to_string_pretty(self)?;
+        Ok(json)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RejectReasonCountRecord {
+    category: String,
+    count: u64,
+}
+
+/// Number of rejected candidate programs per category.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RejectReasonCounts {
+    counts: BTreeMap<String, u64>,
+}
+
+impl RejectReasonCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the counts from a previous run. If the file doesn't exist, then start from zero.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut instance = Self::new();
+        if !path.is_file() {
+            return Ok(instance);
+        }
+        let records: Vec<RejectReasonCountRecord> = parse_csv_file(path)
+            .map_err(|e| anyhow::anyhow!("Unable to load reject reason counts from: {:?} error: {:?}", path, e))?;
+        for record in records {
+            *instance.counts.entry(record.category).or_insert(0) += record.count;
+        }
+        Ok(instance)
+    }
+
+    pub fn increment(&mut self, reason: &RejectReason) {
+        *self.counts.entry(reason.category().to_string()).or_insert(0) += 1;
+    }
+
+    pub fn append(&mut self, other: &RejectReasonCounts) {
+        for (category, count) in &other.counts {
+            *self.counts.entry(category.clone()).or_insert(0) += count;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Save as a `.csv` file, with the most frequent categories first.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut records: Vec<RejectReasonCountRecord> = self.counts.iter().map(|(category, count)| {
+            RejectReasonCountRecord {
+                category: category.clone(),
+                count: *count,
+            }
+        }).collect();
+        records.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.category.cmp(&b.category)));
+        create_csv_file(&records, path)
+            .map_err(|e| anyhow::anyhow!("Unable to save reject reason counts to: {:?} error: {:?}", path, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_10000_category() {
+        let reasons: Vec<RejectReason> = vec![
+            RejectReason::EvalTimeout,
+            RejectReason::TooFewTerms { number_of_correct_terms: 5 },
+            RejectReason::Duplicate { duplicate_of: "A000045".to_string() },
+            RejectReason::from("hello"),
+        ];
+        for reason in reasons {
+            let json: String = serde_json::to_string(&reason).unwrap();
+            let expected: String = format!("{{\"category\":\"{}\"", reason.category());
+            assert!(json.starts_with(&expected), "json: {} reason: {:?}", json, reason);
+        }
+    }
+
+    #[test]
+    fn test_10001_eval_error_timeout() {
+        let error: anyhow::Result<()> = Err(LodaCppError::Timeout).map_err(|e| anyhow::Error::new(e).context("exceeded time limit"));
+        assert_eq!(RejectReason::eval_error(&error.unwrap_err()), RejectReason::EvalTimeout);
+        let error = anyhow::anyhow!("boom");
+        assert_eq!(RejectReason::check_error(&error).category(), "check_error");
+    }
+
+    #[test]
+    fn test_20000_most_relevant() {
+        let items: Vec<(OeisId, RejectReason)> = vec![
+            (OeisId::from(40), RejectReason::CheckTimeout),
+            (OeisId::from(45), RejectReason::SlowerThanExisting),
+            (OeisId::from(142), RejectReason::TooFewTerms { number_of_correct_terms: 3 }),
+            (OeisId::from(290), RejectReason::Duplicate { duplicate_of: "A000290".to_string() }),
+        ];
+        assert_eq!(RejectReason::most_relevant(&items), Some(&RejectReason::SlowerThanExisting));
+        assert_eq!(RejectReason::most_relevant(&items[0..1]), Some(&RejectReason::CheckTimeout));
+        assert_eq!(RejectReason::most_relevant(&[]), None);
+    }
+
+    #[test]
+    fn test_30000_sidecar_to_json() -> anyhow::Result<()> {
+        // Arrange
+        let items: Vec<(OeisId, RejectReason)> = vec![
+            (OeisId::from(45), RejectReason::Mismatch { index: 7 }),
+        ];
+        let sidecar = RejectReasonSidecar::new("a.asm".to_string(), &items[0].1, &items);
+
+        // Act
+        let json: String = sidecar.to_json()?;
+
+        // Assert
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        assert_eq!(value["filename"], "a.asm");
+        assert_eq!(value["category"], "mismatch");
+        assert_eq!(value["index"], 7);
+        assert_eq!(value["message"], "Mismatch at index 7");
+        assert_eq!(value["comparisons"][0]["oeis_id"], "A000045");
+        assert_eq!(value["comparisons"][0]["category"], "mismatch");
+        Ok(())
+    }
+
+    #[test]
+    fn test_40000_counts_save_load() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_40000_counts_save_load");
+        fs::create_dir(&basedir)?;
+        let path: PathBuf = basedir.join("reject_reasons.csv");
+        let mut counts = RejectReasonCounts::new();
+        counts.increment(&RejectReason::SlowerThanExisting);
+        counts.increment(&RejectReason::SlowerThanExisting);
+        counts.increment(&RejectReason::NoMatchingSequence);
+        counts.save(&path)?;
+
+        // Act
+        let mut counts2 = RejectReasonCounts::load(&path)?;
+        counts2.append(&counts);
+        counts2.save(&path)?;
+
+        // Assert
+        let expected = "category;count\nslower_than_existing;4\nno_matching_sequence;2\n";
+        assert_eq!(fs::read_to_string(&path)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_40001_counts_load_missing_file() -> anyhow::Result<()> {
+        let path = PathBuf::from("/non-existing/reject_reasons.csv");
+        let counts = RejectReasonCounts::load(&path)?;
+        assert_eq!(counts.is_empty(), true);
+        Ok(())
+    }
+}
@@ -10,15 +10,22 @@ const NUMBER_OF_TERMS_TO_VALIDATE: u64 = 1;
 
 pub struct ValidateSingleProgram {
     loda_programs_oeis_dir: PathBuf,
+    unofficial_function_registry: UnofficialFunctionRegistry,
 }
 
 impl ValidateSingleProgram {
     pub fn new(loda_programs_oeis_dir: PathBuf) -> Self {
         Self {
             loda_programs_oeis_dir: loda_programs_oeis_dir,
+            unofficial_function_registry: UnofficialFunctionRegistry::new(),
         }
     }
 
+    /// The unofficial functions that the programs are allowed to use.
+    pub fn set_unofficial_function_registry(&mut self, unofficial_function_registry: UnofficialFunctionRegistry) {
+        self.unofficial_function_registry = unofficial_function_registry;
+    }
+
     pub fn run(&self, program_path: &Path) -> anyhow::Result<()> {
         // Load the program
         if !program_path.is_file() {
@@ -31,7 +38,7 @@ impl ValidateSingleProgram {
         let mut dm = DependencyManager::new(
             DependencyManagerFileSystemMode::System,
             self.loda_programs_oeis_dir.clone(),
-            UnofficialFunctionRegistry::new(),
+            self.unofficial_function_registry.clone(),
         );
         let result_parse = dm.parse(
             ProgramId::ProgramWithoutId, 
@@ -1,6 +1,6 @@
 //! The `loda-rust similar` subcommand, identifies similar programs.
 use crate::analytics::{Analytics, AnalyticsDirectory};
-use crate::common::{find_asm_files_recursively, oeis_id_from_path};
+use crate::common::{create_csv_file, find_asm_files_recursively, oeis_id_from_path};
 use crate::common::RecordBigram;
 use crate::common::SimpleLog;
 use super::{Word, WordPair, WordsFromProgram};
@@ -138,6 +138,16 @@ impl Similar {
             let content = format!("\n\nnumber of program_meta items: {}", program_meta_vec.len());
             self.simple_log.println(content);    
         }
+
+        {
+            let records: Vec<DuplicateRecord> = DuplicateRecord::find_duplicates(&program_meta_vec);
+            let path: PathBuf = self.similar_programs.join(Path::new("duplicate_programs.csv"));
+            create_csv_file(&records, &path)
+                .map_err(|e| anyhow::anyhow!("Failed to create csv file with duplicate programs: {:?} error: {:?}", path, e))?;
+            let content = format!("\n\nnumber of structurally identical programs: {}", records.len());
+            self.simple_log.println(content);
+            println!("Found {} programs that are structurally identical to another program", records.len());
+        }
        
         {
             let start = Instant::now();
@@ -240,12 +250,14 @@ impl Similar {
         }
     
         let signature: BitSet = indexes_array.compute_signature(&match_set);
+        let canonical_hash: u64 = parsed_program.canonical_form().hash();
     
         let program_meta = ProgramMeta::new(
             program_id,
             PathBuf::from(path),
             line_count,
-            signature
+            signature,
+            canonical_hash
         );
         Some(program_meta)
     }
@@ -277,16 +289,50 @@ struct ProgramMeta {
     line_count: u16,
 
     signature: BitSet,
+
+    /// Programs with the same canonical form are structurally identical.
+    canonical_hash: u64,
 }
 
 impl ProgramMeta {
-    fn new(program_id: u32, path_input: PathBuf, line_count: u16, signature: BitSet) -> Self {
+    fn new(program_id: u32, path_input: PathBuf, line_count: u16, signature: BitSet, canonical_hash: u64) -> Self {
         Self {
             program_id: program_id,
             path_input: path_input,
             line_count: line_count,
-            signature: signature
+            signature: signature,
+            canonical_hash: canonical_hash
+        }
+    }
+}
+
+/// A program that is structurally identical to a program with a lower program id.
+#[derive(Debug, PartialEq, Serialize)]
+struct DuplicateRecord {
+    #[serde(rename = "program id")]
+    program_id: u32,
+
+    #[serde(rename = "duplicate of")]
+    duplicate_of: u32,
+}
+
+impl DuplicateRecord {
+    /// Group the programs by their canonical form, and refer to the lowest program id in each group.
+    fn find_duplicates(program_meta_vec: &[ProgramMeta]) -> Vec<DuplicateRecord> {
+        let mut hash_to_program_id = HashMap::<u64, u32>::new();
+        for program_meta in program_meta_vec {
+            let program_id: &mut u32 = hash_to_program_id.entry(program_meta.canonical_hash).or_insert(program_meta.program_id);
+            *program_id = (*program_id).min(program_meta.program_id);
+        }
+        let mut records: Vec<DuplicateRecord> = vec!();
+        for program_meta in program_meta_vec {
+            let duplicate_of: u32 = hash_to_program_id[&program_meta.canonical_hash];
+            if duplicate_of != program_meta.program_id {
+                records.push(DuplicateRecord { program_id: program_meta.program_id, duplicate_of });
+            }
         }
+        records.sort_by_key(|record| record.program_id);
+        records
     }
 }
 
@@ -380,3 +426,34 @@ impl OutputManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program_meta(program_id: u32, canonical_hash: u64) -> ProgramMeta {
+        ProgramMeta::new(program_id, PathBuf::from("program.asm"), 1, BitSet::new(), canonical_hash)
+    }
+
+    #[test]
+    fn test_10000_find_duplicates() {
+        // Arrange
+        let program_meta_vec = vec![
+            program_meta(45, 1000),
+            program_meta(7, 2000),
+            program_meta(40, 1000),
+            program_meta(99, 2000),
+            program_meta(12, 3000),
+        ];
+
+        // Act
+        let records: Vec<DuplicateRecord> = DuplicateRecord::find_duplicates(&program_meta_vec);
+
+        // Assert
+        let expected = vec![
+            DuplicateRecord { program_id: 45, duplicate_of: 40 },
+            DuplicateRecord { program_id: 99, duplicate_of: 7 },
+        ];
+        assert_eq!(records, expected);
+    }
+}
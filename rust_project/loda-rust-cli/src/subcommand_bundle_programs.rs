@@ -0,0 +1,163 @@
+//! The `loda-rust bundle-programs` subcommand, writes many programs into a single bundle file.
+use crate::common::{create_unofficial_function_registry, find_asm_files_recursively, oeis_id_from_path};
+use crate::config::Config;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, ProgramBundle};
+use loda_rust_core::oeis::OeisId;
+use loda_rust_core::parser::ParsedProgram;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// Write programs from the `loda-programs` repo into a single bundle file.
+///
+/// The bundle can be loaded by `loda-rust-web`, so that the browser doesn't have to
+/// fetch the dependencies one at a time.
+///
+/// When program ids are provided, then the bundle contains these programs and all their direct/indirect dependencies.
+///
+/// When no program ids are provided, then the bundle contains all the programs in the `loda-programs/oeis` dir.
+pub struct SubcommandBundlePrograms {
+    config: Config,
+    output_path: PathBuf,
+    program_ids: Vec<u64>,
+}
+
+impl SubcommandBundlePrograms {
+    pub fn new(output_path: PathBuf, program_ids: Vec<u64>) -> Self {
+        Self {
+            config: Config::load(),
+            output_path,
+            program_ids,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let loda_programs_oeis_dir: PathBuf = self.config.loda_programs_oeis_dir();
+        let bundle: ProgramBundle = if self.program_ids.is_empty() {
+            Self::bundle_all_programs(&loda_programs_oeis_dir)?
+        } else {
+            let dm = DependencyManager::new(
+                DependencyManagerFileSystemMode::System,
+                loda_programs_oeis_dir,
+                create_unofficial_function_registry(&self.config)?,
+            );
+            Self::bundle_programs_with_dependencies(&dm, &self.program_ids)?
+        };
+        let content: String = bundle.to_bundle_string();
+        fs::write(&self.output_path, &content)
+            .with_context(|| format!("Unable to write bundle file: {:?}", self.output_path))?;
+        println!("Wrote {} programs, {} bytes, to: {:?}", bundle.len(), content.len(), self.output_path);
+        Ok(())
+    }
+
+    fn bundle_all_programs(loda_programs_oeis_dir: &Path) -> anyhow::Result<ProgramBundle> {
+        let paths: Vec<PathBuf> = find_asm_files_recursively(loda_programs_oeis_dir);
+        let mut bundle = ProgramBundle::new();
+        for path in paths {
+            let oeis_id: OeisId = match oeis_id_from_path(&path) {
+                Some(value) => value,
+                None => {
+                    debug!("Ignoring file without an OEIS id in its filename: {:?}", path);
+                    continue;
+                }
+            };
+            let source_code: String = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read program: {:?}", path))?;
+            bundle.insert(oeis_id.raw() as u64, source_code);
+        }
+        Ok(bundle)
+    }
+
+    /// The `dm` is only used for constructing paths to the programs.
+    fn bundle_programs_with_dependencies(dm: &DependencyManager, program_ids: &[u64]) -> anyhow::Result<ProgramBundle> {
+        let mut bundle = ProgramBundle::new();
+        let mut visited = HashSet::<u64>::new();
+        let mut pending: Vec<u64> = program_ids.to_vec();
+        while let Some(program_id) = pending.pop() {
+            if !visited.insert(program_id) {
+                continue;
+            }
+            let path: PathBuf = dm.path_to_program(program_id);
+            let source_code: String = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read program {} at path: {:?}", program_id, path))?;
+            let parsed_program: ParsedProgram = ParsedProgram::parse_program(&source_code)
+                .map_err(|error| anyhow::anyhow!("Unable to parse program {}. error: {}", program_id, error))?;
+            pending.extend(parsed_program.direct_dependencies());
+            bundle.insert(program_id, source_code);
+        }
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+    use std::fs;
+
+    fn bundle_programs_with_dependencies(basedir: &Path, program_ids: &[u64]) -> anyhow::Result<ProgramBundle> {
+        let dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            basedir.to_path_buf(),
+            UnofficialFunctionRegistry::new(),
+        );
+        SubcommandBundlePrograms::bundle_programs_with_dependencies(&dm, program_ids)
+    }
+
+    #[test]
+    fn test_10000_bundle_programs_with_dependencies() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_10000_bundle_programs_with_dependencies");
+        let dir000: PathBuf = basedir.join("000");
+        fs::create_dir_all(&dir000).expect("ok");
+        fs::write(dir000.join("A000001.asm"), "seq $0,2\nseq $0,3\n").expect("ok");
+        fs::write(dir000.join("A000002.asm"), "seq $0,3\n").expect("ok");
+        fs::write(dir000.join("A000003.asm"), "add $0,1\n").expect("ok");
+        fs::write(dir000.join("A000004.asm"), "add $0,2\n").expect("ok");
+
+        // Act
+        let bundle: ProgramBundle = bundle_programs_with_dependencies(&basedir, &[1]).expect("bundle");
+
+        // Assert
+        assert_eq!(bundle.program_ids(), vec![1, 2, 3]);
+        assert_eq!(bundle.get(2).expect("program"), "seq $0,3\n");
+    }
+
+    #[test]
+    fn test_10001_bundle_programs_with_missing_dependency() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_10001_bundle_programs_with_missing_dependency");
+        let dir000: PathBuf = basedir.join("000");
+        fs::create_dir_all(&dir000).expect("ok");
+        fs::write(dir000.join("A000001.asm"), "seq $0,2\n").expect("ok");
+
+        // Act
+        let result = bundle_programs_with_dependencies(&basedir, &[1]);
+
+        // Assert
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_bundle_all_programs() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_20000_bundle_all_programs");
+        let dir000: PathBuf = basedir.join("000");
+        let dir123: PathBuf = basedir.join("123");
+        fs::create_dir_all(&dir000).expect("ok");
+        fs::create_dir_all(&dir123).expect("ok");
+        fs::write(dir000.join("A000001.asm"), "seq $0,2\n").expect("ok");
+        fs::write(dir123.join("A123456.asm"), "mov $0,1\n").expect("ok");
+        fs::write(dir123.join("README.md"), "ignore").expect("ok");
+
+        // Act
+        let bundle: ProgramBundle = SubcommandBundlePrograms::bundle_all_programs(&basedir).expect("bundle");
+
+        // Assert
+        assert_eq!(bundle.program_ids(), vec![1, 123456]);
+    }
+}
@@ -0,0 +1,151 @@
+//! The `loda-rust compare` subcommand, compares two programs.
+use crate::common::{create_unofficial_function_registry, DiffLine};
+use crate::compare::{loop_aligned_diff, CompareTermsResult, ProgramEvaluation, TermMismatch};
+use crate::config::Config;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::execute::{ProgramCache, ProgramId, ProgramRunner};
+use loda_rust_core::parser::ParsedProgram;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// Compare two programs.
+///
+/// - Instruction level diff, where the loops are aligned.
+/// - Evaluate both programs, and show the first differing term and the ratio between the number of steps.
+/// - Optional bounded equivalence check, by evaluating both programs for every index in a range.
+pub struct SubcommandCompare {
+    path0: PathBuf,
+    path1: PathBuf,
+    term_count: usize,
+    equivalence_range: Option<Range<i64>>,
+}
+
+impl SubcommandCompare {
+    pub fn new(path0: PathBuf, path1: PathBuf, term_count: usize, equivalence_range: Option<Range<i64>>) -> Self {
+        Self {
+            path0,
+            path1,
+            term_count,
+            equivalence_range,
+        }
+    }
+
+    /// Parse a range of indexes, such as `0..1000`.
+    pub fn parse_range(s: &str) -> anyhow::Result<Range<i64>> {
+        let (start, end) = s.split_once("..")
+            .ok_or_else(|| anyhow::anyhow!("Expected range like '0..1000', but got: {:?}", s))?;
+        let start: i64 = start.trim().parse()
+            .with_context(|| format!("Unable to parse the start of the range: {:?}", s))?;
+        let end: i64 = end.trim().parse()
+            .with_context(|| format!("Unable to parse the end of the range: {:?}", s))?;
+        if start >= end {
+            return Err(anyhow::anyhow!("The range must be non-empty, but got: {:?}", s));
+        }
+        Ok(start..end)
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let parsed_program0: ParsedProgram = Self::load_program(&self.path0)?;
+        let parsed_program1: ParsedProgram = Self::load_program(&self.path1)?;
+
+        println!("--- a: {:?}", self.path0);
+        println!("+++ b: {:?}", self.path1);
+        let diff: Vec<DiffLine> = loop_aligned_diff(&parsed_program0.instruction_vec, &parsed_program1.instruction_vec);
+        for line in &diff {
+            println!("{}", line);
+        }
+        println!();
+
+        // Dependencies are loaded from the `loda-programs` repo.
+        let config = Config::load();
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            config.loda_programs_oeis_dir(),
+            create_unofficial_function_registry(&config)?,
+        );
+        let runner0: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, &parsed_program0)
+            .map_err(|e| anyhow::anyhow!("Unable to create program a. error: {:?}", e))?;
+        let runner1: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, &parsed_program1)
+            .map_err(|e| anyhow::anyhow!("Unable to create program b. error: {:?}", e))?;
+        let mut cache = ProgramCache::new();
+
+        let result = CompareTermsResult::with_term_count(&runner0, &runner1, &mut cache, self.term_count as u64);
+        println!("Evaluating {} terms, for the indexes {}..{}", self.term_count, result.range.start, result.range.end);
+        Self::print_result(&result);
+
+        if let Some(range) = &self.equivalence_range {
+            println!();
+            println!("Bounded equivalence check for the range {}..{}", range.start, range.end);
+            let result = CompareTermsResult::new(&runner0, &runner1, &mut cache, range.clone());
+            if result.is_equivalent() {
+                println!("Equivalent for all {} indexes in the range.", range.end - range.start);
+            } else {
+                println!("Not equivalent.");
+            }
+            Self::print_result(&result);
+        }
+        Ok(())
+    }
+
+    fn load_program(path: &Path) -> anyhow::Result<ParsedProgram> {
+        let contents: String = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read program: {:?}", path))?;
+        ParsedProgram::parse_program(&contents)
+            .map_err(|e| anyhow::anyhow!("Unable to parse program: {:?} error: {:?}", path, e))
+    }
+
+    fn print_evaluation(name: &str, evaluation: &ProgramEvaluation) {
+        println!("{}: {} terms, {} steps", name, evaluation.terms.len(), evaluation.total_steps());
+        if let Some(error) = &evaluation.error {
+            println!("{}: stopped early. {}", name, error);
+        }
+    }
+
+    fn print_result(result: &CompareTermsResult) {
+        Self::print_evaluation("a", &result.evaluation0);
+        Self::print_evaluation("b", &result.evaluation1);
+        match result.first_mismatch() {
+            Some(TermMismatch { index, term0, term1 }) => {
+                let format_term = |term: Option<num_bigint::BigInt>| -> String {
+                    match term {
+                        Some(value) => value.to_string(),
+                        None => "none".to_string()
+                    }
+                };
+                println!("First differing term: a({}) = {}, b({}) = {}", index, format_term(term0), index, format_term(term1));
+            },
+            None => {
+                println!("Identical terms");
+            }
+        }
+        match result.step_ratio() {
+            Some(ratio) => println!("Step count ratio b/a: {:.3}", ratio),
+            None => println!("Step count ratio b/a: undefined")
+        }
+        if let Some(verdict) = result.performance_verdict() {
+            println!("PerformanceClassifier verdict for b relative to a: {:?}", verdict);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_parse_range() {
+        assert_eq!(SubcommandCompare::parse_range("0..1000").unwrap(), 0..1000);
+        assert_eq!(SubcommandCompare::parse_range("-5..5").unwrap(), -5..5);
+        assert_eq!(SubcommandCompare::parse_range(" 10 .. 20 ").unwrap(), 10..20);
+    }
+
+    #[test]
+    fn test_10001_parse_range_error() {
+        assert_eq!(SubcommandCompare::parse_range("").is_err(), true);
+        assert_eq!(SubcommandCompare::parse_range("5").is_err(), true);
+        assert_eq!(SubcommandCompare::parse_range("5..5").is_err(), true);
+        assert_eq!(SubcommandCompare::parse_range("a..b").is_err(), true);
+    }
+}
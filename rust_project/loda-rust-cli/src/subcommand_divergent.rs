@@ -0,0 +1,10 @@
+//! The `loda-rust divergent` subcommand, re-evaluates the near-miss programs and promotes the corrected ones.
+use crate::divergent::AnalyzeDivergent;
+
+pub struct SubcommandDivergent;
+
+impl SubcommandDivergent {
+    pub fn run(dry_run: bool) -> anyhow::Result<()> {
+        AnalyzeDivergent::run(dry_run)
+    }
+}
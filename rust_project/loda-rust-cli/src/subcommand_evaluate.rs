@@ -1,12 +1,14 @@
 //! The `loda-rust eval` subcommand, evaluates terms of a program.
 use loda_rust_core;
+use std::io::{self, Write};
 use std::time::Instant;
 use std::rc::Rc;
-use std::path::PathBuf;
-use loda_rust_core::control::{DependencyManager,DependencyManagerFileSystemMode};
-use loda_rust_core::execute::{NodeLoopLimit, ProgramCache, ProgramRunner, RegisterValue, RunMode};
-use loda_rust_core::execute::NodeRegisterLimit;
+use std::ops::Range;
+use std::num::NonZeroUsize;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, EvaluationPool};
+use loda_rust_core::execute::{ProgramCache, ProgramRunner, RunMode, SharedProgramCache, TermResult};
 use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+use crate::common::create_unofficial_function_registry;
 use crate::config::Config;
 
 pub enum SubcommandEvaluateMode {
@@ -15,148 +17,139 @@ pub enum SubcommandEvaluateMode {
     PrintDebug,
 }
 
+/// The number of values in the cache that is shared between the threads.
+const SHARED_CACHE_CAPACITY: usize = 100000;
+
+fn create_dependency_manager(config: &Config, registry: &UnofficialFunctionRegistry) -> DependencyManager {
+    DependencyManager::new(
+        DependencyManagerFileSystemMode::System,
+        config.loda_programs_oeis_dir(),
+        registry.clone(),
+    )
+}
+
+/// With more than 1 thread, the terms are evaluated in parallel. This is not supported by `PrintDebug`.
 pub fn subcommand_evaluate(
     program_id: u64, 
+    start_index: Option<i64>,
     number_of_terms: u64,
     mode: SubcommandEvaluateMode,
-) {
+    number_of_threads: NonZeroUsize,
+) -> anyhow::Result<()> {
+    if number_of_terms < 1 {
+        panic!("Expected number of terms to be 1 or greater.");
+    }
     let config = Config::load();
-    let loda_programs_oeis_dir: PathBuf = config.loda_programs_oeis_dir();
+    let registry: UnofficialFunctionRegistry = create_unofficial_function_registry(&config)?;
+    let mut dm: DependencyManager = create_dependency_manager(&config, &registry);
+    let program_runner: Rc::<ProgramRunner> = dm.load(program_id)
+        .map_err(|error| anyhow::anyhow!("Failed to load program: {}", error))?;
 
-    let mut dm = DependencyManager::new(
-        DependencyManagerFileSystemMode::System,
-        loda_programs_oeis_dir,
-        UnofficialFunctionRegistry::new(),
-    );
-    let program_runner: Rc::<ProgramRunner> = match dm.load(program_id) {
-        Ok(value) => value,
-        Err(error) => {
-            panic!("Failed to load program: {:?}", error);
-        }
-    };
-    match mode {
-        SubcommandEvaluateMode::PrintTerms => {
-            program_runner.print_terms(number_of_terms);
-        },
-        SubcommandEvaluateMode::PrintSteps => {
-            program_runner.print_steps(number_of_terms);
-        },
+    // The indexes are in OEIS coordinates, and by default starts at the `#offset` of the program.
+    let first_index: i64 = start_index.unwrap_or(program_runner.first_index());
+    let count: i64 = i64::try_from(number_of_terms).expect("Value is too high. Cannot be converted to 64bit signed integer.");
+    let indexes: Range<i64> = first_index..first_index.saturating_add(count);
+    let print: fn(&mut dyn Iterator<Item = TermResult>) = match mode {
+        SubcommandEvaluateMode::PrintTerms => print_terms,
+        SubcommandEvaluateMode::PrintSteps => print_steps,
         SubcommandEvaluateMode::PrintDebug => {
-            program_runner.print_debug(number_of_terms);
+            print_debug(&program_runner, indexes);
+            return Ok(());
         }
+    };
+    if number_of_threads.get() > 1 {
+        let mut pool = EvaluationPool::new(number_of_threads);
+        pool.set_shared_cache(SharedProgramCache::new(NonZeroUsize::new(SHARED_CACHE_CAPACITY).unwrap()));
+        let indexes: Vec<i64> = indexes.collect();
+        let terms: Vec<TermResult> = pool.map(&indexes, || create_dependency_manager(&config, &registry), |dm, cache, index| {
+            match dm.load(program_id) {
+                Ok(runner) => eval_term(&runner, *index, cache, RunMode::Silent),
+                Err(error) => TermResult {
+                    index: *index,
+                    value: Err(anyhow::anyhow!("Failed to load program: {}", error)),
+                    step_count: 0,
+                }
+            }
+        });
+        print(&mut terms.into_iter());
+        return Ok(());
     }
+    let mut cache = ProgramCache::new();
+    let start_time = Instant::now();
+    print(&mut program_runner.eval_terms(indexes, &mut cache));
+    debug!("cache: {}", cache.hit_miss_info());
+    debug!("elapsed: {:?} ms", start_time.elapsed().as_millis());
+    Ok(())
 }
 
-trait PrintTermsStepsDebug {
-    fn print_terms(&self, count: u64);
-    fn print_steps(&self, count: u64);
-    fn print_debug(&self, count: u64);
+/// Evaluate a single term.
+///
+/// There is no term when the index is `i64::MAX`, since the range ends right after the index.
+fn eval_term(runner: &ProgramRunner, index: i64, cache: &mut ProgramCache, run_mode: RunMode) -> TermResult {
+    let term: Option<TermResult> = runner.eval_terms(index..index.saturating_add(1), cache)
+        .run_mode(run_mode)
+        .next();
+    term.unwrap_or_else(|| TermResult {
+        index,
+        value: Err(anyhow::anyhow!("The index is out of range")),
+        step_count: 0,
+    })
 }
 
-impl PrintTermsStepsDebug for ProgramRunner {
-    fn print_terms(&self, count: u64) {
-        if count >= 0x7fff_ffff_ffff_ffff {
-            panic!("Value is too high. Cannot be converted to 64bit signed integer.");
-        }
-        if count < 1 {
-            panic!("Expected number of terms to be 1 or greater.");
+fn print_debug(runner: &ProgramRunner, indexes: Range<i64>) {
+    let mut cache = ProgramCache::new();
+    let mut step_count: u64 = 0;
+    for index in indexes {
+        // The input is printed before the term is evaluated, so the verbose output is shown below it.
+        println!("INPUT: a({})", index);
+        let term: TermResult = eval_term(runner, index, &mut cache, RunMode::Verbose);
+        step_count += term.step_count;
+        match term.value {
+            Ok(value) => println!("OUTPUT: a({}) = {}", index, value),
+            Err(error) => println!("OUTPUT: {}", format_error(index, &error)),
         }
-        let mut cache = ProgramCache::new();
-        let step_count_limit: u64 = 1000000000;
-        let mut step_count: u64 = 0;
-        let start_time = Instant::now();
-        for index in 0..(count as i64) {
-            let input = RegisterValue::from_i64(index);
-            let result_run = self.run(
-                input, 
-                RunMode::Silent, 
-                &mut step_count, 
-                step_count_limit,
-                NodeRegisterLimit::Unlimited,
-                NodeLoopLimit::Unlimited,
-                &mut cache
-            );
-            let output: RegisterValue = match result_run {
-                Ok(value) => value,
-                Err(error) => {
-                    panic!("Failure while computing term {}, error: {:?}", index, error);
-                }
-            };
-            if index == 0 {
-                print!("{}", output.0);
-                continue;
-            }
-            print!(",{}", output.0);
-        }
-        print!("\n");
-        debug!("steps: {}", step_count);
-        debug!("cache: {}", cache.hit_miss_info());
-        debug!("elapsed: {:?} ms", start_time.elapsed().as_millis());
     }
+    debug!("stats: step_count: {}", step_count);
+}
 
-    fn print_steps(&self, count: u64) {
-        if count >= 0x7fff_ffff_ffff_ffff {
-            panic!("Value is too high. Cannot be converted to 64bit signed integer.");
-        }
-        if count < 1 {
-            panic!("Expected number of terms to be 1 or greater.");
-        }
-        let mut cache = ProgramCache::new();
-        let step_count_limit: u64 = 1000000000;
-        for index in 0..(count as i64) {
-            let input = RegisterValue::from_i64(index);
-            let mut step_count: u64 = 0;
-            let result_run = self.run(
-                input, 
-                RunMode::Silent, 
-                &mut step_count, 
-                step_count_limit,
-                NodeRegisterLimit::Unlimited,
-                NodeLoopLimit::Unlimited,
-                &mut cache,
-            );
-            if let Err(error) = result_run {
-                panic!("Failure while computing term {}, error: {:?}", index, error);
-            }
-            if index == 0 {
-                print!("{}", step_count);
-                continue;
-            }
-            print!(",{}", step_count);
-        }
-        print!("\n");
+/// Print the terms comma separated, each term as soon as it has been computed.
+///
+/// A term that cannot be computed is printed as `index: error`, and the following terms are still computed.
+fn print_terms(terms: &mut dyn Iterator<Item = TermResult>) {
+    let mut step_count: u64 = 0;
+    for (position, term) in terms.enumerate() {
+        step_count += term.step_count;
+        let item: String = match term.value {
+            Ok(value) => value.to_string(),
+            Err(error) => format_error(term.index, &error),
+        };
+        print_item(position, &item);
     }
+    println!();
+    debug!("steps: {}", step_count);
+}
 
-    fn print_debug(&self, count: u64) {
-        if count >= 0x7fff_ffff_ffff_ffff {
-            panic!("Value is too high. Cannot be converted to 64bit signed integer.");
-        }
-        if count < 1 {
-            panic!("Expected number of terms to be 1 or greater.");
-        }
-        let mut cache = ProgramCache::new();
-        let step_count_limit: u64 = 1000000000;
-        let mut step_count: u64 = 0;
-        for index in 0..(count as i64) {
-            println!("INPUT: a({})", index);
-            let input = RegisterValue::from_i64(index);
-            let result_run = self.run(
-                input, 
-                RunMode::Verbose, 
-                &mut step_count, 
-                step_count_limit,
-                NodeRegisterLimit::Unlimited,
-                NodeLoopLimit::Unlimited,
-                &mut cache,
-            );
-            let output: RegisterValue = match result_run {
-                Ok(value) => value,
-                Err(error) => {
-                    panic!("Failure while computing term {}, error: {:?}", index, error);
-                }
-            };
-            println!("OUTPUT: a({}) = {}", index, output.0);
-        }
-        debug!("stats: step_count: {}", step_count);
+/// Print the number of steps used for each term, comma separated, same as `print_terms()`.
+fn print_steps(terms: &mut dyn Iterator<Item = TermResult>) {
+    for (position, term) in terms.enumerate() {
+        let item: String = match term.value {
+            Ok(_) => term.step_count.to_string(),
+            Err(error) => format_error(term.index, &error),
+        };
+        print_item(position, &item);
     }
+    println!();
+}
+
+fn print_item(position: usize, item: &str) {
+    if position > 0 {
+        print!(",");
+    }
+    print!("{}", item);
+    io::stdout().flush().expect("Unable to flush stdout");
+}
+
+/// The root cause is more telling than the context, such as `Division by zero`.
+fn format_error(index: i64, error: &anyhow::Error) -> String {
+    format!("{}: {}", index, error.root_cause())
 }
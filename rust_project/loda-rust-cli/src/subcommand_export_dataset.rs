@@ -1,4 +1,4 @@
-//! The `loda-rust export-dataset` subcommand, exports terms and programs to a CSV file.
+//! The `loda-rust export-dataset` subcommand, exports terms and programs to CSV or JSON files.
 use crate::analytics::{Analytics, AnalyticsDirectory};
 use crate::config::Config;
 use crate::common::{find_asm_files_recursively, load_program_ids_csv_file, oeis_id_from_path};
@@ -6,14 +6,16 @@ use crate::common::create_csv_file;
 use crate::oeis::{ProcessStrippedFile, StrippedRow};
 use anyhow::Context;
 use loda_rust_core::util::BigIntVecToString;
+use loda_rust_core::control::DependencyManager;
 use loda_rust_core::oeis::OeisIdHashSet;
 use loda_rust_core::oeis::OeisId;
-use loda_rust_core::parser::{ParsedProgram};
+use loda_rust_core::parser::{InstructionId, ParseInstructionId, ParsedProgram};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Instant;
 use console::Style;
 use indicatif::{HumanDuration, ProgressBar};
@@ -21,20 +23,208 @@ use serde::Serialize;
 use num_bigint::BigInt;
 use num_traits::Zero;
 
-/// In the OEIS stripped file. Ignore sequences that too short.
-const MIN_TERM_COUNT: usize = 20;
+pub type OeisIdToTermsSet = HashMap::<OeisId, String>;
 
-/// In the OEIS stripped file. Only extract up to this number of terms.
-const MAX_TERM_COUNT: usize = 20;
+/// The file format of the exported dataset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportDatasetFormat {
+    /// Semicolon separated, with a header row. The line breaks in the programs are escaped as `\n`.
+    Csv,
 
-pub type OeisIdToTermsSet = HashMap::<OeisId, String>;
+    /// One JSON object per line.
+    Jsonl,
+
+    /// Columnar JSON, a single JSON object with one array per column, that can be loaded directly as a data frame.
+    Columnar,
+}
+
+impl ExportDatasetFormat {
+    fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Columnar => "json",
+        }
+    }
+}
+
+impl FromStr for ExportDatasetFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::Jsonl),
+            "columnar" => Ok(Self::Columnar),
+            _ => Err(anyhow::anyhow!("Unrecognized format {:?}, expected csv, jsonl or columnar", s))
+        }
+    }
+}
+
+/// The train/validation/test split.
+///
+/// A sequence always ends up in the same split, regardless of what other sequences are exported,
+/// since the split is determined by a hash of the OEIS id.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExportDatasetSplit {
+    train_percent: u8,
+    validation_percent: u8,
+    test_percent: u8,
+}
+
+impl ExportDatasetSplit {
+    pub fn new(train_percent: u8, validation_percent: u8, test_percent: u8) -> anyhow::Result<Self> {
+        let sum: u16 = (train_percent as u16) + (validation_percent as u16) + (test_percent as u16);
+        if sum != 100 {
+            return Err(anyhow::anyhow!("Expected the split percentages to add up to 100, but got {}", sum));
+        }
+        let instance = Self {
+            train_percent,
+            validation_percent,
+            test_percent,
+        };
+        Ok(instance)
+    }
+
+    /// Returns `"train"`, `"validation"` or `"test"`.
+    pub fn name_for_oeis_id(&self, oeis_id: OeisId) -> &'static str {
+        // SplitMix64 finalizer, so consecutive OEIS ids are spread evenly.
+        let mut value: u64 = oeis_id.raw() as u64;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+        value ^= value >> 31;
+        let bucket: u8 = (value % 100) as u8;
+        if bucket < self.train_percent {
+            return "train";
+        }
+        if bucket < self.train_percent + self.validation_percent {
+            return "validation";
+        }
+        "test"
+    }
+
+    fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::<&'static str>::new();
+        if self.train_percent > 0 {
+            names.push("train");
+        }
+        if self.validation_percent > 0 {
+            names.push("validation");
+        }
+        if self.test_percent > 0 {
+            names.push("test");
+        }
+        names
+    }
+}
+
+impl FromStr for ExportDatasetSplit {
+    type Err = anyhow::Error;
+
+    /// Parse a string like `80,10,10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<u8> = s.split(',')
+            .map(|value| value.trim().parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+            .with_context(|| format!("Unable to parse split {:?}, expected train,validation,test percentages such as 80,10,10", s))?;
+        if values.len() != 3 {
+            return Err(anyhow::anyhow!("Expected 3 percentages such as 80,10,10, but got {:?}", s));
+        }
+        Self::new(values[0], values[1], values[2])
+    }
+}
+
+/// Settings for the exported dataset.
+#[derive(Clone, Debug)]
+pub struct ExportDatasetOptions {
+    /// In the OEIS stripped file. Ignore sequences that are shorter.
+    pub min_term_count: usize,
+
+    /// In the OEIS stripped file. Only extract up to this number of terms.
+    pub max_term_count: usize,
+
+    pub format: ExportDatasetFormat,
+
+    /// When `None`, then everything is saved in a single file.
+    pub split: Option<ExportDatasetSplit>,
+
+    /// Add a `dependencies` column with the source code of the programs invoked via `seq`, including indirect dependencies.
+    pub inline_dependencies: bool,
+
+    /// When true, the programs are reformatted and the comments are removed.
+    /// When false, the programs are exported as they are in the `loda-programs` repo.
+    pub normalize: bool,
+
+    /// Ignore programs with fewer instructions.
+    pub min_instruction_count: Option<usize>,
+
+    /// Ignore programs with more instructions.
+    pub max_instruction_count: Option<usize>,
+
+    /// Ignore programs that use instructions outside this set.
+    pub instruction_set: Option<HashSet<InstructionId>>,
+}
+
+impl Default for ExportDatasetOptions {
+    fn default() -> Self {
+        Self {
+            min_term_count: 20,
+            max_term_count: 20,
+            format: ExportDatasetFormat::Csv,
+            split: None,
+            inline_dependencies: false,
+            normalize: true,
+            min_instruction_count: None,
+            max_instruction_count: None,
+            instruction_set: None,
+        }
+    }
+}
+
+impl ExportDatasetOptions {
+    /// Parse a comma separated list of instructions, such as `add,sub,mov,lpb,lpe`.
+    pub fn parse_instruction_set(s: &str) -> anyhow::Result<HashSet<InstructionId>> {
+        let mut instruction_set = HashSet::<InstructionId>::new();
+        for item in s.split(',') {
+            let instruction_id: InstructionId = InstructionId::parse(item.trim(), 0)
+                .map_err(|_| anyhow::anyhow!("Unrecognized instruction {:?} in the instruction set", item))?;
+            instruction_set.insert(instruction_id);
+        }
+        Ok(instruction_set)
+    }
+
+    /// Check that the program satisfies the complexity and instruction set filters.
+    fn accept_program(&self, parsed_program: &ParsedProgram) -> bool {
+        let instruction_count: usize = parsed_program.instruction_vec.len();
+        if let Some(min_instruction_count) = self.min_instruction_count {
+            if instruction_count < min_instruction_count {
+                return false;
+            }
+        }
+        if let Some(max_instruction_count) = self.max_instruction_count {
+            if instruction_count > max_instruction_count {
+                return false;
+            }
+        }
+        if let Some(instruction_set) = &self.instruction_set {
+            let all_allowed: bool = parsed_program.instruction_vec.iter().all(|instruction| {
+                instruction_set.contains(&instruction.instruction_id)
+            });
+            if !all_allowed {
+                return false;
+            }
+        }
+        true
+    }
+}
 
-/// This outputs a CSV file.
-/// 
-/// Path to the generated file: `~/.loda-rust/analytics/dataset.csv`.
-/// 
+/// Exports the terms and programs, for use as AI training data.
+///
+/// Path to the generated files: `~/.loda-rust/analytics/dataset.csv`.
+/// With a split, the files are named `dataset_train.csv`, `dataset_validation.csv` and `dataset_test.csv`.
+///
 /// Sample data:
-/// 
+///
 /// ```csv
 /// oeis;terms;loda
 /// 1950;2,5,7,10,13,15,18,20,23,26,28,31,34,36,39,41,44,47,49,52;mov $1,$0\nadd $0,1\nseq $0,99267\nsub $0,1\nadd $0,$1
@@ -45,14 +235,19 @@ pub type OeisIdToTermsSet = HashMap::<OeisId, String>;
 pub struct SubcommandExportDataset {
     analytics_directory: AnalyticsDirectory,
     config: Config,
+    options: ExportDatasetOptions,
     count_ignored: usize,
     count_insufficient_number_of_terms: usize,
+    count_rejected_by_filter: usize,
     oeis_id_to_terms_set: OeisIdToTermsSet,
     records: Vec<Record>,
 }
 
 impl SubcommandExportDataset {
-    pub fn export_dataset() -> anyhow::Result<()> {
+    pub fn export_dataset(options: ExportDatasetOptions) -> anyhow::Result<()> {
+        if options.min_term_count > options.max_term_count {
+            return Err(anyhow::anyhow!("The min term count {} is greater than the max term count {}", options.min_term_count, options.max_term_count));
+        }
         let config = Config::load();
         let analytics_directory = AnalyticsDirectory::new(
             config.analytics_oeis_dir()
@@ -61,8 +256,10 @@ impl SubcommandExportDataset {
         let mut instance = Self {
             analytics_directory,
             config,
+            options,
             count_ignored: 0,
             count_insufficient_number_of_terms: 0,
+            count_rejected_by_filter: 0,
             records: vec!(),
             oeis_id_to_terms_set: OeisIdToTermsSet::new(),
         };
@@ -92,15 +289,15 @@ impl SubcommandExportDataset {
         let file = File::open(oeis_stripped_file)?;
         let mut oeis_stripped_file_reader = BufReader::new(file);
 
-        let padding_value = BigInt::zero();    
+        let padding_value = BigInt::zero();
         let mut processor = ProcessStrippedFile::new();
         let oeis_ids_to_ignore = OeisIdHashSet::new();
         processor.execute(
             &mut oeis_stripped_file_reader,
-            MIN_TERM_COUNT,
-            MAX_TERM_COUNT,
+            self.options.min_term_count,
+            self.options.max_term_count,
             &oeis_ids_to_ignore,
-            &padding_value, 
+            &padding_value,
             false,
             callback
         );
@@ -131,13 +328,18 @@ impl SubcommandExportDataset {
 
         let pb = ProgressBar::new(number_of_paths as u64);
         let start = Instant::now();
-        for path in paths {
-            self.process_program_file(&path, &ignore_program_ids)?;
+        for path in &paths {
+            match self.process_program_file(path, &ignore_program_ids)? {
+                ProgramFileOutcome::Ignored => self.count_ignored += 1,
+                ProgramFileOutcome::InsufficientNumberOfTerms => self.count_insufficient_number_of_terms += 1,
+                ProgramFileOutcome::RejectedByFilter => self.count_rejected_by_filter += 1,
+                ProgramFileOutcome::Record(record) => self.records.push(record),
+            }
             pb.inc(1);
         }
         pb.finish_and_clear();
 
-        let green_bold = Style::new().green().bold();        
+        let green_bold = Style::new().green().bold();
         println!(
             "{:>12} exported dataset in {}",
             green_bold.apply_to("Finished"),
@@ -150,10 +352,13 @@ impl SubcommandExportDataset {
         if self.count_insufficient_number_of_terms > 0 {
             println!("count_insufficient_number_of_terms: {}", self.count_insufficient_number_of_terms);
         }
+        if self.count_rejected_by_filter > 0 {
+            println!("count_rejected_by_filter: {}", self.count_rejected_by_filter);
+        }
         Ok(())
     }
 
-    fn process_program_file(&mut self, path_to_program: &Path, ignore_program_ids: &HashSet<u32>) -> anyhow::Result<()> {
+    fn process_program_file(&self, path_to_program: &Path, ignore_program_ids: &HashSet<u32>) -> anyhow::Result<ProgramFileOutcome> {
         let program_id: u32 = match oeis_id_from_path(path_to_program) {
             Some(oeis_id) => oeis_id.raw(),
             None => {
@@ -161,8 +366,7 @@ impl SubcommandExportDataset {
             }
         };
         if ignore_program_ids.contains(&program_id) {
-            self.count_ignored += 1;
-            return Ok(());
+            return Ok(ProgramFileOutcome::Ignored);
         }
         let oeis_id = OeisId::from(program_id);
         let terms: String = match self.oeis_id_to_terms_set.get(&oeis_id) {
@@ -170,11 +374,10 @@ impl SubcommandExportDataset {
                 value.clone()
             },
             None => {
-                self.count_insufficient_number_of_terms += 1;
-                return Ok(());
+                return Ok(ProgramFileOutcome::InsufficientNumberOfTerms);
             }
         };
-        let contents: String = match fs::read_to_string(&path_to_program) {
+        let contents: String = match fs::read_to_string(path_to_program) {
             Ok(value) => value,
             Err(error) => {
                 return Err(anyhow::anyhow!("loading program_id: {:?}, something went wrong reading the file: {:?}", program_id, error));
@@ -186,37 +389,125 @@ impl SubcommandExportDataset {
                 return Err(anyhow::anyhow!("parsing program_id: {:?}, something went wrong parsing the file: {:?}", program_id, error));
             }
         };
-        let instructions: Vec<String> = parsed_program.instruction_vec.iter().map(|instruction| {
-            instruction.to_string()
-        }).collect();
-        let instructions_joined: String = instructions.join("\\n");
+        if !self.options.accept_program(&parsed_program) {
+            return Ok(ProgramFileOutcome::RejectedByFilter);
+        }
+        let program: String = self.program_text(&contents, &parsed_program);
+        let dependencies: Option<String> = if self.options.inline_dependencies {
+            Some(self.dependencies_text(program_id, &parsed_program)?)
+        } else {
+            None
+        };
 
         let record = Record {
-            program_id: program_id,
-            terms: terms,
-            program: instructions_joined
+            program_id,
+            terms,
+            program,
+            dependencies,
         };
-        self.records.push(record);
+        Ok(ProgramFileOutcome::Record(record))
+    }
 
-        Ok(())
+    fn program_text(&self, contents: &str, parsed_program: &ParsedProgram) -> String {
+        if !self.options.normalize {
+            return contents.trim().to_string();
+        }
+        let instructions: Vec<String> = parsed_program.instruction_vec.iter().map(|instruction| {
+            instruction.to_string()
+        }).collect();
+        instructions.join("\n")
+    }
+
+    /// The source code of all the programs that this program depends on, directly or indirectly.
+    ///
+    /// Each program starts with a `; A000045` line, and the programs are sorted by program id.
+    fn dependencies_text(&self, program_id: u32, parsed_program: &ParsedProgram) -> anyhow::Result<String> {
+        let mut visited = HashSet::<u64>::new();
+        visited.insert(program_id as u64);
+        let mut pending: Vec<u64> = parsed_program.direct_dependencies();
+        let mut dependencies = Vec::<(u64, String)>::new();
+        while let Some(dependency_program_id) = pending.pop() {
+            if !visited.insert(dependency_program_id) {
+                continue;
+            }
+            let path: PathBuf = DependencyManager::path_to_program_in_dir(&self.config.loda_programs_oeis_dir(), dependency_program_id);
+            let contents: String = fs::read_to_string(&path)
+                .with_context(|| format!("program_id: {:?}, unable to read dependency: {:?}", program_id, path))?;
+            let parsed_dependency: ParsedProgram = ParsedProgram::parse_program(&contents)
+                .map_err(|error| anyhow::anyhow!("program_id: {:?}, unable to parse dependency: {:?} error: {:?}", program_id, path, error))?;
+            pending.extend(parsed_dependency.direct_dependencies());
+            let text: String = self.program_text(&contents, &parsed_dependency);
+            dependencies.push((dependency_program_id, text));
+        }
+        dependencies.sort_unstable_by_key(|(dependency_program_id, _)| *dependency_program_id);
+        let items: Vec<String> = dependencies.iter().map(|(dependency_program_id, text)| {
+            format!("; A{:0>6}\n{}", dependency_program_id, text)
+        }).collect();
+        Ok(items.join("\n"))
     }
 
-    /// Save as a CSV file
     fn save(&self) -> anyhow::Result<()> {
         let mut records: Vec<Record> = self.records.clone();
-        records.sort_unstable_by_key(|item| (item.program_id));
+        records.sort_unstable_by_key(|item| item.program_id);
 
-        let output_path: PathBuf = self.config.analytics_oeis_dir().join("dataset.csv");
-        match create_csv_file(&records, &output_path) {
-            Ok(_) => {},
-            Err(error) => {
-                return Err(anyhow::anyhow!("Unable to save csv file at {:?}, error: {:?}", output_path, error));
+        let extension: &str = self.options.format.file_extension();
+        let split: ExportDatasetSplit = match self.options.split {
+            Some(value) => value,
+            None => {
+                let output_path: PathBuf = self.config.analytics_oeis_dir().join(format!("dataset.{}", extension));
+                return save_records(&records, self.options.format, &output_path);
             }
+        };
+        for name in split.names() {
+            let split_records: Vec<Record> = records
+                .iter()
+                .filter(|record| split.name_for_oeis_id(OeisId::from(record.program_id)) == name)
+                .cloned()
+                .collect();
+            let output_path: PathBuf = self.config.analytics_oeis_dir().join(format!("dataset_{}.{}", name, extension));
+            save_records(&split_records, self.options.format, &output_path)?;
+            println!("{}: {} records", name, split_records.len());
         }
         Ok(())
     }
 }
 
+fn save_records(records: &[Record], format: ExportDatasetFormat, output_path: &Path) -> anyhow::Result<()> {
+    match format {
+        ExportDatasetFormat::Csv => {
+            // The line breaks are escaped, so each record is on a single line.
+            let records_escaped: Vec<Record> = records.iter().map(|record| record.with_escaped_newlines()).collect();
+            create_csv_file(&records_escaped, output_path)
+                .map_err(|error| anyhow::anyhow!("Unable to save csv file at {:?}, error: {:?}", output_path, error))
+        },
+        ExportDatasetFormat::Jsonl => {
+            let file = File::create(output_path)
+                .with_context(|| format!("Unable to create file: {:?}", output_path))?;
+            let mut writer = BufWriter::new(file);
+            for record in records {
+                let json: String = serde_json::to_string(record)?;
+                writeln!(writer, "{}", json)?;
+            }
+            writer.flush()?;
+            Ok(())
+        },
+        ExportDatasetFormat::Columnar => {
+            let columns = Columns::from(records);
+            let json: String = serde_json::to_string(&columns)?;
+            fs::write(output_path, json)
+                .with_context(|| format!("Unable to save file: {:?}", output_path))
+        }
+    }
+}
+
+/// What happened to a single program file.
+enum ProgramFileOutcome {
+    Ignored,
+    InsufficientNumberOfTerms,
+    RejectedByFilter,
+    Record(Record),
+}
+
 #[derive(Clone, Serialize)]
 struct Record {
     #[serde(rename = "oeis")]
@@ -224,4 +515,137 @@ struct Record {
     terms: String,
     #[serde(rename = "loda")]
     program: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<String>,
+}
+
+impl Record {
+    fn with_escaped_newlines(&self) -> Self {
+        Self {
+            program_id: self.program_id,
+            terms: self.terms.clone(),
+            program: self.program.replace('\n', "\\n"),
+            dependencies: self.dependencies.as_ref().map(|s| s.replace('\n', "\\n")),
+        }
+    }
+}
+
+/// The same data as `Record`, but with one array per column.
+#[derive(Serialize)]
+struct Columns {
+    oeis: Vec<u32>,
+    terms: Vec<String>,
+    loda: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<Vec<String>>,
+}
+
+impl From<&[Record]> for Columns {
+    fn from(records: &[Record]) -> Self {
+        let has_dependencies: bool = records.iter().any(|record| record.dependencies.is_some());
+        let dependencies: Option<Vec<String>> = if has_dependencies {
+            Some(records.iter().map(|record| record.dependencies.clone().unwrap_or_default()).collect())
+        } else {
+            None
+        };
+        Self {
+            oeis: records.iter().map(|record| record.program_id).collect(),
+            terms: records.iter().map(|record| record.terms.clone()).collect(),
+            loda: records.iter().map(|record| record.program.clone()).collect(),
+            dependencies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record { program_id: 45, terms: "0,1,1,2".to_string(), program: "mov $1,1\nadd $0,$1".to_string(), dependencies: Some(String::new()) },
+            Record { program_id: 40, terms: "2,3,5,7".to_string(), program: "seq $0,45".to_string(), dependencies: Some("; A000045\nmov $1,1".to_string()) },
+        ]
+    }
+
+    #[test]
+    fn test_10000_split_from_str() {
+        let split: ExportDatasetSplit = ExportDatasetSplit::from_str("80, 10,10").expect("split");
+        assert_eq!(split, ExportDatasetSplit::new(80, 10, 10).expect("split"));
+        assert_eq!(split.names(), vec!["train", "validation", "test"]);
+        assert_eq!(ExportDatasetSplit::from_str("80,10").is_err(), true);
+        assert_eq!(ExportDatasetSplit::from_str("80,10,11").is_err(), true);
+        assert_eq!(ExportDatasetSplit::from_str("80,x,10").is_err(), true);
+        assert_eq!(ExportDatasetSplit::from_str("100,0,0").expect("split").names(), vec!["train"]);
+    }
+
+    #[test]
+    fn test_10001_split_name_for_oeis_id() {
+        // Arrange
+        let split: ExportDatasetSplit = ExportDatasetSplit::new(80, 10, 10).expect("split");
+
+        // Act
+        let mut histogram = HashMap::<&str, usize>::new();
+        for i in 1..10001 {
+            let name: &str = split.name_for_oeis_id(OeisId::from(i));
+            *histogram.entry(name).or_insert(0) += 1;
+        }
+
+        // Assert
+        assert_eq!(split.name_for_oeis_id(OeisId::from(45)), split.name_for_oeis_id(OeisId::from(45)));
+        let train: usize = histogram["train"];
+        let validation: usize = histogram["validation"];
+        let test: usize = histogram["test"];
+        assert_eq!(train + validation + test, 10000);
+        assert!(train > 7700 && train < 8300, "train: {}", train);
+        assert!(validation > 850 && validation < 1150, "validation: {}", validation);
+        assert!(test > 850 && test < 1150, "test: {}", test);
+    }
+
+    #[test]
+    fn test_20000_accept_program() {
+        // Arrange
+        let program: ParsedProgram = ParsedProgram::parse_program("mov $1,2\nlpb $0\n  sub $0,1\n  mul $1,2\nlpe\nmov $0,$1").expect("program");
+        let mut options = ExportDatasetOptions::default();
+
+        // Act + Assert
+        assert_eq!(options.accept_program(&program), true);
+        options.max_instruction_count = Some(5);
+        assert_eq!(options.accept_program(&program), false);
+        options.max_instruction_count = Some(6);
+        options.min_instruction_count = Some(6);
+        assert_eq!(options.accept_program(&program), true);
+        options.instruction_set = Some(ExportDatasetOptions::parse_instruction_set("mov,lpb,lpe,sub").expect("instruction set"));
+        assert_eq!(options.accept_program(&program), false);
+        options.instruction_set = Some(ExportDatasetOptions::parse_instruction_set("mov,lpb,lpe,sub,mul").expect("instruction set"));
+        assert_eq!(options.accept_program(&program), true);
+        assert_eq!(ExportDatasetOptions::parse_instruction_set("mov,xyz").is_err(), true);
+    }
+
+    #[test]
+    fn test_30000_save_records() -> anyhow::Result<()> {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let basedir = PathBuf::from(&tempdir.path()).join("test_30000_save_records");
+        fs::create_dir(&basedir)?;
+        let records: Vec<Record> = records();
+
+        // Act
+        save_records(&records, ExportDatasetFormat::Csv, &basedir.join("dataset.csv"))?;
+        save_records(&records, ExportDatasetFormat::Jsonl, &basedir.join("dataset.jsonl"))?;
+        save_records(&records, ExportDatasetFormat::Columnar, &basedir.join("dataset.json"))?;
+
+        // Assert
+        let csv: String = fs::read_to_string(basedir.join("dataset.csv"))?;
+        assert_eq!(csv, "oeis;terms;loda;dependencies\n45;0,1,1,2;mov $1,1\\nadd $0,$1;\n40;2,3,5,7;seq $0,45;\"; A000045\\nmov $1,1\"\n");
+        let jsonl: String = fs::read_to_string(basedir.join("dataset.jsonl"))?;
+        let expected_jsonl = r#"{"oeis":45,"terms":"0,1,1,2","loda":"mov $1,1\nadd $0,$1","dependencies":""}
+{"oeis":40,"terms":"2,3,5,7","loda":"seq $0,45","dependencies":"; A000045\nmov $1,1"}
+"#;
+        assert_eq!(jsonl, expected_jsonl);
+        let columnar: String = fs::read_to_string(basedir.join("dataset.json"))?;
+        let expected_columnar = r#"{"oeis":[45,40],"terms":["0,1,1,2","2,3,5,7"],"loda":["mov $1,1\nadd $0,$1","seq $0,45"],"dependencies":["","; A000045\nmov $1,1"]}"#;
+        assert_eq!(columnar, expected_columnar);
+        Ok(())
+    }
 }
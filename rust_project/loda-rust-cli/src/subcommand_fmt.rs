@@ -0,0 +1,144 @@
+//! The `loda-rust fmt` subcommand, reformats programs the same way as the programs in the `loda-programs` repo.
+use crate::common::{find_asm_files_recursively, oeis_id_from_path, OeisIdStringMap};
+use crate::config::Config;
+use crate::postmine::{batch_lookup_names, ProgramSerializerContextWithSequenceName};
+use loda_rust_core::control::DependencyManager;
+use loda_rust_core::execute::ProgramSerializerContext;
+use loda_rust_core::oeis::{OeisId, OeisIdHashSet};
+use loda_rust_core::parser::{formatted_source_code, ParsedProgram};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+use anyhow::Context;
+
+/// A program, or a dir with programs, to be formatted.
+pub enum SubcommandFmtInput {
+    /// A program in the `loda-programs` repo.
+    ProgramId(u64),
+
+    /// A program file, or a dir that is searched recursively for programs.
+    Path(PathBuf),
+}
+
+/// Reformat programs with consistent indentation and spacing, while preserving the comments.
+///
+/// In `check` mode, the files are not modified, and an error is returned when one or more files are not formatted.
+///
+/// In `names` mode, the sequence names in the header and after the `seq` instructions are updated from the OEIS 'names' file.
+pub struct SubcommandFmt {
+    inputs: Vec<SubcommandFmtInput>,
+    check: bool,
+    names: bool,
+}
+
+impl SubcommandFmt {
+    pub fn new(inputs: Vec<SubcommandFmtInput>, check: bool, names: bool) -> Self {
+        Self {
+            inputs,
+            check,
+            names,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        // The config is only loaded when a program id is to be resolved.
+        let mut loda_programs_oeis_dir: Option<PathBuf> = None;
+        let mut paths: Vec<PathBuf> = vec!();
+        for input in &self.inputs {
+            match input {
+                SubcommandFmtInput::ProgramId(program_id) => {
+                    let dir: &PathBuf = loda_programs_oeis_dir.get_or_insert_with(|| Config::load().loda_programs_oeis_dir());
+                    paths.push(DependencyManager::path_to_program_in_dir(dir, *program_id));
+                },
+                SubcommandFmtInput::Path(path) => {
+                    if path.is_dir() {
+                        paths.extend(find_asm_files_recursively(path));
+                    } else {
+                        paths.push(path.clone());
+                    }
+                }
+            }
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut sources: Vec<(PathBuf, String)> = vec!();
+        for path in paths {
+            let contents: String = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read program: {:?}", path))?;
+            sources.push((path, contents));
+        }
+
+        let context: Option<ProgramSerializerContextWithSequenceName> = if self.names {
+            let config = Config::load();
+            let oeis_id_name_map: OeisIdStringMap = lookup_names(&config, &sources)?;
+            Some(ProgramSerializerContextWithSequenceName::new(oeis_id_name_map))
+        } else {
+            None
+        };
+        let context_ref: Option<&dyn ProgramSerializerContext> = context.as_ref().map(|context| context as &dyn ProgramSerializerContext);
+
+        let mut unformatted_count: usize = 0;
+        let mut error_count: usize = 0;
+        for (path, contents) in &sources {
+            let formatted: String = match formatted_source_code(contents, context_ref) {
+                Ok(value) => value,
+                Err(error) => {
+                    println!("{}: {}", path.display(), error);
+                    error_count += 1;
+                    continue;
+                }
+            };
+            if formatted == *contents {
+                continue;
+            }
+            unformatted_count += 1;
+            if self.check {
+                println!("{}: not formatted", path.display());
+                continue;
+            }
+            fs::write(path, formatted)
+                .with_context(|| format!("Unable to write program: {:?}", path))?;
+            println!("{}: formatted", path.display());
+        }
+
+        if self.check {
+            println!("Checked {} files. {} files are not formatted. {} files cannot be parsed.", sources.len(), unformatted_count, error_count);
+            if unformatted_count > 0 || error_count > 0 {
+                return Err(anyhow::anyhow!("{} files are not formatted, {} files cannot be parsed", unformatted_count, error_count));
+            }
+            return Ok(());
+        }
+        println!("Formatted {} of {} files. {} files cannot be parsed.", unformatted_count, sources.len(), error_count);
+        if error_count > 0 {
+            return Err(anyhow::anyhow!("{} files cannot be parsed", error_count));
+        }
+        Ok(())
+    }
+}
+
+/// Look up the names of the programs and the sequences they depend on.
+fn lookup_names(config: &Config, sources: &[(PathBuf, String)]) -> anyhow::Result<OeisIdStringMap> {
+    let mut oeis_ids = OeisIdHashSet::new();
+    for (path, contents) in sources {
+        if let Some(oeis_id) = oeis_id_from_path(path) {
+            oeis_ids.insert(oeis_id);
+        }
+        if let Ok(parsed_program) = ParsedProgram::parse_program(contents) {
+            for program_id in parsed_program.direct_dependencies() {
+                if let Ok(value) = u32::try_from(program_id) {
+                    oeis_ids.insert(OeisId::from(value));
+                }
+            }
+        }
+    }
+    let oeis_names_file: PathBuf = config.oeis_names_file();
+    let file = File::open(&oeis_names_file)
+        .with_context(|| format!("Failed to open OEIS 'names' file: {:?}", oeis_names_file))?;
+    let filesize: usize = file.metadata()
+        .with_context(|| format!("Failed to extract metadata for OEIS 'names' file: {:?}", oeis_names_file))?
+        .len() as usize;
+    let mut reader = BufReader::new(file);
+    batch_lookup_names(&mut reader, filesize, &oeis_ids)
+        .map_err(|error| anyhow::anyhow!("Unable to look up names. error: {:?}", error))
+}
@@ -0,0 +1,73 @@
+//! The `loda-rust fuzz` subcommand, differential testing of `loda-rust` against `loda-cpp`.
+use crate::analytics::AnalyticsDirectory;
+use crate::config::Config;
+use crate::fuzz::{DifferentialFuzzer, DifferentialTest, FuzzCorpus};
+use crate::lodacpp::LodaCpp;
+use crate::mine::{create_genome_mutate_context, CreateGenomeMutateContextMode, GenomeMutateContext};
+use crate::postmine::PostMineBackendNative;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+use std::fs;
+use std::path::PathBuf;
+use rand::{RngCore, thread_rng};
+use anyhow::Context;
+
+/// Generate random programs, evaluate them with both `loda-cpp` and `loda-rust`,
+/// and save the programs where they disagree to the `~/.loda-rust/fuzz/corpus` dir.
+///
+/// When `seed` is `None`, then a random seed is picked. The seed is printed, so a run can be repeated.
+pub struct SubcommandFuzz {
+    iterations: usize,
+    seed: Option<u64>,
+    term_count: usize,
+    compare_steps: bool,
+}
+
+impl SubcommandFuzz {
+    pub fn new(iterations: usize, seed: Option<u64>, term_count: usize, compare_steps: bool) -> Self {
+        Self {
+            iterations,
+            seed,
+            term_count,
+            compare_steps,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let config = Config::load();
+        let loda_cpp_executable: PathBuf = config.loda_cpp_executable();
+        if !loda_cpp_executable.is_file() {
+            return Err(anyhow::anyhow!("The 'loda_cpp_executable' cannot be found at path: {:?}. The fuzzer compares against a locally built loda-cpp.", loda_cpp_executable));
+        }
+        let loda_programs_oeis_dir: PathBuf = config.loda_programs_oeis_dir();
+
+        // The unofficial functions are not supported by loda-cpp, so the mutations doesn't insert them.
+        let unofficial_function_registry = UnofficialFunctionRegistry::new();
+        let analytics_directory = AnalyticsDirectory::new(
+            config.analytics_oeis_dir()
+        ).with_context(||"unable to create AnalyticsDirectory instance")?;
+        let context: GenomeMutateContext = create_genome_mutate_context(CreateGenomeMutateContextMode::OEIS, analytics_directory, &unofficial_function_registry)
+            .context("Unable to create GenomeMutateContext. Run 'loda-rust analytics-oeis' first.")?;
+        let dependency_manager = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            loda_programs_oeis_dir.clone(),
+            unofficial_function_registry,
+        );
+
+        let fuzz_dir: PathBuf = config.fuzz_dir();
+        fs::create_dir_all(&fuzz_dir)
+            .with_context(|| format!("Unable to create fuzz dir: {:?}", fuzz_dir))?;
+        let reference = LodaCpp::new(loda_cpp_executable);
+        let candidate = PostMineBackendNative::new(loda_programs_oeis_dir);
+        let mut differential_test = DifferentialTest::new(Box::new(reference), Box::new(candidate), fuzz_dir.join("program.asm"));
+        differential_test.set_term_count(self.term_count);
+        differential_test.set_compare_steps(self.compare_steps);
+        let corpus = FuzzCorpus::new(fuzz_dir.join("corpus"));
+        println!("corpus dir: {:?}", corpus.corpus_dir());
+
+        let seed: u64 = self.seed.unwrap_or_else(|| thread_rng().next_u64());
+        println!("seed: {}", seed);
+        let mut fuzzer = DifferentialFuzzer::new(seed, dependency_manager, context, differential_test, corpus);
+        fuzzer.run(self.iterations)
+    }
+}
@@ -0,0 +1,106 @@
+//! The `loda-rust inline` subcommand, replaces the `seq` instructions with the called programs.
+use crate::common::create_unofficial_function_registry;
+use crate::compare::{CompareTermsResult, TermMismatch};
+use crate::config::Config;
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode, InlineSeq, InlineSeqResult, InlinedProgram};
+use loda_rust_core::execute::{ProgramCache, ProgramId, ProgramRunner};
+use loda_rust_core::parser::ParsedProgram;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// The program to be inlined.
+pub enum SubcommandInlineInput {
+    /// A program in the `loda-programs` repo.
+    ProgramId(u64),
+
+    /// A program anywhere in the file system.
+    Path(PathBuf),
+}
+
+/// Print a dependency-free version of a program, where the `seq` instructions are replaced by the called programs.
+///
+/// The terms of the inlined program are verified against the original program, before anything is printed.
+/// The output is a valid LODA program, with the verification status as comments at the end.
+pub struct SubcommandInline {
+    input: SubcommandInlineInput,
+    term_count: usize,
+}
+
+impl SubcommandInline {
+    pub fn new(input: SubcommandInlineInput, term_count: usize) -> Self {
+        Self {
+            input,
+            term_count,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let config = Config::load();
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            config.loda_programs_oeis_dir(),
+            create_unofficial_function_registry(&config)?,
+        );
+
+        let path: PathBuf = match &self.input {
+            SubcommandInlineInput::ProgramId(program_id) => dm.path_to_program(*program_id),
+            SubcommandInlineInput::Path(path) => path.clone(),
+        };
+        let original: ParsedProgram = load_program(&path)?;
+
+        let mut load = |program_id: u64| -> anyhow::Result<ParsedProgram> {
+            load_program(&dm.path_to_program(program_id))
+        };
+        let result: InlinedProgram = match InlineSeq::inline(&original, &mut load) {
+            InlineSeqResult::Inlined(value) => value,
+            InlineSeqResult::NotInlinable { reason } => {
+                return Err(anyhow::anyhow!("Cannot inline the program: {:?}, {}", path, reason));
+            }
+        };
+
+        let runner_original: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, &original)
+            .map_err(|e| anyhow::anyhow!("Unable to create the original program. error: {:?}", e))?;
+        let runner_inlined: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, &result.program)
+            .map_err(|e| anyhow::anyhow!("Unable to create the inlined program. error: {:?}", e))?;
+        let mut cache = ProgramCache::new();
+        let compare = CompareTermsResult::with_term_count(&runner_original, &runner_inlined, &mut cache, self.term_count as u64);
+        if let Some(TermMismatch { index, term0, term1 }) = compare.first_mismatch() {
+            let format_term = |term: Option<num_bigint::BigInt>| -> String {
+                match term {
+                    Some(value) => value.to_string(),
+                    None => "none".to_string()
+                }
+            };
+            return Err(anyhow::anyhow!(
+                "The inlined program differs from the original program. a({}) original: {}, inlined: {}",
+                index, format_term(term0), format_term(term1)
+            ));
+        }
+
+        print!("{}", result.program.to_string_with_comments());
+        if !result.inlined_program_ids.is_empty() {
+            let program_ids: Vec<String> = result.inlined_program_ids
+                .iter()
+                .map(|program_id| format!("A{:0>6}", program_id))
+                .collect();
+            println!("; Inlined: {}", program_ids.join(", "));
+        }
+        for skipped in &result.skipped {
+            println!("; Not inlined: A{:0>6}, {}", skipped.program_id, skipped.reason);
+        }
+
+        println!(
+            "; Verified: {} terms are identical to the original program, for the indexes {}..{}",
+            self.term_count, compare.range.start, compare.range.end
+        );
+        Ok(())
+    }
+}
+
+fn load_program(path: &Path) -> anyhow::Result<ParsedProgram> {
+    let contents: String = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read program: {:?}", path))?;
+    ParsedProgram::parse_program_with_source(&contents)
+        .map_err(|e| anyhow::anyhow!("Unable to parse program: {:?} error: {:?}", path, e))
+}
@@ -0,0 +1,165 @@
+//! The `loda-rust lint` subcommand, reports dead stores and unused registers in programs.
+use crate::common::{create_unofficial_function_registry, find_asm_files_recursively};
+use crate::config::Config;
+use loda_rust_core::analysis::{Finding, ProgramAnalysis};
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::parser::{Diagnostic, ParsedProgram};
+use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+
+/// A program, or a dir with programs, to be linted.
+pub enum SubcommandLintInput {
+    /// A program in the `loda-programs` repo.
+    ProgramId(u64),
+
+    /// A program file, or a dir that is searched recursively for programs.
+    Path(PathBuf),
+}
+
+/// Print the findings of the static analysis, one line per finding, prefixed with the path and line number.
+///
+/// When no inputs are provided, then all the programs in the `loda-programs` repo are linted.
+pub struct SubcommandLint {
+    inputs: Vec<SubcommandLintInput>,
+}
+
+impl SubcommandLint {
+    pub fn new(inputs: Vec<SubcommandLintInput>) -> Self {
+        Self {
+            inputs,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let config = Config::load();
+        let registry: UnofficialFunctionRegistry = create_unofficial_function_registry(&config)?;
+        let mut paths: Vec<PathBuf> = vec!();
+        if self.inputs.is_empty() {
+            paths = find_asm_files_recursively(&config.loda_programs_oeis_dir());
+        }
+        for input in &self.inputs {
+            match input {
+                SubcommandLintInput::ProgramId(program_id) => {
+                    let dm = DependencyManager::new(
+                        DependencyManagerFileSystemMode::System,
+                        config.loda_programs_oeis_dir(),
+                        registry.clone(),
+                    );
+                    paths.push(dm.path_to_program(*program_id));
+                },
+                SubcommandLintInput::Path(path) => {
+                    if path.is_dir() {
+                        paths.extend(find_asm_files_recursively(path));
+                    } else {
+                        paths.push(path.clone());
+                    }
+                }
+            }
+        }
+        paths.sort();
+
+        let mut finding_count: usize = 0;
+        let mut files_with_findings: usize = 0;
+        let mut error_count: usize = 0;
+        for path in &paths {
+            let lines: Vec<String> = match lint_file(path, &registry) {
+                Ok(value) => value,
+                Err(error) => {
+                    println!("{}: {:?}", path.display(), error);
+                    error_count += 1;
+                    continue;
+                }
+            };
+            if !lines.is_empty() {
+                files_with_findings += 1;
+            }
+            for line in &lines {
+                println!("{}:{}", path.display(), line);
+            }
+            finding_count += lines.len();
+        }
+        println!("Linted {} files. {} findings in {} files. {} files could not be read.", paths.len(), finding_count, files_with_findings, error_count);
+        Ok(())
+    }
+}
+
+fn lint_file(path: &Path, registry: &UnofficialFunctionRegistry) -> anyhow::Result<Vec<String>> {
+    let contents: String = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read program: {:?}", path))?;
+    Ok(lint_source(&contents, registry))
+}
+
+/// Lint the source code of a program.
+///
+/// Each line has the format `line_number: message`, or ` message` when the finding is not tied to a line.
+///
+/// When the program cannot be parsed, all the parse errors are reported with the format `line_number:column: message`.
+///
+/// The unofficial functions with a side effect, such as asserts, are looked up in the `registry`.
+fn lint_source(source: &str, registry: &UnofficialFunctionRegistry) -> Vec<String> {
+    let program: ParsedProgram = match ParsedProgram::parse_with_diagnostics(source) {
+        Ok(value) => value,
+        Err(diagnostics) => {
+            return diagnostics.iter().map(|diagnostic: &Diagnostic| {
+                format!("{}:{}: {}", diagnostic.line, diagnostic.column, diagnostic)
+            }).collect();
+        }
+    };
+    let analysis: ProgramAnalysis = match ProgramAnalysis::analyze_with_registry(&program, registry) {
+        Ok(value) => value,
+        Err(error) => {
+            return vec![format!(" {}", error)];
+        }
+    };
+    analysis.findings().iter().map(|finding: &Finding| {
+        match finding.line_number() {
+            Some(line_number) => format!("{}: {}", line_number, finding),
+            None => format!(" {}", finding),
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::unofficial_function::register_common_functions;
+
+    fn lint(source: &str) -> Vec<String> {
+        let registry = UnofficialFunctionRegistry::new();
+        register_common_functions(&registry);
+        lint_source(source, &registry)
+    }
+
+    #[test]
+    fn test_10000_lint_source() {
+        // Arrange
+        let source = "; A000004: The zero sequence.\nmov $1,$0\nmov $0,0";
+
+        // Act
+        let lines: Vec<String> = lint(source);
+
+        // Assert
+        assert_eq!(lines, vec![
+            "2: dead store, the value written to $1 is never read".to_string(),
+            " the register $1 is written, but never read".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_10001_lint_source_errors() {
+        assert_eq!(lint("mov $0,1\nboom $0"), vec!["2:1: unknown mnemonic: Unrecognized instruction 'boom'.".to_string()]);
+        assert_eq!(lint("mov $0,1\nlpe"), vec!["2:1: unbalanced loop: Premature ending of loop in root scope. There is no 'lpb' for this 'lpe'.".to_string()]);
+        assert_eq!(lint("addd $0,1\nmov $x,1"), vec![
+            "1:1: unknown mnemonic: Unrecognized instruction 'addd'. Did you mean 'add'?".to_string(),
+            "2:5: bad parameter: Unrecognized parameter. Expected a constant, a register such as '$1', or an indirect register such as '$$1'.".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_10002_lint_source_assert() {
+        let source = "mov $1,$0\nmul $1,2\nmov $2,$0\nadd $2,$0\nf20 $1,1030\nmul $0,2";
+        assert_eq!(lint(source), Vec::<String>::new());
+    }
+}
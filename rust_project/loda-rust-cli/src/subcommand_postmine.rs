@@ -0,0 +1,16 @@
+//! The `loda-rust postmine` subcommand, processes the pending candidate programs in the `mine-event` dir.
+use crate::postmine::PostMine;
+
+pub struct SubcommandPostmine;
+
+impl SubcommandPostmine {
+    /// Run postmine without the miner.
+    /// 
+    /// With `dry_run` enabled, all the checks are performed, but no files gets renamed or added to the repos.
+    /// Inspect the generated `report.html` to see what would have been kept or rejected.
+    pub fn run(dry_run: bool) -> anyhow::Result<()> {
+        let mut postmine = PostMine::new()?;
+        postmine.set_dry_run(dry_run);
+        postmine.run()
+    }
+}
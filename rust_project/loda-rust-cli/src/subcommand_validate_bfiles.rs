@@ -0,0 +1,312 @@
+//! The `loda-rust validate-bfiles` subcommand, checks programs against the OEIS b-files and offsets.
+use crate::common::{create_unofficial_function_registry, find_asm_files_recursively, oeis_id_from_path};
+use crate::compare::ProgramEvaluation;
+use crate::config::Config;
+use crate::oeis::{BFile, OeisOffsets, StrippedRow};
+use loda_rust_core::control::{DependencyManager, DependencyManagerFileSystemMode};
+use loda_rust_core::execute::{ProgramCache, ProgramRunner};
+use loda_rust_core::oeis::OeisId;
+use num_bigint::BigInt;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::rc::Rc;
+use anyhow::Context;
+
+/// A disagreement between a program and the OEIS data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BFileIssue {
+    /// The `#offset` of the program is different from the OEIS offset.
+    OffsetMismatch { program_offset: i64, oeis_offset: i64 },
+
+    /// The first term where the program disagrees with the b-file.
+    ///
+    /// When `beyond_stripped` is true, then the program agrees with all the terms in the `stripped` file,
+    /// and is only detected by using the b-file.
+    TermMismatch { index: i64, expected: BigInt, actual: BigInt, beyond_stripped: bool },
+
+    /// The program failed to compute a term that is in the b-file.
+    EvaluationError { message: String },
+}
+
+/// Compare the terms of a program with the terms of a b-file.
+///
+/// The OEIS offset is taken from the offsets file, and when it's not available, then from the first index in the b-file.
+///
+/// The `stripped_row` has the terms of the sequence from the `stripped` file, starting at the OEIS offset.
+pub fn validate_program_against_bfile(
+    runner: &ProgramRunner,
+    cache: &mut ProgramCache,
+    oeis_offset: Option<i64>,
+    bfile: &BFile,
+    stripped_row: Option<&StrippedRow>,
+) -> Vec<BFileIssue> {
+    let mut issues = Vec::<BFileIssue>::new();
+    let program_offset: i64 = runner.program().offset().unwrap_or(0) as i64;
+    let oeis_offset: i64 = oeis_offset.unwrap_or(bfile.offset());
+    if program_offset != oeis_offset {
+        issues.push(BFileIssue::OffsetMismatch { program_offset, oeis_offset });
+    }
+
+    // The program computes a(n) for n >= program_offset.
+    let first_index: i64 = bfile.offset().max(program_offset);
+    let end_index: i64 = bfile.end_index();
+    if first_index >= end_index {
+        return issues;
+    }
+    let evaluation = ProgramEvaluation::evaluate(runner, cache, first_index..end_index);
+    for (actual, index) in evaluation.terms.iter().zip(first_index..) {
+        let expected: &BigInt = match bfile.term(index) {
+            Some(value) => value,
+            None => break
+        };
+        if actual != expected {
+            issues.push(BFileIssue::TermMismatch {
+                index,
+                expected: expected.clone(),
+                actual: actual.clone(),
+                beyond_stripped: stripped_row.and_then(|row| row.term(index)).is_none(),
+            });
+            return issues;
+        }
+    }
+    if let Some(message) = evaluation.error {
+        issues.push(BFileIssue::EvaluationError { message });
+    }
+    issues
+}
+
+/// Check the programs in the `loda-programs` repo against the OEIS b-files.
+///
+/// Reports programs where the `#offset` disagrees with the OEIS offset,
+/// and programs where the terms disagree with the b-file, beyond the terms in the `stripped` file.
+///
+/// Programs for sequences without a b-file are skipped.
+pub struct SubcommandValidateBFiles {
+    term_count: usize,
+    program_ids: Vec<u32>,
+}
+
+impl SubcommandValidateBFiles {
+    pub fn new(term_count: usize, program_ids: Vec<u32>) -> Self {
+        Self {
+            term_count,
+            program_ids,
+        }
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let config = Config::load();
+        let bfile_dir = config.oeis_bfile_dir();
+        if !bfile_dir.is_dir() {
+            return Err(anyhow::anyhow!("The b-file dir doesn't exist: {:?}", bfile_dir));
+        }
+        let offsets_file = config.oeis_offsets_file();
+        let oeis_offsets: OeisOffsets = if offsets_file.is_file() {
+            let oeis_offsets = OeisOffsets::load(&offsets_file)?;
+            debug!("Loaded {} offsets from {:?}", oeis_offsets.len(), offsets_file);
+            oeis_offsets
+        } else {
+            println!("No offsets file at {:?}, using the first index of each b-file as the offset.", offsets_file);
+            OeisOffsets::new()
+        };
+        let oeis_ids: Vec<OeisId> = if self.program_ids.is_empty() {
+            let mut oeis_ids: Vec<OeisId> = find_asm_files_recursively(&config.loda_programs_oeis_dir())
+                .iter()
+                .filter_map(|path| oeis_id_from_path(path))
+                .collect();
+            oeis_ids.sort();
+            oeis_ids
+        } else {
+            self.program_ids.iter().map(|program_id| OeisId::from(*program_id)).collect()
+        };
+        let oeis_id_set: HashSet<OeisId> = oeis_ids.iter().copied().collect();
+        let mut stripped_rows: HashMap<OeisId, StrippedRow> = self.load_stripped_rows(&config.oeis_stripped_file(), &oeis_id_set)?;
+
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            config.loda_programs_oeis_dir(),
+            create_unofficial_function_registry(&config)?,
+        );
+        let mut cache = ProgramCache::new();
+
+        let mut count_checked: usize = 0;
+        let mut count_without_bfile: usize = 0;
+        let mut count_offset_mismatch: usize = 0;
+        let mut count_term_mismatch: usize = 0;
+        let mut count_term_mismatch_beyond_stripped: usize = 0;
+        let mut count_error: usize = 0;
+        for oeis_id in oeis_ids {
+            let bfile: BFile = match BFile::load(&bfile_dir, oeis_id, Some(self.term_count)) {
+                Ok(Some(value)) => value,
+                Ok(None) => {
+                    count_without_bfile += 1;
+                    continue;
+                },
+                Err(error) => {
+                    println!("{}: unable to load b-file. {:?}", oeis_id.a_number(), error);
+                    count_error += 1;
+                    continue;
+                }
+            };
+            debug!("{}: {} terms in b-file", bfile.oeis_id().a_number(), bfile.len());
+            let runner: Rc<ProgramRunner> = match dm.load(oeis_id.raw() as u64) {
+                Ok(value) => value,
+                Err(error) => {
+                    println!("{}: unable to load program. {:?}", oeis_id.a_number(), error);
+                    count_error += 1;
+                    continue;
+                }
+            };
+            count_checked += 1;
+            let oeis_offset: Option<i64> = oeis_offsets.get(oeis_id);
+            let stripped_row: Option<&StrippedRow> = stripped_rows.get_mut(&oeis_id).map(|row| {
+                row.set_offset(oeis_offset.unwrap_or(bfile.offset()));
+                &*row
+            });
+            let issues: Vec<BFileIssue> = validate_program_against_bfile(
+                &runner,
+                &mut cache,
+                oeis_offset,
+                &bfile,
+                stripped_row
+            );
+            for issue in issues {
+                match issue {
+                    BFileIssue::OffsetMismatch { program_offset, oeis_offset } => {
+                        println!("{}: offset mismatch, program has #offset {}, but OEIS has offset {}", oeis_id.a_number(), program_offset, oeis_offset);
+                        count_offset_mismatch += 1;
+                    },
+                    BFileIssue::TermMismatch { index, expected, actual, beyond_stripped } => {
+                        let location: &str = if beyond_stripped { "beyond the stripped file" } else { "within the stripped file" };
+                        println!("{}: term mismatch {}, a({}) expected {}, but got {}", oeis_id.a_number(), location, index, expected, actual);
+                        count_term_mismatch += 1;
+                        if beyond_stripped {
+                            count_term_mismatch_beyond_stripped += 1;
+                        }
+                    },
+                    BFileIssue::EvaluationError { message } => {
+                        println!("{}: evaluation error. {}", oeis_id.a_number(), message);
+                        count_error += 1;
+                    }
+                }
+            }
+        }
+        println!();
+        println!("checked: {} programs", count_checked);
+        println!("without b-file: {}", count_without_bfile);
+        println!("offset mismatch: {}", count_offset_mismatch);
+        println!("term mismatch: {}, of which beyond the stripped file: {}", count_term_mismatch, count_term_mismatch_beyond_stripped);
+        println!("errors: {}", count_error);
+        Ok(())
+    }
+
+    /// The terms of the sequences in the `stripped` file, only for the sequences that are to be checked.
+    fn load_stripped_rows(&self, path: &Path, oeis_ids: &HashSet<OeisId>) -> anyhow::Result<HashMap<OeisId, StrippedRow>> {
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open stripped file: {:?}", path))?;
+        let reader = BufReader::new(file);
+        let mut result = HashMap::<OeisId, StrippedRow>::new();
+        for line in reader.lines() {
+            let line: String = line?;
+            if let Some(row) = StrippedRow::parse(&line, Some(self.term_count)) {
+                if oeis_ids.contains(&row.oeis_id()) {
+                    result.insert(row.oeis_id(), row);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loda_rust_core::execute::ProgramId;
+    use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
+    use std::path::PathBuf;
+
+    fn validate(program: &str, oeis_offset: Option<i64>, bfile_content: &str, stripped_content: &str) -> Vec<BFileIssue> {
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+        let runner: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, program).expect("program");
+        let mut reader: &[u8] = bfile_content.as_bytes();
+        let bfile: BFile = BFile::parse(&mut reader, OeisId::from(1), None).expect("bfile");
+        let mut stripped_row: StrippedRow = StrippedRow::parse(&stripped_content.to_string(), None).expect("stripped row");
+        stripped_row.set_offset(oeis_offset.unwrap_or(bfile.offset()));
+        let mut cache = ProgramCache::new();
+        validate_program_against_bfile(&runner, &mut cache, oeis_offset, &bfile, Some(&stripped_row))
+    }
+
+    #[test]
+    fn test_10000_no_issues() {
+        let issues = validate("#offset 1\nmul $0,2", Some(1), "1 2\n2 4\n3 6\n4 8\n", "A000001 ,2,4,");
+        assert_eq!(issues, vec!());
+    }
+
+    #[test]
+    fn test_10001_offset_mismatch() {
+        // Arrange
+        let program = "mul $0,2";
+        let bfile_content = "1 2\n2 4\n3 6\n";
+
+        // Act
+        let issues_with_offsets_file = validate(program, Some(1), bfile_content, "A000001 ,2,4,");
+        let issues_without_offsets_file = validate(program, None, bfile_content, "A000001 ,2,4,");
+
+        // Assert
+        let expected = vec![BFileIssue::OffsetMismatch { program_offset: 0, oeis_offset: 1 }];
+        assert_eq!(issues_with_offsets_file, expected);
+        assert_eq!(issues_without_offsets_file, expected);
+    }
+
+    #[test]
+    fn test_10002_term_mismatch_beyond_stripped() {
+        // Act
+        let issues = validate("mul $0,2\nmin $0,5", None, "0 0\n1 2\n2 4\n3 6\n4 8\n", "A000001 ,0,2,4,");
+
+        // Assert
+        let expected = vec![BFileIssue::TermMismatch {
+            index: 3,
+            expected: BigInt::from(6),
+            actual: BigInt::from(5),
+            beyond_stripped: true,
+        }];
+        assert_eq!(issues, expected);
+    }
+
+    #[test]
+    fn test_10003_term_mismatch_within_stripped() {
+        let issues = validate("mul $0,2\nmin $0,5", None, "0 0\n1 2\n2 4\n3 6\n4 8\n", "A000001 ,0,2,4,6,");
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], BFileIssue::TermMismatch { index: 3, beyond_stripped: false, .. }));
+    }
+
+    #[test]
+    fn test_10004_evaluation_error() {
+        let issues = validate("mov $1,2\nsub $1,$0\ndiv $0,$1\nmov $0,0", None, "0 0\n1 0\n2 0\n3 0\n", "A000001 ,0,0,0,0,");
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], BFileIssue::EvaluationError { .. }));
+    }
+
+    #[test]
+    fn test_10005_stripped_terms_aligned_with_offset() {
+        // Arrange
+        let program = "#offset 1\nmul $0,2\nmin $0,5";
+        let bfile_content = "1 2\n2 4\n3 6\n4 8\n";
+
+        // Act
+        let issues_within = validate(program, Some(1), bfile_content, "A000001 ,2,4,6,");
+        let issues_beyond = validate(program, Some(1), bfile_content, "A000001 ,2,4,");
+
+        // Assert
+        assert_eq!(issues_within.len(), 1);
+        assert!(matches!(issues_within[0], BFileIssue::TermMismatch { index: 3, beyond_stripped: false, .. }));
+        assert_eq!(issues_beyond.len(), 1);
+        assert!(matches!(issues_beyond[0], BFileIssue::TermMismatch { index: 3, beyond_stripped: true, .. }));
+    }
+}
@@ -0,0 +1,297 @@
+use super::RegisterAccess;
+use crate::parser::{Instruction, InstructionId, InstructionParameter, ParameterType, ParsedProgram};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Renumbering registers may change the order of the instructions, which may change the register numbering.
+/// Stop after this many rounds, in case it doesn't settle.
+const MAX_NUMBER_OF_ROUNDS: usize = 8;
+
+/// A program rewritten into a canonical form, for detecting duplicate programs structurally.
+///
+/// - Idioms are normalized, such as `mul $1,0` and `sub $1,$1` into `mov $1,0`.
+/// - Instructions without effect are removed, such as `add $1,0` and `mov $1,$1`.
+/// - Independent instructions between loop boundaries are sorted, such as `mov $2,1` and `mov $1,2`.
+/// - Registers are renumbered in the order of first use. The input/output register `$0` is kept.
+/// - Comments and line numbers are removed.
+///
+/// Programs that only differ in these ways have the same canonical form and the same hash.
+///
+/// The registers are not renumbered when the program uses indirect memory access, such as `$$1`,
+/// or instructions that access a range of registers, such as `lpb $1,2` or `f21 $1,1104`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanonicalForm {
+    program: ParsedProgram,
+    hash: u64,
+}
+
+impl CanonicalForm {
+    pub fn new(program: &ParsedProgram) -> Self {
+        let mut instructions: Vec<Instruction> = program.instruction_vec.iter()
+            .filter_map(normalized_instruction)
+            .collect();
+        let renumber: bool = can_renumber_registers(&instructions);
+        for _ in 0..MAX_NUMBER_OF_ROUNDS {
+            let instructions_before: Vec<Instruction> = instructions.clone();
+            sort_independent_instructions(&mut instructions);
+            if renumber {
+                renumber_registers(&mut instructions);
+            }
+            if instructions == instructions_before {
+                break;
+            }
+        }
+        let mut canonical_program = ParsedProgram::new();
+        canonical_program.optional_offset = program.optional_offset;
+        canonical_program.instruction_vec = instructions;
+
+        let mut hasher = DefaultHasher::new();
+        canonical_program.to_string().hash(&mut hasher);
+        Self {
+            program: canonical_program,
+            hash: hasher.finish(),
+        }
+    }
+
+    pub fn program(&self) -> &ParsedProgram {
+        &self.program
+    }
+
+    /// Hash of the canonical program.
+    ///
+    /// The hash is only meant for comparisons within the same process. It's not stable across Rust versions.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl fmt::Display for CanonicalForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)
+    }
+}
+
+impl ParsedProgram {
+    /// The program rewritten into a canonical form, see `CanonicalForm`.
+    pub fn canonical_form(&self) -> CanonicalForm {
+        CanonicalForm::new(self)
+    }
+}
+
+/// Rewrite an idiom into its canonical instruction.
+///
+/// Returns `None` when the instruction has no effect.
+fn normalized_instruction(instruction: &Instruction) -> Option<Instruction> {
+    let mut instruction: Instruction = instruction.clone();
+    instruction.line_number = 0;
+    instruction.source = None;
+    if instruction.parameter_vec.len() != 2 {
+        return Some(instruction);
+    }
+    let target: InstructionParameter = instruction.parameter_vec[0].clone();
+    let source: &InstructionParameter = &instruction.parameter_vec[1];
+    if target.parameter_type == ParameterType::Constant {
+        return Some(instruction);
+    }
+    let is_same_register: bool = *source == target;
+    let constant: Option<i64> = match source.parameter_type {
+        ParameterType::Constant => Some(source.parameter_value),
+        _ => None
+    };
+    match (instruction.instruction_id, constant) {
+        (InstructionId::Move, _) if is_same_register => None,
+        (InstructionId::Max, _) | (InstructionId::Min, _) | (InstructionId::BitwiseAnd, _) | (InstructionId::BitwiseOr, _) if is_same_register => None,
+        (InstructionId::Add, Some(0)) | (InstructionId::Subtract, Some(0)) => None,
+        (InstructionId::Multiply, Some(1)) | (InstructionId::Divide, Some(1)) | (InstructionId::DivideIf, Some(1)) | (InstructionId::Power, Some(1)) => None,
+        (InstructionId::Multiply, Some(0)) | (InstructionId::BitwiseAnd, Some(0)) => Some(move_zero(target)),
+        (InstructionId::Subtract, _) | (InstructionId::Truncate, _) | (InstructionId::BitwiseXor, _) if is_same_register => Some(move_zero(target)),
+        _ => Some(instruction)
+    }
+}
+
+fn move_zero(target: InstructionParameter) -> Instruction {
+    Instruction {
+        instruction_id: InstructionId::Move,
+        parameter_vec: vec![
+            target,
+            InstructionParameter::new(ParameterType::Constant, 0),
+        ],
+        line_number: 0,
+        source: None,
+    }
+}
+
+/// Renumbering is only possible when every instruction accesses individual registers.
+fn can_renumber_registers(instructions: &[Instruction]) -> bool {
+    for instruction in instructions {
+        if instruction.parameter_vec.iter().any(|parameter| parameter.parameter_type == ParameterType::Indirect) {
+            return false;
+        }
+        match instruction.instruction_id {
+            InstructionId::LoopBegin => {
+                if let Some(parameter) = instruction.parameter_vec.get(1) {
+                    if parameter.parameter_type != ParameterType::Constant || parameter.parameter_value != 1 {
+                        return false;
+                    }
+                }
+            },
+            InstructionId::UnofficialFunction { input_count, output_count } if input_count > 1 || output_count > 1 => {
+                return false;
+            },
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Assign register numbers in the order of first use, so `$0` stays `$0`, the next register becomes `$1`, and so on.
+fn renumber_registers(instructions: &mut [Instruction]) {
+    let mut mapping = HashMap::<i64, i64>::new();
+    mapping.insert(0, 0);
+    for instruction in instructions.iter_mut() {
+        for parameter in instruction.parameter_vec.iter_mut() {
+            if parameter.parameter_type != ParameterType::Direct {
+                continue;
+            }
+            let next_register: i64 = mapping.len() as i64;
+            let register: i64 = *mapping.entry(parameter.parameter_value).or_insert(next_register);
+            parameter.parameter_value = register;
+        }
+    }
+}
+
+fn is_loop_boundary(instruction: &Instruction) -> bool {
+    matches!(instruction.instruction_id, InstructionId::LoopBegin | InstructionId::LoopEnd | InstructionId::UnofficialLoopBeginSubtract)
+}
+
+/// Two instructions are independent when they can be swapped without changing the result.
+fn is_independent(access0: &RegisterAccess, access1: &RegisterAccess) -> bool {
+    if access0.reads_unknown || access0.writes_unknown || access1.reads_unknown || access1.writes_unknown {
+        return false;
+    }
+    let conflict0: bool = access0.writes.iter().any(|register| access1.reads.contains(register) || access1.writes.contains(register));
+    let conflict1: bool = access1.writes.iter().any(|register| access0.reads.contains(register));
+    !conflict0 && !conflict1
+}
+
+/// Sort the instructions between the loop boundaries, while keeping the dependent instructions in order.
+fn sort_independent_instructions(instructions: &mut [Instruction]) {
+    for block in instructions.split_mut(is_loop_boundary) {
+        if block.len() >= 2 {
+            sort_block(block);
+        }
+    }
+}
+
+/// The instruction with the registers masked out, followed by the instruction itself.
+///
+/// Masking the registers makes the order mostly independent of the register numbering.
+fn sort_key(instruction: &Instruction) -> (String, String) {
+    let parameters: Vec<String> = instruction.parameter_vec.iter().map(|parameter| {
+        match parameter.parameter_type {
+            ParameterType::Constant => parameter.parameter_value.to_string(),
+            ParameterType::Direct => "$".to_string(),
+            ParameterType::Indirect => "$$".to_string(),
+        }
+    }).collect();
+    let masked = format!("{} {}", instruction.instruction_id, parameters.join(","));
+    (masked, instruction.to_string())
+}
+
+/// Topological sort, where the instruction with the smallest sort key goes first, among those whose dependencies are satisfied.
+fn sort_block(block: &mut [Instruction]) {
+    let count: usize = block.len();
+    let accesses: Vec<RegisterAccess> = block.iter().map(RegisterAccess::from_instruction).collect();
+    let keys: Vec<(String, String)> = block.iter().map(sort_key).collect();
+    let mut dependencies: Vec<Vec<usize>> = vec![vec!(); count];
+    for index1 in 0..count {
+        for index0 in 0..index1 {
+            if !is_independent(&accesses[index0], &accesses[index1]) {
+                dependencies[index1].push(index0);
+            }
+        }
+    }
+    let mut is_placed: Vec<bool> = vec![false; count];
+    let mut order: Vec<usize> = Vec::with_capacity(count);
+    while order.len() < count {
+        let mut best: Option<usize> = None;
+        for index in 0..count {
+            if is_placed[index] || dependencies[index].iter().any(|dependency| !is_placed[*dependency]) {
+                continue;
+            }
+            best = match best {
+                Some(best_index) if keys[best_index] <= keys[index] => Some(best_index),
+                _ => Some(index)
+            };
+        }
+        let index: usize = best.expect("There is always an instruction without unplaced dependencies");
+        is_placed[index] = true;
+        order.push(index);
+    }
+    let sorted: Vec<Instruction> = order.iter().map(|index| block[*index].clone()).collect();
+    block.clone_from_slice(&sorted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(source: &str) -> String {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        program.canonical_form().to_string()
+    }
+
+    fn same_hash(source0: &str, source1: &str) -> bool {
+        let program0 = ParsedProgram::parse_program(source0).expect("program0");
+        let program1 = ParsedProgram::parse_program(source1).expect("program1");
+        program0.canonical_form().hash() == program1.canonical_form().hash()
+    }
+
+    #[test]
+    fn test_10000_renumber_registers() {
+        assert_eq!(canonical("mov $7,$0\nadd $7,1\nmov $0,$7"), "mov $1,$0\nadd $1,1\nmov $0,$1");
+        assert_eq!(canonical("mov $3,2\nmov $5,$0\nlpb $5\n  sub $5,1\n  mul $3,2\nlpe\nmov $0,$3"), "mov $1,$0\nmov $2,2\nlpb $1\nmul $2,2\nsub $1,1\nlpe\nmov $0,$2");
+    }
+
+    #[test]
+    fn test_10001_no_renumbering() {
+        assert_eq!(canonical("mov $5,$0\nmov $$5,1"), "mov $5,$0\nmov $$5,1");
+        assert_eq!(canonical("mov $3,$0\nlpb $3,2\n  sub $3,1\nlpe"), "mov $3,$0\nlpb $3,2\nsub $3,1\nlpe");
+        assert_eq!(canonical("mov $3,$0\nf21 $3,1104"), "mov $3,$0\nf21 $3,1104");
+    }
+
+    #[test]
+    fn test_20000_normalize_idioms() {
+        assert_eq!(canonical("mul $0,0"), "mov $0,0");
+        assert_eq!(canonical("sub $0,$0"), "mov $0,0");
+        assert_eq!(canonical("bxo $0,$0"), "mov $0,0");
+        assert_eq!(canonical("trn $0,$0"), "mov $0,0");
+        assert_eq!(canonical("ban $0,0"), "mov $0,0");
+        assert_eq!(canonical("add $0,0\nsub $0,0\nmul $0,1\ndiv $0,1\ndif $0,1\npow $0,1\nmov $0,$0\nmax $0,$0\nadd $0,2"), "add $0,2");
+        assert_eq!(canonical("add $0,$0"), "add $0,$0");
+        assert_eq!(canonical("mul $0,$0"), "mul $0,$0");
+    }
+
+    #[test]
+    fn test_30000_sort_independent_instructions() {
+        assert_eq!(canonical("mov $2,3\nmov $1,2\nadd $0,$1"), "mov $1,2\nadd $0,$1\nmov $2,3");
+        assert_eq!(canonical("add $0,$1\nmov $1,2"), "add $0,$1\nmov $1,2");
+        assert_eq!(canonical("lpb $0\n  sub $0,1\n  mov $2,1\nlpe\nmov $1,1"), "lpb $0\nmov $1,1\nsub $0,1\nlpe\nmov $2,1");
+    }
+
+    #[test]
+    fn test_40000_same_hash() {
+        assert_eq!(same_hash("mov $1,$0\nmov $2,1\nadd $1,$2\nmov $0,$1", "mov $2,1\nmov $5,$0\nadd $5,$2\nmov $0,$5"), true);
+        assert_eq!(same_hash("; comment\nmul $0,2 ; double\n", "\n\nmul $0,2\n"), true);
+        assert_eq!(same_hash("mov $1,$0\nsub $1,$1\nadd $0,$1", "mov $3,$0\nmul $3,0\nadd $0,$3"), true);
+    }
+
+    #[test]
+    fn test_40001_different_hash() {
+        assert_eq!(same_hash("mul $0,2", "mul $0,3"), false);
+        assert_eq!(same_hash("mul $0,2", "#offset 1\nmul $0,2"), false);
+        assert_eq!(same_hash("sub $0,1\nmul $0,2", "mul $0,2\nsub $0,1"), false);
+    }
+}
@@ -0,0 +1,810 @@
+use super::{Growth, LoopNode, LoopTree, LoopTreeError, RegisterAccess};
+use crate::parser::{Instruction, InstructionId, InstructionParameter, ParameterType, ParsedProgram};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Weights of the loop-carried registers are capped at this value. A weight of 2 or more means doubling.
+const MAX_WEIGHT: u8 = 2;
+
+/// The growth of the registers inside a loop is refined this many times, before giving up.
+const MAX_FIXPOINT_ROUNDS: usize = 5;
+
+/// Loops with a constant range longer than this, are treated as unknown.
+const MAX_CONSTANT_RANGE_LENGTH: i64 = 100;
+
+/// Whether a loop is known to stop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoopTermination {
+    /// The loop registers are not modified by the loop body.
+    /// The loop stops after the first iteration, and the changes are discarded, so the loop has no effect.
+    NoProgress,
+
+    /// The loop registers must decrease in every iteration and cannot go below zero, so the loop stops.
+    Terminating,
+
+    /// The loop uses indirect memory access, or a range from a register, such as `lpb $0,$1`.
+    Unknown,
+}
+
+/// The outcome of the analysis of a single loop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopReport {
+    /// The line of the `lpb` instruction.
+    pub line_number: usize,
+
+    pub termination: LoopTermination,
+
+    /// The number of iterations, as a function of the input `n`.
+    pub iterations: Growth,
+
+    /// The number of steps that the entire loop uses, as a function of the input `n`.
+    pub cost: Growth,
+}
+
+/// Estimate of the time complexity of a program, and the number of iterations of each loop.
+///
+/// Every single-register loop stops, since the counter must decrease in every iteration and cannot go below zero.
+/// So the number of iterations is bounded by the value of the counter, when the loop begins.
+/// The magnitudes of the registers are tracked as a function of the input `n`,
+/// with the loop-carried dependencies between the registers, so that `add $1,1` inside a loop grows linear,
+/// and the fibonacci recurrence grows exponential.
+///
+/// The estimate is an upper bound, and is `Unknown` for programs with `seq`, unofficial functions or indirect memory access.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComplexityEstimate {
+    /// The loops sorted by line number.
+    pub loops: Vec<LoopReport>,
+
+    /// The number of steps used for computing a term.
+    pub time: Growth,
+
+    /// The magnitude of the output.
+    pub output: Growth,
+}
+
+impl ComplexityEstimate {
+    pub fn analyze(program: &ParsedProgram) -> Result<Self, LoopTreeError> {
+        let loop_tree = LoopTree::build(&program.instruction_vec)?;
+        let accesses: Vec<RegisterAccess> = program.instruction_vec.iter().map(RegisterAccess::from_instruction).collect();
+        let mut evaluator = Evaluator {
+            instructions: &program.instruction_vec,
+            accesses: &accesses,
+            reports: BTreeMap::new(),
+        };
+        let has_indirect_memory_access: bool = accesses.iter().any(|access| access.reads_unknown || access.writes_unknown);
+        if has_indirect_memory_access {
+            evaluator.report_unknown(&loop_tree.roots);
+            let instance = Self {
+                loops: evaluator.reports.into_values().collect(),
+                time: Growth::Unknown,
+                output: Growth::Unknown,
+            };
+            return Ok(instance);
+        }
+        let mut bounds = Bounds::new();
+        bounds.insert(0, Growth::linear());
+        let mut env = Env::new();
+        let time: Growth = evaluator.eval_block(0, program.instruction_vec.len(), &loop_tree.roots, &mut env, &bounds);
+        let output: Growth = match env.get(&0) {
+            Some(term) => term.value,
+            None => Growth::linear(),
+        };
+        let instance = Self {
+            loops: evaluator.reports.into_values().collect(),
+            time,
+            output,
+        };
+        Ok(instance)
+    }
+
+    /// True when one or more loops have no effect, because the loop registers are not modified.
+    pub fn has_loop_without_progress(&self) -> bool {
+        self.loops.iter().any(|report| report.termination == LoopTermination::NoProgress)
+    }
+}
+
+/// Upper bounds for the magnitude of the registers. Registers without an entry are constant.
+type Bounds = BTreeMap<u64, Growth>;
+
+/// The value of the registers that have been written, as a function of the registers before the loop iteration.
+type Env = BTreeMap<u64, Term>;
+
+fn bound(bounds: &Bounds, register: u64) -> Growth {
+    bounds.get(&register).cloned().unwrap_or(Growth::Constant)
+}
+
+fn max_optional(a: Option<Growth>, b: Option<Growth>) -> Option<Growth> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (Some(a), None) => Some(a),
+        (None, b) => b,
+    }
+}
+
+fn add_weight(carried: &mut BTreeMap<u64, u8>, register: u64, weight: u8) {
+    let entry: &mut u8 = carried.entry(register).or_insert(0);
+    *entry = entry.saturating_add(weight).min(MAX_WEIGHT);
+}
+
+/// The value of a register, in terms of the values of the registers before a loop iteration.
+#[derive(Clone, Debug, PartialEq)]
+struct Term {
+    /// The magnitude of the value.
+    value: Growth,
+
+    /// The magnitude of the part that doesn't depend on the registers before the iteration.
+    fresh: Option<Growth>,
+
+    /// The registers before the iteration that are added, with their weights.
+    carried: BTreeMap<u64, u8>,
+
+    /// The registers before the iteration are multiplied with each other, or raised to a power.
+    nonlinear: bool,
+}
+
+impl Term {
+    fn constant() -> Self {
+        Self {
+            value: Growth::Constant,
+            fresh: Some(Growth::Constant),
+            carried: BTreeMap::new(),
+            nonlinear: false,
+        }
+    }
+
+    fn unknown() -> Self {
+        Self {
+            value: Growth::Unknown,
+            fresh: Some(Growth::Unknown),
+            carried: BTreeMap::new(),
+            nonlinear: false,
+        }
+    }
+
+    fn identity(register: u64, bounds: &Bounds) -> Self {
+        Self {
+            value: bound(bounds, register),
+            fresh: None,
+            carried: BTreeMap::from([(register, 1)]),
+            nonlinear: false,
+        }
+    }
+
+    fn is_carried(&self) -> bool {
+        !self.carried.is_empty()
+    }
+
+    fn with_value(mut self, value: Growth) -> Self {
+        self.value = value;
+        self
+    }
+
+    fn sum(&self, other: &Term) -> Term {
+        let mut carried = self.carried.clone();
+        for (register, weight) in &other.carried {
+            add_weight(&mut carried, *register, *weight);
+        }
+        Term {
+            value: self.value.max(other.value),
+            fresh: max_optional(self.fresh, other.fresh),
+            carried,
+            nonlinear: self.nonlinear || other.nonlinear,
+        }
+    }
+
+    fn product(&self, other: &Term) -> Term {
+        let value: Growth = self.value * other.value;
+        if !other.is_carried() && other.value == Growth::Constant {
+            // Multiplying by a constant, the weights are scaled.
+            return self.scaled().with_value(value);
+        }
+        if !self.is_carried() && self.value == Growth::Constant {
+            return other.scaled().with_value(value);
+        }
+        let mut carried = self.carried.clone();
+        for (register, weight) in &other.carried {
+            add_weight(&mut carried, *register, *weight);
+        }
+        let fresh: Option<Growth> = match (self.fresh, other.fresh) {
+            (Some(a), Some(b)) => Some(a * b),
+            _ => None,
+        };
+        Term {
+            value,
+            fresh,
+            carried,
+            nonlinear: self.nonlinear || other.nonlinear || self.is_carried() || other.is_carried(),
+        }
+    }
+
+    fn scaled(&self) -> Term {
+        let mut term: Term = self.clone();
+        for weight in term.carried.values_mut() {
+            *weight = MAX_WEIGHT;
+        }
+        term
+    }
+
+    /// The term raised to a power, where `value` is the magnitude of the result.
+    fn power(&self, other: &Term, value: Growth) -> Term {
+        let mut carried = self.carried.clone();
+        for (register, weight) in &other.carried {
+            add_weight(&mut carried, *register, *weight);
+        }
+        Term {
+            value,
+            fresh: Some(value),
+            carried,
+            nonlinear: self.is_carried() || other.is_carried(),
+        }
+    }
+}
+
+fn literal(parameter: Option<&InstructionParameter>) -> Option<i64> {
+    let parameter: &InstructionParameter = parameter?;
+    if parameter.parameter_type == ParameterType::Constant {
+        return Some(parameter.parameter_value);
+    }
+    None
+}
+
+/// Strongly connected components of the dependency graph, ordered so that a component comes after the components it depends on.
+fn strongly_connected_components(nodes: &BTreeSet<u64>, edges: &BTreeMap<u64, BTreeMap<u64, u8>>) -> Vec<Vec<u64>> {
+    struct Tarjan<'a> {
+        edges: &'a BTreeMap<u64, BTreeMap<u64, u8>>,
+        index: BTreeMap<u64, usize>,
+        lowlink: BTreeMap<u64, usize>,
+        stack: Vec<u64>,
+        on_stack: BTreeSet<u64>,
+        components: Vec<Vec<u64>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: u64) {
+            let index: usize = self.index.len();
+            self.index.insert(node, index);
+            self.lowlink.insert(node, index);
+            self.stack.push(node);
+            self.on_stack.insert(node);
+            let successors: Vec<u64> = self.edges.get(&node).map(|targets| targets.keys().cloned().collect()).unwrap_or_default();
+            for successor in successors {
+                if !self.index.contains_key(&successor) {
+                    self.visit(successor);
+                    let lowlink: usize = self.lowlink[&node].min(self.lowlink[&successor]);
+                    self.lowlink.insert(node, lowlink);
+                } else if self.on_stack.contains(&successor) {
+                    let lowlink: usize = self.lowlink[&node].min(self.index[&successor]);
+                    self.lowlink.insert(node, lowlink);
+                }
+            }
+            if self.lowlink[&node] == self.index[&node] {
+                let mut component: Vec<u64> = vec!();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        index: BTreeMap::new(),
+        lowlink: BTreeMap::new(),
+        stack: vec!(),
+        on_stack: BTreeSet::new(),
+        components: vec!(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(*node);
+        }
+    }
+    tarjan.components
+}
+
+/// The values of the registers after a number of iterations, given the values after a single iteration.
+fn apply_iterations(body: &Env, iterations: Growth, entry: &Bounds) -> Env {
+    // The dependency graph, where an edge goes from a register to the registers before the iteration that it depends on.
+    let mut nodes = BTreeSet::<u64>::new();
+    let mut edges = BTreeMap::<u64, BTreeMap<u64, u8>>::new();
+    for (register, term) in body {
+        nodes.insert(*register);
+        nodes.extend(term.carried.keys());
+        edges.insert(*register, term.carried.clone());
+    }
+    for register in &nodes {
+        // Registers that are not modified by the loop body keeps their value.
+        edges.entry(*register).or_insert_with(|| BTreeMap::from([(*register, 1)]));
+    }
+
+    let components: Vec<Vec<u64>> = strongly_connected_components(&nodes, &edges);
+    let mut component_of = BTreeMap::<u64, usize>::new();
+    for (component_index, component) in components.iter().enumerate() {
+        for register in component {
+            component_of.insert(*register, component_index);
+        }
+    }
+    let mut is_cyclic: Vec<bool> = vec![false; components.len()];
+    let mut is_exponential: Vec<bool> = vec![false; components.len()];
+    for (component_index, component) in components.iter().enumerate() {
+        let mut internal_edge_count: usize = 0;
+        for register in component {
+            for (target, weight) in &edges[register] {
+                if component_of[target] != component_index {
+                    continue;
+                }
+                internal_edge_count += 1;
+                if *weight >= MAX_WEIGHT {
+                    is_exponential[component_index] = true;
+                }
+                if body.get(register).map(|term| term.nonlinear).unwrap_or(false) {
+                    is_exponential[component_index] = true;
+                }
+            }
+        }
+        is_cyclic[component_index] = internal_edge_count > 0;
+        if internal_edge_count > component.len() {
+            // More than a simple cycle, such as the fibonacci recurrence.
+            is_exponential[component_index] = true;
+        }
+    }
+
+    let mut result = Env::new();
+    for (register, term) in body {
+        // The longest path measured in cyclic components, from this register to the components that it depends on.
+        // The components are in reverse topological order, so the dependencies have lower indexes.
+        let start: usize = component_of[register];
+        let mut cycle_count: Vec<Option<u32>> = vec![None; components.len()];
+        cycle_count[start] = Some(is_cyclic[start] as u32);
+        for component_index in (0..=start).rev() {
+            let count: u32 = match cycle_count[component_index] {
+                Some(value) => value,
+                None => continue
+            };
+            for member in &components[component_index] {
+                for target in edges[member].keys() {
+                    let target_index: usize = component_of[target];
+                    if target_index == component_index {
+                        continue;
+                    }
+                    let candidate: u32 = count + (is_cyclic[target_index] as u32);
+                    if cycle_count[target_index].map(|value| candidate > value).unwrap_or(true) {
+                        cycle_count[target_index] = Some(candidate);
+                    }
+                }
+            }
+        }
+
+        let reachable: Vec<u64> = nodes.iter().filter(|node| cycle_count[component_of[node]].is_some()).cloned().collect();
+        let any_exponential: bool = reachable.iter().any(|node| is_exponential[component_of[node]]);
+        let any_nonlinear: bool = reachable.iter().any(|node| body.get(node).map(|term| term.nonlinear).unwrap_or(false));
+        let mut carried = BTreeMap::<u64, u8>::new();
+        let weight: u8 = if any_exponential { MAX_WEIGHT } else { 1 };
+        for node in &reachable {
+            carried.insert(*node, weight);
+        }
+
+        let mut value: Growth = term.value;
+        let mut fresh: Option<Growth> = None;
+        for node in &reachable {
+            let count: u32 = cycle_count[component_of[node]].unwrap_or(0);
+            let node_fresh: Option<Growth> = body.get(node).and_then(|term| term.fresh);
+            let node_value: Growth = match body.get(node) {
+                Some(term) => term.value,
+                None => bound(entry, *node),
+            };
+            let (accumulated_fresh, accumulated_entry) = if iterations == Growth::Constant {
+                (node_fresh, bound(entry, *node))
+            } else {
+                let fresh_part: Option<Growth> = node_fresh.map(|growth| growth * iterations.pow(count));
+                let entry_part: Growth = bound(entry, *node) * iterations.pow(count.saturating_sub(1));
+                (fresh_part, entry_part)
+            };
+            fresh = max_optional(fresh, accumulated_fresh);
+            value = value.max(node_value).max(accumulated_entry);
+            if let Some(growth) = accumulated_fresh {
+                value = value.max(growth);
+            }
+        }
+        if any_exponential && iterations != Growth::Constant {
+            value = value.max(iterations.exp());
+            fresh = Some(value);
+        }
+        let term = Term {
+            value,
+            fresh,
+            carried,
+            nonlinear: any_nonlinear || any_exponential,
+        };
+        result.insert(*register, term);
+    }
+    result
+}
+
+struct Evaluator<'a> {
+    instructions: &'a [Instruction],
+    accesses: &'a [RegisterAccess],
+    reports: BTreeMap<usize, LoopReport>,
+}
+
+impl Evaluator<'_> {
+    /// Returns the number of steps used by the instructions in the range.
+    fn eval_block(&mut self, start: usize, end: usize, children: &[LoopNode], env: &mut Env, bounds: &Bounds) -> Growth {
+        let mut cost = Growth::Constant;
+        let mut index: usize = start;
+        while index < end {
+            if let Some(node) = children.iter().find(|node| node.begin == index) {
+                cost = cost.max(self.eval_loop(node, env, bounds));
+                index = node.end + 1;
+                continue;
+            }
+            cost = cost.max(self.eval_instruction(index, env, bounds));
+            index += 1;
+        }
+        cost
+    }
+
+    fn operand(&self, parameter: Option<&InstructionParameter>, env: &Env, bounds: &Bounds) -> Term {
+        let parameter: &InstructionParameter = match parameter {
+            Some(value) => value,
+            None => return Term::constant()
+        };
+        match parameter.parameter_type {
+            ParameterType::Constant => Term::constant(),
+            ParameterType::Direct => {
+                let register: u64 = parameter.parameter_value as u64;
+                match env.get(&register) {
+                    Some(term) => term.clone(),
+                    None => Term::identity(register, bounds),
+                }
+            },
+            ParameterType::Indirect => Term::unknown(),
+        }
+    }
+
+    fn eval_instruction(&mut self, index: usize, env: &mut Env, bounds: &Bounds) -> Growth {
+        let instruction: &Instruction = &self.instructions[index];
+        let parameter0: Option<&InstructionParameter> = instruction.parameter_vec.first();
+        let parameter1: Option<&InstructionParameter> = instruction.parameter_vec.get(1);
+        let target_register: u64 = match parameter0 {
+            Some(parameter) => parameter.parameter_value as u64,
+            None => return Growth::Constant
+        };
+        let target: Term = self.operand(parameter0, env, bounds);
+        let source: Term = self.operand(parameter1, env, bounds);
+        let constant: Option<i64> = literal(parameter1);
+        let (term, cost) = match instruction.instruction_id {
+            InstructionId::EvalSequence => (Term::unknown(), Growth::Unknown),
+            InstructionId::UnofficialFunction { output_count, .. } => {
+                for register in target_register..(target_register + output_count as u64) {
+                    env.insert(register, Term::unknown());
+                }
+                return Growth::Unknown;
+            },
+            InstructionId::Move => (source, Growth::Constant),
+            InstructionId::Add | InstructionId::Subtract | InstructionId::BitwiseOr | InstructionId::BitwiseXor => {
+                (target.sum(&source), Growth::Constant)
+            },
+            InstructionId::Multiply => {
+                let term: Term = match constant {
+                    Some(0) => Term::constant(),
+                    Some(1) | Some(-1) => target,
+                    _ => target.product(&source),
+                };
+                (term, Growth::Constant)
+            },
+            InstructionId::Power | InstructionId::Binomial => {
+                let term: Term = match constant {
+                    Some(value) if value <= 0 => Term::constant(),
+                    Some(1) => target,
+                    Some(value) => {
+                        let exponent: u32 = value.min(u32::MAX as i64) as u32;
+                        target.power(&source, target.value.pow(exponent))
+                    },
+                    None => {
+                        let value: Growth = match (target.value, source.value) {
+                            (Growth::Unknown, _) | (_, Growth::Unknown) => Growth::Unknown,
+                            (Growth::Constant, exponent) => exponent.exp(),
+                            (_, Growth::Constant) => Growth::Unknown,
+                            _ => Growth::Exponential,
+                        };
+                        target.power(&source, value)
+                    }
+                };
+                (term, Growth::Constant)
+            },
+            InstructionId::Modulo => {
+                let term: Term = match constant {
+                    Some(_) => Term::constant(),
+                    None => source,
+                };
+                (term, Growth::Constant)
+            },
+            InstructionId::Min => {
+                let term: Term = match constant {
+                    Some(_) => Term::constant(),
+                    None => target,
+                };
+                (term, Growth::Constant)
+            },
+            InstructionId::Logarithm | InstructionId::DigitSum => {
+                let value: Growth = target.value.log();
+                (target.with_value(value), Growth::Constant)
+            },
+            InstructionId::DigitalRoot |
+            InstructionId::Compare |
+            InstructionId::Equal |
+            InstructionId::NotEqual |
+            InstructionId::LessOrEqual |
+            InstructionId::GreaterOrEqual => (Term::constant(), Growth::Constant),
+            InstructionId::Divide |
+            InstructionId::DivideIf |
+            InstructionId::Truncate |
+            InstructionId::BitwiseAnd |
+            InstructionId::NthRoot => {
+                // The result is not bigger than the target.
+                (target, Growth::Constant)
+            },
+            InstructionId::Max | InstructionId::GCD => {
+                // The result is not bigger than the max of target and source, since `gcd $0,$1` is `$1` when `$0` is zero.
+                (target.sum(&source), Growth::Constant)
+            },
+            InstructionId::LoopBegin | InstructionId::LoopEnd | InstructionId::UnofficialLoopBeginSubtract => {
+                return Growth::Constant;
+            },
+        };
+        env.insert(target_register, term);
+        cost
+    }
+
+    /// The loop registers, and whether the loop makes progress.
+    fn classify(&self, node: &LoopNode) -> (LoopTermination, Vec<u64>) {
+        let instruction: &Instruction = &self.instructions[node.begin];
+        let parameter0: &InstructionParameter = match instruction.parameter_vec.first() {
+            Some(value) => value,
+            None => return (LoopTermination::Unknown, vec!())
+        };
+        if parameter0.parameter_type != ParameterType::Direct {
+            return (LoopTermination::Unknown, vec!());
+        }
+        let start: u64 = parameter0.parameter_value as u64;
+        if instruction.instruction_id == InstructionId::UnofficialLoopBeginSubtract {
+            return (LoopTermination::Terminating, vec![start]);
+        }
+        let registers: Vec<u64> = match instruction.parameter_vec.get(1) {
+            None => vec![start],
+            Some(parameter1) => {
+                let length: i64 = match literal(Some(parameter1)) {
+                    Some(value) => value,
+                    None => return (LoopTermination::Unknown, vec!())
+                };
+                if length > MAX_CONSTANT_RANGE_LENGTH {
+                    return (LoopTermination::Unknown, vec!());
+                }
+                (start..(start + length.max(0) as u64)).collect()
+            }
+        };
+        let body: &[RegisterAccess] = &self.accesses[(node.begin + 1)..node.end];
+        let writes_unknown: bool = body.iter().any(|access| access.writes_unknown);
+        let writes_loop_register: bool = body.iter().any(|access| {
+            registers.iter().any(|register| access.writes.contains(register))
+        });
+        if !writes_unknown && !writes_loop_register {
+            return (LoopTermination::NoProgress, registers);
+        }
+        (LoopTermination::Terminating, registers)
+    }
+
+    fn iterations(&self, node: &LoopNode, termination: &LoopTermination, registers: &[u64], entry: &Bounds) -> Growth {
+        match termination {
+            LoopTermination::NoProgress => return Growth::Constant,
+            LoopTermination::Unknown => return Growth::Unknown,
+            LoopTermination::Terminating => {}
+        }
+        let counter: u64 = match registers.first() {
+            Some(value) => *value,
+            None => return Growth::Constant
+        };
+        let body_indexes = (node.begin + 1)..node.end;
+        let writes_other_loop_register: bool = body_indexes.clone().any(|index| {
+            let access: &RegisterAccess = &self.accesses[index];
+            access.writes_unknown || registers[1..].iter().any(|register| access.writes.contains(register))
+        });
+        if writes_other_loop_register {
+            // The registers are compared lexicographically, so the counter may go up again.
+            return Growth::Unknown;
+        }
+        let value: Growth = bound(entry, counter);
+        let writers: Vec<&Instruction> = body_indexes
+            .filter(|index| self.accesses[*index].writes.contains(&counter))
+            .map(|index| &self.instructions[index])
+            .collect();
+        let is_logarithmic_decrease = |instruction: &&Instruction| -> bool {
+            match instruction.instruction_id {
+                InstructionId::Divide | InstructionId::DivideIf | InstructionId::NthRoot | InstructionId::Logarithm => {
+                    literal(instruction.parameter_vec.get(1)).map(|value| value >= 2).unwrap_or(false)
+                },
+                InstructionId::DigitSum => true,
+                _ => false
+            }
+        };
+        if !writers.is_empty() && writers.iter().all(is_logarithmic_decrease) {
+            return value.log();
+        }
+        value
+    }
+
+    fn eval_loop(&mut self, node: &LoopNode, env: &mut Env, bounds: &Bounds) -> Growth {
+        let mut entry: Bounds = bounds.clone();
+        for (register, term) in env.iter() {
+            entry.insert(*register, term.value);
+        }
+        let (termination, registers) = self.classify(node);
+        let iterations: Growth = self.iterations(node, &termination, &registers, &entry);
+        let line_number: usize = self.instructions[node.begin].line_number;
+
+        if termination == LoopTermination::NoProgress {
+            // A single iteration that is discarded, the registers are unchanged.
+            let mut body = Env::new();
+            let cost: Growth = self.eval_block(node.begin + 1, node.end, &node.children, &mut body, &entry);
+            self.reports.insert(node.begin, LoopReport { line_number, termination, iterations, cost });
+            return cost;
+        }
+
+        let mut inner_bounds: Bounds = entry.clone();
+        let mut summary = Env::new();
+        let mut body_cost = Growth::Unknown;
+        let mut is_stable = false;
+        for _ in 0..MAX_FIXPOINT_ROUNDS {
+            let mut body = Env::new();
+            body_cost = self.eval_block(node.begin + 1, node.end, &node.children, &mut body, &inner_bounds);
+            summary = apply_iterations(&body, iterations, &entry);
+            let mut next_bounds: Bounds = entry.clone();
+            for (register, term) in &summary {
+                let value: Growth = bound(&entry, *register).max(term.value);
+                next_bounds.insert(*register, value);
+            }
+            if next_bounds == inner_bounds {
+                is_stable = true;
+                break;
+            }
+            inner_bounds = next_bounds;
+        }
+        if !is_stable {
+            for term in summary.values_mut() {
+                term.value = Growth::Unknown;
+                term.fresh = Some(Growth::Unknown);
+            }
+            body_cost = Growth::Unknown;
+        }
+        let cost: Growth = iterations * body_cost;
+        self.reports.insert(node.begin, LoopReport { line_number, termination, iterations, cost });
+
+        // Express the values after the loop, in terms of the values before the loop.
+        let before: Env = env.clone();
+        for (register, term) in summary {
+            let mut result = Term {
+                value: term.value,
+                fresh: term.fresh,
+                carried: BTreeMap::new(),
+                nonlinear: term.nonlinear,
+            };
+            for (carried_register, weight) in &term.carried {
+                let carried_term: Term = match before.get(carried_register) {
+                    Some(value) => value.clone(),
+                    None => Term::identity(*carried_register, bounds),
+                };
+                result.fresh = max_optional(result.fresh, carried_term.fresh);
+                result.nonlinear |= carried_term.nonlinear;
+                for (register_before, weight_before) in &carried_term.carried {
+                    add_weight(&mut result.carried, *register_before, weight.saturating_mul(*weight_before));
+                }
+            }
+            env.insert(register, result);
+        }
+        cost
+    }
+
+    /// Report the loops, without estimating the number of iterations.
+    fn report_unknown(&mut self, nodes: &[LoopNode]) {
+        for node in nodes {
+            let (termination, _) = self.classify(node);
+            let line_number: usize = self.instructions[node.begin].line_number;
+            self.reports.insert(node.begin, LoopReport { line_number, termination, iterations: Growth::Unknown, cost: Growth::Unknown });
+            self.report_unknown(&node.children);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> ComplexityEstimate {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        ComplexityEstimate::analyze(&program).expect("estimate")
+    }
+
+    fn summary(source: &str) -> String {
+        let estimate: ComplexityEstimate = analyze(source);
+        let loops: Vec<String> = estimate.loops.iter().map(|report| {
+            format!("{}:{:?}:{}", report.line_number, report.termination, report.iterations)
+        }).collect();
+        format!("time: {} output: {} loops: {}", estimate.time, estimate.output, loops.join(" "))
+    }
+
+    #[test]
+    fn test_10000_no_loops() {
+        assert_eq!(summary("mul $0,2\nadd $0,1"), "time: O(1) output: O(n) loops: ");
+        assert_eq!(summary("pow $0,3"), "time: O(1) output: O(n^3) loops: ");
+        assert_eq!(summary("mov $1,2\npow $1,$0\nmov $0,$1"), "time: O(1) output: O(2^n) loops: ");
+        assert_eq!(summary("mov $1,2\npow $1,$0\nmov $0,0\ngcd $0,$1"), "time: O(1) output: O(2^n) loops: ");
+    }
+
+    #[test]
+    fn test_10001_fibonacci() {
+        let source = "mov $3,1\nlpb $0\n  sub $0,1\n  mov $2,$1\n  add $1,$3\n  mov $3,$2\nlpe\nmov $0,$1";
+        assert_eq!(summary(source), "time: O(n) output: O(2^n) loops: 2:Terminating:O(n)");
+    }
+
+    #[test]
+    fn test_10002_nested_loops() {
+        let source = "lpb $0\n  mov $2,$0\n  lpb $2\n    sub $2,1\n    add $1,1\n  lpe\n  sub $0,1\nlpe\nmov $0,$1";
+        assert_eq!(summary(source), "time: O(n^2) output: O(n^2) loops: 1:Terminating:O(n) 3:Terminating:O(n)");
+    }
+
+    #[test]
+    fn test_10003_logarithmic() {
+        let source = "lpb $0\n  div $0,2\n  add $1,1\nlpe\nmov $0,$1";
+        assert_eq!(summary(source), "time: O(log n) output: O(log n) loops: 1:Terminating:O(log n)");
+    }
+
+    #[test]
+    fn test_10004_exponential_time() {
+        let source = "mov $1,2\npow $1,$0\nlpb $1\n  sub $1,1\n  add $2,3\nlpe\nmov $0,$2";
+        assert_eq!(summary(source), "time: O(2^n) output: O(2^n) loops: 3:Terminating:O(2^n)");
+    }
+
+    #[test]
+    fn test_10005_accumulate_input() {
+        // Sum of i*n for i in 0..n
+        let source = "mov $1,$0\nlpb $0\n  sub $0,1\n  add $2,$1\n  add $3,$2\nlpe\nmov $0,$3";
+        assert_eq!(summary(source), "time: O(n) output: O(n^3) loops: 2:Terminating:O(n)");
+    }
+
+    #[test]
+    fn test_10006_doubling() {
+        let source = "mov $1,1\nlpb $0\n  sub $0,1\n  mul $1,2\nlpe\nmov $0,$1";
+        assert_eq!(summary(source), "time: O(n) output: O(2^n) loops: 2:Terminating:O(n)");
+    }
+
+    #[test]
+    fn test_20000_no_progress() {
+        // Arrange
+        let source = "lpb $0\n  add $1,1\nlpe\nlpb $0,0\n  sub $0,1\nlpe";
+
+        // Act
+        let estimate: ComplexityEstimate = analyze(source);
+
+        // Assert
+        assert_eq!(summary(source), "time: O(1) output: O(n) loops: 1:NoProgress:O(1) 4:NoProgress:O(1)");
+        assert_eq!(estimate.has_loop_without_progress(), true);
+    }
+
+    #[test]
+    fn test_20001_unknown() {
+        assert_eq!(summary("seq $0,45"), "time: unknown output: unknown loops: ");
+        assert_eq!(summary("lpb $0,$1\n  sub $0,1\nlpe"), "time: unknown output: unknown loops: 1:Unknown:unknown");
+        assert_eq!(summary("lpb $0\n  sub $$1,1\nlpe"), "time: unknown output: unknown loops: 1:Terminating:unknown");
+    }
+
+    #[test]
+    fn test_20002_loop_subtract() {
+        let source = "lps $0\n  add $1,2\nlpe\nmov $0,$1";
+        assert_eq!(summary(source), "time: O(n) output: O(n) loops: 1:Terminating:O(n)");
+    }
+}
@@ -0,0 +1,154 @@
+use super::{LoopTree, RegisterAccess};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Where the value of a register comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Definition {
+    /// The value when the program starts. The input is in `$0`, the other registers are zero.
+    Initial,
+
+    /// The instruction with this index writes the register.
+    Instruction(usize),
+}
+
+/// The definitions that may reach a point in the program.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReachingDefinitions {
+    /// Registers without an entry have only the `Definition::Initial` value.
+    registers: BTreeMap<u64, BTreeSet<Definition>>,
+
+    /// Instructions with indirect writes, such as `mov $$1,5`, that may have written any register.
+    unknown: BTreeSet<usize>,
+}
+
+impl ReachingDefinitions {
+    /// The definitions of a register that may reach this point.
+    pub fn get(&self, register: u64) -> BTreeSet<Definition> {
+        let mut definitions: BTreeSet<Definition> = match self.registers.get(&register) {
+            Some(definitions) => definitions.clone(),
+            None => BTreeSet::from([Definition::Initial]),
+        };
+        for index in &self.unknown {
+            definitions.insert(Definition::Instruction(*index));
+        }
+        definitions
+    }
+
+    /// The definitions of all the registers that may reach this point.
+    pub fn get_all(&self) -> BTreeSet<Definition> {
+        let mut definitions = BTreeSet::<Definition>::from([Definition::Initial]);
+        for register_definitions in self.registers.values() {
+            definitions.extend(register_definitions);
+        }
+        for index in &self.unknown {
+            definitions.insert(Definition::Instruction(*index));
+        }
+        definitions
+    }
+
+    fn union_with(&mut self, other: &ReachingDefinitions) -> bool {
+        let mut changed = false;
+        for (register, other_definitions) in &other.registers {
+            let definitions: &mut BTreeSet<Definition> = self.registers
+                .entry(*register)
+                .or_insert_with(|| BTreeSet::from([Definition::Initial]));
+            for definition in other_definitions {
+                changed |= definitions.insert(*definition);
+            }
+        }
+        for (register, definitions) in self.registers.iter_mut() {
+            if !other.registers.contains_key(register) {
+                changed |= definitions.insert(Definition::Initial);
+            }
+        }
+        for index in &other.unknown {
+            changed |= self.unknown.insert(*index);
+        }
+        changed
+    }
+
+    fn transfer(&self, index: usize, access: &RegisterAccess) -> ReachingDefinitions {
+        let mut result: ReachingDefinitions = self.clone();
+        for register in &access.writes {
+            result.registers.insert(*register, BTreeSet::from([Definition::Instruction(index)]));
+        }
+        if access.writes_unknown {
+            result.unknown.insert(index);
+        }
+        result
+    }
+
+    /// Find the definitions that reach each instruction.
+    ///
+    /// Returns one item per instruction, and an extra item for the end of the program.
+    pub fn compute(accesses: &[RegisterAccess], loop_tree: &LoopTree) -> Vec<ReachingDefinitions> {
+        let count: usize = accesses.len();
+        let mut reaching: Vec<Option<ReachingDefinitions>> = vec![None; count + 1];
+        reaching[0] = Some(ReachingDefinitions::default());
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..count {
+                let out: ReachingDefinitions = match &reaching[index] {
+                    Some(item) => item.transfer(index, &accesses[index]),
+                    None => continue,
+                };
+                for successor in loop_tree.successors(index) {
+                    match &mut reaching[successor] {
+                        Some(item) => {
+                            changed |= item.union_with(&out);
+                        },
+                        None => {
+                            reaching[successor] = Some(out.clone());
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        reaching.into_iter().map(|item| item.unwrap_or_default()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedProgram;
+
+    fn compute(source: &str) -> Vec<ReachingDefinitions> {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        let accesses: Vec<RegisterAccess> = program.instruction_vec.iter().map(RegisterAccess::from_instruction).collect();
+        let loop_tree = LoopTree::build(&program.instruction_vec).expect("loop tree");
+        ReachingDefinitions::compute(&accesses, &loop_tree)
+    }
+
+    #[test]
+    fn test_10000_straight_line() {
+        // Act
+        let reaching: Vec<ReachingDefinitions> = compute("mov $1,2\nadd $1,$0\nmov $0,$1");
+
+        // Assert
+        assert_eq!(reaching[1].get(1), BTreeSet::from([Definition::Instruction(0)]));
+        assert_eq!(reaching[1].get(0), BTreeSet::from([Definition::Initial]));
+        assert_eq!(reaching[3].get(0), BTreeSet::from([Definition::Instruction(2)]));
+    }
+
+    #[test]
+    fn test_10001_loop() {
+        // Act
+        let reaching: Vec<ReachingDefinitions> = compute("lpb $0\n  sub $0,1\n  add $1,2\nlpe\nmov $0,$1");
+
+        // Assert
+        assert_eq!(reaching[2].get(1), BTreeSet::from([Definition::Initial, Definition::Instruction(2)]));
+        assert_eq!(reaching[4].get(0), BTreeSet::from([Definition::Initial, Definition::Instruction(1)]));
+    }
+
+    #[test]
+    fn test_10002_indirect_write() {
+        // Act
+        let reaching: Vec<ReachingDefinitions> = compute("mov $$0,1\nmov $1,$5");
+
+        // Assert
+        assert_eq!(reaching[1].get(5), BTreeSet::from([Definition::Initial, Definition::Instruction(0)]));
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::ops;
+
+/// How fast a quantity grows as a function of the input `n`.
+///
+/// Logarithmic factors are ignored when combined with polynomials, so `n*log(n)` is `Polynomial(1)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Growth {
+    Constant,
+    Logarithmic,
+
+    /// The degree of the polynomial, 1 or higher.
+    Polynomial(u32),
+
+    Exponential,
+
+    /// The growth cannot be determined, such as for programs that calls other programs.
+    Unknown,
+}
+
+impl Growth {
+    pub fn linear() -> Self {
+        Self::Polynomial(1)
+    }
+
+    /// The growth of `self^exponent`, where the exponent is a constant.
+    pub fn pow(self, exponent: u32) -> Growth {
+        if exponent == 0 {
+            return Self::Constant;
+        }
+        match self {
+            Self::Polynomial(degree) => Self::Polynomial(degree.saturating_mul(exponent)),
+            value => value,
+        }
+    }
+
+    /// The growth of `log(self)`.
+    ///
+    /// A single exponential is assumed, so the logarithm of `Exponential` is `Polynomial(1)`.
+    pub fn log(self) -> Growth {
+        match self {
+            Self::Constant => Self::Constant,
+            Self::Logarithmic | Self::Polynomial(_) => Self::Logarithmic,
+            Self::Exponential => Self::linear(),
+            Self::Unknown => Self::Unknown,
+        }
+    }
+
+    /// The growth of `c^self`, where `c` is a constant.
+    pub fn exp(self) -> Growth {
+        match self {
+            Self::Constant => Self::Constant,
+            Self::Logarithmic => Self::linear(),
+            Self::Polynomial(_) | Self::Exponential => Self::Exponential,
+            Self::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// The growth of a product.
+impl ops::Mul for Growth {
+    type Output = Growth;
+
+    fn mul(self, other: Growth) -> Growth {
+        match (self, other) {
+            (Self::Unknown, _) | (_, Self::Unknown) => Self::Unknown,
+            (Self::Exponential, _) | (_, Self::Exponential) => Self::Exponential,
+            (Self::Constant, value) | (value, Self::Constant) => value,
+            (Self::Logarithmic, value) | (value, Self::Logarithmic) => value,
+            (Self::Polynomial(degree0), Self::Polynomial(degree1)) => Self::Polynomial(degree0.saturating_add(degree1)),
+        }
+    }
+}
+
+impl fmt::Display for Growth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Constant => write!(f, "O(1)"),
+            Self::Logarithmic => write!(f, "O(log n)"),
+            Self::Polynomial(1) => write!(f, "O(n)"),
+            Self::Polynomial(degree) => write!(f, "O(n^{})", degree),
+            Self::Exponential => write!(f, "O(2^n)"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_ordering() {
+        assert_eq!(Growth::Constant < Growth::Logarithmic, true);
+        assert_eq!(Growth::Logarithmic < Growth::Polynomial(1), true);
+        assert_eq!(Growth::Polynomial(1) < Growth::Polynomial(2), true);
+        assert_eq!(Growth::Polynomial(9) < Growth::Exponential, true);
+        assert_eq!(Growth::Exponential < Growth::Unknown, true);
+    }
+
+    #[test]
+    fn test_20000_mul() {
+        assert_eq!(Growth::Polynomial(2) * Growth::Polynomial(3), Growth::Polynomial(5));
+        assert_eq!(Growth::Constant * Growth::Logarithmic, Growth::Logarithmic);
+        assert_eq!(Growth::Logarithmic * Growth::Polynomial(1), Growth::Polynomial(1));
+        assert_eq!(Growth::Exponential * Growth::Polynomial(1), Growth::Exponential);
+        assert_eq!(Growth::Unknown * Growth::Constant, Growth::Unknown);
+    }
+
+    #[test]
+    fn test_20001_pow_log_exp() {
+        assert_eq!(Growth::Polynomial(2).pow(3), Growth::Polynomial(6));
+        assert_eq!(Growth::Polynomial(2).pow(0), Growth::Constant);
+        assert_eq!(Growth::Polynomial(2).log(), Growth::Logarithmic);
+        assert_eq!(Growth::Exponential.log(), Growth::Polynomial(1));
+        assert_eq!(Growth::Polynomial(1).exp(), Growth::Exponential);
+        assert_eq!(Growth::Constant.exp(), Growth::Constant);
+    }
+
+    #[test]
+    fn test_30000_to_string() {
+        assert_eq!(Growth::Constant.to_string(), "O(1)");
+        assert_eq!(Growth::Logarithmic.to_string(), "O(log n)");
+        assert_eq!(Growth::Polynomial(1).to_string(), "O(n)");
+        assert_eq!(Growth::Polynomial(3).to_string(), "O(n^3)");
+        assert_eq!(Growth::Exponential.to_string(), "O(2^n)");
+        assert_eq!(Growth::Unknown.to_string(), "unknown");
+    }
+}
@@ -0,0 +1,125 @@
+use super::{LoopTree, RegisterAccess};
+use std::collections::BTreeSet;
+
+/// A set of registers, or all registers when indirect memory access is involved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegisterSet {
+    pub all: bool,
+    pub registers: BTreeSet<u64>,
+}
+
+impl RegisterSet {
+    pub fn contains(&self, register: u64) -> bool {
+        self.all || self.registers.contains(&register)
+    }
+
+    fn union_with(&mut self, other: &RegisterSet) -> bool {
+        let mut changed = false;
+        if other.all && !self.all {
+            self.all = true;
+            changed = true;
+        }
+        for register in &other.registers {
+            changed |= self.registers.insert(*register);
+        }
+        changed
+    }
+}
+
+/// The registers whose values may be read later on, before and after each instruction.
+///
+/// The program output in `$0` is live at the end of the program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Liveness {
+    pub live_in: Vec<RegisterSet>,
+    pub live_out: Vec<RegisterSet>,
+}
+
+impl Liveness {
+    pub fn compute(accesses: &[RegisterAccess], loop_tree: &LoopTree) -> Self {
+        let count: usize = accesses.len();
+        let mut live_in: Vec<RegisterSet> = vec![RegisterSet::default(); count + 1];
+        let mut live_out: Vec<RegisterSet> = vec![RegisterSet::default(); count];
+        live_in[count].registers.insert(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in (0..count).rev() {
+                let mut out = RegisterSet::default();
+                for successor in loop_tree.successors(index) {
+                    out.union_with(&live_in[successor]);
+                }
+                let access: &RegisterAccess = &accesses[index];
+                let mut set: RegisterSet = out.clone();
+                if !set.all {
+                    for register in &access.writes {
+                        if !access.reads.contains(register) {
+                            set.registers.remove(register);
+                        }
+                    }
+                }
+                set.all |= access.reads_unknown;
+                set.registers.extend(&access.reads);
+                changed |= live_in[index].union_with(&set);
+                live_out[index] = out;
+            }
+        }
+        live_in.truncate(count);
+        Self {
+            live_in,
+            live_out,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedProgram;
+
+    fn compute(source: &str) -> Liveness {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        let accesses: Vec<RegisterAccess> = program.instruction_vec.iter().map(RegisterAccess::from_instruction).collect();
+        let loop_tree = LoopTree::build(&program.instruction_vec).expect("loop tree");
+        Liveness::compute(&accesses, &loop_tree)
+    }
+
+    fn format(set: &RegisterSet) -> String {
+        if set.all {
+            return "all".to_string();
+        }
+        let items: Vec<String> = set.registers.iter().map(|register| format!("${}", register)).collect();
+        items.join(" ")
+    }
+
+    #[test]
+    fn test_10000_straight_line() {
+        // Act
+        let liveness: Liveness = compute("mov $2,7\nmov $1,$0\nmul $1,3\nmov $0,$1");
+
+        // Assert
+        assert_eq!(format(&liveness.live_out[0]), "$0");
+        assert_eq!(format(&liveness.live_in[1]), "$0");
+        assert_eq!(format(&liveness.live_out[1]), "$1");
+        assert_eq!(format(&liveness.live_out[3]), "$0");
+    }
+
+    #[test]
+    fn test_10001_loop() {
+        // Act
+        let liveness: Liveness = compute("mov $1,1\nlpb $0\n  sub $0,1\n  mul $1,2\nlpe\nmov $0,$1");
+
+        // Assert
+        assert_eq!(format(&liveness.live_out[0]), "$0 $1");
+        assert_eq!(format(&liveness.live_out[3]), "$0 $1");
+    }
+
+    #[test]
+    fn test_10002_indirect_read() {
+        // Act
+        let liveness: Liveness = compute("mov $3,5\nmov $0,$$1");
+
+        // Assert
+        assert_eq!(format(&liveness.live_out[0]), "all");
+    }
+}
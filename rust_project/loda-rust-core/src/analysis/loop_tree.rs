@@ -0,0 +1,157 @@
+use crate::parser::{Instruction, InstructionId};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoopTreeError {
+    /// A `lpe` instruction without a `lpb` instruction. The line number.
+    LoopEndWithoutLoopBegin(usize),
+
+    /// A `lpb` instruction without a `lpe` instruction. The line number.
+    LoopBeginWithoutLoopEnd(usize),
+}
+
+impl fmt::Display for LoopTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LoopEndWithoutLoopBegin(line_number) => write!(f, "lpe without lpb in line {}", line_number),
+            Self::LoopBeginWithoutLoopEnd(line_number) => write!(f, "lpb without lpe in line {}", line_number),
+        }
+    }
+}
+
+/// A loop in the program, from the `lpb` instruction to the `lpe` instruction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopNode {
+    /// Index of the `lpb` or `lps` instruction.
+    pub begin: usize,
+
+    /// Index of the `lpe` instruction.
+    pub end: usize,
+
+    /// The loops inside this loop.
+    pub children: Vec<LoopNode>,
+}
+
+/// The nesting of the loops in a program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopTree {
+    /// The outermost loops.
+    pub roots: Vec<LoopNode>,
+
+    /// For each instruction, the index of the matching `lpb` or `lpe` instruction.
+    partner: Vec<Option<usize>>,
+
+    /// For each instruction, the number of loops around it. The `lpb` and `lpe` instructions are outside their own loop.
+    depth: Vec<usize>,
+}
+
+impl LoopTree {
+    pub fn build(instructions: &[Instruction]) -> Result<Self, LoopTreeError> {
+        let mut partner: Vec<Option<usize>> = vec![None; instructions.len()];
+        let mut depth: Vec<usize> = vec![0; instructions.len()];
+        let mut stack: Vec<(usize, Vec<LoopNode>)> = vec!();
+        let mut roots: Vec<LoopNode> = vec!();
+        for (index, instruction) in instructions.iter().enumerate() {
+            depth[index] = stack.len();
+            match instruction.instruction_id {
+                InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract => {
+                    stack.push((index, vec!()));
+                },
+                InstructionId::LoopEnd => {
+                    let (begin, children) = stack.pop()
+                        .ok_or(LoopTreeError::LoopEndWithoutLoopBegin(instruction.line_number))?;
+                    depth[index] = stack.len();
+                    partner[begin] = Some(index);
+                    partner[index] = Some(begin);
+                    let node = LoopNode { begin, end: index, children };
+                    match stack.last_mut() {
+                        Some((_, siblings)) => siblings.push(node),
+                        None => roots.push(node),
+                    }
+                },
+                _ => {}
+            }
+        }
+        if let Some((begin, _)) = stack.first() {
+            return Err(LoopTreeError::LoopBeginWithoutLoopEnd(instructions[*begin].line_number));
+        }
+        let instance = Self {
+            roots,
+            partner,
+            depth,
+        };
+        Ok(instance)
+    }
+
+    /// For a `lpb` instruction the index of the `lpe` instruction, and the other way around.
+    pub fn partner(&self, index: usize) -> Option<usize> {
+        self.partner.get(index).cloned().flatten()
+    }
+
+    /// The number of loops around the instruction.
+    pub fn depth(&self, index: usize) -> usize {
+        self.depth.get(index).cloned().unwrap_or(0)
+    }
+
+    /// The instructions that can execute after the instruction with `index`.
+    ///
+    /// The loop body may be skipped, so a `lpb` continues after its `lpe`.
+    /// A `lpe` either jumps back to its `lpb` or continues with the next instruction.
+    /// The index `instruction_count` is the end of the program.
+    pub fn successors(&self, index: usize) -> Vec<usize> {
+        match self.partner(index) {
+            Some(end) if end > index => vec![index + 1, end + 1],
+            Some(begin) => vec![begin, index + 1],
+            None => vec![index + 1],
+        }
+    }
+
+    /// The deepest nesting of loops in the program.
+    pub fn max_depth(&self) -> usize {
+        fn node_depth(node: &LoopNode) -> usize {
+            1 + node.children.iter().map(node_depth).max().unwrap_or(0)
+        }
+        self.roots.iter().map(node_depth).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedProgram;
+
+    fn build(source: &str) -> Result<LoopTree, LoopTreeError> {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        LoopTree::build(&program.instruction_vec)
+    }
+
+    #[test]
+    fn test_10000_nested_loops() {
+        // Arrange
+        let source = "mov $1,2\nlpb $0\n  lpb $1\n    sub $1,1\n  lpe\n  sub $0,1\nlpe\nlpb $2\nlpe";
+
+        // Act
+        let tree: LoopTree = build(source).expect("tree");
+
+        // Assert
+        assert_eq!(tree.roots.len(), 2);
+        assert_eq!(tree.roots[0].begin, 1);
+        assert_eq!(tree.roots[0].end, 6);
+        assert_eq!(tree.roots[0].children, vec![LoopNode { begin: 2, end: 4, children: vec!() }]);
+        assert_eq!(tree.partner(2), Some(4));
+        assert_eq!(tree.partner(6), Some(1));
+        assert_eq!(tree.partner(0), None);
+        assert_eq!(tree.depth(3), 2);
+        assert_eq!(tree.depth(4), 1);
+        assert_eq!(tree.max_depth(), 2);
+        assert_eq!(tree.successors(0), vec![1]);
+        assert_eq!(tree.successors(1), vec![2, 7]);
+        assert_eq!(tree.successors(6), vec![1, 7]);
+    }
+
+    #[test]
+    fn test_10001_unbalanced() {
+        assert_eq!(build("lpb $0\nlpb $1\nlpe"), Err(LoopTreeError::LoopBeginWithoutLoopEnd(1)));
+        assert_eq!(build("mov $0,1\nlpe"), Err(LoopTreeError::LoopEndWithoutLoopBegin(2)));
+    }
+}
@@ -0,0 +1,20 @@
+//! Static analysis of a program, without running it.
+mod canonical_form;
+mod complexity;
+mod def_use;
+mod growth;
+mod liveness;
+mod loop_tree;
+mod program_analysis;
+mod register_access;
+mod register_usage;
+
+pub use canonical_form::CanonicalForm;
+pub use complexity::{ComplexityEstimate, LoopReport, LoopTermination};
+pub use def_use::{Definition, ReachingDefinitions};
+pub use growth::Growth;
+pub use liveness::{Liveness, RegisterSet};
+pub use loop_tree::{LoopNode, LoopTree, LoopTreeError};
+pub use program_analysis::{Finding, ProgramAnalysis};
+pub use register_access::RegisterAccess;
+pub use register_usage::RegisterUsage;
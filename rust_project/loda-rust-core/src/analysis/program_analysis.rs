@@ -0,0 +1,318 @@
+use super::{Definition, Liveness, LoopTree, LoopTreeError, ReachingDefinitions, RegisterAccess, RegisterSet};
+use crate::parser::{Instruction, InstructionId, ParameterType, ParsedProgram};
+use crate::unofficial_function::{UnofficialFunctionId, UnofficialFunctionRegistry};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A problem found by `ProgramAnalysis::findings()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Finding {
+    /// The instruction writes a register, and the value is never read.
+    DeadStore { line_number: usize, register: u64 },
+
+    /// The value computed by the instruction is only used by instructions that have no effect on the output.
+    NoEffectOnOutput { line_number: usize },
+
+    /// The register is written, but never read.
+    UnusedRegister { register: u64 },
+
+    /// The register is read, but never written, so it's always zero.
+    RegisterNeverWritten { register: u64 },
+}
+
+impl Finding {
+    /// The line where the problem occurred, or `None` when the problem is not tied to a line.
+    pub fn line_number(&self) -> Option<usize> {
+        match self {
+            Self::DeadStore { line_number, .. } => Some(*line_number),
+            Self::NoEffectOnOutput { line_number } => Some(*line_number),
+            Self::UnusedRegister { .. } => None,
+            Self::RegisterNeverWritten { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DeadStore { register, .. } => write!(f, "dead store, the value written to ${} is never read", register),
+            Self::NoEffectOnOutput { .. } => write!(f, "the instruction has no effect on the output"),
+            Self::UnusedRegister { register } => write!(f, "the register ${} is written, but never read", register),
+            Self::RegisterNeverWritten { register } => write!(f, "the register ${} is read, but never written, so it's always zero", register),
+        }
+    }
+}
+
+/// Register usage, def-use chains and liveness of a program, without running it.
+///
+/// The analysis is conservative. A loop body may run zero or more times.
+/// Indirect memory access, such as `$$1`, may read or write any register.
+pub struct ProgramAnalysis {
+    instructions: Vec<Instruction>,
+    accesses: Vec<RegisterAccess>,
+    side_effects: Vec<bool>,
+    loop_tree: LoopTree,
+    reaching: Vec<ReachingDefinitions>,
+    liveness: Liveness,
+}
+
+impl ProgramAnalysis {
+    pub fn analyze(program: &ParsedProgram) -> Result<Self, LoopTreeError> {
+        Self::analyze_with_registry(program, &UnofficialFunctionRegistry::new())
+    }
+
+    /// Same as `analyze()`, and the unofficial functions with a side effect, such as asserts,
+    /// are looked up in the registry, so they are never reported as having no effect on the output.
+    pub fn analyze_with_registry(program: &ParsedProgram, registry: &UnofficialFunctionRegistry) -> Result<Self, LoopTreeError> {
+        let instructions: Vec<Instruction> = program.instruction_vec.clone();
+        let loop_tree = LoopTree::build(&instructions)?;
+        let mut accesses: Vec<RegisterAccess> = instructions.iter().map(RegisterAccess::from_instruction).collect();
+        for index in 0..instructions.len() {
+            if instructions[index].instruction_id == InstructionId::LoopEnd {
+                // The `lpe` instruction compares the loop registers, with their values before the iteration.
+                if let Some(begin) = loop_tree.partner(index) {
+                    accesses[index] = accesses[begin].clone();
+                }
+            }
+        }
+        let reaching: Vec<ReachingDefinitions> = ReachingDefinitions::compute(&accesses, &loop_tree);
+        let liveness: Liveness = Liveness::compute(&accesses, &loop_tree);
+        let side_effects: Vec<bool> = instructions.iter().map(|instruction| has_side_effect(instruction, registry)).collect();
+        let instance = Self {
+            instructions,
+            accesses,
+            side_effects,
+            loop_tree,
+            reaching,
+            liveness,
+        };
+        Ok(instance)
+    }
+
+    pub fn register_access(&self, index: usize) -> &RegisterAccess {
+        &self.accesses[index]
+    }
+
+    pub fn loop_tree(&self) -> &LoopTree {
+        &self.loop_tree
+    }
+
+    /// The registers that may be read later, after the instruction.
+    pub fn live_out(&self, index: usize) -> &RegisterSet {
+        &self.liveness.live_out[index]
+    }
+
+    /// The registers that may be read later, before the instruction.
+    pub fn live_in(&self, index: usize) -> &RegisterSet {
+        &self.liveness.live_in[index]
+    }
+
+    /// The use-def chain, where the value of the register read by the instruction may come from.
+    ///
+    /// The index `instruction_count` is the end of the program.
+    pub fn definitions(&self, index: usize, register: u64) -> BTreeSet<Definition> {
+        self.reaching[index].get(register)
+    }
+
+    /// The def-use chain, the instructions that may read the value written by the instruction.
+    ///
+    /// The index `instruction_count` is the end of the program, that reads the output in `$0`.
+    pub fn uses(&self, index: usize) -> Vec<usize> {
+        let definition = Definition::Instruction(index);
+        (0..=self.instructions.len()).filter(|use_index| {
+            self.used_definitions(*use_index).contains(&definition)
+        }).collect()
+    }
+
+    /// The definitions of the registers read by the instruction.
+    fn used_definitions(&self, index: usize) -> BTreeSet<Definition> {
+        if index == self.instructions.len() {
+            return self.reaching[index].get(0);
+        }
+        let access: &RegisterAccess = &self.accesses[index];
+        if access.reads_unknown {
+            return self.reaching[index].get_all();
+        }
+        let mut definitions = BTreeSet::<Definition>::new();
+        for register in &access.reads {
+            definitions.extend(self.reaching[index].get(*register));
+        }
+        definitions
+    }
+
+    /// The instructions that contribute to the output, to the loop conditions, or that have a side effect.
+    fn useful_instructions(&self) -> Vec<bool> {
+        let count: usize = self.instructions.len();
+        let mut useful: Vec<bool> = vec![false; count];
+        let mut pending: Vec<usize> = vec![count];
+        for (index, is_useful) in useful.iter_mut().enumerate() {
+            if self.loop_tree.partner(index).is_some() || self.accesses[index].writes_unknown || self.side_effects[index] {
+                *is_useful = true;
+                pending.push(index);
+            }
+        }
+        while let Some(index) = pending.pop() {
+            for definition in self.used_definitions(index) {
+                if let Definition::Instruction(definition_index) = definition {
+                    if !useful[definition_index] {
+                        useful[definition_index] = true;
+                        pending.push(definition_index);
+                    }
+                }
+            }
+        }
+        useful
+    }
+
+    /// Dead stores, instructions without effect on the output, and registers that are not used.
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = vec!();
+        let useful: Vec<bool> = self.useful_instructions();
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            if useful[index] {
+                continue;
+            }
+            let live_out: &RegisterSet = &self.liveness.live_out[index];
+            let dead_register: Option<&u64> = self.accesses[index].writes.iter().find(|register| !live_out.contains(**register));
+            let finding = match dead_register {
+                Some(register) => Finding::DeadStore { line_number: instruction.line_number, register: *register },
+                None => Finding::NoEffectOnOutput { line_number: instruction.line_number },
+            };
+            findings.push(finding);
+        }
+
+        let any_reads_unknown: bool = self.accesses.iter().any(|access| access.reads_unknown);
+        let any_writes_unknown: bool = self.accesses.iter().any(|access| access.writes_unknown);
+        let mut reads = BTreeSet::<u64>::new();
+        let mut writes = BTreeSet::<u64>::new();
+        for access in &self.accesses {
+            reads.extend(&access.reads);
+            writes.extend(&access.writes);
+        }
+        if !any_reads_unknown {
+            for register in writes.difference(&reads) {
+                if *register != 0 {
+                    findings.push(Finding::UnusedRegister { register: *register });
+                }
+            }
+        }
+        if !any_writes_unknown {
+            for register in reads.difference(&writes) {
+                if *register != 0 {
+                    findings.push(Finding::RegisterNeverWritten { register: *register });
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// Unofficial functions such as `f20 $1,1030` that asserts that `$1` equals `$2`.
+fn has_side_effect(instruction: &Instruction, registry: &UnofficialFunctionRegistry) -> bool {
+    let (input_count, output_count) = match instruction.instruction_id {
+        InstructionId::UnofficialFunction { input_count, output_count } => (input_count, output_count),
+        _ => return false
+    };
+    let function_id: u32 = match instruction.parameter_vec.get(1) {
+        Some(parameter) if parameter.parameter_type == ParameterType::Constant => {
+            match u32::try_from(parameter.parameter_value) {
+                Ok(value) => value,
+                Err(_) => return false
+            }
+        },
+        _ => return false
+    };
+    let key = UnofficialFunctionId::InputOutput { id: function_id, inputs: input_count, outputs: output_count };
+    registry.lookup(key).is_some_and(|function| function.has_side_effect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unofficial_function::register_common_functions;
+
+    fn analyze(source: &str) -> ProgramAnalysis {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        ProgramAnalysis::analyze(&program).expect("analysis")
+    }
+
+    fn findings(source: &str) -> String {
+        let findings: Vec<String> = analyze(source).findings().iter().map(|finding| {
+            match finding.line_number() {
+                Some(line_number) => format!("{}: {}", line_number, finding),
+                None => finding.to_string(),
+            }
+        }).collect();
+        findings.join("\n")
+    }
+
+    #[test]
+    fn test_10000_no_findings() {
+        let source = "mov $3,1\nlpb $0\n  sub $0,1\n  mov $2,$1\n  add $1,$3\n  mov $3,$2\nlpe\nmov $0,$1";
+        assert_eq!(findings(source), "");
+    }
+
+    #[test]
+    fn test_10001_dead_store() {
+        let source = "mov $1,5\nmov $1,$0\nmul $1,2\nmov $0,$1";
+        assert_eq!(findings(source), "1: dead store, the value written to $1 is never read");
+    }
+
+    #[test]
+    fn test_10002_no_effect_on_output() {
+        let source = "lpb $0\n  sub $0,1\n  add $2,1\n  add $1,$2\nlpe\nmul $0,2";
+        let expected = "3: the instruction has no effect on the output\n4: the instruction has no effect on the output";
+        assert_eq!(findings(source), expected);
+    }
+
+    #[test]
+    fn test_10003_unused_register() {
+        let source = "mov $1,5\nmov $0,2";
+        assert_eq!(findings(source), "1: dead store, the value written to $1 is never read\nthe register $1 is written, but never read");
+    }
+
+    #[test]
+    fn test_10004_register_never_written() {
+        let source = "add $0,$4";
+        assert_eq!(findings(source), "the register $4 is read, but never written, so it's always zero");
+    }
+
+    #[test]
+    fn test_10005_indirect_memory_access() {
+        let source = "mov $5,7\nmov $0,$$0";
+        assert_eq!(findings(source), "");
+    }
+
+    #[test]
+    fn test_10006_side_effect() {
+        // Arrange
+        let program = ParsedProgram::parse_program("mov $1,$0\nmov $2,5\nf20 $1,1030\nadd $0,1").expect("program");
+        let registry = UnofficialFunctionRegistry::new();
+        register_common_functions(&registry);
+
+        // Act
+        let without_registry: Vec<Finding> = ProgramAnalysis::analyze(&program).expect("analysis").findings();
+        let with_registry: Vec<Finding> = ProgramAnalysis::analyze_with_registry(&program, &registry).expect("analysis").findings();
+
+        // Assert
+        assert_eq!(without_registry.len(), 3);
+        assert_eq!(with_registry, vec![]);
+    }
+
+    #[test]
+    fn test_20000_def_use_chains() {
+        // Arrange
+        let analysis = analyze("mov $1,1\nlpb $0\n  sub $0,1\n  mul $1,2\nlpe\nmov $0,$1");
+
+        // Act
+        let uses_of_mov: Vec<usize> = analysis.uses(0);
+        let uses_of_mul: Vec<usize> = analysis.uses(3);
+        let definitions: BTreeSet<Definition> = analysis.definitions(5, 1);
+
+        // Assert
+        assert_eq!(uses_of_mov, vec![3, 5]);
+        assert_eq!(uses_of_mul, vec![3, 5]);
+        assert_eq!(definitions, BTreeSet::from([Definition::Instruction(0), Definition::Instruction(3)]));
+        assert_eq!(analysis.uses(5), vec![6]);
+    }
+}
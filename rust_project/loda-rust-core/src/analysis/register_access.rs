@@ -0,0 +1,186 @@
+use crate::parser::{Instruction, InstructionId, InstructionParameter, ParameterType};
+use std::collections::BTreeSet;
+
+/// Loops with a constant range longer than this, are treated as if the range is unknown.
+const MAX_CONSTANT_RANGE_LENGTH: i64 = 100;
+
+/// The registers that an instruction reads and writes.
+///
+/// Indirect memory access, such as `$$1`, reads the address from `$1`,
+/// but the register that is accessed is not known before the program runs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegisterAccess {
+    pub reads: BTreeSet<u64>,
+    pub writes: BTreeSet<u64>,
+
+    /// The instruction may read any register.
+    pub reads_unknown: bool,
+
+    /// The instruction may write any register.
+    pub writes_unknown: bool,
+
+    /// The instruction uses indirect memory access, such as `$$1`.
+    pub indirect: bool,
+}
+
+impl RegisterAccess {
+    pub fn from_instruction(instruction: &Instruction) -> Self {
+        let mut access = Self::default();
+        let parameters: &Vec<InstructionParameter> = &instruction.parameter_vec;
+        match instruction.instruction_id {
+            InstructionId::LoopEnd => {},
+            InstructionId::LoopBegin => {
+                if let Some(parameter0) = parameters.first() {
+                    let range_length: Option<i64> = match parameters.get(1) {
+                        None => Some(1),
+                        Some(parameter1) => {
+                            match parameter1.parameter_type {
+                                ParameterType::Constant => Some(parameter1.parameter_value),
+                                ParameterType::Direct | ParameterType::Indirect => {
+                                    access.read(parameter1);
+                                    None
+                                }
+                            }
+                        }
+                    };
+                    match (&parameter0.parameter_type, range_length) {
+                        (ParameterType::Direct, Some(length)) if length <= MAX_CONSTANT_RANGE_LENGTH => {
+                            let start: i64 = parameter0.parameter_value;
+                            for register in start..(start + length.max(0)) {
+                                access.reads.insert(register as u64);
+                            }
+                        },
+                        _ => {
+                            access.read(parameter0);
+                            access.reads_unknown = true;
+                        }
+                    }
+                }
+            },
+            InstructionId::UnofficialLoopBeginSubtract => {
+                if let Some(parameter0) = parameters.first() {
+                    access.read(parameter0);
+                    access.write(parameter0);
+                }
+            },
+            InstructionId::UnofficialFunction { input_count, output_count } => {
+                if let Some(parameter0) = parameters.first() {
+                    match parameter0.parameter_type {
+                        ParameterType::Direct => {
+                            let start: u64 = parameter0.parameter_value as u64;
+                            for register in start..(start + input_count as u64) {
+                                access.reads.insert(register);
+                            }
+                            for register in start..(start + output_count as u64) {
+                                access.writes.insert(register);
+                            }
+                        },
+                        _ => {
+                            access.read(parameter0);
+                            access.reads_unknown = true;
+                            access.writes_unknown = true;
+                        }
+                    }
+                }
+            },
+            InstructionId::Move => {
+                if let Some(parameter1) = parameters.get(1) {
+                    access.read(parameter1);
+                }
+                if let Some(parameter0) = parameters.first() {
+                    access.write(parameter0);
+                }
+            },
+            _ => {
+                // The target is both read and written, such as `add $0,1` and `seq $0,45`.
+                for parameter in parameters {
+                    access.read(parameter);
+                }
+                if let Some(parameter0) = parameters.first() {
+                    access.write(parameter0);
+                }
+            }
+        }
+        access
+    }
+
+    fn read(&mut self, parameter: &InstructionParameter) {
+        match parameter.parameter_type {
+            ParameterType::Constant => {},
+            ParameterType::Direct => {
+                self.reads.insert(parameter.parameter_value as u64);
+            },
+            ParameterType::Indirect => {
+                self.reads.insert(parameter.parameter_value as u64);
+                self.reads_unknown = true;
+                self.indirect = true;
+            }
+        }
+    }
+
+    fn write(&mut self, parameter: &InstructionParameter) {
+        match parameter.parameter_type {
+            ParameterType::Constant => {},
+            ParameterType::Direct => {
+                self.writes.insert(parameter.parameter_value as u64);
+            },
+            ParameterType::Indirect => {
+                // The address is read from the register.
+                self.reads.insert(parameter.parameter_value as u64);
+                self.writes_unknown = true;
+                self.indirect = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedProgram;
+
+    fn access(source: &str) -> String {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        let instruction: &Instruction = program.instruction_vec.first().expect("instruction");
+        let access = RegisterAccess::from_instruction(instruction);
+        let format_set = |set: &BTreeSet<u64>, unknown: bool| -> String {
+            let mut items: Vec<String> = set.iter().map(|register| format!("${}", register)).collect();
+            if unknown {
+                items.push("?".to_string());
+            }
+            items.join(" ")
+        };
+        format!("reads: {} writes: {}", format_set(&access.reads, access.reads_unknown), format_set(&access.writes, access.writes_unknown))
+    }
+
+    #[test]
+    fn test_10000_calc() {
+        assert_eq!(access("mov $1,$2"), "reads: $2 writes: $1");
+        assert_eq!(access("mov $1,5"), "reads:  writes: $1");
+        assert_eq!(access("add $1,$2"), "reads: $1 $2 writes: $1");
+        assert_eq!(access("sub $1,1"), "reads: $1 writes: $1");
+        assert_eq!(access("seq $3,45"), "reads: $3 writes: $3");
+    }
+
+    #[test]
+    fn test_10001_indirect() {
+        assert_eq!(access("mov $$1,$2"), "reads: $1 $2 writes: ?");
+        assert_eq!(access("add $1,$$2"), "reads: $1 $2 ? writes: $1");
+        assert_eq!(access("add $$1,1"), "reads: $1 ? writes: ?");
+    }
+
+    #[test]
+    fn test_10002_loop() {
+        assert_eq!(access("lpb $1\nlpe"), "reads: $1 writes: ");
+        assert_eq!(access("lpb $1,3\nlpe"), "reads: $1 $2 $3 writes: ");
+        assert_eq!(access("lpb $1,$4\nlpe"), "reads: $1 $4 ? writes: ");
+        assert_eq!(access("lpb $$1\nlpe"), "reads: $1 ? writes: ");
+        assert_eq!(access("lps $2\nlpe"), "reads: $2 writes: $2");
+    }
+
+    #[test]
+    fn test_10003_unofficial_function() {
+        assert_eq!(access("f21 $3,1104"), "reads: $3 $4 writes: $3");
+        assert_eq!(access("f12 $3,1000"), "reads: $3 writes: $3 $4");
+    }
+}
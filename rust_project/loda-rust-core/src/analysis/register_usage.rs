@@ -0,0 +1,110 @@
+use super::RegisterAccess;
+use crate::parser::{Instruction, InstructionId, ParameterType, ParsedProgram};
+use std::collections::BTreeSet;
+
+/// The registers that a program reads and writes, for the entire program.
+///
+/// Usecase:
+/// Inlining a program and the miner needs to know which registers are free to use.
+/// Analytics needs to know if a program uses indirect memory access.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegisterUsage {
+    pub reads: BTreeSet<u64>,
+    pub writes: BTreeSet<u64>,
+
+    /// The program uses indirect memory access, such as `$$1`.
+    pub indirect: bool,
+
+    /// The program has a loop with a range from a register, such as `lpb $0,$1`.
+    pub loop_with_range_from_register: bool,
+
+    highest_register: Option<u64>,
+}
+
+impl RegisterUsage {
+    pub fn analyze(program: &ParsedProgram) -> Self {
+        let mut usage = Self::default();
+        for instruction in &program.instruction_vec {
+            let access = RegisterAccess::from_instruction(instruction);
+            usage.indirect |= access.indirect;
+            usage.reads.extend(&access.reads);
+            usage.writes.extend(&access.writes);
+            let highest: Option<u64> = access.reads.iter().chain(&access.writes).max().copied();
+            usage.highest_register = usage.highest_register.max(highest).max(highest_loop_register(instruction));
+            if instruction.instruction_id == InstructionId::LoopBegin {
+                if let Some(parameter1) = instruction.parameter_vec.get(1) {
+                    usage.loop_with_range_from_register |= parameter1.parameter_type != ParameterType::Constant;
+                }
+            }
+        }
+        usage
+    }
+
+    /// The highest register that the program accesses, ignoring indirect memory access.
+    ///
+    /// A loop like `lpb $3,2` compares the registers `$3` and `$4`.
+    /// An unofficial function like `f21 $3,1234` reads `$3` and `$4`.
+    pub fn highest_register(&self) -> Option<u64> {
+        self.highest_register
+    }
+}
+
+/// The `RegisterAccess` of a loop with a long constant range, such as `lpb $3,200`, only has the first register.
+fn highest_loop_register(instruction: &Instruction) -> Option<u64> {
+    if instruction.instruction_id != InstructionId::LoopBegin {
+        return None;
+    }
+    let parameter0 = instruction.parameter_vec.first()?;
+    let parameter1 = instruction.parameter_vec.get(1)?;
+    if parameter0.parameter_type != ParameterType::Direct || parameter1.parameter_type != ParameterType::Constant {
+        return None;
+    }
+    let start: u64 = u64::try_from(parameter0.parameter_value).ok()?;
+    let length: u64 = u64::try_from(parameter1.parameter_value).ok()?;
+    Some(start + length.max(1) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(source: &str) -> RegisterUsage {
+        let program = ParsedProgram::parse_program(source).expect("program");
+        RegisterUsage::analyze(&program)
+    }
+
+    #[test]
+    fn test_10000_highest_register() {
+        assert_eq!(usage("").highest_register(), None);
+        assert_eq!(usage("mov $0,1").highest_register(), Some(0));
+        assert_eq!(usage("mov $4,$7\nadd $1,100").highest_register(), Some(7));
+        assert_eq!(usage("lpb $3,2\nlpe").highest_register(), Some(4));
+        assert_eq!(usage("lpb $3,200\nlpe").highest_register(), Some(202));
+        assert_eq!(usage("f21 $5,1234").highest_register(), Some(6));
+    }
+
+    #[test]
+    fn test_10001_indirect() {
+        assert_eq!(usage("seq $1,40 ; fibonacci\nseq $2,40\nseq $3,10").indirect, false);
+        assert_eq!(usage("mov $$1,5").indirect, true);
+        assert_eq!(usage("mov $1,$$1").indirect, true);
+        assert_eq!(usage("lpb $$1\nlpe").indirect, true);
+    }
+
+    #[test]
+    fn test_10002_loop_with_range_from_register() {
+        assert_eq!(usage("lpb $0\nlpe").loop_with_range_from_register, false);
+        assert_eq!(usage("lpb $0,5\nlpe").loop_with_range_from_register, false);
+        assert_eq!(usage("lpb $0,$1\nlpe").loop_with_range_from_register, true);
+    }
+
+    #[test]
+    fn test_10003_reads_writes() {
+        // Act
+        let usage: RegisterUsage = usage("mov $1,$0\nadd $1,$2\nmov $0,$1");
+
+        // Assert
+        assert_eq!(usage.reads, BTreeSet::from([0, 1, 2]));
+        assert_eq!(usage.writes, BTreeSet::from([0, 1]));
+    }
+}
@@ -8,8 +8,8 @@ use crate::execute::node_calc::NodeCalcSemanticMode;
 use crate::parser::{ParsedProgram, ParseProgramError, CreateProgram};
 use crate::execute::{Program, ProgramId, ProgramRunner, ProgramRunnerManager};
 use crate::execute::compiletime_error::*;
-use crate::unofficial_function::UnofficialFunctionRegistry;
-use super::ExecuteProfile;
+use crate::unofficial_function::{ScriptedFunctionManifest, UnofficialFunctionRegistry};
+use super::{ExecuteProfile, ProgramBundle};
 
 #[derive(Debug, PartialEq)]
 pub struct CyclicDependencyError {
@@ -121,6 +121,16 @@ impl DependencyManager {
         self.execute_profile = execute_profile;
     }
 
+    /// Register the unofficial functions that are implemented as LODA programs, listed in a manifest file.
+    ///
+    /// The `seq` instructions inside these programs are resolved using the `loda_programs_oeis_dir`.
+    ///
+    /// Returns the number of functions registered.
+    pub fn load_scripted_functions(&mut self, manifest_path: &Path) -> anyhow::Result<usize> {
+        let manifest = ScriptedFunctionManifest::load(manifest_path)?;
+        manifest.register(&self.unofficial_function_registry, &self.loda_programs_oeis_dir)
+    }
+
     pub fn reset(&mut self) {
         self.programid_dependencies.clear();
         self.programids_currently_loading.clear();
@@ -130,6 +140,19 @@ impl DependencyManager {
         self.virtual_filesystem.insert(program_id, file_content);
     }
 
+    /// Insert all the programs in the bundle into the virtual filesystem.
+    /// 
+    /// Existing files with the same program id are replaced.
+    pub fn virtual_filesystem_insert_bundle(&mut self, bundle: &ProgramBundle) {
+        for (program_id, file_content) in bundle.iter() {
+            self.virtual_filesystem.insert(*program_id, file_content.clone());
+        }
+    }
+
+    pub fn virtual_filesystem_get(&self, program_id: u64) -> Option<&String> {
+        self.virtual_filesystem.get(&program_id)
+    }
+
     pub fn virtual_filesystem_inspect_filenames(&self) -> String {
         let mut program_ids: Vec<u64> = self.virtual_filesystem.iter().map(|(key,_value)| *key).collect();
         program_ids.sort();
@@ -260,15 +283,25 @@ impl DependencyManager {
 
     /// Construct a path, like this: `/absolute/path/123/A123456.asm`
     pub fn path_to_program(&self, program_id: u64) -> PathBuf {
-        let dir_index: u64 = program_id / 1000;
-        let dir_index_string: String = format!("{:0>3}", dir_index);
+        Self::path_to_program_in_dir(&self.loda_programs_oeis_dir, program_id)
+    }
+
+    /// Same as `path_to_program()`, without creating a `DependencyManager`.
+    pub fn path_to_program_in_dir(loda_programs_oeis_dir: &Path, program_id: u64) -> PathBuf {
+        let dir_index_string: String = Self::dir_index_string(program_id);
         let filename_string: String = format!("A{:0>6}.asm", program_id);
         let dirname = Path::new(&dir_index_string);
         let filename = Path::new(&filename_string);
-        let pathbuf: PathBuf = self.loda_programs_oeis_dir.join(dirname).join(filename);
+        let pathbuf: PathBuf = loda_programs_oeis_dir.join(dirname).join(filename);
         pathbuf
     }
 
+    /// The name of the subdir that contains the program, like this: `123` for `A123456`.
+    pub fn dir_index_string(program_id: u64) -> String {
+        let dir_index: u64 = program_id / 1000;
+        format!("{:0>3}", dir_index)
+    }
+
     pub fn print_dependencies(&self) {
         let strings: Vec<String> = self.programid_dependencies.iter().map(|program_id| {
             program_id.to_string()
@@ -305,6 +338,8 @@ impl fmt::Debug for DependencyManager {
 mod tests {
     use super::*;
     use std::rc::Rc;
+    use std::time::Instant;
+    use crate::execute::{NodeLoopLimit, NodeRegisterLimit, ProgramCache, ProgramState, RegisterValue, RunMode};
 
     #[test]
     fn test_10000_parse_string() {
@@ -353,6 +388,17 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_10100_load_scripted_functions() {
+        let mut dm: DependencyManager = dependency_manager_mock("tests/scripted_functions");
+        let manifest_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/scripted_functions/functions/manifest.toml");
+        let count: usize = dm.load_scripted_functions(&manifest_path).expect("count");
+        assert_eq!(count, 3);
+        let runner: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, "mov $1,12
+f21 $0,5002").expect("ProgramRunner");
+        assert_eq!(runner.inspect(7), "4095,1,3,7,15,1,63");
+    }
+
     #[test]
     fn test_10101_load_simple1() {
         let mut dm: DependencyManager = dependency_manager_mock("tests/load_simple1");
@@ -552,4 +598,101 @@ mod tests {
         let runner: Rc::<ProgramRunner> = dm.load(9194).unwrap();
         assert_eq!(runner.inspect(10), "1,1,1,1,1,6,1,1,1,2");
     }
+
+    #[test]
+    fn test_80000_virtual_filesystem_insert_bundle() {
+        // Arrange
+        let mut bundle = ProgramBundle::new();
+        bundle.insert(45, "mov $3,1\nlpb $0\n  sub $0,1\n  mov $2,$1\n  add $1,$3\n  mov $3,$2\nlpe\nmov $0,$1".to_string());
+        bundle.insert(1, "seq $0,45\nmul $0,2".to_string());
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+
+        // Act
+        dm.virtual_filesystem_insert_bundle(&bundle);
+        let runner: Rc::<ProgramRunner> = dm.load(1).expect("ProgramRunner");
+
+        // Assert
+        assert_eq!(dm.virtual_filesystem_inspect_filenames(), "1,45");
+        assert_eq!(dm.virtual_filesystem_get(1).map(|s| s.as_str()), Some("seq $0,45\nmul $0,2"));
+        assert_eq!(runner.inspect(8), "0,2,2,4,6,10,16,26");
+    }
+
+    fn run_terms(runner: &ProgramRunner, term_count: u64) -> (Vec<RegisterValue>, u64) {
+        let mut cache = ProgramCache::new();
+        let mut step_count: u64 = 0;
+        let mut terms = Vec::<RegisterValue>::new();
+        for index in 0..term_count {
+            let input = RegisterValue::from_i64(index as i64);
+            let output: RegisterValue = runner.run(
+                input,
+                RunMode::Silent,
+                &mut step_count,
+                u64::MAX,
+                NodeRegisterLimit::Unlimited,
+                NodeLoopLimit::Unlimited,
+                &mut cache
+            ).expect("run");
+            terms.push(output);
+        }
+        (terms, step_count)
+    }
+
+    #[test]
+    fn test_80000_journal_same_as_clone_baseline() {
+        // Arrange
+        let mut dm: DependencyManager = dependency_manager_mock("tests/parametertype_indirect3");
+        let runner: Rc::<ProgramRunner> = dm.load(71325).unwrap();
+
+        // Act
+        let (terms_journal, step_count_journal) = run_terms(&runner, 30);
+        ProgramState::set_clone_baseline(true);
+        let (terms_clone, step_count_clone) = run_terms(&runner, 30);
+        ProgramState::set_clone_baseline(false);
+
+        // Assert
+        assert_eq!(terms_journal, terms_clone);
+        assert_eq!(step_count_journal, step_count_clone);
+    }
+
+    /// Compare the time it takes to evaluate programs with nested loops, with the journal,
+    /// and with the clone of all the registers, like the loops did before the journal was introduced.
+    ///
+    /// Run it like this:
+    /// ```text
+    /// cargo test --release -p loda-rust-core -- --ignored --nocapture test_90000_benchmark
+    /// ```
+    #[test]
+    #[ignore]
+    fn test_90000_benchmark_loop_programs() {
+        let items: [(&str, u64, u64); 4] = [
+            ("tests/parametertype_indirect4", 41, 150),
+            ("tests/parametertype_indirect4", 203, 3000),
+            ("tests/parametertype_indirect2", 2212, 300),
+            ("tests/parametertype_indirect3", 71325, 3000),
+        ];
+        for (relative_path, program_id, term_count) in items {
+            // Arrange
+            let mut dm: DependencyManager = dependency_manager_mock(relative_path);
+            let runner: Rc::<ProgramRunner> = dm.load(program_id).unwrap();
+
+            // Act
+            ProgramState::set_clone_baseline(true);
+            let start = Instant::now();
+            let (terms_clone, step_count) = run_terms(&runner, term_count);
+            let elapsed_clone = start.elapsed();
+            ProgramState::set_clone_baseline(false);
+
+            let start = Instant::now();
+            let (terms_journal, _) = run_terms(&runner, term_count);
+            let elapsed_journal = start.elapsed();
+
+            // Assert
+            assert_eq!(terms_clone, terms_journal);
+            println!("A{:0>6}  terms: {:>5}  steps: {:>12}  clone: {:>10?}  journal: {:>10?}", program_id, term_count, step_count, elapsed_clone, elapsed_journal);
+        }
+    }
 }
@@ -0,0 +1,188 @@
+use super::DependencyManager;
+use crate::execute::{ProgramCache, SharedProgramCache};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// The default number of values that each thread keeps in its own cache.
+const DEFAULT_CACHE_CAPACITY: usize = 1500;
+
+/// Process many items in parallel, such as evaluating all the programs in the `loda-programs` repository.
+///
+/// A `ProgramRunner` is not thread safe, so each thread has its own `DependencyManager` and its own `ProgramCache`.
+/// The threads can optionally share a `SharedProgramCache`, so the terms of popular programs are only computed once.
+pub struct EvaluationPool {
+    number_of_threads: NonZeroUsize,
+    cache_capacity: NonZeroUsize,
+    shared_cache: Option<SharedProgramCache>,
+}
+
+impl EvaluationPool {
+    pub fn new(number_of_threads: NonZeroUsize) -> Self {
+        Self {
+            number_of_threads,
+            cache_capacity: NonZeroUsize::new(DEFAULT_CACHE_CAPACITY).unwrap(),
+            shared_cache: None,
+        }
+    }
+
+    pub fn number_of_threads(&self) -> usize {
+        self.number_of_threads.get()
+    }
+
+    /// The capacity of the cache that each thread has.
+    pub fn set_cache_capacity(&mut self, cache_capacity: NonZeroUsize) {
+        self.cache_capacity = cache_capacity;
+    }
+
+    pub fn set_shared_cache(&mut self, shared_cache: SharedProgramCache) {
+        self.shared_cache = Some(shared_cache);
+    }
+
+    fn create_cache(&self) -> ProgramCache {
+        let mut cache = ProgramCache::with_capacity(self.cache_capacity);
+        if let Some(shared_cache) = &self.shared_cache {
+            cache.set_shared_cache(shared_cache.clone());
+        }
+        cache
+    }
+
+    /// Invoke the callback for each item, and return the results in the same order as the items.
+    ///
+    /// The `create_dependency_manager` closure is invoked once for each thread.
+    ///
+    /// The items are handed out one at a time, so a slow item doesn't hold back the other threads.
+    pub fn map<T, R, C, F>(&self, items: &[T], create_dependency_manager: C, callback: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        C: Fn() -> DependencyManager + Sync,
+        F: Fn(&mut DependencyManager, &mut ProgramCache, &T) -> R + Sync,
+    {
+        let number_of_threads: usize = self.number_of_threads.get().min(items.len()).max(1);
+        let next_index = AtomicUsize::new(0);
+        let mut indexed_results: Vec<(usize, R)> = Vec::with_capacity(items.len());
+        thread::scope(|scope| {
+            let mut handles = Vec::with_capacity(number_of_threads);
+            for _ in 0..number_of_threads {
+                let handle = scope.spawn(|| {
+                    let mut dependency_manager: DependencyManager = create_dependency_manager();
+                    let mut cache: ProgramCache = self.create_cache();
+                    let mut results = Vec::<(usize, R)>::new();
+                    loop {
+                        let index: usize = next_index.fetch_add(1, Ordering::Relaxed);
+                        let item: &T = match items.get(index) {
+                            Some(value) => value,
+                            None => break
+                        };
+                        let result: R = callback(&mut dependency_manager, &mut cache, item);
+                        results.push((index, result));
+                    }
+                    results
+                });
+                handles.push(handle);
+            }
+            for handle in handles {
+                match handle.join() {
+                    Ok(results) => indexed_results.extend(results),
+                    Err(error) => std::panic::resume_unwind(error)
+                }
+            }
+        });
+        indexed_results.sort_unstable_by_key(|(index, _)| *index);
+        indexed_results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::DependencyManagerFileSystemMode;
+    use crate::execute::ProgramRunner;
+    use crate::unofficial_function::UnofficialFunctionRegistry;
+    use crate::util::{BigIntVec, BigIntVecToString};
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    fn create_dependency_manager() -> DependencyManager {
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+        // a(n) = 2^n
+        dm.virtual_filesystem_insert_file(79, "mov $1,2\npow $1,$0\nmov $0,$1".to_string());
+        // a(n) = 2^n + 1
+        dm.virtual_filesystem_insert_file(51, "seq $0,79\nadd $0,1".to_string());
+        // a(n) = 2^n - 1
+        dm.virtual_filesystem_insert_file(225, "seq $0,79\nsub $0,1".to_string());
+        // a(n) = 1/n
+        dm.virtual_filesystem_insert_file(1000, "mov $1,1\ndiv $1,$0\nmov $0,$1".to_string());
+        dm
+    }
+
+    fn compute_terms(dm: &mut DependencyManager, cache: &mut ProgramCache, program_id: &u64) -> String {
+        let runner: Rc<ProgramRunner> = match dm.load(*program_id) {
+            Ok(value) => value,
+            Err(_) => {
+                return "LOAD ERROR".to_string();
+            }
+        };
+        let mut terms = BigIntVec::new();
+        for term in runner.eval_terms(0..5, cache) {
+            match term.value {
+                Ok(value) => terms.push(value),
+                Err(_) => {
+                    return "COMPUTE ERROR".to_string();
+                }
+            }
+        }
+        terms.to_compact_comma_string()
+    }
+
+    #[test]
+    fn test_10000_map_preserves_order() {
+        // Arrange
+        let pool = EvaluationPool::new(NonZeroUsize::new(3).unwrap());
+        let program_ids: Vec<u64> = vec![225, 51, 1000, 79, 999, 51];
+
+        // Act
+        let results: Vec<String> = pool.map(&program_ids, create_dependency_manager, compute_terms);
+
+        // Assert
+        let expected: Vec<&str> = vec![
+            "0,1,3,7,15",
+            "2,3,5,9,17",
+            "COMPUTE ERROR",
+            "1,2,4,8,16",
+            "LOAD ERROR",
+            "2,3,5,9,17",
+        ];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_10001_map_empty() {
+        let pool = EvaluationPool::new(NonZeroUsize::new(4).unwrap());
+        let program_ids: Vec<u64> = vec![];
+        let results: Vec<String> = pool.map(&program_ids, create_dependency_manager, compute_terms);
+        assert_eq!(results.is_empty(), true);
+    }
+
+    #[test]
+    fn test_20000_shared_cache() {
+        // Arrange
+        let shared_cache = SharedProgramCache::new(NonZeroUsize::new(100).unwrap());
+        let mut pool = EvaluationPool::new(NonZeroUsize::new(2).unwrap());
+        pool.set_shared_cache(shared_cache.clone());
+        let program_ids: Vec<u64> = vec![51, 225];
+
+        // Act
+        let results: Vec<String> = pool.map(&program_ids, create_dependency_manager, compute_terms);
+
+        // Assert
+        assert_eq!(results, vec!["2,3,5,9,17", "0,1,3,7,15"]);
+        // The terms of 79 are shared, together with the terms of 51 and 225.
+        assert_eq!(shared_cache.len(), 15);
+    }
+}
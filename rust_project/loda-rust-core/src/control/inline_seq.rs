@@ -0,0 +1,396 @@
+use crate::analysis::RegisterUsage;
+use crate::parser::{Instruction, InstructionId, InstructionParameter, InstructionSource, ParameterType, ParsedProgram};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Why a `seq` instruction was not inlined, or why a program cannot be inlined at all.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InlineSeqSkipReason {
+    /// The `seq` instruction has an indirect target, such as `seq $$1,45`.
+    IndirectTarget,
+
+    /// The program uses indirect memory access, so it may depend on registers that are not renamed.
+    UsesIndirectMemoryAccess,
+
+    /// The program has a loop with a range from a register, such as `lpb $0,$1`,
+    /// so the registers it compares are not known in advance.
+    UsesLoopWithRangeFromRegister,
+
+    /// The called program invokes the calling program, directly or indirectly.
+    CyclicDependency,
+
+    /// The called program could not be loaded.
+    LoadError { message: String },
+}
+
+impl fmt::Display for InlineSeqSkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IndirectTarget => write!(f, "the seq instruction has an indirect target"),
+            Self::UsesIndirectMemoryAccess => write!(f, "the program uses indirect memory access"),
+            Self::UsesLoopWithRangeFromRegister => write!(f, "the program has a loop with a range from a register"),
+            Self::CyclicDependency => write!(f, "cyclic dependency"),
+            Self::LoadError { message } => write!(f, "unable to load the program. {}", message),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlineSeqSkipped {
+    pub program_id: u64,
+    pub reason: InlineSeqSkipReason,
+}
+
+/// The outcome of `InlineSeq::inline`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InlineSeqResult {
+    Inlined(InlinedProgram),
+
+    /// The program itself cannot be transformed, since the inlined code may end up
+    /// in registers that the program accesses. No `seq` instructions are replaced.
+    NotInlinable { reason: InlineSeqSkipReason },
+}
+
+/// A program where the `seq` instructions have been replaced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlinedProgram {
+    pub program: ParsedProgram,
+
+    /// The programs that were inlined, including indirect dependencies, sorted and without duplicates.
+    pub inlined_program_ids: Vec<u64>,
+
+    /// The `seq` instructions that are still in the program.
+    pub skipped: Vec<InlineSeqSkipped>,
+}
+
+impl InlinedProgram {
+    /// True when the program no longer depends on other programs.
+    pub fn is_dependency_free(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+/// Replace `seq` instructions with the body of the called program.
+///
+/// The instruction `seq $3,45` evaluates program 45 with `$0` set to the value of `$3`,
+/// and all other registers set to zero. The `#offset` of program 45 is ignored.
+/// Finally `$3` is set to the value of `$0` of program 45.
+///
+/// The inlined version copies the input to an unused register, runs the body of the called program
+/// with the registers renamed to start at the unused register, and copies the output back.
+///
+/// ```text
+/// seq $3,45           mov $7,$3
+///               =>    ...body of A000045 with $0 renamed to $7, $1 renamed to $8, etc...
+///                     mov $3,$7
+/// ```
+///
+/// Each inlined call gets its own range of registers, above the highest register used by the caller.
+/// When the call is inside a loop, the registers are cleared before the body, since the called program
+/// assumes that all registers except `$0` are zero.
+///
+/// Limitations:
+///
+/// - Programs that use indirect memory access, such as `$$1`, or a loop with a range from a register,
+///   such as `lpb $0,$1`, are not transformed, since they can access any register.
+/// - The `seq` instruction fails when the input is below both `0` and the `#offset` of the called program.
+///   The inlined version doesn't fail.
+/// - The step count differs, since the `seq` instruction is counted as a step and its results are cached.
+pub struct InlineSeq<'a> {
+    load: &'a mut dyn FnMut(u64) -> anyhow::Result<ParsedProgram>,
+    inlined_program_ids: HashSet<u64>,
+    skipped: Vec<InlineSeqSkipped>,
+}
+
+impl<'a> InlineSeq<'a> {
+    /// Inline the called programs recursively.
+    ///
+    /// The `load` callback provides the called programs.
+    pub fn inline(program: &ParsedProgram, load: &'a mut dyn FnMut(u64) -> anyhow::Result<ParsedProgram>) -> InlineSeqResult {
+        if let Some(reason) = not_inlinable_reason(&RegisterUsage::analyze(program)) {
+            return InlineSeqResult::NotInlinable { reason };
+        }
+        let mut instance = Self {
+            load,
+            inlined_program_ids: HashSet::new(),
+            skipped: vec!(),
+        };
+        let mut call_stack: Vec<u64> = vec!();
+        let mut program_inlined: ParsedProgram = instance.inline_program(program, &mut call_stack);
+        program_inlined.optional_offset = program.optional_offset;
+        program_inlined.header_comments = program.header_comments.clone();
+        program_inlined.trailing_comments = program.trailing_comments.clone();
+        program_inlined.assign_zero_line_numbers();
+        let mut inlined_program_ids: Vec<u64> = instance.inlined_program_ids.into_iter().collect();
+        inlined_program_ids.sort();
+        let result = InlinedProgram {
+            program: program_inlined,
+            inlined_program_ids,
+            skipped: instance.skipped,
+        };
+        InlineSeqResult::Inlined(result)
+    }
+
+    fn inline_program(&mut self, program: &ParsedProgram, call_stack: &mut Vec<u64>) -> ParsedProgram {
+        let mut next_free_register: u64 = RegisterUsage::analyze(program).highest_register().map_or(0, |register| register + 1);
+        let mut loop_depth: usize = 0;
+        let mut instructions: Vec<Instruction> = vec!();
+        for instruction in &program.instruction_vec {
+            match instruction.instruction_id {
+                InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract => {
+                    loop_depth += 1;
+                },
+                InstructionId::LoopEnd => {
+                    loop_depth = loop_depth.saturating_sub(1);
+                },
+                _ => {}
+            }
+            let (target, program_id) = match seq_target_and_program_id(instruction) {
+                Some(value) => value,
+                None => {
+                    instructions.push(instruction.clone());
+                    continue;
+                }
+            };
+            let callee: ParsedProgram = match self.load_callee(target, program_id, call_stack) {
+                Ok(value) => value,
+                Err(reason) => {
+                    self.skipped.push(InlineSeqSkipped { program_id, reason });
+                    instructions.push(instruction.clone());
+                    continue;
+                }
+            };
+            self.inlined_program_ids.insert(program_id);
+
+            let base: u64 = next_free_register;
+            let callee_highest_register: u64 = RegisterUsage::analyze(&callee).highest_register().unwrap_or(0);
+            next_free_register = base + callee_highest_register + 1;
+
+            // The comments of the `seq` instruction are kept before the inlined code.
+            let mut first_instruction: Instruction = mov(base as i64, ParameterType::Direct, target.parameter_value);
+            first_instruction.source = instruction.source.as_ref().map(|source| {
+                let mut leading_comments: Vec<String> = source.leading_comments.clone();
+                leading_comments.extend(source.comment.clone());
+                InstructionSource {
+                    line: source.line.clone(),
+                    comment: None,
+                    leading_comments,
+                }
+            });
+            instructions.push(first_instruction);
+            if loop_depth > 0 {
+                for register in (base + 1)..=(base + callee_highest_register) {
+                    instructions.push(mov(register as i64, ParameterType::Constant, 0));
+                }
+            }
+            for callee_instruction in &callee.instruction_vec {
+                instructions.push(rename_registers(callee_instruction, base));
+            }
+            instructions.push(mov(target.parameter_value, ParameterType::Direct, base as i64));
+        }
+        let mut result = ParsedProgram::new();
+        result.instruction_vec = instructions;
+        result
+    }
+
+    /// Load the called program, and inline its own dependencies.
+    fn load_callee(&mut self, target: &InstructionParameter, program_id: u64, call_stack: &mut Vec<u64>) -> Result<ParsedProgram, InlineSeqSkipReason> {
+        if target.parameter_type != ParameterType::Direct {
+            return Err(InlineSeqSkipReason::IndirectTarget);
+        }
+        if call_stack.contains(&program_id) {
+            return Err(InlineSeqSkipReason::CyclicDependency);
+        }
+        let callee: ParsedProgram = (self.load)(program_id)
+            .map_err(|error| InlineSeqSkipReason::LoadError { message: format!("{:?}", error) })?;
+        if let Some(reason) = not_inlinable_reason(&RegisterUsage::analyze(&callee)) {
+            return Err(reason);
+        }
+        call_stack.push(program_id);
+        let callee_inlined: ParsedProgram = self.inline_program(&callee, call_stack);
+        call_stack.pop();
+        Ok(callee_inlined)
+    }
+}
+
+/// Extract the target and program id from an instruction like `seq $3,45`.
+fn seq_target_and_program_id(instruction: &Instruction) -> Option<(&InstructionParameter, u64)> {
+    if instruction.instruction_id != InstructionId::EvalSequence || instruction.parameter_vec.len() != 2 {
+        return None;
+    }
+    let parameter1: &InstructionParameter = &instruction.parameter_vec[1];
+    if parameter1.parameter_type != ParameterType::Constant || parameter1.parameter_value < 0 {
+        return None;
+    }
+    Some((&instruction.parameter_vec[0], parameter1.parameter_value as u64))
+}
+
+fn mov(target: i64, source_type: ParameterType, source_value: i64) -> Instruction {
+    Instruction {
+        instruction_id: InstructionId::Move,
+        parameter_vec: vec![
+            InstructionParameter::new(ParameterType::Direct, target),
+            InstructionParameter::new(source_type, source_value),
+        ],
+        line_number: 0,
+        source: None,
+    }
+}
+
+/// Add `base` to all the registers. The constants are left unchanged.
+///
+/// The comments of the called program are not kept, since they refer to its own registers.
+fn rename_registers(instruction: &Instruction, base: u64) -> Instruction {
+    let mut instruction: Instruction = instruction.clone();
+    instruction.source = None;
+    for parameter in instruction.parameter_vec.iter_mut() {
+        if parameter.parameter_type == ParameterType::Direct {
+            parameter.parameter_value += base as i64;
+        }
+    }
+    instruction
+}
+
+/// The registers of a program with indirect memory access, or a loop with a range from a register, cannot be renamed.
+fn not_inlinable_reason(usage: &RegisterUsage) -> Option<InlineSeqSkipReason> {
+    if usage.indirect {
+        return Some(InlineSeqSkipReason::UsesIndirectMemoryAccess);
+    }
+    if usage.loop_with_range_from_register {
+        return Some(InlineSeqSkipReason::UsesLoopWithRangeFromRegister);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{DependencyManager, DependencyManagerFileSystemMode};
+    use crate::execute::{ProgramId, ProgramRunner};
+    use crate::unofficial_function::UnofficialFunctionRegistry;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn programs() -> HashMap<u64, &'static str> {
+        let mut programs = HashMap::<u64, &'static str>::new();
+        // A000045: Fibonacci numbers
+        programs.insert(45, "mov $3,1\nlpb $0\n  sub $0,1\n  mov $2,$1\n  add $1,$3\n  mov $3,$2\nlpe\nmov $0,$1");
+        // A005843: The nonnegative even numbers
+        programs.insert(5843, "#offset 0\nmul $0,2");
+        // A000290: The squares, calls A005843 inside a loop
+        programs.insert(290, "mov $1,$0\nlpb $1\n  mov $3,$1\n  seq $3,5843\n  add $2,$3\n  sub $2,1\n  sub $1,1\nlpe\nmov $0,$2");
+        // Uses indirect memory access
+        programs.insert(1, "mov $1,5\nmov $$1,$0\nmov $0,$5");
+        // Cyclic dependency
+        programs.insert(2, "seq $0,3");
+        programs.insert(3, "seq $0,2");
+        programs
+    }
+
+    fn inline(source: &str) -> InlinedProgram {
+        let programs: HashMap<u64, &'static str> = programs();
+        let mut load = |program_id: u64| -> anyhow::Result<ParsedProgram> {
+            let source: &str = programs.get(&program_id).ok_or_else(|| anyhow::anyhow!("no program {}", program_id))?;
+            ParsedProgram::parse_program(source).map_err(|error| anyhow::anyhow!("{:?}", error))
+        };
+        let program: ParsedProgram = ParsedProgram::parse_program(source).expect("program");
+        match InlineSeq::inline(&program, &mut load) {
+            InlineSeqResult::Inlined(value) => value,
+            InlineSeqResult::NotInlinable { reason } => panic!("not inlinable: {}", reason),
+        }
+    }
+
+    fn terms(program: &ParsedProgram) -> String {
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+        for (program_id, source) in programs() {
+            dm.virtual_filesystem_insert_file(program_id, source.to_string());
+        }
+        let runner: ProgramRunner = dm.parse_stage2(ProgramId::ProgramWithoutId, program).expect("runner");
+        runner.inspect(10)
+    }
+
+    #[test]
+    fn test_10000_inline_simple() {
+        // Act
+        let result: InlinedProgram = inline("mov $1,$0\nseq $1,45\nadd $0,$1");
+
+        // Assert
+        let expected = "mov $1,$0\nmov $2,$1\nmov $5,1\nlpb $2\nsub $2,1\nmov $4,$3\nadd $3,$5\nmov $5,$4\nlpe\nmov $2,$3\nmov $1,$2\nadd $0,$1";
+        assert_eq!(result.program.to_string(), expected);
+        assert_eq!(result.inlined_program_ids, vec![45]);
+        assert_eq!(result.is_dependency_free(), true);
+    }
+
+    #[test]
+    fn test_10001_inline_inside_loop_clears_registers() {
+        // Arrange
+        let source = "mov $1,$0\nmov $2,0\nlpb $1\nmov $3,$1\nseq $3,45\nadd $2,$3\nsub $1,1\nlpe\nmov $0,$2";
+        let original: ParsedProgram = ParsedProgram::parse_program(source).expect("program");
+
+        // Act
+        let result: InlinedProgram = inline(source);
+
+        // Assert
+        assert_eq!(result.program.to_string().contains("mov $5,0\nmov $6,0\nmov $7,0\n"), true);
+        assert_eq!(terms(&result.program), terms(&original));
+        assert_eq!(terms(&result.program), "0,1,2,4,7,12,20,33,54,88");
+    }
+
+    #[test]
+    fn test_10002_inline_recursively_and_keep_offset() {
+        // Arrange
+        let source = "#offset 1\nsub $0,1\nseq $0,290\nseq $0,5843";
+        let original: ParsedProgram = ParsedProgram::parse_program(source).expect("program");
+
+        // Act
+        let result: InlinedProgram = inline(source);
+
+        // Assert
+        assert_eq!(result.program.optional_offset, Some(1));
+        assert_eq!(result.inlined_program_ids, vec![290, 5843]);
+        assert_eq!(result.is_dependency_free(), true);
+        assert_eq!(result.program.to_string().contains("seq"), false);
+        assert_eq!(terms(&result.program), terms(&original));
+    }
+
+    #[test]
+    fn test_20000_skip_callee_with_indirect_memory_access() {
+        let result: InlinedProgram = inline("seq $0,1\nseq $0,45");
+        assert_eq!(result.skipped, vec![InlineSeqSkipped { program_id: 1, reason: InlineSeqSkipReason::UsesIndirectMemoryAccess }]);
+        assert_eq!(result.inlined_program_ids, vec![45]);
+        assert_eq!(result.program.instruction_vec[0].to_string(), "seq $0,1");
+    }
+
+    #[test]
+    fn test_20001_skip_cyclic_dependency_and_missing_program() {
+        // Act
+        let result: InlinedProgram = inline("seq $0,2\nseq $0,999");
+
+        // Assert
+        assert_eq!(result.inlined_program_ids, vec![2, 3]);
+        assert_eq!(result.skipped.len(), 2);
+        assert_eq!(result.skipped[0], InlineSeqSkipped { program_id: 2, reason: InlineSeqSkipReason::CyclicDependency });
+        assert_eq!(result.skipped[1].program_id, 999);
+        assert_eq!(result.is_dependency_free(), false);
+    }
+
+    #[test]
+    fn test_20002_caller_with_indirect_memory_access() {
+        let program: ParsedProgram = ParsedProgram::parse_program("mov $$0,1\nseq $0,45").expect("program");
+        let mut load = |_program_id: u64| -> anyhow::Result<ParsedProgram> { Ok(ParsedProgram::new()) };
+        let expected = InlineSeqResult::NotInlinable { reason: InlineSeqSkipReason::UsesIndirectMemoryAccess };
+        assert_eq!(InlineSeq::inline(&program, &mut load), expected);
+    }
+
+    #[test]
+    fn test_20003_caller_with_loop_with_range_from_register() {
+        let program: ParsedProgram = ParsedProgram::parse_program("mov $1,2\nlpb $0,$1\n  seq $0,45\n  sub $0,1\nlpe").expect("program");
+        let mut load = |_program_id: u64| -> anyhow::Result<ParsedProgram> { Ok(ParsedProgram::new()) };
+        let expected = InlineSeqResult::NotInlinable { reason: InlineSeqSkipReason::UsesLoopWithRangeFromRegister };
+        assert_eq!(InlineSeq::inline(&program, &mut load), expected);
+    }
+}
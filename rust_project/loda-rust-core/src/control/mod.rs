@@ -1,6 +1,12 @@
-//! Loading of programs, manage dependencies between programs, caching of programs.
+//! Loading of programs, manage dependencies between programs, caching of programs, inlining of programs, evaluating programs in parallel.
 mod dependency_manager;
+mod evaluation_pool;
 mod execute_profile;
+mod inline_seq;
+mod program_bundle;
 
 pub use dependency_manager::{DependencyManager, DependencyManagerError, DependencyManagerFileSystemMode};
+pub use evaluation_pool::EvaluationPool;
 pub use execute_profile::ExecuteProfile;
+pub use inline_seq::{InlineSeq, InlineSeqResult, InlineSeqSkipped, InlineSeqSkipReason, InlinedProgram};
+pub use program_bundle::ProgramBundle;
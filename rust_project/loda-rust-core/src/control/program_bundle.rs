@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use anyhow::Context;
+
+/// The first line of a bundle file.
+const HEADER_LINE: &str = "; LODA program bundle, version 1";
+
+/// The line that precedes every program in a bundle file, followed by the program id.
+const PROGRAM_PREFIX: &str = "#program ";
+
+/// Many programs in a single file, so that they can be loaded without accessing the `loda-programs` repo.
+///
+/// Usecase:
+/// The browser has no access to the file system, and fetching one dependency at a time is slow.
+/// A bundle with the programs and all their dependencies can be loaded into
+/// the `DependencyManagerFileSystemMode::Virtual` filesystem with a single request.
+///
+/// The bundle is a text file, with this format:
+///
+/// ```text
+/// ; LODA program bundle, version 1
+/// #program 40
+/// ; A000040: The prime numbers.
+/// ...
+/// #program 45
+/// ; A000045: Fibonacci numbers.
+/// ...
+/// ```
+///
+/// The programs are sorted by program id, so that the file is the same every time it's generated.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProgramBundle {
+    programs: BTreeMap<u64, String>,
+}
+
+impl ProgramBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, program_id: u64, source_code: String) {
+        self.programs.insert(program_id, source_code);
+    }
+
+    pub fn get(&self, program_id: u64) -> Option<&String> {
+        self.programs.get(&program_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// The program ids in ascending order.
+    pub fn program_ids(&self) -> Vec<u64> {
+        self.programs.keys().copied().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&u64, &String)> {
+        self.programs.iter()
+    }
+
+    pub fn to_bundle_string(&self) -> String {
+        let mut result = String::new();
+        result.push_str(HEADER_LINE);
+        result.push('\n');
+        for (program_id, source_code) in &self.programs {
+            result.push_str(PROGRAM_PREFIX);
+            result.push_str(&program_id.to_string());
+            result.push('\n');
+            let trimmed: &str = source_code.trim_end();
+            if !trimmed.is_empty() {
+                result.push_str(trimmed);
+                result.push('\n');
+            }
+        }
+        result
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut lines = content.lines().enumerate();
+        match lines.next() {
+            Some((_, line)) if line.trim_end() == HEADER_LINE => {},
+            _ => {
+                return Err(anyhow::anyhow!("Not a program bundle. Expected the first line to be {:?}", HEADER_LINE));
+            }
+        }
+        let mut bundle = Self::new();
+        let mut current: Option<(u64, Vec<&str>)> = None;
+        for (index, line) in lines {
+            if let Some(program_id_raw) = line.strip_prefix(PROGRAM_PREFIX) {
+                let program_id: u64 = program_id_raw.trim().parse()
+                    .with_context(|| format!("Invalid program id in line {}", index + 1))?;
+                if let Some((program_id, rows)) = current.take() {
+                    bundle.insert(program_id, rows.join("\n"));
+                }
+                if bundle.programs.contains_key(&program_id) {
+                    return Err(anyhow::anyhow!("Duplicate program id {} in line {}", program_id, index + 1));
+                }
+                current = Some((program_id, vec!()));
+                continue;
+            }
+            match current.as_mut() {
+                Some((_, rows)) => rows.push(line),
+                None => {
+                    if !line.trim().is_empty() {
+                        return Err(anyhow::anyhow!("Expected {:?} in line {}", PROGRAM_PREFIX.trim_end(), index + 1));
+                    }
+                }
+            }
+        }
+        if let Some((program_id, rows)) = current.take() {
+            bundle.insert(program_id, rows.join("\n"));
+        }
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_roundtrip() {
+        // Arrange
+        let mut bundle = ProgramBundle::new();
+        bundle.insert(45, "; A000045: Fibonacci numbers.\n#offset 0\nmov $3,1\nlpb $0\n  sub $0,1\nlpe\n".to_string());
+        bundle.insert(40, "seq $0,45\n".to_string());
+
+        // Act
+        let content: String = bundle.to_bundle_string();
+        let bundle2: ProgramBundle = ProgramBundle::parse(&content).expect("bundle");
+
+        // Assert
+        let expected = "; LODA program bundle, version 1\n#program 40\nseq $0,45\n#program 45\n; A000045: Fibonacci numbers.\n#offset 0\nmov $3,1\nlpb $0\n  sub $0,1\nlpe\n";
+        assert_eq!(content, expected);
+        assert_eq!(bundle2.program_ids(), vec![40, 45]);
+        assert_eq!(bundle2.get(40).expect("program"), "seq $0,45");
+        assert_eq!(bundle2.get(45).expect("program"), "; A000045: Fibonacci numbers.\n#offset 0\nmov $3,1\nlpb $0\n  sub $0,1\nlpe");
+    }
+
+    #[test]
+    fn test_20000_parse_error() {
+        assert_eq!(ProgramBundle::parse("").is_err(), true);
+        assert_eq!(ProgramBundle::parse("mov $0,1").is_err(), true);
+        assert_eq!(ProgramBundle::parse("; LODA program bundle, version 1\nmov $0,1").is_err(), true);
+        assert_eq!(ProgramBundle::parse("; LODA program bundle, version 1\n#program x\n").is_err(), true);
+        assert_eq!(ProgramBundle::parse("; LODA program bundle, version 1\n#program 5\n#program 5\n").is_err(), true);
+    }
+
+    #[test]
+    fn test_20001_parse_empty_bundle() {
+        let bundle: ProgramBundle = ProgramBundle::parse("; LODA program bundle, version 1\n\n").expect("bundle");
+        assert_eq!(bundle.is_empty(), true);
+    }
+}
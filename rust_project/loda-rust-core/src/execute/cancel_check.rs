@@ -0,0 +1,42 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Decides if an evaluation should stop early, such as when a time limit is exceeded,
+/// or when the user presses stop in the editor.
+///
+/// The check is polled from inside the step counting, every `INTERVAL` steps,
+/// so a single slow term can be stopped, not only in between terms.
+/// When it returns `true`, then the evaluation fails with `EvalError::Cancelled`.
+#[derive(Clone)]
+pub struct CancelCheck {
+    callback: Arc<dyn Fn() -> bool + Send + Sync>,
+}
+
+impl CancelCheck {
+    /// The number of steps between each time the callback is invoked.
+    pub const INTERVAL: u64 = 1024;
+
+    pub fn new<F>(callback: F) -> Self
+        where F: Fn() -> bool + Send + Sync + 'static
+    {
+        Self {
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Cancel when the deadline has passed.
+    pub fn deadline(deadline: Instant) -> Self {
+        Self::new(move || Instant::now() >= deadline)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        (self.callback)()
+    }
+}
+
+impl fmt::Debug for CancelCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CancelCheck")
+    }
+}
@@ -45,6 +45,9 @@ pub enum EvalError {
     /// Using way too many cpu cycles
     StepCountExceededLimit,
 
+    /// The `CancelCheck` decided to stop the evaluation, such as when the time limit is exceeded
+    Cancelled,
+
     // When attempting to use a constant as the first parameter for an instruction
     CannotGetAddressOfConstant,
     CannotSetValueOfConstant,
@@ -90,6 +93,8 @@ impl fmt::Display for EvalError {
                 write!(f, "Loop count exceeded limit, stuck in a loop that takes way too long time to compute"),
             Self::StepCountExceededLimit => 
                 write!(f, "Step count exceeded limit, using way too many cpu cycles"),
+            Self::Cancelled => 
+                write!(f, "Cancelled"),
             Self::CannotGetAddressOfConstant => 
                 write!(f, "Cannot get address of a constant"),
             Self::CannotSetValueOfConstant => 
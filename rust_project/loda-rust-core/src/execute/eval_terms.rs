@@ -0,0 +1,260 @@
+use super::{CancelCheck, NodeLoopLimit, NodeRegisterLimit, ProgramCache, ProgramRunner, ProgramState, RegisterValue, RunMode};
+use num_bigint::BigInt;
+use std::ops::Range;
+
+/// The default number of steps that a single term may use.
+const DEFAULT_STEP_COUNT_LIMIT: u64 = 1000000000;
+
+/// The outcome of evaluating the program for a single index.
+#[derive(Debug)]
+pub struct TermResult {
+    /// The index in OEIS coordinates, so with `#offset 1` the first term is at index `1`.
+    pub index: i64,
+
+    /// The term, or the reason why the term could not be computed.
+    pub value: anyhow::Result<BigInt>,
+
+    /// The number of steps used for computing this term.
+    pub step_count: u64,
+}
+
+/// Lazily evaluate the terms for a range of indexes, in OEIS coordinates.
+///
+/// Each term is evaluated when the iterator is advanced, so the caller can stop early.
+/// An error for one index doesn't stop the iterator, the following indexes are still evaluated.
+///
+/// The step count limit applies to each term individually.
+pub struct EvalTerms<'a> {
+    runner: &'a ProgramRunner,
+    cache: &'a mut ProgramCache,
+    indexes: Range<i64>,
+    run_mode: RunMode,
+    step_count_limit: u64,
+    node_register_limit: NodeRegisterLimit,
+    node_loop_limit: NodeLoopLimit,
+    cancel_check: Option<CancelCheck>,
+}
+
+impl<'a> EvalTerms<'a> {
+    pub fn new(runner: &'a ProgramRunner, indexes: Range<i64>, cache: &'a mut ProgramCache) -> Self {
+        Self {
+            runner,
+            cache,
+            indexes,
+            run_mode: RunMode::Silent,
+            step_count_limit: DEFAULT_STEP_COUNT_LIMIT,
+            node_register_limit: NodeRegisterLimit::Unlimited,
+            node_loop_limit: NodeLoopLimit::Unlimited,
+            cancel_check: None,
+        }
+    }
+
+    pub fn run_mode(mut self, run_mode: RunMode) -> Self {
+        self.run_mode = run_mode;
+        self
+    }
+
+    pub fn step_count_limit(mut self, step_count_limit: u64) -> Self {
+        self.step_count_limit = step_count_limit;
+        self
+    }
+
+    pub fn node_register_limit(mut self, node_register_limit: NodeRegisterLimit) -> Self {
+        self.node_register_limit = node_register_limit;
+        self
+    }
+
+    pub fn node_loop_limit(mut self, node_loop_limit: NodeLoopLimit) -> Self {
+        self.node_loop_limit = node_loop_limit;
+        self
+    }
+
+    /// Stop evaluating a term, when the `cancel_check` says so.
+    pub fn cancel_check(mut self, cancel_check: CancelCheck) -> Self {
+        self.cancel_check = Some(cancel_check);
+        self
+    }
+}
+
+impl<'a> Iterator for EvalTerms<'a> {
+    type Item = TermResult;
+
+    fn next(&mut self) -> Option<TermResult> {
+        let index: i64 = self.indexes.next()?;
+        let mut step_count: u64 = 0;
+        let mut state = ProgramState::new(
+            self.run_mode,
+            self.step_count_limit,
+            self.node_register_limit.clone(),
+            self.node_loop_limit.clone(),
+        );
+        state.set_cancel_check(self.cancel_check.clone());
+        let value: anyhow::Result<BigInt> = self.runner.run_without_offset_with_state(
+            RegisterValue::from_i64(index),
+            state,
+            &mut step_count,
+            self.cache,
+        ).map(|output| output.0);
+        Some(TermResult { index, value, step_count })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indexes.size_hint()
+    }
+}
+
+impl ProgramRunner {
+    /// The index of the first term in OEIS coordinates. This is the `#offset`, or `0` when there is no `#offset`.
+    pub fn first_index(&self) -> i64 {
+        self.program().offset().unwrap_or(0) as i64
+    }
+
+    /// The indexes of the first `count` terms in OEIS coordinates, starting at the `#offset`.
+    pub fn indexes_from_offset(&self, count: u64) -> Range<i64> {
+        let first_index: i64 = self.first_index();
+        let count: i64 = i64::try_from(count).unwrap_or(i64::MAX);
+        first_index..first_index.saturating_add(count)
+    }
+
+    /// Evaluate the terms for a range of indexes in OEIS coordinates, such as `-1..10`.
+    ///
+    /// Unlike `run()`, the indexes are not adjusted by the `#offset`.
+    pub fn eval_terms<'a>(&'a self, indexes: Range<i64>, cache: &'a mut ProgramCache) -> EvalTerms<'a> {
+        EvalTerms::new(self, indexes, cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{DependencyManager, DependencyManagerFileSystemMode};
+    use crate::execute::{EvalError, ProgramId};
+    use crate::unofficial_function::UnofficialFunctionRegistry;
+    use std::path::PathBuf;
+
+    fn runner(source: &str) -> ProgramRunner {
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            UnofficialFunctionRegistry::new(),
+        );
+        // a(n) = n*10, for n >= -2
+        dm.virtual_filesystem_insert_file(1000, "#offset -2\nmul $0,10".to_string());
+        // a(n) = n+1, for n >= 0
+        dm.virtual_filesystem_insert_file(1001, "add $0,1".to_string());
+        // a(n) = 0, using around 2*n steps
+        dm.virtual_filesystem_insert_file(1002, "lpb $0\n  sub $0,1\nlpe".to_string());
+        dm.parse(ProgramId::ProgramWithoutId, source).expect("runner")
+    }
+
+    fn format_terms(runner: &ProgramRunner, indexes: Range<i64>) -> String {
+        let mut cache = ProgramCache::new();
+        let items: Vec<String> = runner.eval_terms(indexes, &mut cache).map(|term| {
+            match term.value {
+                Ok(value) => format!("a({})={}", term.index, value),
+                Err(_) => format!("a({})=BOOM", term.index),
+            }
+        }).collect();
+        items.join(" ")
+    }
+
+    #[test]
+    fn test_10000_indexes_from_offset() {
+        assert_eq!(runner("mul $0,2").indexes_from_offset(3), 0..3);
+        assert_eq!(runner("#offset 1\nmul $0,2").indexes_from_offset(3), 1..4);
+        assert_eq!(runner("#offset -3\nmul $0,2").indexes_from_offset(2), -3..-1);
+    }
+
+    #[test]
+    fn test_10001_offset_coordinates() {
+        let runner: ProgramRunner = runner("#offset 1\nmul $0,2");
+        assert_eq!(format_terms(&runner, runner.indexes_from_offset(3)), "a(1)=2 a(2)=4 a(3)=6");
+        assert_eq!(format_terms(&runner, -1..1), "a(-1)=-2 a(0)=0");
+    }
+
+    #[test]
+    fn test_10002_error_doesnt_stop_the_iterator() {
+        let runner: ProgramRunner = runner("mov $1,1\ndiv $1,$0\nmov $0,$1");
+        assert_eq!(format_terms(&runner, -1..3), "a(-1)=-1 a(0)=BOOM a(1)=1 a(2)=0");
+    }
+
+    #[test]
+    fn test_10003_stop_early() {
+        // Arrange
+        let runner: ProgramRunner = runner("mul $0,3");
+        let mut cache = ProgramCache::new();
+
+        // Act
+        let first_large_term: Option<TermResult> = runner
+            .eval_terms(0..i64::MAX, &mut cache)
+            .find(|term| term.value.as_ref().is_ok_and(|value| *value > BigInt::from(100)));
+
+        // Assert
+        assert_eq!(first_large_term.map(|term| term.index), Some(34));
+    }
+
+    #[test]
+    fn test_10004_step_count_per_term() {
+        // Arrange
+        let runner: ProgramRunner = runner("lpb $0\n  sub $0,1\nlpe");
+        let mut cache = ProgramCache::new();
+
+        // Act
+        let results: Vec<TermResult> = runner.eval_terms(0..5, &mut cache).step_count_limit(6).collect();
+
+        // Assert
+        let step_counts: Vec<u64> = results.iter().map(|term| term.step_count).collect();
+        let is_ok: Vec<bool> = results.iter().map(|term| term.value.is_ok()).collect();
+        assert_eq!(is_ok, vec![true, true, true, true, false]);
+        assert_eq!(step_counts[0] < step_counts[1], true);
+    }
+
+    #[test]
+    fn test_10005_cancel_check() {
+        // Arrange
+        let runner: ProgramRunner = runner("seq $0,1002");
+        let mut cache = ProgramCache::new();
+        let cancel_check = CancelCheck::new(|| true);
+
+        // Act
+        let results: Vec<TermResult> = runner.eval_terms(0..1, &mut cache).cancel_check(cancel_check.clone())
+            .chain(runner.eval_terms(5000..5001, &mut ProgramCache::new()).cancel_check(cancel_check))
+            .collect();
+
+        // Assert
+        assert_eq!(results[0].value.as_ref().expect("value"), &BigInt::from(0));
+        let error: &anyhow::Error = results[1].value.as_ref().expect_err("error");
+        let eval_error: Option<&EvalError> = error.root_cause().downcast_ref::<EvalError>();
+        assert_eq!(matches!(eval_error, Some(EvalError::Cancelled)), true);
+        assert_eq!(results[1].step_count < 2 * CancelCheck::INTERVAL, true);
+    }
+
+    #[test]
+    fn test_20000_negative_offset() {
+        let runner: ProgramRunner = runner("#offset -2\nmul $0,10");
+        assert_eq!(format_terms(&runner, runner.indexes_from_offset(4)), "a(-2)=-20 a(-1)=-10 a(0)=0 a(1)=10");
+    }
+
+    #[test]
+    fn test_20001_seq_with_negative_offset() {
+        // The called program has a negative `#offset`, but `seq` rejects negative parameters like loda-cpp.
+        let runner: ProgramRunner = runner("#offset -2\nseq $0,1000");
+        assert_eq!(format_terms(&runner, runner.indexes_from_offset(4)), "a(-2)=BOOM a(-1)=BOOM a(0)=0 a(1)=10");
+    }
+
+    #[test]
+    fn test_20002_seq_with_negative_parameter() {
+        // Arrange
+        let runner: ProgramRunner = runner("#offset -1\nseq $0,1001");
+        let mut cache = ProgramCache::new();
+
+        // Act
+        let results: Vec<TermResult> = runner.eval_terms(-1..1, &mut cache).collect();
+
+        // Assert
+        let error: &anyhow::Error = results[0].value.as_ref().expect_err("error");
+        let eval_error: Option<&EvalError> = error.root_cause().downcast_ref::<EvalError>();
+        assert_eq!(matches!(eval_error, Some(EvalError::EvalSequenceWithNegativeParameter)), true);
+        assert_eq!(results[1].value.as_ref().expect("value"), &BigInt::from(1));
+    }
+}
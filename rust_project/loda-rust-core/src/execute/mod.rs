@@ -1,6 +1,8 @@
 //! Instruction execution.
+mod cancel_check;
 mod check_value;
 mod eval_error;
+mod eval_terms;
 mod node;
 mod node_loop_shared;
 mod node_register_limit;
@@ -23,18 +25,21 @@ mod semantics;
 mod semantic_binomial;
 mod semantic_power;
 mod semantic_simple;
+mod step_trace;
 
 use check_value::*;
+pub use cancel_check::CancelCheck;
 pub use program::Program;
 pub use program_id::ProgramId;
 pub use program_runner::ProgramRunner;
 pub use program_runner_manager::ProgramRunnerManager;
 pub use program_state::ProgramState;
-pub use program_cache::{CacheValue, ProgramCache};
+pub use program_cache::{CacheValue, ProgramCache, SharedProgramCache};
 pub use program_serializer::ProgramSerializer;
 pub use program_serializer_context::ProgramSerializerContext;
 pub use run_mode::RunMode;
 pub use eval_error::{EvalError, ValidateCallError};
+pub use eval_terms::{EvalTerms, TermResult};
 pub use node::{BoxNode, Node};
 pub use node_loop_shared::{NodeLoopLimit, LOOP_RANGE_MAX_BITS};
 pub use node_register_limit::NodeRegisterLimit;
@@ -48,6 +53,7 @@ pub use semantics::{Semantics, SemanticsWithoutLimits, SemanticsWithSmallLimits}
 pub use semantic_binomial::{SemanticBinomialConfig, SemanticBinomialError};
 pub use semantic_power::{SemanticPowerConfig, SemanticPowerError};
 pub use semantic_simple::{SemanticSimpleConfig, SemanticSimpleError};
+pub use step_trace::{StepTrace, StepTraceItem};
 
 pub mod compiletime_error;
 pub mod node_calc;
@@ -50,12 +50,13 @@ impl Node for NodeLoopConstant {
         let limit: NodeLoopLimit = state.node_loop_limit().clone();
         let mut cycles = 0;
         loop {
-            let old_state: ProgramState = state.clone();
+            // Only the registers that are modified by the loop body, are saved.
+            state.journal_begin();
 
             self.program.run(state, cache)?;
 
-            let is_less: bool = state.is_less_range(
-                &old_state, 
+            let is_less: bool = state.is_less_than_journal_frame_range(
+                self.register_start.0, 
                 self.register_start.0, 
                 self.range_length
             );
@@ -67,11 +68,10 @@ impl Node for NodeLoopConstant {
                 }
 
                 // When the loop reaches its end, the previous state is restored.
-                let mut new_state: ProgramState = old_state.clone();
-                new_state.set_step_count(state.step_count());
-                *state = new_state;
+                state.journal_rollback();
                 break;
             }
+            state.journal_commit();
 
             // Prevent looping for too long
             match limit {
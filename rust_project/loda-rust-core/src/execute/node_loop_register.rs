@@ -69,7 +69,8 @@ impl Node for NodeLoopRegister {
         let limit: NodeLoopLimit = state.node_loop_limit().clone();
         let mut cycles = 0;
         loop {
-            let old_state: ProgramState = state.clone();
+            // Only the registers that are modified by the loop body, are saved.
+            state.journal_begin();
 
             self.program.run(state, cache)?;
 
@@ -103,8 +104,8 @@ impl Node for NodeLoopRegister {
                 currently_smallest_range_length
             );
 
-            let is_less: bool = state.is_less_range(
-                &old_state, 
+            let is_less: bool = state.is_less_than_journal_frame_range(
+                self.register_start.0,
                 self.register_start.0,
                 currently_smallest_range_length
             );
@@ -116,16 +117,20 @@ impl Node for NodeLoopRegister {
 
                 if state.run_mode() == RunMode::Verbose {
                     let before = state.memory_full_to_string();
-                    let after = old_state.memory_full_to_string();
+                    let after = state.memory_full_to_string_before_journal_frame();
                     println!("{:12} {} => {}  break", "lpe", before, after);
                 }
 
                 // When the loop reaches its end, the previous state is restored.
-                let mut new_state: ProgramState = old_state.clone();
-                new_state.set_step_count(state.step_count());
-                *state = new_state;
+                state.journal_rollback();
                 break;
             }
+            if state.run_mode() == RunMode::Verbose {
+                let before = state.memory_full_to_string();
+                let after = state.memory_full_to_string_before_journal_frame();
+                println!("{:12} {} => {}  continue", "lpe", before, after);
+            }
+            state.journal_commit();
 
             // Prevent looping for too long
             match limit {
@@ -138,11 +143,6 @@ impl Node for NodeLoopRegister {
                     }
                 }
             }
-            if state.run_mode() == RunMode::Verbose {
-                let before = state.memory_full_to_string();
-                let after = old_state.memory_full_to_string();
-                println!("{:12} {} => {}  continue", "lpe", before, after);
-            }
         }
 
         state.increment_step_count()?;
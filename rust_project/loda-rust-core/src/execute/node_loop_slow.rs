@@ -57,7 +57,8 @@ impl Node for NodeLoopSlow {
         let limit: NodeLoopLimit = state.node_loop_limit().clone();
         let mut cycles = 0;
         loop {
-            let old_state: ProgramState = state.clone();
+            // Only the registers that are modified by the loop body, are saved.
+            state.journal_begin();
 
             let old_target: BigInt = state.get(&self.target, true)?;
             let old_target_u64: u64 = match old_target.to_u64() {
@@ -124,12 +125,11 @@ impl Node for NodeLoopSlow {
                 println!("LOOP: old_target={}, old_range={}", old_target_u64, old_range_length);
                 println!("LOOP: new_target={}, new_range={}", new_target_u64, new_range_length);
 
-                let snapshot0 = old_state.memory_full_to_string();
+                let snapshot0 = state.memory_full_to_string_before_journal_frame();
                 let snapshot1 = state.memory_full_to_string();
                 println!("LOOP: old={} new={}", snapshot0, snapshot1);
             }
-            let is_less: bool = state.is_less_twostartindexes_range(
-                &old_state, 
+            let is_less: bool = state.is_less_than_journal_frame_range(
                 new_target_u64, 
                 old_target_u64, 
                 range_length
@@ -141,11 +141,10 @@ impl Node for NodeLoopSlow {
                 }
 
                 // When the loop reaches its end, the previous state is restored.
-                let mut new_state: ProgramState = old_state.clone();
-                new_state.set_step_count(state.step_count());
-                *state = new_state;
+                state.journal_rollback();
                 break;
             }
+            state.journal_commit();
 
             // Prevent looping for too long
             match limit {
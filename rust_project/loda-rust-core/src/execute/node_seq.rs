@@ -51,7 +51,9 @@ impl Node for NodeSeq {
         let input: BigInt = state.get(&self.target, false)?;
 
         if input.is_negative() {
-            // Prevent calling other programs with a negative parameter.
+            // Prevent calling other programs with a negative parameter, like loda-cpp does.
+            // A program with a negative `#offset` can only be evaluated at the negative indexes at the top level,
+            // such as with `ProgramRunner::eval_terms()`.
             // It's fragile allowing negative values.
             // Example: If program A depends on program B. 
             // Some day program B gets changed, and it breaks program A,
@@ -68,13 +70,17 @@ impl Node for NodeSeq {
 
         // Invoke the actual run() function
         let input_value = RegisterValue(input);
-        let run_result = self.program_runner_rc.run_without_offset(
-            input_value, 
+        let mut inner_state = ProgramState::new(
             state.run_mode(), 
-            &mut step_count, 
             step_count_limit,
             state.node_register_limit().clone(),
             state.node_loop_limit().clone(),
+        );
+        inner_state.set_cancel_check(state.cancel_check().cloned());
+        let run_result = self.program_runner_rc.run_without_offset_with_state(
+            input_value, 
+            inner_state, 
+            &mut step_count, 
             cache,
         );
 
@@ -39,8 +39,6 @@ impl Node for NodeUnofficialFunction {
     }
 
     fn eval(&self, state: &mut ProgramState, _cache: &mut ProgramCache) -> anyhow::Result<()> {
-        let input: BigInt = state.get(&self.target, false)?;
-
         // Start address
         let start_address_bigint: BigInt = state.get(&self.target, true)?;
         let start_address: u64 = start_address_bigint.to_u64()
@@ -54,13 +52,13 @@ impl Node for NodeUnofficialFunction {
             // debug!("input#{} = address {} = value {:?}", i, address, value);
 
             // Abort if the input value is beyond the limit (optional)
-            state.check_value().input(&input)?;
+            state.check_value().input(value)?;
 
             input_vec.push(value.clone());
         }
 
         // Run the function
-        let run_result = self.unofficial_function.run(input_vec);
+        let run_result = self.unofficial_function.run_with_state(input_vec, state);
         let output_vec: Vec<BigInt> = run_result.context("NodeUnofficialFunction.eval run returned error")?;
         
         // Output from the function
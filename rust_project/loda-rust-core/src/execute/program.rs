@@ -39,6 +39,9 @@ impl Program {
     }
 
     pub fn run(&self, state: &mut ProgramState, cache: &mut ProgramCache) -> anyhow::Result<()> {
+        if state.step_trace().is_some() {
+            return self.run_step_trace(state, cache);
+        }
         match state.run_mode() {
             RunMode::Verbose => self.run_verbose(state, cache),
             RunMode::Silent => self.run_silent(state, cache),
@@ -70,6 +73,17 @@ impl Program {
         Ok(())
     }
 
+    fn run_step_trace(&self, state: &mut ProgramState, cache: &mut ProgramCache) -> anyhow::Result<()> {
+        for node in &self.node_vec {
+            node.eval(state, cache)?;
+            let instruction: String = node.formatted_instruction();
+            if !instruction.is_empty() {
+                state.record_step(instruction);
+            }
+        }
+        Ok(())
+    }
+
     pub fn update_call(&mut self, program_manager: &mut ProgramRunnerManager) {
         for node in &mut self.node_vec {
             node.update_call(program_manager);
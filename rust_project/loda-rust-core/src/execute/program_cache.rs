@@ -1,5 +1,7 @@
 use num_bigint::BigInt;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
+use std::sync::{Arc, RwLock};
 use cached::{SizedCache, Cached};
 
 const DEFAULT_CACHE_CAPACITY: usize = 1500;
@@ -18,6 +20,7 @@ pub struct CacheValue {
 
 pub struct ProgramCache {
     cache: SizedCache<CacheKey, CacheValue>,
+    shared_cache: Option<SharedProgramCache>,
     metric_hit: u64,
     metric_miss_for_program_oeis: u64,
     metric_miss_for_program_without_id: u64,
@@ -33,12 +36,18 @@ impl ProgramCache {
         let cache: SizedCache<CacheKey, CacheValue> = SizedCache::with_size(capacity.get());
         Self {
             cache: cache,
+            shared_cache: None,
             metric_hit: 0,
             metric_miss_for_program_oeis: 0,
             metric_miss_for_program_without_id: 0,
         }
     }
 
+    /// Look in the shared cache, when the value isn't found in this cache.
+    pub fn set_shared_cache(&mut self, shared_cache: SharedProgramCache) {
+        self.shared_cache = Some(shared_cache);
+    }
+
     pub fn register_cache_hit(&mut self) {
         self.metric_hit += 1;
     }
@@ -78,6 +87,13 @@ impl ProgramCache {
             program_id: program_id,
             index: index.clone(),
         };
+        if let Some(shared_cache) = &self.shared_cache {
+            if self.cache.cache_get(&key).is_none() {
+                if let Some(value) = shared_cache.get(&key) {
+                    self.cache.cache_set(key.clone(), value);
+                }
+            }
+        }
         self.cache.cache_get(&key)
     }
 
@@ -90,10 +106,78 @@ impl ProgramCache {
             value: value,
             step_count: step_count,
         };
+        if let Some(shared_cache) = &self.shared_cache {
+            shared_cache.set(&key, &value);
+        }
         self.cache.cache_set(key, value);
     }
 }
 
+/// Cache that is shared between threads, so the terms of popular programs are only computed once.
+///
+/// Each thread has its own `ProgramCache`, that looks in the shared cache when there is a miss.
+///
+/// Only the programs in `program_ids` are shared, and no more values are inserted when the capacity is reached.
+/// This way the shared cache is mostly read, and the threads rarely wait for each other.
+#[derive(Clone, Debug)]
+pub struct SharedProgramCache {
+    inner: Arc<RwLock<HashMap<CacheKey, CacheValue>>>,
+    program_ids: Option<Arc<HashSet<u64>>>,
+    capacity: usize,
+}
+
+impl SharedProgramCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            program_ids: None,
+            capacity: capacity.get(),
+        }
+    }
+
+    /// Only share the values for these programs. By default the values of all programs are shared.
+    pub fn set_program_ids(&mut self, program_ids: HashSet<u64>) {
+        self.program_ids = Some(Arc::new(program_ids));
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().expect("SharedProgramCache.len() RwLock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CacheValue> {
+        if let Some(program_ids) = &self.program_ids {
+            if !program_ids.contains(&key.program_id) {
+                return None;
+            }
+        }
+        let inner = self.inner.read().expect("SharedProgramCache.get() RwLock poisoned");
+        inner.get(key).cloned()
+    }
+
+    fn set(&self, key: &CacheKey, value: &CacheValue) {
+        if let Some(program_ids) = &self.program_ids {
+            if !program_ids.contains(&key.program_id) {
+                return;
+            }
+        }
+        {
+            // Avoid the write lock, when there is nothing to be inserted.
+            let inner = self.inner.read().expect("SharedProgramCache.set() RwLock poisoned");
+            if inner.len() >= self.capacity || inner.contains_key(key) {
+                return;
+            }
+        }
+        let mut inner = self.inner.write().expect("SharedProgramCache.set() RwLock poisoned");
+        if inner.len() < self.capacity {
+            inner.insert(key.clone(), value.clone());
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -122,4 +206,47 @@ mod tests {
         assert_ne!(cache.get(40, &1u8.to_bigint().unwrap()), None, "has data");
         assert_ne!(cache.get(40, &2u8.to_bigint().unwrap()), None, "has data");
     }
+
+    #[test]
+    fn test_20000_shared_cache() {
+        // Arrange
+        let shared_cache = SharedProgramCache::new(NonZeroUsize::new(100).unwrap());
+        let mut cache0 = ProgramCache::new();
+        cache0.set_shared_cache(shared_cache.clone());
+        let mut cache1 = ProgramCache::new();
+        cache1.set_shared_cache(shared_cache.clone());
+
+        // Act
+        cache0.set(40, 5u8.to_bigint().unwrap(), 11u8.to_bigint().unwrap(), 7);
+
+        // Assert
+        let expected = CacheValue { value: 11u8.to_bigint().unwrap(), step_count: 7 };
+        assert_eq!(cache1.get(40, &5u8.to_bigint().unwrap()), Some(&expected));
+        assert_eq!(cache1.get(40, &6u8.to_bigint().unwrap()), None);
+        assert_eq!(shared_cache.len(), 1);
+    }
+
+    #[test]
+    fn test_20001_shared_cache_only_popular_programs() {
+        // Arrange
+        let mut shared_cache = SharedProgramCache::new(NonZeroUsize::new(2).unwrap());
+        shared_cache.set_program_ids(HashSet::from([40, 45]));
+        let mut cache0 = ProgramCache::new();
+        cache0.set_shared_cache(shared_cache.clone());
+        let mut cache1 = ProgramCache::new();
+        cache1.set_shared_cache(shared_cache.clone());
+
+        // Act
+        cache0.set(10, 0u8.to_bigint().unwrap(), 1u8.to_bigint().unwrap(), 1);
+        cache0.set(40, 0u8.to_bigint().unwrap(), 2u8.to_bigint().unwrap(), 1);
+        cache0.set(45, 0u8.to_bigint().unwrap(), 0u8.to_bigint().unwrap(), 1);
+        cache0.set(45, 1u8.to_bigint().unwrap(), 1u8.to_bigint().unwrap(), 1);
+
+        // Assert
+        assert_eq!(cache1.get(10, &0u8.to_bigint().unwrap()), None, "not a popular program");
+        assert_ne!(cache1.get(40, &0u8.to_bigint().unwrap()), None, "has data");
+        assert_ne!(cache1.get(45, &0u8.to_bigint().unwrap()), None, "has data");
+        assert_eq!(cache1.get(45, &1u8.to_bigint().unwrap()), None, "exceeded capacity");
+        assert_eq!(shared_cache.len(), 2);
+    }
 }
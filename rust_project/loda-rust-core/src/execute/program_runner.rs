@@ -1,4 +1,4 @@
-use super::{NodeLoopLimit, ProgramCache, Program, ProgramId, ProgramSerializer, ProgramState, RegisterValue, RunMode};
+use super::{NodeLoopLimit, ProgramCache, Program, ProgramId, ProgramSerializer, ProgramState, RegisterValue, RunMode, StepTrace};
 use super::NodeRegisterLimit;
 use anyhow::Context;
 use num_bigint::BigInt;
@@ -53,6 +53,27 @@ impl ProgramRunner {
         node_register_limit: NodeRegisterLimit, 
         node_loop_limit: NodeLoopLimit,
         cache: &mut ProgramCache
+    ) -> anyhow::Result<RegisterValue> {
+        let state = ProgramState::new(
+            run_mode, 
+            step_count_limit, 
+            node_register_limit,
+            node_loop_limit,
+        );
+        self.run_without_offset_with_state(input, state, step_count, cache)
+    }
+
+    /// Same as `run_without_offset()`, where the caller provides the initial state, such as with a `CancelCheck`.
+    ///
+    /// The `state` must be newly created with `ProgramState::new()`, the step count is taken from `step_count`.
+    ///
+    /// The `cancel_check` of the state is passed on to the programs invoked via `seq`.
+    pub fn run_without_offset_with_state(
+        &self, 
+        input: RegisterValue,
+        mut state: ProgramState,
+        step_count: &mut u64, 
+        cache: &mut ProgramCache
     ) -> anyhow::Result<RegisterValue> {
         let step_count_before: u64 = *step_count;
 
@@ -68,12 +89,6 @@ impl ProgramRunner {
         }
 
         // Initial state
-        let mut state = ProgramState::new(
-            run_mode, 
-            step_count_limit, 
-            node_register_limit,
-            node_loop_limit,
-        );
         state.set_step_count(step_count_before);
         state.set_input_value(&input);
 
@@ -115,6 +130,36 @@ impl ProgramRunner {
         Ok(output)
     }
 
+    /// Evaluate a single term and record the register state for every executed instruction.
+    ///
+    /// The cache is not consulted for the root program, so that all the instructions gets recorded.
+    pub fn run_with_step_trace(
+        &self,
+        input: RegisterValue,
+        step_count: &mut u64,
+        step_count_limit: u64,
+        step_trace: &StepTrace,
+        cache: &mut ProgramCache
+    ) -> anyhow::Result<RegisterValue> {
+        let value_adjusted: BigInt = input.0 + self.program.offset().unwrap_or(0);
+        let input_adjusted = RegisterValue(value_adjusted);
+
+        let mut state = ProgramState::new(
+            RunMode::Silent,
+            step_count_limit,
+            NodeRegisterLimit::Unlimited,
+            NodeLoopLimit::Unlimited,
+        );
+        state.set_step_count(*step_count);
+        state.set_input_value(&input_adjusted);
+        state.set_step_trace(Some(step_trace.clone()));
+
+        let run_result = self.program.run(&mut state, cache);
+        *step_count = state.step_count();
+        run_result.context("run_result error in program.run")?;
+        Ok(state.remove_output_value())
+    }
+
     pub fn run_vec(
         &self, 
         input: Vec<BigInt>, 
@@ -126,15 +171,48 @@ impl ProgramRunner {
         cache: &mut ProgramCache,
         output_count: u8,
     ) -> anyhow::Result<Vec<BigInt>> {
-        let step_count_before: u64 = *step_count;
-
-        // Initial state
-        let mut state = ProgramState::new(
+        let state = ProgramState::new(
             run_mode, 
             step_count_limit, 
             node_register_limit,
             node_loop_limit,
         );
+        let output_registers: Vec<u64> = (0..output_count as u64).collect();
+        self.run_vec_with_state(input, state, step_count, cache, &output_registers)
+    }
+
+    /// The input values are placed in the registers `$0`, `$1`, `$2`, etc.
+    /// 
+    /// The output values are read from the `output_registers`.
+    pub fn run_vec_with_output_registers(
+        &self, 
+        input: Vec<BigInt>, 
+        step_count: &mut u64, 
+        step_count_limit: u64, 
+        cache: &mut ProgramCache,
+        output_registers: &[u64],
+    ) -> anyhow::Result<Vec<BigInt>> {
+        let state = ProgramState::new(
+            RunMode::Silent, 
+            step_count_limit, 
+            NodeRegisterLimit::Unlimited,
+            NodeLoopLimit::Unlimited,
+        );
+        self.run_vec_with_state(input, state, step_count, cache, output_registers)
+    }
+
+    /// Same as `run_vec_with_output_registers()`, with the limits and the cancellation of the `state`.
+    pub fn run_vec_with_state(
+        &self, 
+        input: Vec<BigInt>, 
+        mut state: ProgramState,
+        step_count: &mut u64, 
+        cache: &mut ProgramCache,
+        output_registers: &[u64],
+    ) -> anyhow::Result<Vec<BigInt>> {
+        let step_count_before: u64 = *step_count;
+
+        // Initial state
         state.set_step_count(step_count_before);
 
         // Input vector
@@ -154,9 +232,9 @@ impl ProgramRunner {
         run_result.context("run_result error in program.run")?;
 
         // Output vector
-        let mut output_vec = Vec::<BigInt>::with_capacity(output_count as usize);
-        for index in 0..output_count {
-            let value: BigInt = state.get_u64(index as u64).clone();
+        let mut output_vec = Vec::<BigInt>::with_capacity(output_registers.len());
+        for address in output_registers {
+            let value: BigInt = state.get_u64(*address).clone();
             output_vec.push(value);
         }
 
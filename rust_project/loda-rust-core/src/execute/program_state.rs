@@ -1,4 +1,4 @@
-use super::{EvalError, NodeLoopLimit, RegisterIndex, RegisterValue, RunMode};
+use super::{CancelCheck, EvalError, NodeLoopLimit, RegisterIndex, RegisterValue, RunMode, StepTrace, StepTraceItem};
 use super::NodeRegisterLimit;
 use super::BoxCheckValue;
 use crate::parser::{InstructionParameter, ParameterType};
@@ -22,16 +22,29 @@ const OUTPUT_REGISTER: u64 = 0;
 
 const MAX_NUMBER_OF_REGISTERS: u64 = 10000;
 
+/// The values of the registers before they were modified, `None` if the register was unset.
+type JournalFrame = HashMap<u64, Option<BigInt>>;
+
+#[cfg(test)]
+thread_local! {
+    static CLONE_BASELINE: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
 
 #[derive(Clone)]
 pub struct ProgramState {
     memory_full: HashMap<u64, BigInt>,
+    journal: Vec<JournalFrame>,
+    journal_depth: usize,
+    #[cfg(test)]
+    clone_baseline: Vec<HashMap<u64, BigInt>>,
     step_count: u64,
     run_mode: RunMode,
     step_count_limit: u64,
     node_register_limit: NodeRegisterLimit,
     node_loop_limit: NodeLoopLimit,
     check_value: BoxCheckValue,
+    step_trace: Option<StepTrace>,
+    cancel_check: Option<CancelCheck>,
 }
 
 impl ProgramState {
@@ -45,12 +58,18 @@ impl ProgramState {
 
         Self {
             memory_full: HashMap::new(),
+            journal: vec!(),
+            journal_depth: 0,
+            #[cfg(test)]
+            clone_baseline: vec!(),
             step_count: 0,
             run_mode: run_mode,
             step_count_limit: step_count_limit,
             node_register_limit: node_register_limit,
             node_loop_limit: node_loop_limit,
             check_value: check_value,
+            step_trace: None,
+            cancel_check: None,
         }
     }
 
@@ -70,6 +89,41 @@ impl ProgramState {
         self.run_mode
     }
 
+    pub fn step_trace(&self) -> Option<&StepTrace> {
+        self.step_trace.as_ref()
+    }
+
+    pub fn set_step_trace(&mut self, step_trace: Option<StepTrace>) {
+        self.step_trace = step_trace;
+    }
+
+    pub fn cancel_check(&self) -> Option<&CancelCheck> {
+        self.cancel_check.as_ref()
+    }
+
+    pub fn set_cancel_check(&mut self, cancel_check: Option<CancelCheck>) {
+        self.cancel_check = cancel_check;
+    }
+
+    /// Append a snapshot of the registers to the step trace, if tracing is enabled.
+    pub fn record_step(&self, instruction: String) {
+        let step_trace: &StepTrace = match &self.step_trace {
+            Some(value) => value,
+            None => return
+        };
+        let mut registers: Vec<(u64, BigInt)> = self.memory_full.iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(key, value)| (*key, value.clone()))
+            .collect();
+        registers.sort_unstable_by_key(|(key, _)| *key);
+        let item = StepTraceItem {
+            instruction,
+            step_count: self.step_count,
+            registers,
+        };
+        step_trace.push(item);
+    }
+
     pub fn get_u64(&self, address: u64) -> &BigInt {
         match self.memory_full.get(&address) {
             Some(value) => { return value; },
@@ -167,6 +221,7 @@ impl ProgramState {
     /// Why use `remove_output_value()`?
     /// This eliminates one `BigInt.clone()` operation.
     pub fn remove_output_value(&mut self) -> RegisterValue {
+        self.journal_record(OUTPUT_REGISTER);
         match self.memory_full.remove(&OUTPUT_REGISTER) {
             Some(value) => { 
                 return RegisterValue(value); 
@@ -198,12 +253,14 @@ impl ProgramState {
         if address >= MAX_NUMBER_OF_REGISTERS {
             return Err(EvalError::AddressIsOutsideMaxCapacity);
         }
+        self.journal_record(address);
         self.memory_full.insert(address, value);
         Ok(())
     }
 
     /// Write a value to register 0, the input register.
     pub fn set_input_value(&mut self, register_value: &RegisterValue) {
+        self.journal_record(INPUT_REGISTER);
         self.memory_full.insert(INPUT_REGISTER, register_value.0.clone());
     }
    
@@ -214,6 +271,9 @@ impl ProgramState {
             return Err(EvalError::AddressIsOutsideMaxCapacity);
         }
         for _ in 0..count {
+            if self.memory_full.contains_key(&index) {
+                self.journal_record(index);
+            }
             self.memory_full.remove(&index);
             index += 1;
         }
@@ -222,13 +282,43 @@ impl ProgramState {
 
     /// Make the internal state human readable
     pub fn memory_full_to_string(&self) -> String {
-        let key_refs: Vec<&u64> = Vec::from_iter(self.memory_full.keys());
+        Self::memory_to_string(&self.memory_full)
+    }
+
+    /// Make the internal state human readable, as it was when `journal_begin()` was called.
+    pub fn memory_full_to_string_before_journal_frame(&self) -> String {
+        #[cfg(test)]
+        if let Some(memory) = self.clone_baseline.last() {
+            return Self::memory_to_string(memory);
+        }
+        let frame: &JournalFrame = match self.journal_top_frame() {
+            Some(frame) => frame,
+            None => {
+                return self.memory_full_to_string();
+            }
+        };
+        let mut memory: HashMap<u64, BigInt> = self.memory_full.clone();
+        for (address, old_value) in frame {
+            match old_value {
+                Some(value) => {
+                    memory.insert(*address, value.clone());
+                },
+                None => {
+                    memory.remove(address);
+                }
+            }
+        }
+        Self::memory_to_string(&memory)
+    }
+
+    fn memory_to_string(memory: &HashMap<u64, BigInt>) -> String {
+        let key_refs: Vec<&u64> = Vec::from_iter(memory.keys());
         let mut keys: Vec<u64> = key_refs.iter().map(|&key| *key).collect();
         keys.sort();
 
         let mut strings = Vec::<String>::new();
         for key in keys {
-            match self.memory_full.get(&key) {
+            match memory.get(&key) {
                 Some(value) => {
                     strings.push(format!("{}:{}", key, value));
                 },
@@ -279,6 +369,142 @@ impl ProgramState {
     }
 }
 
+/// Undo log of the memory, so that a loop can restore the registers without cloning the entire `ProgramState`.
+///
+/// A loop calls `journal_begin()` before each iteration. The first time a register is modified,
+/// its previous value is saved in the top frame. Only the registers that are modified are saved.
+///
+/// When the loop exits, the iteration is undone with `journal_rollback()`.
+/// When the loop continues, the iteration is kept with `journal_commit()`, and the saved values
+/// are merged into the frame of the outer loop, so the outer loop can still undo its iteration.
+///
+/// When an error occurs, the frames are left as they are, and the `ProgramState` must be discarded.
+impl ProgramState {
+    pub fn journal_begin(&mut self) {
+        #[cfg(test)]
+        if CLONE_BASELINE.with(|value| value.get()) {
+            self.clone_baseline.push(self.memory_full.clone());
+            return;
+        }
+        if self.journal.len() <= self.journal_depth {
+            self.journal.push(JournalFrame::new());
+        }
+        self.journal_depth += 1;
+    }
+
+    /// Restore the registers to their values when `journal_begin()` was called.
+    ///
+    /// The step count is not restored.
+    pub fn journal_rollback(&mut self) {
+        #[cfg(test)]
+        if let Some(memory) = self.clone_baseline.pop() {
+            self.memory_full = memory;
+            return;
+        }
+        assert!(self.journal_depth > 0, "journal_rollback without journal_begin");
+        self.journal_depth -= 1;
+        let frame: &mut JournalFrame = &mut self.journal[self.journal_depth];
+        for (address, old_value) in frame.drain() {
+            match old_value {
+                Some(value) => {
+                    self.memory_full.insert(address, value);
+                },
+                None => {
+                    self.memory_full.remove(&address);
+                }
+            }
+        }
+    }
+
+    /// Keep the modified registers.
+    pub fn journal_commit(&mut self) {
+        #[cfg(test)]
+        if self.clone_baseline.pop().is_some() {
+            return;
+        }
+        assert!(self.journal_depth > 0, "journal_commit without journal_begin");
+        self.journal_depth -= 1;
+        let depth: usize = self.journal_depth;
+        if depth == 0 {
+            self.journal[0].clear();
+            return;
+        }
+        let (parents, children) = self.journal.split_at_mut(depth);
+        let parent: &mut JournalFrame = &mut parents[depth - 1];
+        for (address, old_value) in children[0].drain() {
+            // The parent frame keeps the oldest value.
+            parent.entry(address).or_insert(old_value);
+        }
+    }
+
+    pub fn journal_depth(&self) -> usize {
+        self.journal_depth
+    }
+
+    /// Baseline for the benchmarks. When enabled, then `journal_begin()` clones all the registers,
+    /// like the loops did before the journal was introduced. Applies to the current thread.
+    #[cfg(test)]
+    pub fn set_clone_baseline(enabled: bool) {
+        CLONE_BASELINE.with(|value| value.set(enabled));
+    }
+
+    fn journal_top_frame(&self) -> Option<&JournalFrame> {
+        if self.journal_depth == 0 {
+            return None;
+        }
+        Some(&self.journal[self.journal_depth - 1])
+    }
+
+    fn journal_record(&mut self, address: u64) {
+        if self.journal_depth == 0 {
+            return;
+        }
+        let frame: &mut JournalFrame = &mut self.journal[self.journal_depth - 1];
+        if frame.contains_key(&address) {
+            return;
+        }
+        let old_value: Option<BigInt> = self.memory_full.get(&address).cloned();
+        frame.insert(address, old_value);
+    }
+
+    /// The value of a register, as it was when `journal_begin()` was called.
+    pub fn get_u64_before_journal_frame(&self, address: u64) -> &BigInt {
+        #[cfg(test)]
+        if let Some(memory) = self.clone_baseline.last() {
+            return memory.get(&address).unwrap_or(&BIGINT_ZERO);
+        }
+        if let Some(frame) = self.journal_top_frame() {
+            if let Some(old_value) = frame.get(&address) {
+                return match old_value {
+                    Some(value) => value,
+                    None => &BIGINT_ZERO
+                };
+            }
+        }
+        self.get_u64(address)
+    }
+
+    /// Similar to `is_less_twostartindexes_range()`, where the other state is the state
+    /// when `journal_begin()` was called.
+    pub fn is_less_than_journal_frame_range(&self, start_index0: u64, start_index1: u64, range_length: u64) -> bool {
+        for i in 0..range_length {
+            let a_value: &BigInt = self.get_u64(start_index0 + i);
+            if a_value.is_negative() {
+                // Negative value encountered
+                return false;
+            }
+            let b_value: &BigInt = self.get_u64_before_journal_frame(start_index1 + i);
+            let ordering: Ordering = a_value.cmp(b_value);
+            match ordering {
+                Ordering::Less => return true,
+                Ordering::Greater => return false,
+                Ordering::Equal => continue,
+            }
+        }
+        false
+    }
+}
+
 impl ProgramState {
     pub fn step_count_limit(&self) -> u64 {
         self.step_count_limit
@@ -295,6 +521,13 @@ impl ProgramState {
         if count >= self.step_count_limit {
             return Err(EvalError::StepCountExceededLimit);
         }
+        if count.is_multiple_of(CancelCheck::INTERVAL) {
+            if let Some(cancel_check) = &self.cancel_check {
+                if cancel_check.is_cancelled() {
+                    return Err(EvalError::Cancelled);
+                }
+            }
+        }
         Ok(())
     }
 
@@ -306,6 +539,7 @@ impl ProgramState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
 
     fn set_value_not_failable(state: &mut ProgramState, address: u64, value: i64) {
         let value_bigint = value.to_bigint().expect("should not fail");
@@ -673,4 +907,142 @@ mod tests {
         assert_eq!(state.memory_full_to_string(), "[1:100]");
         assert_eq!(output, RegisterValue(BigInt::zero()));
     }
+
+    #[test]
+    fn test_60000_journal_rollback() {
+        // Arrange
+        let mut state = mock_program_state();
+        state.journal_begin();
+        set_value_not_failable(&mut state, 1, 5);
+        set_value_not_failable(&mut state, 1, 6);
+        set_value_not_failable(&mut state, 7, 107);
+        state.set_register_range_to_zero(RegisterIndex(2), 2).expect("ok");
+        state.increment_step_count().expect("ok");
+        assert_eq!(state.memory_full_to_string(), "[0:100,1:6,7:107]");
+        assert_eq!(state.memory_full_to_string_before_journal_frame(), "[0:100,1:101,2:102,3:103]");
+
+        // Act
+        state.journal_rollback();
+
+        // Assert
+        assert_eq!(state.memory_full_to_string(), "[0:100,1:101,2:102,3:103]");
+        assert_eq!(state.journal_depth(), 0);
+        assert_eq!(state.step_count(), 1);
+    }
+
+    #[test]
+    fn test_60001_journal_commit() {
+        // Arrange
+        let mut state = mock_program_state();
+        state.journal_begin();
+        set_value_not_failable(&mut state, 1, 5);
+        set_value_not_failable(&mut state, 7, 107);
+
+        // Act
+        state.journal_commit();
+
+        // Assert
+        assert_eq!(state.memory_full_to_string(), "[0:100,1:5,2:102,3:103,7:107]");
+        assert_eq!(state.journal_depth(), 0);
+    }
+
+    #[test]
+    fn test_60002_journal_nested_commit_then_rollback() {
+        // Arrange
+        let mut state = mock_program_state();
+        state.journal_begin();
+        set_value_not_failable(&mut state, 1, 5);
+        state.journal_begin();
+        set_value_not_failable(&mut state, 1, 6);
+        set_value_not_failable(&mut state, 2, 7);
+        set_value_not_failable(&mut state, 8, 8);
+        assert_eq!(state.get_u64_before_journal_frame(1).to_i64(), Some(5));
+        assert_eq!(state.get_u64_before_journal_frame(8).to_i64(), Some(0));
+        state.journal_commit();
+        assert_eq!(state.get_u64_before_journal_frame(1).to_i64(), Some(101));
+        assert_eq!(state.get_u64_before_journal_frame(2).to_i64(), Some(102));
+
+        // Act
+        state.journal_rollback();
+
+        // Assert
+        assert_eq!(state.memory_full_to_string(), "[0:100,1:101,2:102,3:103]");
+    }
+
+    #[test]
+    fn test_60003_is_less_than_journal_frame_range() {
+        {
+            let mut state = mock_program_state();
+            state.journal_begin();
+            set_value_not_failable(&mut state, 1, 100);
+            assert_eq!(state.is_less_than_journal_frame_range(1, 1, 1), true);
+            assert_eq!(state.is_less_than_journal_frame_range(2, 2, 1), false);
+            assert_eq!(state.is_less_than_journal_frame_range(0, 0, 2), true);
+        }
+        {
+            let mut state = mock_program_state();
+            state.journal_begin();
+            set_value_not_failable(&mut state, 1, -1);
+            assert_eq!(state.is_less_than_journal_frame_range(1, 1, 1), false);
+        }
+        {
+            let mut state = mock_program_state();
+            state.journal_begin();
+            set_value_not_failable(&mut state, 11, 102);
+            assert_eq!(state.is_less_than_journal_frame_range(11, 3, 1), true);
+            assert_eq!(state.is_less_than_journal_frame_range(11, 2, 1), false);
+        }
+    }
+
+    /// Compare the time it takes to save and restore the registers, with a clone of the `ProgramState`
+    /// and with the journal, when the loop body modifies 2 registers.
+    ///
+    /// Run it like this:
+    /// ```text
+    /// cargo test --release -p loda-rust-core -- --ignored --nocapture test_90000_benchmark
+    /// ```
+    #[test]
+    #[ignore]
+    fn test_90000_benchmark_clone_versus_journal() {
+        let iterations: u64 = 100000;
+        for register_count in [4, 20, 100, 1000] {
+            // Arrange
+            let mut state = empty_program_state();
+            for address in 0..register_count {
+                set_value_not_failable(&mut state, address, 1000000000);
+            }
+
+            // Act
+            let start = Instant::now();
+            let mut count_clone: u64 = 0;
+            for i in 0..iterations {
+                let old_state: ProgramState = state.clone();
+                set_value_not_failable(&mut state, 0, i as i64);
+                set_value_not_failable(&mut state, 1, 42);
+                if state.is_less_range(&old_state, 0, 1) {
+                    count_clone += 1;
+                }
+                state = old_state;
+            }
+            let elapsed_clone = start.elapsed();
+
+            let start = Instant::now();
+            let mut count_journal: u64 = 0;
+            for i in 0..iterations {
+                state.journal_begin();
+                set_value_not_failable(&mut state, 0, i as i64);
+                set_value_not_failable(&mut state, 1, 42);
+                if state.is_less_than_journal_frame_range(0, 0, 1) {
+                    count_journal += 1;
+                }
+                state.journal_rollback();
+            }
+            let elapsed_journal = start.elapsed();
+
+            // Assert
+            assert_eq!(count_clone, iterations);
+            assert_eq!(count_journal, iterations);
+            println!("registers: {:>5}  clone: {:>10?}  journal: {:>10?}", register_count, elapsed_clone, elapsed_journal);
+        }
+    }
 }
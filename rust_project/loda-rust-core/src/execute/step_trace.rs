@@ -0,0 +1,79 @@
+use num_bigint::BigInt;
+use std::sync::{Arc, Mutex};
+
+/// The register state right after an instruction has been executed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepTraceItem {
+    /// The instruction, such as `add $1,$0` or `lpb $0`.
+    pub instruction: String,
+
+    /// The total number of steps, right after the instruction has been executed.
+    pub step_count: u64,
+
+    /// The registers with a non-zero value, sorted by register index.
+    pub registers: Vec<(u64, BigInt)>,
+}
+
+/// Records a snapshot of the registers for every executed instruction.
+///
+/// Usecase:
+/// A visual debugger that lets the user step forward and backward through the evaluation of a term.
+///
+/// The instructions inside a loop are recorded for every iteration.
+/// The loop itself is recorded after it has ended, with the restored register state.
+/// A `seq` instruction is recorded as a single step, the instructions of the called program are not recorded.
+///
+/// The trace is a shared handle, so the caller keeps a clone and reads the items after the evaluation,
+/// while the `ProgramState` holds another clone that the items are pushed to.
+///
+/// Recording stops when the `limit` has been reached, so that a long running program
+/// doesn't use up all the memory. Use `is_truncated()` to check if items were dropped.
+#[derive(Clone, Debug)]
+pub struct StepTrace {
+    inner: Arc<Mutex<StepTraceInner>>,
+}
+
+#[derive(Debug)]
+struct StepTraceInner {
+    limit: usize,
+    items: Vec<StepTraceItem>,
+    truncated: bool,
+}
+
+impl StepTrace {
+    pub fn new(limit: usize) -> Self {
+        let inner = StepTraceInner {
+            limit,
+            items: vec!(),
+            truncated: false,
+        };
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    pub fn push(&self, item: StepTraceItem) {
+        let mut inner = self.inner.lock().expect("StepTrace Mutex poisoned");
+        if inner.items.len() >= inner.limit {
+            inner.truncated = true;
+            return;
+        }
+        inner.items.push(item);
+    }
+
+    pub fn items(&self) -> Vec<StepTraceItem> {
+        let inner = self.inner.lock().expect("StepTrace Mutex poisoned");
+        inner.items.clone()
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        let inner = self.inner.lock().expect("StepTrace Mutex poisoned");
+        inner.truncated
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().expect("StepTrace Mutex poisoned");
+        inner.items.clear();
+        inner.truncated = false;
+    }
+}
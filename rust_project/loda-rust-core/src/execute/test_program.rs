@@ -1,7 +1,7 @@
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Program, ProgramId, ProgramRunner, ProgramRunnerManager, ProgramSerializer, RegisterIndex};
+    use super::super::{Program, ProgramCache, ProgramId, ProgramRunner, ProgramRunnerManager, ProgramSerializer, RegisterIndex, RegisterValue, StepTrace, StepTraceItem};
     use crate::parser::{InstructionId, InstructionParameter, ParameterType};
     use super::super::node_calc::*;
     use super::super::node_loop_simple::*;
@@ -90,4 +90,78 @@ mov $0,$1"#;
             assert_eq!(this_program.validate_call_nodes().is_ok(), true);
         }
     }
+
+    #[test]
+    fn test_20000_run_with_step_trace() {
+        // Arrange
+        let runner = ProgramRunner::new(
+            ProgramId::ProgramWithoutId,
+            program_a000045()
+        );
+        let step_trace = StepTrace::new(1000);
+        let mut cache = ProgramCache::new();
+        let mut step_count: u64 = 0;
+
+        // Act
+        let output: RegisterValue = runner.run_with_step_trace(
+            RegisterValue::from_i64(2),
+            &mut step_count,
+            1000,
+            &step_trace,
+            &mut cache
+        ).expect("output");
+
+        // Assert
+        assert_eq!(output, RegisterValue::from_i64(1));
+        let items: Vec<StepTraceItem> = step_trace.items();
+        let rows: Vec<String> = items.iter().map(|item| {
+            let registers: Vec<String> = item.registers.iter().map(|(index, value)| format!("{}:{}", index, value)).collect();
+            format!("{} {} [{}]", item.step_count, item.instruction, registers.join(","))
+        }).collect();
+        let expected: Vec<&str> = vec![
+            "1 mov $3,1 [0:2,3:1]",
+            "2 sub $0,1 [0:1,3:1]",
+            "3 mov $2,$1 [0:1,3:1]",
+            "4 add $1,$3 [0:1,1:1,3:1]",
+            "5 mov $3,$2 [0:1,1:1]",
+            "6 sub $0,1 [1:1]",
+            "7 mov $2,$1 [1:1,2:1]",
+            "8 add $1,$3 [1:1,2:1]",
+            "9 mov $3,$2 [1:1,2:1,3:1]",
+            "10 sub $0,1 [0:-1,1:1,2:1,3:1]",
+            "11 mov $2,$1 [0:-1,1:1,2:1,3:1]",
+            "12 add $1,$3 [0:-1,1:2,2:1,3:1]",
+            "13 mov $3,$2 [0:-1,1:2,2:1,3:1]",
+            "14 lpb $0 [1:1,2:1,3:1]",
+            "15 mov $0,$1 [0:1,1:1,2:1,3:1]",
+        ];
+        assert_eq!(rows, expected);
+        assert_eq!(step_trace.is_truncated(), false);
+    }
+
+    #[test]
+    fn test_20001_step_trace_limit() {
+        // Arrange
+        let runner = ProgramRunner::new(
+            ProgramId::ProgramWithoutId,
+            program_a000045()
+        );
+        let step_trace = StepTrace::new(3);
+        let mut cache = ProgramCache::new();
+        let mut step_count: u64 = 0;
+
+        // Act
+        let output: RegisterValue = runner.run_with_step_trace(
+            RegisterValue::from_i64(10),
+            &mut step_count,
+            1000,
+            &step_trace,
+            &mut cache
+        ).expect("output");
+
+        // Assert
+        assert_eq!(output, RegisterValue::from_i64(55));
+        assert_eq!(step_trace.items().len(), 3);
+        assert_eq!(step_trace.is_truncated(), true);
+    }
 }
@@ -8,6 +8,7 @@
 #[macro_use]
 extern crate log;
 
+pub mod analysis;
 pub mod control;
 pub mod execute;
 pub mod oeis;
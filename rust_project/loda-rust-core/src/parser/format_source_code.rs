@@ -0,0 +1,213 @@
+use super::{Instruction, InstructionId, ParameterType, ParsedProgram, ParseProgramError};
+use crate::execute::ProgramSerializerContext;
+use regex::Regex;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Extract the OEIS id from a header comment, such as `; A000045: Fibonacci numbers`
+    static ref EXTRACT_HEADER_OEIS_ID_RE: Regex = Regex::new(
+        "^;\\s*A(\\d+):"
+    ).unwrap();
+}
+
+/// Reformat the source code, the same way as the programs in the `loda-programs` repository.
+///
+/// - The comments at the top, until the first blank line, are the header.
+/// - The header, the `#offset` and the instructions are separated by a single blank line.
+/// - The instructions are indented by 2 spaces per loop level.
+/// - The parameters are separated by a comma, without spaces, such as `mov $1,$0`.
+/// - Comments are preserved. A comment after an instruction is separated by ` ; `.
+/// - Multiple blank lines are collapsed into a single blank line.
+/// - Blank lines at the beginning and at the end are removed.
+///
+/// When a `context` is provided, then the sequence name in the header, such as `; A000045: Fibonacci numbers.`,
+/// and the comment after a `seq` instruction, are replaced by the name that the context has for that sequence.
+///
+/// Returns `Err` when the source code cannot be parsed.
+pub fn formatted_source_code(source_code: &str, context: Option<&dyn ProgramSerializerContext>) -> Result<String, ParseProgramError> {
+    let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source(source_code)?;
+    Ok(formatted_program(&parsed_program, context))
+}
+
+/// Format a parsed program together with the comments that the parser has preserved.
+///
+/// Instructions without a `source`, such as those created by the miner, are formatted without comments.
+pub fn formatted_program(program: &ParsedProgram, context: Option<&dyn ProgramSerializerContext>) -> String {
+    let mut rows: Vec<String> = vec!();
+    let mut depth: usize = 0;
+    for instruction in &program.instruction_vec {
+        if let Some(source) = &instruction.source {
+            for comment in &source.leading_comments {
+                rows.push(formatted_comment(comment, depth));
+            }
+        }
+        if instruction.instruction_id == InstructionId::LoopEnd {
+            depth = depth.saturating_sub(1);
+        }
+        let mut row: String = format!("{}{}", indentation(depth), instruction);
+        let comment: Option<String> = match sequence_name(instruction, context) {
+            Some(name) => Some(format!("; {}", name)),
+            None => instruction.source.as_ref().and_then(|source| source.comment.clone()),
+        };
+        if let Some(comment) = comment {
+            row = format!("{} {}", row, comment);
+        }
+        rows.push(row);
+        match instruction.instruction_id {
+            InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract => {
+                depth += 1;
+            },
+            _ => {}
+        }
+    }
+    for comment in &program.trailing_comments {
+        rows.push(formatted_comment(comment, depth));
+    }
+    // Blank lines before the first instruction are replaced by the separator between the sections.
+    while rows.first().is_some_and(|row| row.is_empty()) {
+        rows.remove(0);
+    }
+
+    let mut sections: Vec<String> = vec!();
+    if !program.header_comments.is_empty() {
+        let header_rows: Vec<String> = program.header_comments.iter()
+            .map(|comment| formatted_header_comment(comment, context))
+            .collect();
+        sections.push(header_rows.join("\n"));
+    }
+    if let Some(offset) = program.optional_offset {
+        sections.push(format!("#offset {}", offset));
+    }
+    if !rows.is_empty() {
+        sections.push(rows.join("\n"));
+    }
+    if sections.is_empty() {
+        return String::new();
+    }
+    let mut result: String = sections.join("\n\n");
+    result.push('\n');
+    result
+}
+
+/// A comment line indented to the loop level, or a blank line.
+fn formatted_comment(comment: &str, depth: usize) -> String {
+    if comment.is_empty() {
+        return String::new();
+    }
+    format!("{}{}", indentation(depth), comment)
+}
+
+fn indentation(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+/// Replace the name in `; A000045: Fibonacci numbers.` with the name from the context.
+fn formatted_header_comment(line: &str, context: Option<&dyn ProgramSerializerContext>) -> String {
+    let context: &dyn ProgramSerializerContext = match context {
+        Some(value) => value,
+        None => return line.to_string()
+    };
+    let captures = match EXTRACT_HEADER_OEIS_ID_RE.captures(line) {
+        Some(value) => value,
+        None => return line.to_string()
+    };
+    let oeis_id: u64 = match captures.get(1).map(|m| m.as_str().parse::<u64>()) {
+        Some(Ok(value)) => value,
+        _ => return line.to_string()
+    };
+    match context.sequence_name_for_oeis_id(oeis_id) {
+        Some(name) => format!("; A{:0>6}: {}", oeis_id, name),
+        None => line.to_string()
+    }
+}
+
+/// The name of the sequence that a `seq` instruction calls.
+fn sequence_name(instruction: &Instruction, context: Option<&dyn ProgramSerializerContext>) -> Option<String> {
+    if instruction.instruction_id != InstructionId::EvalSequence {
+        return None;
+    }
+    let parameter = instruction.parameter_vec.get(1)?;
+    if parameter.parameter_type != ParameterType::Constant || parameter.parameter_value < 0 {
+        return None;
+    }
+    context?.sequence_name_for_oeis_id(parameter.parameter_value as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockProgramSerializerContext {}
+
+    impl ProgramSerializerContext for MockProgramSerializerContext {
+        fn sequence_name_for_oeis_id(&self, oeis_id: u64) -> Option<String> {
+            match oeis_id {
+                40 => Some("The prime numbers.".to_string()),
+                45 => Some("Fibonacci numbers.".to_string()),
+                _ => None
+            }
+        }
+    }
+
+    fn format(source_code: &str) -> String {
+        match formatted_source_code(source_code, None) {
+            Ok(value) => value,
+            Err(error) => format!("BOOM: {}", error)
+        }
+    }
+
+    #[test]
+    fn test_10000_empty() {
+        assert_eq!(format(""), "");
+        assert_eq!(format("\n \n\t\n"), "");
+    }
+
+    #[test]
+    fn test_10001_indentation_and_spacing() {
+        assert_eq!(format("mul $0,-1"), "mul $0,-1\n");
+        assert_eq!(format("\tmov  $1 , $0\nlpb $0\nsub $0, 1\n    lpb $1\n add $2,1\nlpe\nlpe\n\n\n"), "mov $1,$0\nlpb $0\n  sub $0,1\n  lpb $1\n    add $2,1\n  lpe\nlpe\n");
+    }
+
+    #[test]
+    fn test_10002_comments() {
+        assert_eq!(format("mov $1,$0   ;  comment  \nlpb $0\n; inside loop\nsub $0,1\nlpe"), "mov $1,$0 ;  comment\nlpb $0\n  ; inside loop\n  sub $0,1\nlpe\n");
+    }
+
+    #[test]
+    fn test_10003_blank_lines() {
+        assert_eq!(format("\n\nmov $1,2\n\n\n\nmov $3,4\n\n"), "mov $1,2\n\nmov $3,4\n");
+    }
+
+    #[test]
+    fn test_10004_header_and_offset() {
+        let input = "; A000045: Fibonacci\n; 0,1,1,2,3,5\n#offset 1\nmov $1,$0 ; keep\n\n\n; tail comment";
+        let expected = "; A000045: Fibonacci\n; 0,1,1,2,3,5\n\n#offset 1\n\nmov $1,$0 ; keep\n\n; tail comment\n";
+        assert_eq!(format(input), expected);
+    }
+
+    #[test]
+    fn test_10005_idempotent() {
+        let input = "; A000045: Fibonacci\n\n#offset 1\n\n; the algorithm\nmov $3,1\nlpb $0\n  sub $0,1 ; decrement\n  mov $2,$1\nlpe\n";
+        assert_eq!(format(input), input);
+        assert_eq!(format(&format(input)), input);
+    }
+
+    #[test]
+    fn test_20000_parse_error() {
+        assert_eq!(format("boom $0"), "BOOM: ParseInstructionId error: Unrecognized instruction in line 1");
+    }
+
+    #[test]
+    fn test_30000_context_sequence_names() {
+        // Arrange
+        let input = "; A45: old name\n; 0,1,1,2\n\nseq $0,40 ; old comment\nseq $1,99 ; unknown\nseq $2,45";
+        let context = MockProgramSerializerContext {};
+
+        // Act
+        let actual: String = formatted_source_code(input, Some(&context)).expect("ok");
+
+        // Assert
+        let expected = "; A000045: Fibonacci numbers.\n; 0,1,1,2\n\nseq $0,40 ; The prime numbers.\nseq $1,99 ; unknown\nseq $2,45 ; Fibonacci numbers.\n";
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,11 +1,40 @@
 use super::{InstructionId, InstructionParameter};
 use std::fmt;
 
+/// Where an instruction came from in the source code, and the comments around it.
 #[derive(Clone, Debug, PartialEq)]
+pub struct InstructionSource {
+    /// The raw line, such as `  mov $1,$0 ; comment`.
+    pub line: String,
+
+    /// The comment after the instruction, including the `;`, such as `; comment`.
+    pub comment: Option<String>,
+
+    /// The comment lines right before the instruction, such as `; the algorithm`.
+    ///
+    /// An empty string represents a blank line between comments or instructions.
+    pub leading_comments: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
 pub struct Instruction {
     pub instruction_id: InstructionId,
     pub parameter_vec: Vec<InstructionParameter>,
     pub line_number: usize,
+
+    /// The source code of the instruction, when it was created by the parser.
+    ///
+    /// Instructions that are created programmatically, such as by the miner, have no source.
+    pub source: Option<InstructionSource>,
+}
+
+impl PartialEq for Instruction {
+    /// The `source` is ignored, so that programs that only differ in comments are equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.instruction_id == other.instruction_id &&
+            self.parameter_vec == other.parameter_vec &&
+            self.line_number == other.line_number
+    }
 }
 
 impl fmt::Display for Instruction {
@@ -34,7 +63,8 @@ mod tests {
             parameter_vec: vec![
                 InstructionParameter::new(ParameterType::Direct, 0),
                 InstructionParameter::new(ParameterType::Constant, 1)], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
         assert_eq!(instance, instance);
     }
@@ -46,14 +76,16 @@ mod tests {
             parameter_vec: vec![
                 InstructionParameter::new(ParameterType::Direct, 0),
                 InstructionParameter::new(ParameterType::Constant, 1)], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
         let instance1 = Instruction { 
             instruction_id: InstructionId::Subtract, 
             parameter_vec: vec![
                 InstructionParameter::new(ParameterType::Direct, 0),
                 InstructionParameter::new(ParameterType::Constant, 1)], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
         assert_ne!(instance0, instance1);
     }
@@ -65,25 +97,47 @@ mod tests {
             parameter_vec: vec![
                 InstructionParameter::new(ParameterType::Direct, 0),
                 InstructionParameter::new(ParameterType::Constant, 1)], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
         let instance1 = Instruction { 
             instruction_id: InstructionId::Add, 
             parameter_vec: vec![
                 InstructionParameter::new(ParameterType::Direct, 0),
                 InstructionParameter::new(ParameterType::Constant, 2)], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
         assert_ne!(instance0, instance1);
     }
 
+    #[test]
+    fn test_10003_equal_ignores_source() {
+        let instance0 = Instruction {
+            instruction_id: InstructionId::Add,
+            parameter_vec: vec![
+                InstructionParameter::new(ParameterType::Direct, 0),
+                InstructionParameter::new(ParameterType::Constant, 1)],
+                line_number: 0,
+                source: None,
+            };
+        let mut instance1 = instance0.clone();
+        instance1.source = Some(InstructionSource {
+            line: "add $0,1 ; increment".to_string(),
+            comment: Some("; increment".to_string()),
+            leading_comments: vec!["; comment".to_string()],
+        });
+        assert_eq!(instance0, instance1);
+    }
+
     #[test]
     fn test_20000_to_string() {
         {
             let instruction = Instruction { 
                 instruction_id: InstructionId::LoopEnd, 
                 parameter_vec: vec!(), 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
             assert_eq!(instruction.to_string(), "lpe");
         }
@@ -93,7 +147,8 @@ mod tests {
                 parameter_vec: vec![
                     InstructionParameter::new(ParameterType::Direct, 11),
                 ], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
             assert_eq!(instruction.to_string(), "lpb $11");
         }
@@ -104,7 +159,8 @@ mod tests {
                     InstructionParameter::new(ParameterType::Indirect, 5),
                     InstructionParameter::new(ParameterType::Constant, 10051)
                 ], 
-                line_number: 0 
+                line_number: 0,
+                source: None,
             };        
             assert_eq!(instruction.to_string(), "seq $$5,10051");
         }
@@ -0,0 +1,444 @@
+use std::fmt;
+use super::{Instruction, InstructionId, ParsedProgram, ParseProgramError};
+use super::{ParseInstructionId, ParseParametersError, remove_comment};
+use super::parse_program::ParsedLine;
+use super::validate_loops::ValidateLoopError;
+
+/// The deepest nesting of loops that is allowed.
+const MAX_ALLOWED_NESTING_LEVEL: usize = 255;
+
+/// Suggest a mnemonic when it is a single edit away from the mistyped mnemonic, such as `addd` or `mvo`.
+const MAX_SUGGESTION_DISTANCE: usize = 1;
+
+/// The mnemonics that are suggested for mistyped instructions.
+static SUGGESTED_INSTRUCTION_IDS: &[InstructionId] = &[
+    InstructionId::Add,
+    InstructionId::BitwiseAnd,
+    InstructionId::Binomial,
+    InstructionId::BitwiseOr,
+    InstructionId::BitwiseXor,
+    InstructionId::Compare,
+    InstructionId::DigitalRoot,
+    InstructionId::DigitSum,
+    InstructionId::DivideIf,
+    InstructionId::Divide,
+    InstructionId::Equal,
+    InstructionId::GCD,
+    InstructionId::GreaterOrEqual,
+    InstructionId::LessOrEqual,
+    InstructionId::Logarithm,
+    InstructionId::LoopBegin,
+    InstructionId::LoopEnd,
+    InstructionId::Max,
+    InstructionId::Min,
+    InstructionId::Modulo,
+    InstructionId::Move,
+    InstructionId::Multiply,
+    InstructionId::NotEqual,
+    InstructionId::NthRoot,
+    InstructionId::Power,
+    InstructionId::EvalSequence,
+    InstructionId::Subtract,
+    InstructionId::Truncate,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticCategory {
+    /// The line cannot be split into an instruction and parameters.
+    SyntaxError,
+
+    /// The instruction is not recognized, such as `addd`.
+    UnknownMnemonic,
+
+    /// A parameter is malformed, such as `$x` or `00`.
+    BadParameter,
+
+    /// A `lpb` without `lpe`, a `lpe` without `lpb`, or too deep nesting.
+    UnbalancedLoop,
+
+    /// A malformed `#offset`, or an `#offset` that is misplaced.
+    BadOffset,
+}
+
+impl fmt::Display for DiagnosticCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name: &str = match self {
+            Self::SyntaxError => "syntax error",
+            Self::UnknownMnemonic => "unknown mnemonic",
+            Self::BadParameter => "bad parameter",
+            Self::UnbalancedLoop => "unbalanced loop",
+            Self::BadOffset => "bad offset",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A problem in the source code, with a location that an editor can highlight.
+///
+/// Both `line` and `column` starts at `1`. The `end_column` is exclusive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub category: DiagnosticCategory,
+    pub line: usize,
+    pub column: usize,
+    pub end_column: usize,
+    pub message: String,
+
+    /// The replacement for the highlighted text, such as `add` for `addd`.
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.category, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " Did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl ParsedProgram {
+    /// Parse the program and collect all the problems, instead of stopping at the first problem.
+    ///
+    /// Returns `Err` with the problems sorted by line and column.
+    pub fn parse_with_diagnostics(raw_input: &str) -> Result<ParsedProgram, Vec<Diagnostic>> {
+        let mut diagnostics: Vec<Diagnostic> = vec!();
+        let mut optional_offset: Option<i32> = None;
+        let mut instruction_vec: Vec<Instruction> = vec!();
+
+        // The loop instructions with their spans, also those with bad parameters.
+        let mut loop_instructions: Vec<(InstructionId, usize, usize, usize)> = vec!();
+        let mut has_instructions = false;
+
+        for (index, raw_input_line) in raw_input.split("\n").enumerate() {
+            let line_number: usize = index + 1;
+            let (code_begin, code_end) = code_span(raw_input_line);
+            let parsed_line = ParsedLine::parse(raw_input_line, line_number);
+            for (error, span) in &parsed_line.errors {
+                let (column, end_column, text) = match span {
+                    Some(span) => {
+                        let code: &str = &parsed_line.code;
+                        (column(code, span.start), column(code, span.end), &code[span.clone()])
+                    },
+                    None => (code_begin, code_end, parsed_line.code.as_str())
+                };
+                let (category, message, suggestion) = describe_error(error, text);
+                diagnostics.push(Diagnostic {
+                    category,
+                    line: line_number,
+                    column,
+                    end_column,
+                    message,
+                    suggestion,
+                });
+            }
+            let diagnostic_for_line = |category: DiagnosticCategory, message: &str| -> Diagnostic {
+                Diagnostic {
+                    category,
+                    line: line_number,
+                    column: code_begin,
+                    end_column: code_end,
+                    message: message.to_string(),
+                    suggestion: None,
+                }
+            };
+
+            if parsed_line.is_offset {
+                if let Some(value) = parsed_line.offset {
+                    if optional_offset.is_some() {
+                        diagnostics.push(diagnostic_for_line(DiagnosticCategory::BadOffset, "The #offset must occur only once."));
+                    } else if has_instructions {
+                        diagnostics.push(diagnostic_for_line(DiagnosticCategory::BadOffset, "The #offset must occur before the instructions."));
+                    }
+                    optional_offset = Some(value);
+                }
+                continue;
+            }
+            if parsed_line.code.is_empty() {
+                continue;
+            }
+            has_instructions = true;
+
+            let instruction_id: InstructionId = match parsed_line.instruction_id {
+                Some(value) => value,
+                None => continue
+            };
+            match instruction_id {
+                InstructionId::LoopBegin | InstructionId::UnofficialLoopBeginSubtract | InstructionId::LoopEnd => {
+                    loop_instructions.push((instruction_id, line_number, code_begin, code_end));
+                },
+                _ => {}
+            }
+            if parsed_line.errors.is_empty() {
+                let instruction = Instruction {
+                    instruction_id,
+                    parameter_vec: parsed_line.parameter_vec,
+                    line_number,
+                    source: None,
+                };
+                instruction_vec.push(instruction);
+            }
+        }
+
+        diagnostics.append(&mut diagnostics_for_loops(&loop_instructions));
+        if !diagnostics.is_empty() {
+            diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+            return Err(diagnostics);
+        }
+        let parsed_program = Self {
+            optional_offset,
+            instruction_vec,
+            header_comments: vec!(),
+            trailing_comments: vec!(),
+        };
+        Ok(parsed_program)
+    }
+}
+
+/// Find all the unbalanced `lpb` and `lpe` instructions.
+fn diagnostics_for_loops(loop_instructions: &[(InstructionId, usize, usize, usize)]) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = vec!();
+    let mut stack: Vec<(usize, usize, usize)> = vec!();
+    let mut has_reported_too_deep = false;
+    for (instruction_id, line, column, end_column) in loop_instructions {
+        let message: String = match instruction_id {
+            InstructionId::LoopEnd => {
+                if stack.pop().is_some() {
+                    continue;
+                }
+                format!("{} There is no 'lpb' for this 'lpe'.", ValidateLoopError::EndingTooSoon)
+            },
+            _ => {
+                stack.push((*line, *column, *end_column));
+                if stack.len() <= MAX_ALLOWED_NESTING_LEVEL || has_reported_too_deep {
+                    continue;
+                }
+                has_reported_too_deep = true;
+                ValidateLoopError::TooDeep.to_string()
+            }
+        };
+        diagnostics.push(Diagnostic {
+            category: DiagnosticCategory::UnbalancedLoop,
+            line: *line,
+            column: *column,
+            end_column: *end_column,
+            message,
+            suggestion: None,
+        });
+    }
+    for (line, column, end_column) in stack {
+        diagnostics.push(Diagnostic {
+            category: DiagnosticCategory::UnbalancedLoop,
+            line,
+            column,
+            end_column,
+            message: format!("{} There is no 'lpe' for this 'lpb'.", ValidateLoopError::Unbalanced),
+            suggestion: None,
+        });
+    }
+    diagnostics
+}
+
+/// The category, message and suggestion for a problem, where `text` is the code that caused the problem.
+fn describe_error(error: &ParseProgramError, text: &str) -> (DiagnosticCategory, String, Option<String>) {
+    match error {
+        ParseProgramError::ExtractOffset(_) => {
+            (DiagnosticCategory::BadOffset, "Invalid #offset syntax. Expected '#offset' followed by an integer.".to_string(), None)
+        },
+        ParseProgramError::SyntaxError(_) => {
+            (DiagnosticCategory::SyntaxError, "Expected an instruction followed by parameters.".to_string(), suggestion_for_syntax_error(text))
+        },
+        ParseProgramError::ParseInstructionId(_) => {
+            (DiagnosticCategory::UnknownMnemonic, format!("Unrecognized instruction '{}'.", text), suggest_mnemonic(text))
+        },
+        ParseProgramError::ParseParameters(error) => {
+            (DiagnosticCategory::BadParameter, parameter_error_message(error).to_string(), suggestion_for_parameter(error, text))
+        },
+    }
+}
+
+/// The column of a byte index in a line. The first column is `1`.
+fn column(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count() + 1
+}
+
+/// The columns of the code in a line, excluding indentation and comment.
+fn code_span(line: &str) -> (usize, usize) {
+    let code = remove_comment(line);
+    let code_trimmed_end: &str = code.trim_end();
+    let end: usize = code_trimmed_end.chars().count();
+    let begin: usize = code_trimmed_end.chars().take_while(|c| c.is_whitespace()).count();
+    (begin + 1, end.max(begin) + 1)
+}
+
+fn parameter_error_message(error: &ParseParametersError) -> &'static str {
+    match error {
+        ParseParametersError::EmptyParameter(_) => "Empty parameter.",
+        ParseParametersError::UnrecognizedParameter(_) => "Unrecognized parameter. Expected a constant, a register such as '$1', or an indirect register such as '$$1'.",
+        ParseParametersError::UnrecognizedParameterType(_) => "Unrecognized parameter type. Expected no prefix, '$' or '$$'.",
+        ParseParametersError::UnrecognizedParameterValue(_) => "Unrecognized parameter value.",
+        ParseParametersError::StrictIncorrectParameterValue(_) => "Redundant leading zeros or minus sign in the parameter value.",
+        ParseParametersError::NegativeValueNotAllowedForThisParameterType(_) => "Negative register index is not allowed.",
+    }
+}
+
+/// Suggest `$1` for `$01`, and `0` for `-0`.
+fn suggestion_for_parameter(error: &ParseParametersError, parameter: &str) -> Option<String> {
+    if let ParseParametersError::StrictIncorrectParameterValue(_) = error {
+        let value_string: &str = parameter.trim_start_matches('$');
+        let prefix: &str = &parameter[..(parameter.len() - value_string.len())];
+        let value: i64 = value_string.parse::<i64>().ok()?;
+        return Some(format!("{}{}", prefix, value));
+    }
+    None
+}
+
+/// Suggest `add` for `ADD $0,1`.
+fn suggestion_for_syntax_error(line: &str) -> Option<String> {
+    let word: &str = line.split_whitespace().next()?;
+    let lowercase: String = word.to_lowercase();
+    if lowercase == word {
+        return None;
+    }
+    if InstructionId::parse(&lowercase, 0).is_ok() {
+        return Some(lowercase);
+    }
+    None
+}
+
+/// Find the mnemonic that is closest to the mistyped mnemonic.
+fn suggest_mnemonic(mnemonic: &str) -> Option<String> {
+    let mut best: Option<(usize, String)> = None;
+    for instruction_id in SUGGESTED_INSTRUCTION_IDS {
+        let candidate: String = instruction_id.to_string();
+        let distance: usize = edit_distance(mnemonic, &candidate);
+        if distance > MAX_SUGGESTION_DISTANCE {
+            continue;
+        }
+        if best.as_ref().map(|(best_distance, _)| distance < *best_distance).unwrap_or(true) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// The edit distance between two strings, where swapping two adjacent characters counts as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distance: Vec<Vec<usize>> = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, value) in distance[0].iter_mut().enumerate() {
+        *value = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost: usize = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut value: usize = (distance[i - 1][j - 1] + cost)
+                .min(distance[i - 1][j] + 1)
+                .min(distance[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(distance[i - 2][j - 2] + 1);
+            }
+            distance[i][j] = value;
+        }
+    }
+    distance[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(input: &str) -> Vec<String> {
+        match ParsedProgram::parse_with_diagnostics(input) {
+            Ok(_) => vec!(),
+            Err(diagnostics) => {
+                diagnostics.iter().map(|diagnostic| {
+                    format!("{}:{}-{} {}", diagnostic.line, diagnostic.column, diagnostic.end_column, diagnostic)
+                }).collect()
+            }
+        }
+    }
+
+    #[test]
+    fn test_10000_ok() {
+        // Arrange
+        let source = "#offset 1\n; comment\nmov $1,$0\nlpb $0\n  sub $0,1 ; decrement\nlpe";
+
+        // Act
+        let parsed_program: ParsedProgram = ParsedProgram::parse_with_diagnostics(source).expect("ok");
+
+        // Assert
+        assert_eq!(parsed_program, ParsedProgram::parse_program(source).expect("ok"));
+    }
+
+    #[test]
+    fn test_20000_unknown_mnemonic() {
+        assert_eq!(process("  addd $0,1"), vec!["1:3-7 unknown mnemonic: Unrecognized instruction 'addd'. Did you mean 'add'?"]);
+        assert_eq!(process("mvo $0,1"), vec!["1:1-4 unknown mnemonic: Unrecognized instruction 'mvo'. Did you mean 'mov'?"]);
+        assert_eq!(process("boom $0"), vec!["1:1-5 unknown mnemonic: Unrecognized instruction 'boom'."]);
+    }
+
+    #[test]
+    fn test_20001_syntax_error() {
+        assert_eq!(process("ADD $0,1"), vec!["1:1-9 syntax error: Expected an instruction followed by parameters. Did you mean 'add'?"]);
+        assert_eq!(process("\tmov$0"), vec!["1:2-7 syntax error: Expected an instruction followed by parameters."]);
+    }
+
+    #[test]
+    fn test_20002_bad_parameter() {
+        assert_eq!(process("mov $x,1"), vec!["1:5-7 bad parameter: Unrecognized parameter. Expected a constant, a register such as '$1', or an indirect register such as '$$1'."]);
+        assert_eq!(process("mov $1, $$$3"), vec!["1:9-13 bad parameter: Unrecognized parameter type. Expected no prefix, '$' or '$$'."]);
+        assert_eq!(process("mov $01,-0"), vec![
+            "1:5-8 bad parameter: Redundant leading zeros or minus sign in the parameter value. Did you mean '$1'?",
+            "1:9-11 bad parameter: Redundant leading zeros or minus sign in the parameter value. Did you mean '0'?",
+        ]);
+        assert_eq!(process("mov $0,,1"), vec!["1:8-9 bad parameter: Empty parameter."]);
+        assert_eq!(process("mov $-3,1"), vec!["1:5-8 bad parameter: Negative register index is not allowed."]);
+    }
+
+    #[test]
+    fn test_20003_unbalanced_loop() {
+        assert_eq!(process("lpe\nlpb $0\n  lpb $1\n  lpe"), vec![
+            "1:1-4 unbalanced loop: Premature ending of loop in root scope. There is no 'lpb' for this 'lpe'.",
+            "2:1-7 unbalanced loop: Unbalanced number of 'lpb' and 'lpe' encounted. There is no 'lpe' for this 'lpb'.",
+        ]);
+    }
+
+    #[test]
+    fn test_20004_bad_offset() {
+        assert_eq!(process("#offset x"), vec!["1:1-10 bad offset: Invalid #offset syntax. Expected '#offset' followed by an integer."]);
+        assert_eq!(process("#offset 1\n#offset 2"), vec!["2:1-10 bad offset: The #offset must occur only once."]);
+        assert_eq!(process("mov $0,1\n#offset 2"), vec!["2:1-10 bad offset: The #offset must occur before the instructions."]);
+    }
+
+    #[test]
+    fn test_30000_multiple_errors() {
+        // Arrange
+        let source = "#offset 1\nmvo $0,1\nlpb $0\n  sub $0,$x\n  ADD $1,1\nlpe\nlpe";
+
+        // Act
+        let lines: Vec<String> = process(source);
+
+        // Assert
+        assert_eq!(lines, vec![
+            "2:1-4 unknown mnemonic: Unrecognized instruction 'mvo'. Did you mean 'mov'?",
+            "4:10-12 bad parameter: Unrecognized parameter. Expected a constant, a register such as '$1', or an indirect register such as '$$1'.",
+            "5:3-11 syntax error: Expected an instruction followed by parameters. Did you mean 'add'?",
+            "7:1-4 unbalanced loop: Premature ending of loop in root scope. There is no 'lpb' for this 'lpe'.",
+        ]);
+    }
+
+    #[test]
+    fn test_40000_edit_distance() {
+        assert_eq!(edit_distance("add", "add"), 0);
+        assert_eq!(edit_distance("addd", "add"), 1);
+        assert_eq!(edit_distance("mvo", "mov"), 1);
+        assert_eq!(edit_distance("mvo", "bxo"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
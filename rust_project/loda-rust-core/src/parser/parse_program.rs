@@ -1,20 +1,46 @@
 use std::fmt;
-use super::{EXTRACT_ROW_RE,Instruction,InstructionParameter,ParameterType,ParseParametersError,parse_parameters,remove_comment,extract_offset,ExtractOffsetError};
+use std::ops::Range;
+use super::{EXTRACT_ROW_RE,Instruction,InstructionParameter,InstructionSource,formatted_program,ParameterType,ParseParametersError,parse_parameters,remove_comment,extract_offset,ExtractOffsetError};
 use super::{InstructionId,ParseInstructionIdError,ParseInstructionId};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct ParsedProgram {
     pub optional_offset: Option<i32>,
     pub instruction_vec: Vec<Instruction>,
+
+    /// The comments at the top of the program, until the first blank line, such as `; A000045: Fibonacci numbers.`
+    pub header_comments: Vec<String>,
+
+    /// The comments after the last instruction.
+    /// 
+    /// An empty string represents a blank line between comments.
+    pub trailing_comments: Vec<String>,
 }
 
 impl ParsedProgram {
     pub fn new() -> Self {
         Self {
             optional_offset: None,
-            instruction_vec: vec!()
+            instruction_vec: vec!(),
+            header_comments: vec!(),
+            trailing_comments: vec!(),
         }
     }
+
+    /// The program with its comments, formatted the same way as the programs in the `loda-programs` repository.
+    /// 
+    /// Unlike `to_string()`, this preserves the header, the comments between the instructions and the comments after the instructions.
+    pub fn to_string_with_comments(&self) -> String {
+        formatted_program(self, None)
+    }
+}
+
+impl PartialEq for ParsedProgram {
+    /// The comments are ignored, so that programs that only differ in comments are equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.optional_offset == other.optional_offset &&
+            self.instruction_vec == other.instruction_vec
+    }
 }
 
 impl fmt::Display for ParsedProgram {
@@ -50,6 +76,25 @@ impl fmt::Display for ParseProgramError {
     }
 }
 
+impl ParseProgramError {
+    /// The line where the problem occurred. The first line is `1`.
+    pub fn line_number(&self) -> usize {
+        match self {
+            Self::SyntaxError(line_number) => *line_number,
+            Self::ParseInstructionId(ParseInstructionIdError::UnrecognizedInstructionId(line_number)) => *line_number,
+            Self::ParseParameters(error) => match error {
+                ParseParametersError::EmptyParameter(line_number) => *line_number,
+                ParseParametersError::UnrecognizedParameter(line_number) => *line_number,
+                ParseParametersError::UnrecognizedParameterType(line_number) => *line_number,
+                ParseParametersError::UnrecognizedParameterValue(line_number) => *line_number,
+                ParseParametersError::StrictIncorrectParameterValue(line_number) => *line_number,
+                ParseParametersError::NegativeValueNotAllowedForThisParameterType(line_number) => *line_number,
+            },
+            Self::ExtractOffset(ExtractOffsetError::InvalidSyntax(line_number)) => *line_number,
+        }
+    }
+}
+
 impl From<ParseInstructionIdError> for ParseProgramError {
     fn from(err: ParseInstructionIdError) -> ParseProgramError {
         ParseProgramError::ParseInstructionId(err)
@@ -68,20 +113,124 @@ impl From<ExtractOffsetError> for ParseProgramError {
     }
 }
 
+/// A single line of source code, parsed without stopping at the first problem in the line.
+///
+/// Both `parse_program()` and `parse_with_diagnostics()` parse the lines with this.
+pub(crate) struct ParsedLine {
+    /// The line without the comment and without trailing whitespace.
+    pub code: String,
+
+    /// The line starts with `#offset`.
+    pub is_offset: bool,
+
+    /// The value of a valid `#offset` line.
+    pub offset: Option<i32>,
+
+    /// The instruction, or `None` for a line without code, or with an unrecognized mnemonic.
+    pub instruction_id: Option<InstructionId>,
+
+    /// The parameters that could be parsed.
+    pub parameter_vec: Vec<InstructionParameter>,
+
+    /// The problems in the order they occur, with the byte range in `code` that caused them.
+    ///
+    /// The range is `None` when the entire line is to blame.
+    pub errors: Vec<(ParseProgramError, Option<Range<usize>>)>,
+}
+
+impl ParsedLine {
+    pub fn parse(raw_input_line: &str, line_number: usize) -> Self {
+        let code: String = remove_comment(raw_input_line).trim_end().to_string();
+        let mut parsed_line = Self {
+            code: String::new(),
+            is_offset: false,
+            offset: None,
+            instruction_id: None,
+            parameter_vec: vec!(),
+            errors: vec!(),
+        };
+
+        // Extract the `#offset` if present.
+        match extract_offset(raw_input_line, line_number) {
+            Ok((_, Some(value))) => {
+                parsed_line.is_offset = true;
+                parsed_line.offset = Some(value);
+            },
+            Ok((_, None)) => {
+                parsed_line.parse_instruction(&code, line_number);
+            },
+            Err(error) => {
+                parsed_line.is_offset = true;
+                parsed_line.errors.push((error.into(), None));
+            }
+        }
+        parsed_line.code = code;
+        parsed_line
+    }
+
+    fn parse_instruction(&mut self, code: &str, line_number: usize) {
+        if code.is_empty() {
+            return;
+        }
+        let captures = match EXTRACT_ROW_RE.captures(code) {
+            Some(value) => value,
+            None => {
+                self.errors.push((ParseProgramError::SyntaxError(line_number), None));
+                return;
+            }
+        };
+        if let Some(m) = captures.get(1) {
+            match InstructionId::parse(m.as_str(), line_number) {
+                Ok(value) => self.instruction_id = Some(value),
+                Err(error) => self.errors.push((error.into(), Some(m.range())))
+            }
+        }
+        let m = match captures.get(2) {
+            Some(value) => value,
+            None => return
+        };
+        let parameter_string: &str = m.as_str().trim_end();
+        if parameter_string.is_empty() {
+            // There are instructions that takes 0 parameters, so this case is fine.
+            return;
+        }
+        let mut item_start: usize = m.start();
+        for item in parameter_string.split(",") {
+            let item_end: usize = item_start + item.len();
+            let item_trimmed: &str = item.trim();
+            if item_trimmed.is_empty() {
+                let span_end: usize = item_end.max(item_start + 1).min(code.len());
+                self.errors.push((ParseParametersError::EmptyParameter(line_number).into(), Some(item_start..span_end)));
+            } else {
+                let trimmed_start: usize = item_start + (item.len() - item.trim_start().len());
+                match parse_parameters(item_trimmed, line_number) {
+                    Ok(mut parameters) => self.parameter_vec.append(&mut parameters),
+                    Err(error) => self.errors.push((error.into(), Some(trimmed_start..(trimmed_start + item_trimmed.len()))))
+                }
+            }
+            // Skip past the comma
+            item_start = item_end + 1;
+        }
+    }
+}
+
 impl ParsedProgram {
     /// Returns `Ok` if the program can be parsed.
     /// 
     /// Returns `Err` if there is a problem during parsing.
+    ///
+    /// The comments are not preserved, use `parse_program_with_source()` for that.
     pub fn parse_program(raw_input: &str) -> Result<ParsedProgram, ParseProgramError> {
-        let re = &EXTRACT_ROW_RE;
         let mut optional_offset: Option<i32> = None;
         let mut instruction_vec: Vec<Instruction> = vec!();
         for (index, raw_input_line) in raw_input.split("\n").enumerate() {
             let line_number: usize = index + 1;
+            let parsed_line = ParsedLine::parse(raw_input_line, line_number);
+            if let Some((error, _)) = parsed_line.errors.into_iter().next() {
+                return Err(error);
+            }
 
-            // Extract the `#offset` if present.
-            let (line0, extracted_offset) = extract_offset(raw_input_line, line_number)?;
-            if let Some(value) = extracted_offset {
+            if let Some(value) = parsed_line.offset {
                 if optional_offset.is_some() {
                     // The `#offset` line must occur only once.
                     return Err(ParseProgramError::SyntaxError(line_number));
@@ -93,48 +242,106 @@ impl ParsedProgram {
                 optional_offset = Some(value);
                 continue;
             }
-    
-            // Discard comments and trim the line.
-            let line1 = remove_comment(&line0);
-            let line2: &str = line1.trim_end();
-            if line2.is_empty() {
-                // skip lines without code
-                // if it's a line with just a comment, then skip the line.
-                // if it's a line with just blank spaces, then skip the line.
-                continue;
-            }
-    
-            // Parse the instruction
-            let captures = match re.captures(line2) {
+
+            let instruction_id: InstructionId = match parsed_line.instruction_id {
                 Some(value) => value,
                 None => {
-                    return Err(ParseProgramError::SyntaxError(line_number));
+                    // skip lines without code
+                    // if it's a line with just a comment, then skip the line.
+                    // if it's a line with just blank spaces, then skip the line.
+                    continue;
                 }
             };
-            let instruction_raw: &str = captures.get(1).map_or("", |m| m.as_str());
-            let parameter_string: &str = captures.get(2).map_or("", |m| m.as_str());
-    
-            let instruction_id: InstructionId = 
-                InstructionId::parse(instruction_raw, line_number)?;
-    
-            let parameter_vec: Vec<InstructionParameter> = 
-                parse_parameters(parameter_string, line_number)?;
-    
             let instruction = Instruction {
                 instruction_id: instruction_id,
-                parameter_vec: parameter_vec,
+                parameter_vec: parsed_line.parameter_vec,
                 line_number: line_number,
+                source: None,
             };
             instruction_vec.push(instruction);
         }
     
         let parsed_program = Self {
             optional_offset: optional_offset,
-            instruction_vec: instruction_vec
+            instruction_vec: instruction_vec,
+            header_comments: vec!(),
+            trailing_comments: vec!(),
         };
         Ok(parsed_program)
     }    
 
+    /// Same as `parse_program()`, and the comments and the source lines are preserved with `assign_source()`.
+    ///
+    /// Usecase:
+    /// Round-tripping a program, such as when formatting or minimizing, without losing the human annotations.
+    pub fn parse_program_with_source(raw_input: &str) -> Result<ParsedProgram, ParseProgramError> {
+        let mut parsed_program: ParsedProgram = Self::parse_program(raw_input)?;
+        parsed_program.assign_source(raw_input);
+        Ok(parsed_program)
+    }
+
+    /// Attach the source lines and the comments to the instructions that were parsed from `raw_input`.
+    /// 
+    /// The comments at the top, until the first blank line, become the `header_comments`.
+    /// The comments before an instruction become its `leading_comments`.
+    /// The comments after the last instruction become the `trailing_comments`.
+    /// Multiple blank lines between comments and instructions are collapsed into a single blank line.
+    pub fn assign_source(&mut self, raw_input: &str) {
+        let mut header_comments: Vec<String> = vec!();
+        let mut pending_comments: Vec<String> = vec!();
+        let mut is_header = true;
+        let mut has_instructions = false;
+        let mut pending_blank_line = false;
+        let mut instruction_index: usize = 0;
+        for (index, raw_input_line) in raw_input.split("\n").enumerate() {
+            let line_number: usize = index + 1;
+            let line: &str = raw_input_line.trim();
+            if let Some(instruction) = self.instruction_vec.get_mut(instruction_index) {
+                if instruction.line_number == line_number {
+                    instruction_index += 1;
+                    is_header = false;
+                    has_instructions = true;
+                    if pending_blank_line {
+                        pending_comments.push(String::new());
+                        pending_blank_line = false;
+                    }
+                    let comment: Option<String> = line.find(';').map(|comment_index| line[comment_index..].to_string());
+                    instruction.source = Some(InstructionSource {
+                        line: raw_input_line.trim_end().to_string(),
+                        comment,
+                        leading_comments: std::mem::take(&mut pending_comments),
+                    });
+                    continue;
+                }
+            }
+            if line.is_empty() {
+                if !header_comments.is_empty() {
+                    is_header = false;
+                }
+                if has_instructions || !pending_comments.is_empty() {
+                    pending_blank_line = true;
+                }
+                continue;
+            }
+            if line.starts_with("#offset") {
+                is_header = false;
+                continue;
+            }
+            // A line with just a comment
+            if is_header {
+                header_comments.push(line.to_string());
+                continue;
+            }
+            if pending_blank_line {
+                pending_comments.push(String::new());
+                pending_blank_line = false;
+            }
+            pending_comments.push(line.to_string());
+        }
+        self.header_comments = header_comments;
+        self.trailing_comments = pending_comments;
+    }
+
     /// The direct dependencies that this program depends on.
     /// 
     /// This doesn't include the indirect dependencies.
@@ -359,4 +566,92 @@ mod tests {
         parsed_program1.assign_zero_line_numbers();
         assert_ne!(parsed_program0, parsed_program1);
     }
+
+    #[test]
+    fn test_10010_equal_ignores_comments() {
+        let mut parsed_program0: ParsedProgram = ParsedProgram::parse_program(
+            "; A000045\n\nmov $1,1 ; one\n; loop\nlpb $0\nsub $0,1\nlpe\n; tail").unwrap();
+        let mut parsed_program1: ParsedProgram = ParsedProgram::parse_program(
+            "mov $1,1\nlpb $0\nsub $0,1\nlpe").unwrap();
+        parsed_program0.assign_zero_line_numbers();
+        parsed_program1.assign_zero_line_numbers();
+        assert_eq!(parsed_program0, parsed_program1);
+    }
+
+    #[test]
+    fn test_30000_comments_header() {
+        // Arrange
+        let input = "; A000045: Fibonacci\n; 0,1,1,2\n\n; not header\n#offset 1\nmov $1,1";
+
+        // Act
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source(input).unwrap();
+
+        // Assert
+        assert_eq!(parsed_program.header_comments, vec!["; A000045: Fibonacci", "; 0,1,1,2"]);
+        let source: &InstructionSource = parsed_program.instruction_vec[0].source.as_ref().expect("source");
+        assert_eq!(source.leading_comments, vec!["; not header"]);
+        assert_eq!(parsed_program.trailing_comments.is_empty(), true);
+    }
+
+    #[test]
+    fn test_30001_comments_instructions() {
+        // Arrange
+        let input = "mov $1,$0   ; first  \n\n\n; comment a\n\n; comment b\n  add $1,1\n\n; tail a\n; tail b\n\n";
+
+        // Act
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source(input).unwrap();
+
+        // Assert
+        assert_eq!(parsed_program.header_comments.is_empty(), true);
+        let source0: &InstructionSource = parsed_program.instruction_vec[0].source.as_ref().expect("source");
+        assert_eq!(source0.line, "mov $1,$0   ; first");
+        assert_eq!(source0.comment, Some("; first".to_string()));
+        assert_eq!(source0.leading_comments.is_empty(), true);
+        let source1: &InstructionSource = parsed_program.instruction_vec[1].source.as_ref().expect("source");
+        assert_eq!(source1.line, "  add $1,1");
+        assert_eq!(source1.comment, None);
+        assert_eq!(source1.leading_comments, vec!["", "; comment a", "", "; comment b"]);
+        assert_eq!(parsed_program.trailing_comments, vec!["", "; tail a", "; tail b"]);
+    }
+
+    #[test]
+    fn test_30002_comments_only() {
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source("; a\n; b\n\n; c\n\n\n; d").unwrap();
+        assert_eq!(parsed_program.header_comments, vec!["; a", "; b"]);
+        assert_eq!(parsed_program.trailing_comments, vec!["; c", "", "; d"]);
+    }
+
+    #[test]
+    fn test_30003_to_string_with_comments() {
+        // Arrange
+        let input = "; A000045: Fibonacci\n\n#offset 1\n\nmov $3,1 ; init\nlpb $0\n  ; inside loop\n  sub $0,1\nlpe\n\n; tail\n";
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program_with_source(input).unwrap();
+
+        // Act
+        let actual: String = parsed_program.to_string_with_comments();
+
+        // Assert
+        assert_eq!(actual, input);
+        assert_eq!(parsed_program.to_string(), "#offset 1\nmov $3,1\nlpb $0\nsub $0,1\nlpe");
+    }
+
+    #[test]
+    fn test_30004_parse_program_without_source() {
+        let parsed_program: ParsedProgram = ParsedProgram::parse_program("; A000045\n\nmov $1,1 ; one\n; tail").unwrap();
+        assert_eq!(parsed_program.header_comments.is_empty(), true);
+        assert_eq!(parsed_program.instruction_vec[0].source, None);
+        assert_eq!(parsed_program.trailing_comments.is_empty(), true);
+    }
+
+    #[test]
+    fn test_20000_error_line_number() {
+        let line_number = |input: &str| -> usize {
+            ParsedProgram::parse_program_with_source(input).expect_err("error").line_number()
+        };
+        assert_eq!(line_number("mov $0,1\n  boom $0,1"), 2);
+        assert_eq!(line_number("mov $0,1\nmov $0,1\nmov $0,$$$"), 3);
+        assert_eq!(line_number("#offset x"), 1);
+        assert_eq!(line_number("mov $0,1\n#offset 1"), 2);
+        assert_eq!(line_number("mov $0,,1"), 1);
+    }
 }
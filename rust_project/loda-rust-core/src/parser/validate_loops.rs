@@ -59,6 +59,7 @@ mod tests {
                 instruction_id: *instruction_id,
                 parameter_vec: vec!(),
                 line_number: index + 1,
+                source: None,
             };
             instructions.push(instruction);
         }
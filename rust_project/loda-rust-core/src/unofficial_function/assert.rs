@@ -98,6 +98,14 @@ impl UnofficialFunction for AssertFunction {
             }
         }
     }
+
+    fn is_minable(&self) -> bool {
+        false
+    }
+
+    fn has_side_effect(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -28,6 +28,14 @@ impl UnofficialFunction for DebugFunction {
         }
         Ok(vec!())
     }
+
+    fn is_minable(&self) -> bool {
+        false
+    }
+
+    fn has_side_effect(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,143 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use super::number_theory_util::{factorize, input_u64, FACTORIZE_MAX};
+use num_bigint::BigInt;
+
+pub enum FactorizationFunctionMode {
+    /// Euler's totient function `phi(n)`, the number of integers in `[1..n]` that are coprime to `n`.
+    EulerPhi,
+
+    /// Möbius function `mu(n)`, 0 when `n` has a squared prime factor, otherwise `(-1)^k` where `k` is the number of prime factors.
+    Moebius,
+
+    /// Number of prime factors of `n`, counted with multiplicity. Also known as `bigomega(n)`.
+    PrimeFactorCount,
+
+    /// Number of distinct prime factors of `n`. Also known as `omega(n)`.
+    DistinctPrimeFactorCount,
+
+    /// Largest prime factor of `n`, where `1` is used for `n = 1`.
+    LargestPrimeFactor,
+}
+
+/// Functions that are computed from the prime factorization of the input.
+///
+/// The input must be in the range `[1..2^40]`.
+pub struct FactorizationFunction {
+    id: u32,
+    mode: FactorizationFunctionMode,
+}
+
+impl FactorizationFunction {
+    pub fn new(id: u32, mode: FactorizationFunctionMode) -> Self {
+        Self {
+            id,
+            mode,
+        }
+    }
+}
+
+impl UnofficialFunction for FactorizationFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 1, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        match self.mode {
+            FactorizationFunctionMode::EulerPhi => "Euler totient function phi(n).".to_string(),
+            FactorizationFunctionMode::Moebius => "Moebius function mu(n).".to_string(),
+            FactorizationFunctionMode::PrimeFactorCount => "Number of prime factors of n, counted with multiplicity.".to_string(),
+            FactorizationFunctionMode::DistinctPrimeFactorCount => "Number of distinct prime factors of n.".to_string(),
+            FactorizationFunctionMode::LargestPrimeFactor => "Largest prime factor of n.".to_string(),
+        }
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 1 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let n: u64 = input_u64(&input[0], 1, FACTORIZE_MAX, "Factorization input")?;
+        let factors: Vec<(u64, u32)> = factorize(n);
+        let value: i64 = match self.mode {
+            FactorizationFunctionMode::EulerPhi => {
+                let mut result: u64 = n;
+                for (prime, _exponent) in &factors {
+                    result = result / prime * (prime - 1);
+                }
+                result as i64
+            },
+            FactorizationFunctionMode::Moebius => {
+                if factors.iter().any(|(_prime, exponent)| *exponent > 1) {
+                    0
+                } else if factors.len().is_multiple_of(2) {
+                    1
+                } else {
+                    -1
+                }
+            },
+            FactorizationFunctionMode::PrimeFactorCount => {
+                factors.iter().map(|(_prime, exponent)| *exponent as i64).sum()
+            },
+            FactorizationFunctionMode::DistinctPrimeFactorCount => {
+                factors.len() as i64
+            },
+            FactorizationFunctionMode::LargestPrimeFactor => {
+                match factors.last() {
+                    Some((prime, _exponent)) => *prime as i64,
+                    None => 1
+                }
+            },
+        };
+        Ok(vec![BigInt::from(value)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+    use num_traits::ToPrimitive;
+
+    fn run_terms(mode: FactorizationFunctionMode, count: i32) -> Vec<i64> {
+        let f: Box<dyn UnofficialFunction> = Box::new(FactorizationFunction::new(0, mode));
+        (1..=count).map(|input| {
+            let output: Vec<BigInt> = f.run(vec![BigInt::from(input)]).expect("output");
+            output[0].to_i64().unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_10000_euler_phi() {
+        // A000010
+        assert_eq!(run_terms(FactorizationFunctionMode::EulerPhi, 12), vec![1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+    }
+
+    #[test]
+    fn test_20000_moebius() {
+        // A008683
+        assert_eq!(run_terms(FactorizationFunctionMode::Moebius, 12), vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+    }
+
+    #[test]
+    fn test_30000_prime_factor_count() {
+        // A001222
+        assert_eq!(run_terms(FactorizationFunctionMode::PrimeFactorCount, 12), vec![0, 1, 1, 2, 1, 2, 1, 3, 2, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_40000_distinct_prime_factor_count() {
+        // A001221
+        assert_eq!(run_terms(FactorizationFunctionMode::DistinctPrimeFactorCount, 12), vec![0, 1, 1, 1, 1, 2, 1, 1, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn test_50000_largest_prime_factor() {
+        // A006530
+        assert_eq!(run_terms(FactorizationFunctionMode::LargestPrimeFactor, 12), vec![1, 2, 3, 2, 5, 3, 7, 2, 3, 5, 11, 3]);
+    }
+
+    #[test]
+    fn test_60000_out_of_range() {
+        assert_eq!(perform_run(Box::new(FactorizationFunction::new(0, FactorizationFunctionMode::EulerPhi)), vec![0]).is_err(), true);
+        assert_eq!(perform_run(Box::new(FactorizationFunction::new(0, FactorizationFunctionMode::EulerPhi)), vec![-5]).is_err(), true);
+    }
+}
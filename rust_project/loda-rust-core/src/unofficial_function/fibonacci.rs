@@ -0,0 +1,118 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use super::number_theory_util::input_i64;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+pub enum FibonacciFunctionMode {
+    /// Fibonacci numbers, `F(0) = 0, F(1) = 1`.
+    Fibonacci,
+
+    /// Lucas numbers, `L(0) = 2, L(1) = 1`.
+    Lucas,
+}
+
+/// Fibonacci and Lucas numbers, also for negative `n`.
+///
+/// The absolute value of the input must be less than or equal to `100000`.
+pub struct FibonacciFunction {
+    id: u32,
+    mode: FibonacciFunctionMode,
+}
+
+impl FibonacciFunction {
+    pub const N_MAX: u64 = 100000;
+
+    pub fn new(id: u32, mode: FibonacciFunctionMode) -> Self {
+        Self {
+            id,
+            mode,
+        }
+    }
+
+    /// Fast doubling, returns `(F(n), F(n+1))`.
+    fn fibonacci_pair(n: u64) -> (BigInt, BigInt) {
+        if n == 0 {
+            return (BigInt::zero(), BigInt::one());
+        }
+        let (a, b) = Self::fibonacci_pair(n / 2);
+        // F(2k) = F(k) * (2*F(k+1) - F(k))
+        let c: BigInt = &a * (&b * 2 - &a);
+        // F(2k+1) = F(k)^2 + F(k+1)^2
+        let d: BigInt = &a * &a + &b * &b;
+        if n.is_multiple_of(2) {
+            (c, d)
+        } else {
+            let e: BigInt = &c + &d;
+            (d, e)
+        }
+    }
+}
+
+impl UnofficialFunction for FibonacciFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 1, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        match self.mode {
+            FibonacciFunctionMode::Fibonacci => "Fibonacci number F(n).".to_string(),
+            FibonacciFunctionMode::Lucas => "Lucas number L(n).".to_string(),
+        }
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 1 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let n: i64 = input_i64(&input[0], Self::N_MAX, "Fibonacci input")?;
+        let n_abs: u64 = n.unsigned_abs();
+        let (f0, f1) = Self::fibonacci_pair(n_abs);
+        let (value, negate) = match self.mode {
+            // F(-n) = (-1)^(n+1) * F(n)
+            FibonacciFunctionMode::Fibonacci => (f0, n < 0 && n_abs.is_multiple_of(2)),
+            // L(n) = F(n-1) + F(n+1) = 2*F(n+1) - F(n), L(-n) = (-1)^n * L(n)
+            FibonacciFunctionMode::Lucas => (f1 * 2 - f0, n < 0 && n_abs % 2 == 1),
+        };
+        if negate {
+            return Ok(vec![-value]);
+        }
+        Ok(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+
+    fn run_terms(mode: FibonacciFunctionMode, inputs: std::ops::Range<i32>) -> Vec<i32> {
+        let f: Box<dyn UnofficialFunction> = Box::new(FibonacciFunction::new(0, mode));
+        inputs.map(|n| {
+            let output: Vec<BigInt> = f.run(vec![BigInt::from(n)]).expect("output");
+            output[0].to_string().parse::<i32>().unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_10000_fibonacci() {
+        assert_eq!(run_terms(FibonacciFunctionMode::Fibonacci, -6..10), vec![-8, 5, -3, 2, -1, 1, 0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn test_10001_fibonacci_big() {
+        let f = FibonacciFunction::new(0, FibonacciFunctionMode::Fibonacci);
+        let output: Vec<BigInt> = f.run(vec![BigInt::from(100)]).expect("output");
+        assert_eq!(output[0].to_string(), "354224848179261915075");
+    }
+
+    #[test]
+    fn test_20000_lucas() {
+        assert_eq!(run_terms(FibonacciFunctionMode::Lucas, -5..10), vec![-11, 7, -4, 3, -1, 2, 1, 3, 4, 7, 11, 18, 29, 47, 76]);
+    }
+
+    #[test]
+    fn test_30000_out_of_range() {
+        assert_eq!(perform_run(Box::new(FibonacciFunction::new(0, FibonacciFunctionMode::Fibonacci)), vec![100001]).is_err(), true);
+        assert_eq!(perform_run(Box::new(FibonacciFunction::new(0, FibonacciFunctionMode::Lucas)), vec![-100001]).is_err(), true);
+    }
+}
@@ -2,8 +2,17 @@
 
 mod assert;
 mod debug;
+mod factorization;
+mod fibonacci;
+mod modular;
+mod number_theory_util;
+mod partition;
+mod prime;
 mod product;
 mod register_common_functions;
+mod scripted_function;
+mod scripted_function_manifest;
+mod sigma;
 mod sort;
 mod sum;
 mod test_common_functions;
@@ -14,11 +23,19 @@ mod unofficial_function_registry;
 
 pub use assert::{AssertFunction, AssertFunctionMode};
 pub use debug::DebugFunction;
+pub use factorization::{FactorizationFunction, FactorizationFunctionMode};
+pub use fibonacci::{FibonacciFunction, FibonacciFunctionMode};
+pub use modular::{ModularInverseFunction, ModularPowerFunction};
+pub use partition::PartitionFunction;
+pub use prime::{PrimeFunction, PrimeFunctionMode};
 pub use product::ProductFunction;
 pub use register_common_functions::register_common_functions;
+pub use scripted_function::ScriptedFunction;
+pub use scripted_function_manifest::{ScriptedFunctionDefinition, ScriptedFunctionManifest};
+pub use sigma::SigmaFunction;
 pub use sort::SortFunction;
 pub use sum::SumFunction;
 pub use test_util::perform_run;
 pub use unofficial_function::UnofficialFunction;
 pub use unofficial_function_id::UnofficialFunctionId;
-pub use unofficial_function_registry::UnofficialFunctionRegistry;
+pub use unofficial_function_registry::{UnofficialFunctionRegistry, WeakUnofficialFunctionRegistry};
@@ -0,0 +1,144 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+/// Inputs with more bits than this are rejected, so the time spent is bounded.
+const MAX_BITS: u64 = 1024;
+
+fn check_bits(value: &BigInt, name: &str) -> anyhow::Result<()> {
+    if value.bits() > MAX_BITS {
+        return Err(anyhow::anyhow!("{} must have at most {} bits, but got {} bits", name, MAX_BITS, value.bits()));
+    }
+    Ok(())
+}
+
+fn check_modulus(modulus: &BigInt) -> anyhow::Result<()> {
+    check_bits(modulus, "Modulus")?;
+    if !modulus.is_positive() {
+        return Err(anyhow::anyhow!("Modulus must be positive, but got {}", modulus));
+    }
+    Ok(())
+}
+
+/// Modular multiplicative inverse, `x` such that `a*x = 1 (mod m)`, where `0 <= x < m`.
+///
+/// Fails when `a` and `m` are not coprime.
+pub struct ModularInverseFunction {
+    id: u32,
+}
+
+impl ModularInverseFunction {
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+        }
+    }
+}
+
+impl UnofficialFunction for ModularInverseFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 2, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        "Modular inverse of input[0] modulo input[1].".to_string()
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 2 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let a: &BigInt = &input[0];
+        let modulus: &BigInt = &input[1];
+        check_bits(a, "Value")?;
+        check_modulus(modulus)?;
+        let extended = a.mod_floor(modulus).extended_gcd(modulus);
+        if !extended.gcd.is_one() {
+            return Err(anyhow::anyhow!("{} has no inverse modulo {}", a, modulus));
+        }
+        Ok(vec![extended.x.mod_floor(modulus)])
+    }
+}
+
+/// Modular exponentiation, `base^exponent mod m`, where the result is in the range `[0..m-1]`.
+pub struct ModularPowerFunction {
+    id: u32,
+}
+
+impl ModularPowerFunction {
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+        }
+    }
+}
+
+impl UnofficialFunction for ModularPowerFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 3, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        "Modular power, input[0] raised to input[1] modulo input[2].".to_string()
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 3 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let base: &BigInt = &input[0];
+        let exponent: &BigInt = &input[1];
+        let modulus: &BigInt = &input[2];
+        check_bits(base, "Base")?;
+        check_bits(exponent, "Exponent")?;
+        check_modulus(modulus)?;
+        if exponent.is_negative() {
+            return Err(anyhow::anyhow!("Exponent must be non-negative, but got {}", exponent));
+        }
+        if modulus.is_one() {
+            return Ok(vec![BigInt::zero()]);
+        }
+        let value: BigInt = base.mod_floor(modulus).modpow(exponent, modulus);
+        Ok(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+
+    #[test]
+    fn test_10000_modular_inverse() {
+        let f = || Box::new(ModularInverseFunction::new(0));
+        assert_eq!(perform_run(f(), vec![3, 11]).expect("output"), vec![4]);
+        assert_eq!(perform_run(f(), vec![-3, 11]).expect("output"), vec![7]);
+        assert_eq!(perform_run(f(), vec![10, 17]).expect("output"), vec![12]);
+        assert_eq!(perform_run(f(), vec![5, 1]).expect("output"), vec![0]);
+    }
+
+    #[test]
+    fn test_10001_modular_inverse_error() {
+        let f = || Box::new(ModularInverseFunction::new(0));
+        assert_eq!(perform_run(f(), vec![4, 10]).is_err(), true);
+        assert_eq!(perform_run(f(), vec![3, 0]).is_err(), true);
+        assert_eq!(perform_run(f(), vec![3, -11]).is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_modular_power() {
+        let f = || Box::new(ModularPowerFunction::new(0));
+        assert_eq!(perform_run(f(), vec![2, 10, 1000]).expect("output"), vec![24]);
+        assert_eq!(perform_run(f(), vec![-2, 3, 7]).expect("output"), vec![6]);
+        assert_eq!(perform_run(f(), vec![5, 0, 7]).expect("output"), vec![1]);
+        assert_eq!(perform_run(f(), vec![5, 0, 1]).expect("output"), vec![0]);
+    }
+
+    #[test]
+    fn test_20001_modular_power_error() {
+        let f = || Box::new(ModularPowerFunction::new(0));
+        assert_eq!(perform_run(f(), vec![2, -1, 7]).is_err(), true);
+        assert_eq!(perform_run(f(), vec![2, 3, 0]).is_err(), true);
+    }
+}
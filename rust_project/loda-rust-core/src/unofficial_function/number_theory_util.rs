@@ -0,0 +1,217 @@
+//! Shared helpers for the number theory functions.
+//!
+//! The functions are used while mining, where the inputs are arbitrary values.
+//! Every function has an upper limit on its inputs, so that the time spent is bounded,
+//! and the output can be checked by `NodeRegisterLimit::LimitBits` afterwards.
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+
+/// Trial division is used for factorization, so the limit is kept low.
+pub const FACTORIZE_MAX: u64 = 1 << 40;
+
+lazy_static! {
+    /// Prime numbers in ascending order, computed on demand.
+    static ref PRIME_TABLE: RwLock<PrimeTable> = RwLock::new(PrimeTable::new());
+}
+
+struct PrimeTable {
+    sieve_limit: u64,
+    primes: Vec<u32>,
+}
+
+impl PrimeTable {
+    fn new() -> Self {
+        Self {
+            sieve_limit: 0,
+            primes: vec!(),
+        }
+    }
+
+    /// Sieve of Eratosthenes, for all the numbers less than or equal to `limit`.
+    fn sieve(&mut self, limit: u64) {
+        assert!(limit <= (u32::MAX as u64));
+        let length: usize = (limit as usize) + 1;
+        let mut is_composite: Vec<bool> = vec![false; length];
+        let mut primes: Vec<u32> = vec!();
+        for i in 2..length {
+            if is_composite[i] {
+                continue;
+            }
+            primes.push(i as u32);
+            let mut j: usize = i * i;
+            while j < length {
+                is_composite[j] = true;
+                j += i;
+            }
+        }
+        self.sieve_limit = limit;
+        self.primes = primes;
+    }
+}
+
+/// Invoke the closure with all the prime numbers less than or equal to `limit`, and possibly more.
+pub fn with_primes<F, R>(limit: u64, f: F) -> R where F: FnOnce(&[u32]) -> R {
+    {
+        let table = PRIME_TABLE.read().expect("PRIME_TABLE RwLock poisoned");
+        if table.sieve_limit >= limit {
+            return f(&table.primes);
+        }
+    }
+    let mut table = PRIME_TABLE.write().expect("PRIME_TABLE RwLock poisoned");
+    if table.sieve_limit < limit {
+        // Grow in big steps, to avoid sieving many times.
+        let new_limit: u64 = limit.max(1 << 16).next_power_of_two();
+        table.sieve(new_limit);
+    }
+    f(&table.primes)
+}
+
+/// Prime factorization, as pairs of `(prime, exponent)` in ascending order.
+///
+/// The input must be in the range `[1..FACTORIZE_MAX]`.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    assert!((1..=FACTORIZE_MAX).contains(&n));
+    let sqrt_limit: u64 = (n as f64).sqrt() as u64 + 1;
+    with_primes(sqrt_limit, |primes| {
+        let mut result: Vec<(u64, u32)> = vec!();
+        let mut remaining: u64 = n;
+        for prime in primes {
+            let p: u64 = *prime as u64;
+            if p * p > remaining {
+                break;
+            }
+            let mut exponent: u32 = 0;
+            while remaining.is_multiple_of(p) {
+                remaining /= p;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                result.push((p, exponent));
+            }
+        }
+        if remaining > 1 {
+            result.push((remaining, 1));
+        }
+        result
+    })
+}
+
+/// Deterministic Miller-Rabin primality test, for all 64 bit numbers.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for base in BASES {
+        if n.is_multiple_of(base) {
+            return n == base;
+        }
+    }
+    let mut d: u64 = n - 1;
+    let mut s: u32 = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    'outer: for base in BASES {
+        let mut x: u64 = pow_mod(base, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % (m as u128)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result: u64 = 1;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Convert the input to `u64` and check that it's in the range `[min..max]`.
+pub fn input_u64(value: &BigInt, min: u64, max: u64, name: &str) -> anyhow::Result<u64> {
+    let error = || anyhow::anyhow!("{} must be in the range [{}..{}], but got {}", name, min, max, value);
+    if value.is_negative() {
+        return Err(error());
+    }
+    let value_u64: u64 = value.to_u64().ok_or_else(error)?;
+    if value_u64 < min || value_u64 > max {
+        return Err(error());
+    }
+    Ok(value_u64)
+}
+
+/// Convert the input to `i64` and check that the absolute value is less than or equal to `max`.
+pub fn input_i64(value: &BigInt, max: u64, name: &str) -> anyhow::Result<i64> {
+    let error = || anyhow::anyhow!("{} must be in the range [-{}..{}], but got {}", name, max, max, value);
+    let value_i64: i64 = value.to_i64().ok_or_else(error)?;
+    if value_i64.unsigned_abs() > max {
+        return Err(error());
+    }
+    Ok(value_i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_10000_factorize() {
+        assert_eq!(factorize(1), vec!());
+        assert_eq!(factorize(2), vec![(2, 1)]);
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1000003), vec![(1000003, 1)]);
+        assert_eq!(factorize(1000003 * 1000033), vec![(1000003, 1), (1000033, 1)]);
+    }
+
+    #[test]
+    fn test_20000_is_prime() {
+        let primes: Vec<u64> = (0..50).filter(|n| is_prime(*n)).collect();
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]);
+        assert_eq!(is_prime(1000003), true);
+        assert_eq!(is_prime(1000003 * 1000033), false);
+        assert_eq!(is_prime(18446744073709551557), true);
+        assert_eq!(is_prime(3215031751), false); // Strong pseudoprime to the bases 2, 3, 5 and 7
+    }
+
+    #[test]
+    fn test_30000_with_primes() {
+        let count: usize = with_primes(100, |primes| primes.iter().filter(|p| **p <= 100).count());
+        assert_eq!(count, 25);
+    }
+
+    #[test]
+    fn test_40000_input_u64() {
+        assert_eq!(input_u64(&BigInt::from(5), 1, 10, "n").unwrap(), 5);
+        assert_eq!(input_u64(&BigInt::from(0), 1, 10, "n").is_err(), true);
+        assert_eq!(input_u64(&BigInt::from(11), 1, 10, "n").is_err(), true);
+        assert_eq!(input_u64(&BigInt::from(-1), 0, 10, "n").is_err(), true);
+    }
+
+    #[test]
+    fn test_40001_input_i64() {
+        assert_eq!(input_i64(&BigInt::from(-5), 10, "n").unwrap(), -5);
+        assert_eq!(input_i64(&BigInt::from(-11), 10, "n").is_err(), true);
+        assert_eq!(input_i64(&BigInt::from(11), 10, "n").is_err(), true);
+    }
+}
@@ -0,0 +1,122 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use super::number_theory_util::input_u64;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+
+lazy_static! {
+    /// The partition numbers computed so far, `p(0), p(1), p(2), ...`
+    static ref PARTITION_TABLE: RwLock<Vec<BigInt>> = RwLock::new(vec![BigInt::one()]);
+}
+
+/// Number of partitions of `n`, where `p(n) = 0` for negative `n`.
+///
+/// The input must be less than or equal to `10000`.
+pub struct PartitionFunction {
+    id: u32,
+}
+
+impl PartitionFunction {
+    pub const N_MAX: u64 = 10000;
+
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+        }
+    }
+
+    /// Euler's pentagonal number theorem.
+    /// `p(n) = sum_{k>=1} (-1)^(k+1) * (p(n - k(3k-1)/2) + p(n - k(3k+1)/2))`
+    fn extend_table(table: &mut Vec<BigInt>, n: usize) {
+        while table.len() <= n {
+            let m: usize = table.len();
+            let mut sum = BigInt::zero();
+            let mut k: usize = 1;
+            loop {
+                let pentagonal0: usize = k * (3 * k - 1) / 2;
+                if pentagonal0 > m {
+                    break;
+                }
+                let mut term: BigInt = table[m - pentagonal0].clone();
+                let pentagonal1: usize = k * (3 * k + 1) / 2;
+                if pentagonal1 <= m {
+                    term += &table[m - pentagonal1];
+                }
+                if k % 2 == 1 {
+                    sum += term;
+                } else {
+                    sum -= term;
+                }
+                k += 1;
+            }
+            table.push(sum);
+        }
+    }
+}
+
+impl UnofficialFunction for PartitionFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 1, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        "Number of partitions of n.".to_string()
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 1 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        if input[0].is_negative() {
+            return Ok(vec![BigInt::zero()]);
+        }
+        let n: usize = input_u64(&input[0], 0, Self::N_MAX, "Partition input")? as usize;
+        let mut table: Vec<BigInt> = {
+            let table = PARTITION_TABLE.read().expect("PARTITION_TABLE RwLock poisoned");
+            if let Some(value) = table.get(n) {
+                return Ok(vec![value.clone()]);
+            }
+            table.clone()
+        };
+
+        // Compute without holding the lock, so other threads can look up values meanwhile.
+        Self::extend_table(&mut table, n);
+        let value: BigInt = table[n].clone();
+
+        // Another thread may have extended the table in the meantime, only append the missing values.
+        let mut shared_table = PARTITION_TABLE.write().expect("PARTITION_TABLE RwLock poisoned");
+        if shared_table.len() < table.len() {
+            let start: usize = shared_table.len();
+            shared_table.extend(table.drain(start..));
+        }
+        Ok(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+
+    #[test]
+    fn test_10000_partition() {
+        // A000041
+        let terms: Vec<i32> = (-1..15).map(|n| {
+            perform_run(Box::new(PartitionFunction::new(0)), vec![n]).expect("output")[0]
+        }).collect();
+        assert_eq!(terms, vec![0, 1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42, 56, 77, 101, 135]);
+    }
+
+    #[test]
+    fn test_10001_partition_big() {
+        let f = PartitionFunction::new(0);
+        let output: Vec<BigInt> = f.run(vec![BigInt::from(100)]).expect("output");
+        assert_eq!(output[0].to_string(), "190569292");
+    }
+
+    #[test]
+    fn test_20000_out_of_range() {
+        assert_eq!(perform_run(Box::new(PartitionFunction::new(0)), vec![10001]).is_err(), true);
+    }
+}
@@ -0,0 +1,127 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use super::number_theory_util::{input_u64, is_prime, with_primes};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+pub enum PrimeFunctionMode {
+    /// Returns 1 when the input is a prime number, otherwise 0.
+    IsPrime,
+
+    /// The n-th prime number, where `prime(1) = 2`.
+    NthPrime,
+
+    /// The number of primes less than or equal to the input.
+    PrimePi,
+}
+
+pub struct PrimeFunction {
+    id: u32,
+    mode: PrimeFunctionMode,
+}
+
+impl PrimeFunction {
+    pub const NTH_PRIME_MAX: u64 = 1000000;
+    pub const PRIME_PI_MAX: u64 = 1 << 24;
+
+    pub fn new(id: u32, mode: PrimeFunctionMode) -> Self {
+        Self {
+            id,
+            mode,
+        }
+    }
+
+    fn is_prime(value: &BigInt) -> anyhow::Result<BigInt> {
+        if value.is_negative() {
+            return Ok(BigInt::zero());
+        }
+        let n: u64 = value.to_u64()
+            .ok_or_else(|| anyhow::anyhow!("IsPrime input must fit in 64 bits, but got {}", value))?;
+        let result: u8 = if is_prime(n) { 1 } else { 0 };
+        Ok(BigInt::from(result))
+    }
+
+    fn nth_prime(value: &BigInt) -> anyhow::Result<BigInt> {
+        let n: u64 = input_u64(value, 1, Self::NTH_PRIME_MAX, "NthPrime input")?;
+        // Upper bound for the n-th prime: n * (ln(n) + ln(ln(n))), for n >= 6.
+        let nf: f64 = (n.max(6)) as f64;
+        let limit: u64 = (nf * (nf.ln() + nf.ln().ln())).ceil() as u64;
+        let prime: u32 = with_primes(limit, |primes| primes[(n - 1) as usize]);
+        Ok(BigInt::from(prime))
+    }
+
+    fn prime_pi(value: &BigInt) -> anyhow::Result<BigInt> {
+        if value.is_negative() {
+            return Ok(BigInt::zero());
+        }
+        let n: u64 = input_u64(value, 0, Self::PRIME_PI_MAX, "PrimePi input")?;
+        let count: usize = with_primes(n, |primes| primes.partition_point(|p| (*p as u64) <= n));
+        Ok(BigInt::from(count))
+    }
+}
+
+impl UnofficialFunction for PrimeFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 1, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        match self.mode {
+            PrimeFunctionMode::IsPrime => "Is prime, 1 if prime, otherwise 0.".to_string(),
+            PrimeFunctionMode::NthPrime => "The n-th prime number, prime(1) = 2.".to_string(),
+            PrimeFunctionMode::PrimePi => "Number of primes less than or equal to n.".to_string(),
+        }
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 1 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let value: BigInt = match self.mode {
+            PrimeFunctionMode::IsPrime => Self::is_prime(&input[0])?,
+            PrimeFunctionMode::NthPrime => Self::nth_prime(&input[0])?,
+            PrimeFunctionMode::PrimePi => Self::prime_pi(&input[0])?,
+        };
+        Ok(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+
+    fn run_terms(mode: PrimeFunctionMode, inputs: &[i32]) -> Vec<i32> {
+        let f = PrimeFunction::new(0, mode);
+        let f: Box<dyn UnofficialFunction> = Box::new(f);
+        inputs.iter().map(|input| {
+            let output: Vec<BigInt> = f.run(vec![BigInt::from(*input)]).expect("output");
+            output[0].to_i32().unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_10000_is_prime() {
+        assert_eq!(run_terms(PrimeFunctionMode::IsPrime, &[-7, 0, 1, 2, 3, 4, 5, 97, 100]), vec![0, 0, 0, 1, 1, 0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_20000_nth_prime() {
+        assert_eq!(run_terms(PrimeFunctionMode::NthPrime, &[1, 2, 3, 4, 5, 6, 25, 1000]), vec![2, 3, 5, 7, 11, 13, 97, 7919]);
+    }
+
+    #[test]
+    fn test_20001_nth_prime_out_of_range() {
+        assert_eq!(perform_run(Box::new(PrimeFunction::new(0, PrimeFunctionMode::NthPrime)), vec![0]).is_err(), true);
+        assert_eq!(perform_run(Box::new(PrimeFunction::new(0, PrimeFunctionMode::NthPrime)), vec![1000001]).is_err(), true);
+    }
+
+    #[test]
+    fn test_30000_prime_pi() {
+        assert_eq!(run_terms(PrimeFunctionMode::PrimePi, &[-5, 0, 1, 2, 3, 10, 100, 1000]), vec![0, 0, 0, 1, 2, 4, 25, 168]);
+    }
+
+    #[test]
+    fn test_30001_prime_pi_out_of_range() {
+        assert_eq!(perform_run(Box::new(PrimeFunction::new(0, PrimeFunctionMode::PrimePi)), vec![(1 << 24) + 1]).is_err(), true);
+    }
+}
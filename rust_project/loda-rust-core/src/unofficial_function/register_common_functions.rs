@@ -1,4 +1,6 @@
 use super::{AssertFunction, AssertFunctionMode, DebugFunction, ProductFunction, SortFunction, SumFunction, UnofficialFunctionRegistry};
+use super::{FactorizationFunction, FactorizationFunctionMode, FibonacciFunction, FibonacciFunctionMode};
+use super::{ModularInverseFunction, ModularPowerFunction, PartitionFunction, PrimeFunction, PrimeFunctionMode, SigmaFunction};
 use std::sync::Arc;
 
 pub fn register_common_functions(registry: &UnofficialFunctionRegistry) {
@@ -40,4 +42,24 @@ pub fn register_common_functions(registry: &UnofficialFunctionRegistry) {
         register_function!(AssertFunction::new(1034, AssertFunctionMode::GreaterThan));
         register_function!(AssertFunction::new(1035, AssertFunctionMode::GreaterThanOrEqual));
     }
+
+    // Number theory functions
+    // The ids are stable, since they are used in programs.
+    // The inputs have upper limits, so they are safe to use while mining.
+    {
+        register_function!(PrimeFunction::new(1100, PrimeFunctionMode::IsPrime));
+        register_function!(PrimeFunction::new(1101, PrimeFunctionMode::NthPrime));
+        register_function!(PrimeFunction::new(1102, PrimeFunctionMode::PrimePi));
+        register_function!(FactorizationFunction::new(1103, FactorizationFunctionMode::EulerPhi));
+        register_function!(SigmaFunction::new(1104));
+        register_function!(FactorizationFunction::new(1105, FactorizationFunctionMode::Moebius));
+        register_function!(FactorizationFunction::new(1106, FactorizationFunctionMode::PrimeFactorCount));
+        register_function!(FactorizationFunction::new(1107, FactorizationFunctionMode::DistinctPrimeFactorCount));
+        register_function!(FactorizationFunction::new(1108, FactorizationFunctionMode::LargestPrimeFactor));
+        register_function!(PartitionFunction::new(1109));
+        register_function!(FibonacciFunction::new(1110, FibonacciFunctionMode::Fibonacci));
+        register_function!(FibonacciFunction::new(1111, FibonacciFunctionMode::Lucas));
+        register_function!(ModularInverseFunction::new(1112));
+        register_function!(ModularPowerFunction::new(1113));
+    }
 }
@@ -0,0 +1,303 @@
+use super::{ScriptedFunctionDefinition, UnofficialFunction, UnofficialFunctionId, WeakUnofficialFunctionRegistry};
+use crate::control::{DependencyManager, DependencyManagerFileSystemMode};
+use crate::execute::{NodeLoopLimit, NodeRegisterLimit, ProgramCache, ProgramId, ProgramRunner, ProgramState, RunMode};
+use anyhow::Context;
+use cached::{Cached, SizedCache};
+use num_bigint::BigInt;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The max number of compiled programs per thread.
+///
+/// Registries are created and dropped over time, so the compiled programs of deallocated functions
+/// gets evicted, when the cache is full.
+const RUNNER_CACHE_CAPACITY: usize = 1000;
+
+thread_local! {
+    /// The `ProgramRunner` is not thread safe, so each thread compiles its own copy of the program.
+    static RUNNER_CACHE: RefCell<SizedCache<u64, Rc<ProgramRunner>>> = RefCell::new(SizedCache::with_size(RUNNER_CACHE_CAPACITY));
+
+    /// Shared by all the scripted functions that runs on this thread.
+    /// When a scripted function calls another scripted function, then the inner function uses its own cache.
+    static PROGRAM_CACHE: RefCell<Option<ProgramCache>> = const { RefCell::new(None) };
+}
+
+/// An unofficial function, where the implementation is a LODA program, instead of Rust code.
+///
+/// The input values are placed in the registers `$0`, `$1`, `$2`, etc.
+///
+/// The output values are read from the `output_registers`.
+pub struct ScriptedFunction {
+    instance_id: u64,
+    id: u32,
+    inputs: u8,
+    output_registers: Vec<u64>,
+    name: String,
+    program_content: String,
+    step_count_limit: u64,
+    loda_programs_oeis_dir: PathBuf,
+    registry: WeakUnofficialFunctionRegistry,
+}
+
+impl ScriptedFunction {
+    pub const DEFAULT_STEP_COUNT_LIMIT: u64 = 1000000;
+
+    /// The `loda_programs_oeis_dir` is used for resolving `seq` instructions.
+    ///
+    /// The `registry` is used for resolving `f` instructions, so a scripted function can call other unofficial functions.
+    pub fn new(
+        definition: &ScriptedFunctionDefinition,
+        program_content: String,
+        loda_programs_oeis_dir: PathBuf,
+        registry: WeakUnofficialFunctionRegistry
+    ) -> Self {
+        let output_registers: Vec<u64> = definition.resolved_output_registers();
+        assert!(output_registers.len() <= (u8::MAX as usize));
+        Self {
+            instance_id: NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed),
+            id: definition.id,
+            inputs: definition.inputs,
+            output_registers,
+            name: definition.resolved_name(),
+            program_content,
+            step_count_limit: definition.resolved_step_count_limit(),
+            loda_programs_oeis_dir,
+            registry,
+        }
+    }
+
+    /// Compile the program.
+    ///
+    /// This fails if the program cannot be parsed, if a `seq` dependency is missing,
+    /// or if a `f` instruction refers to an unknown function.
+    pub fn create_runner(&self) -> anyhow::Result<ProgramRunner> {
+        let registry = self.registry.upgrade()
+            .context("ScriptedFunction the UnofficialFunctionRegistry has been deallocated")?;
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::System,
+            self.loda_programs_oeis_dir.clone(),
+            registry,
+        );
+        dm.parse(ProgramId::ProgramWithoutId, &self.program_content)
+            .map_err(|error| anyhow::anyhow!("ScriptedFunction {:?} cannot create program. error: {}", self.id(), error))
+    }
+
+    /// The steps are added to the `step_count`, so the caller is charged for the steps.
+    fn run_inner(&self, input: Vec<BigInt>, state: ProgramState, step_count: &mut u64) -> anyhow::Result<Vec<BigInt>> {
+        let runner: Rc<ProgramRunner> = self.runner()?;
+        let mut cache: ProgramCache = PROGRAM_CACHE.with(|cache| cache.borrow_mut().take())
+            .unwrap_or_else(ProgramCache::new);
+        let result = runner.run_vec_with_state(
+            input,
+            state,
+            step_count,
+            &mut cache,
+            &self.output_registers,
+        );
+        PROGRAM_CACHE.with(|cell| {
+            cell.borrow_mut().replace(cache);
+        });
+        result.with_context(|| format!("ScriptedFunction {:?} failed", self.id()))
+    }
+
+    fn runner(&self) -> anyhow::Result<Rc<ProgramRunner>> {
+        let optional_runner: Option<Rc<ProgramRunner>> = RUNNER_CACHE.with(|cache| {
+            cache.borrow_mut().cache_get(&self.instance_id).cloned()
+        });
+        if let Some(runner) = optional_runner {
+            return Ok(runner);
+        }
+        let runner = Rc::new(self.create_runner()?);
+        RUNNER_CACHE.with(|cache| {
+            cache.borrow_mut().cache_set(self.instance_id, runner.clone());
+        });
+        Ok(runner)
+    }
+}
+
+impl UnofficialFunction for ScriptedFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput {
+            id: self.id,
+            inputs: self.inputs,
+            outputs: self.output_registers.len() as u8
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        let state = ProgramState::new(
+            RunMode::Silent,
+            self.step_count_limit,
+            NodeRegisterLimit::Unlimited,
+            NodeLoopLimit::Unlimited,
+        );
+        let mut step_count: u64 = 0;
+        self.run_inner(input, state, &mut step_count)
+    }
+
+    /// The steps are charged to the caller, and the caller's step count limit and cancellation applies.
+    /// The `step_count_limit` of the function applies to each call.
+    fn run_with_state(&self, input: Vec<BigInt>, state: &mut ProgramState) -> anyhow::Result<Vec<BigInt>> {
+        let mut step_count: u64 = state.step_count();
+        let step_count_limit: u64 = state.step_count_limit().min(step_count.saturating_add(self.step_count_limit));
+        let mut inner_state = ProgramState::new(
+            RunMode::Silent,
+            step_count_limit,
+            state.node_register_limit().clone(),
+            state.node_loop_limit().clone(),
+        );
+        inner_state.set_cancel_check(state.cancel_check().cloned());
+        let result = self.run_inner(input, inner_state, &mut step_count);
+
+        // Update statistics, no matter if run succeeded or failed
+        state.set_step_count(step_count);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{perform_run, register_common_functions, UnofficialFunctionRegistry};
+    use crate::execute::{EvalError, RegisterValue};
+    use std::sync::Arc;
+
+    fn definition(inputs: u8, output_registers: Vec<u64>, step_count_limit: Option<u64>) -> ScriptedFunctionDefinition {
+        ScriptedFunctionDefinition {
+            id: 1234,
+            inputs,
+            outputs: output_registers.len() as u8,
+            file: "test.asm".to_string(),
+            name: Some("test".to_string()),
+            output_registers: Some(output_registers),
+            step_count_limit,
+        }
+    }
+
+    fn create(registry: &UnofficialFunctionRegistry, inputs: u8, output_registers: Vec<u64>, program: &str) -> ScriptedFunction {
+        ScriptedFunction::new(
+            &definition(inputs, output_registers, None),
+            program.to_string(),
+            PathBuf::from("non-existing-dir"),
+            registry.downgrade()
+        )
+    }
+
+    #[test]
+    fn test_10000_one_output() {
+        let registry = UnofficialFunctionRegistry::new();
+        let f = create(&registry, 2, vec![0], "mul $0,$1");
+        let v = perform_run(Box::new(f), vec![6, 7]).expect("output");
+        assert_eq!(v, vec![42]);
+    }
+
+    #[test]
+    fn test_10001_output_registers() {
+        let registry = UnofficialFunctionRegistry::new();
+        let f = create(&registry, 2, vec![2, 3], "mov $2,$0\nadd $2,$1\nmov $3,$0\nsub $3,$1");
+        let v = perform_run(Box::new(f), vec![10, 3]).expect("output");
+        assert_eq!(v, vec![13, 7]);
+    }
+
+    #[test]
+    fn test_10002_calls_another_unofficial_function() {
+        let registry = UnofficialFunctionRegistry::new();
+        register_common_functions(&registry);
+        let f = create(&registry, 3, vec![0], "f31 $0,1000 ; Sum of 3 values\nmul $0,2");
+        let v = perform_run(Box::new(f), vec![1, 10, 100]).expect("output");
+        assert_eq!(v, vec![222]);
+    }
+
+    #[test]
+    fn test_20000_step_count_limit_exceeded() {
+        let registry = UnofficialFunctionRegistry::new();
+        let f = ScriptedFunction::new(
+            &definition(1, vec![0], Some(10)),
+            "lpb $0\n  sub $0,1\nlpe".to_string(),
+            PathBuf::from("non-existing-dir"),
+            registry.downgrade()
+        );
+        let result = perform_run(Box::new(f), vec![1000]);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn test_20001_registry_deallocated() {
+        let registry = UnofficialFunctionRegistry::new();
+        let f = create(&registry, 1, vec![0], "add $0,1");
+        drop(registry);
+        assert_eq!(f.create_runner().is_err(), true);
+    }
+
+    #[test]
+    fn test_30000_register_and_use_from_program() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        let f = create(&registry, 2, vec![0, 1], "mov $2,$0\nmov $0,$1\nmov $1,$2");
+        registry.register(Arc::new(Box::new(f)));
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            registry.clone(),
+        );
+
+        // Act
+        let runner: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, "mov $1,100\nf22 $0,1234\nsub $0,$1").expect("runner");
+
+        // Assert
+        assert_eq!(runner.inspect(5), "100,99,98,97,96");
+    }
+
+    fn run_loop_function(input: i64, step_count_limit: u64) -> (anyhow::Result<RegisterValue>, u64) {
+        let registry = UnofficialFunctionRegistry::new();
+        let f = create(&registry, 1, vec![0], "lpb $0\n  sub $0,1\nlpe\nmov $0,7");
+        registry.register(Arc::new(Box::new(f)));
+        let mut dm = DependencyManager::new(
+            DependencyManagerFileSystemMode::Virtual,
+            PathBuf::from("non-existing-dir"),
+            registry.clone(),
+        );
+        let runner: ProgramRunner = dm.parse(ProgramId::ProgramWithoutId, "f11 $0,1234").expect("runner");
+        let mut step_count: u64 = 0;
+        let result = runner.run(
+            RegisterValue::from_i64(input),
+            RunMode::Silent,
+            &mut step_count,
+            step_count_limit,
+            NodeRegisterLimit::Unlimited,
+            NodeLoopLimit::Unlimited,
+            &mut ProgramCache::new(),
+        );
+        (result, step_count)
+    }
+
+    #[test]
+    fn test_30001_steps_are_charged_to_the_caller() {
+        // Act
+        let (result, step_count) = run_loop_function(100, 1000000);
+
+        // Assert
+        assert_eq!(result.expect("output").0, BigInt::from(7));
+        assert_eq!(step_count > 100, true);
+    }
+
+    #[test]
+    fn test_30002_caller_step_count_limit_exceeded() {
+        // Act
+        let (result, step_count) = run_loop_function(100, 50);
+
+        // Assert
+        let error: anyhow::Error = result.expect_err("error");
+        let eval_error: Option<&EvalError> = error.root_cause().downcast_ref::<EvalError>();
+        assert_eq!(matches!(eval_error, Some(EvalError::StepCountExceededLimit)), true);
+        assert_eq!(step_count <= 50, true);
+    }
+}
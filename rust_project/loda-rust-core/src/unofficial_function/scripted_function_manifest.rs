@@ -0,0 +1,348 @@
+use super::{ScriptedFunction, UnofficialFunctionId, UnofficialFunctionRegistry};
+use crate::parser::{InstructionId, ParameterType, ParsedProgram};
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A single `[[function]]` entry in the manifest file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ScriptedFunctionDefinition {
+    pub id: u32,
+    pub inputs: u8,
+    pub outputs: u8,
+
+    /// Path to the LODA program, relative to the manifest file.
+    pub file: String,
+
+    pub name: Option<String>,
+
+    /// When not specified, the output values are read from `$0`, `$1`, `$2`, etc.
+    pub output_registers: Option<Vec<u64>>,
+
+    pub step_count_limit: Option<u64>,
+}
+
+impl ScriptedFunctionDefinition {
+    pub fn function_id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: self.inputs, outputs: self.outputs }
+    }
+
+    pub fn resolved_output_registers(&self) -> Vec<u64> {
+        match &self.output_registers {
+            Some(value) => value.clone(),
+            None => (0..self.outputs as u64).collect()
+        }
+    }
+
+    pub fn resolved_step_count_limit(&self) -> u64 {
+        self.step_count_limit.unwrap_or(ScriptedFunction::DEFAULT_STEP_COUNT_LIMIT)
+    }
+
+    pub fn resolved_name(&self) -> String {
+        match &self.name {
+            Some(value) => value.clone(),
+            None => format!("Scripted function {}", self.file)
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        // Same limits as the `fxx` instruction, where `x` must be in the range [0..9]
+        if self.inputs > 9 || self.outputs > 9 {
+            anyhow::bail!("The number of inputs and outputs must be in the range [0..9]");
+        }
+        if self.file.is_empty() {
+            anyhow::bail!("The file must be non-empty");
+        }
+        if let Some(output_registers) = &self.output_registers {
+            if output_registers.len() != (self.outputs as usize) {
+                anyhow::bail!("Expected {} output_registers, but got {}", self.outputs, output_registers.len());
+            }
+        }
+        if self.step_count_limit == Some(0) {
+            anyhow::bail!("The step_count_limit must be greater than zero");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestContent {
+    #[serde(default)]
+    function: Vec<ScriptedFunctionDefinition>,
+}
+
+/// Unofficial functions implemented as LODA programs, so they can be added without recompiling `loda-rust`.
+///
+/// The manifest is a TOML file, like this:
+///
+/// ```toml
+/// [[function]]
+/// id = 5000
+/// name = "Greatest common divisor and least common multiple"
+/// file = "gcd_lcm.asm"
+/// inputs = 2
+/// outputs = 2
+/// output_registers = [2, 3]
+/// step_count_limit = 10000
+/// ```
+///
+/// The `name`, `output_registers` and `step_count_limit` are optional.
+pub struct ScriptedFunctionManifest {
+    basedir: PathBuf,
+    definitions: Vec<ScriptedFunctionDefinition>,
+}
+
+impl ScriptedFunctionManifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content: String = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read manifest file: {:?}", path))?;
+        let basedir: PathBuf = match path.parent() {
+            Some(value) => value.to_path_buf(),
+            None => PathBuf::new()
+        };
+        Self::parse(&content, basedir)
+            .with_context(|| format!("Invalid manifest file: {:?}", path))
+    }
+
+    pub fn parse(toml_content: &str, basedir: PathBuf) -> anyhow::Result<Self> {
+        let content: ManifestContent = toml::from_str(toml_content)?;
+        let mut function_ids = HashSet::<UnofficialFunctionId>::new();
+        for definition in &content.function {
+            definition.validate()
+                .with_context(|| format!("Invalid function: {:?}", definition.function_id()))?;
+            if !function_ids.insert(definition.function_id()) {
+                anyhow::bail!("The function {:?} is defined multiple times", definition.function_id());
+            }
+        }
+        let instance = Self {
+            basedir,
+            definitions: content.function,
+        };
+        Ok(instance)
+    }
+
+    pub fn definitions(&self) -> &Vec<ScriptedFunctionDefinition> {
+        &self.definitions
+    }
+
+    /// Read the programs, and register them in the registry.
+    ///
+    /// The `loda_programs_oeis_dir` is used for resolving `seq` instructions.
+    ///
+    /// Returns the number of functions registered.
+    pub fn register(&self, registry: &UnofficialFunctionRegistry, loda_programs_oeis_dir: &Path) -> anyhow::Result<usize> {
+        let mut program_contents = Vec::<String>::with_capacity(self.definitions.len());
+        let mut parsed_programs = HashMap::<UnofficialFunctionId, ParsedProgram>::new();
+        for definition in &self.definitions {
+            let path: PathBuf = self.basedir.join(&definition.file);
+            let content: String = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read program for function {:?}, file: {:?}", definition.function_id(), path))?;
+            let parsed_program: ParsedProgram = ParsedProgram::parse_program(&content)
+                .map_err(|error| anyhow::anyhow!("Unable to parse program for function {:?}, file: {:?}, error: {}", definition.function_id(), path, error))?;
+            if registry.lookup(definition.function_id()).is_some() {
+                anyhow::bail!("The function {:?} is already registered", definition.function_id());
+            }
+            program_contents.push(content);
+            parsed_programs.insert(definition.function_id(), parsed_program);
+        }
+        if let Some(function_id) = find_cycle(&parsed_programs) {
+            anyhow::bail!("Detected a cycle, the function {:?} ends up calling itself", function_id);
+        }
+
+        let create_scripted_function = |definition: &ScriptedFunctionDefinition, content: &String| -> ScriptedFunction {
+            ScriptedFunction::new(
+                definition,
+                content.clone(),
+                loda_programs_oeis_dir.to_path_buf(),
+                registry.downgrade(),
+            )
+        };
+        for (definition, content) in self.definitions.iter().zip(&program_contents) {
+            let scripted_function: ScriptedFunction = create_scripted_function(definition, content);
+            registry.register(Arc::new(Box::new(scripted_function)));
+        }
+
+        // Compile the programs now, so problems are reported at startup, rather than when the function is used.
+        // This is done after all the functions have been registered, since they may call each other.
+        for (definition, content) in self.definitions.iter().zip(&program_contents) {
+            let scripted_function: ScriptedFunction = create_scripted_function(definition, content);
+            scripted_function.create_runner()
+                .with_context(|| format!("Unable to compile program for function {:?}, file: {:?}", definition.function_id(), definition.file))?;
+        }
+        Ok(self.definitions.len())
+    }
+}
+
+/// Determine if the functions ends up calling themselves, directly or indirectly.
+fn find_cycle(parsed_programs: &HashMap<UnofficialFunctionId, ParsedProgram>) -> Option<UnofficialFunctionId> {
+    let mut edges = HashMap::<UnofficialFunctionId, Vec<UnofficialFunctionId>>::new();
+    for (function_id, parsed_program) in parsed_programs {
+        let mut callees = Vec::<UnofficialFunctionId>::new();
+        for instruction in &parsed_program.instruction_vec {
+            let (inputs, outputs) = match instruction.instruction_id {
+                InstructionId::UnofficialFunction { input_count, output_count } => (input_count, output_count),
+                _ => continue
+            };
+            let parameter = match instruction.parameter_vec.get(1) {
+                Some(value) => value,
+                None => continue
+            };
+            if parameter.parameter_type != ParameterType::Constant || parameter.parameter_value < 0 || parameter.parameter_value > (u32::MAX as i64) {
+                continue;
+            }
+            let callee = UnofficialFunctionId::InputOutput { id: parameter.parameter_value as u32, inputs, outputs };
+            if parsed_programs.contains_key(&callee) {
+                callees.push(callee);
+            }
+        }
+        edges.insert(*function_id, callees);
+    }
+
+    // Depth first search, where the nodes on the current path are `visiting`.
+    fn visit(
+        node: UnofficialFunctionId,
+        edges: &HashMap<UnofficialFunctionId, Vec<UnofficialFunctionId>>,
+        visiting: &mut HashSet<UnofficialFunctionId>,
+        visited: &mut HashSet<UnofficialFunctionId>
+    ) -> Option<UnofficialFunctionId> {
+        if visited.contains(&node) {
+            return None;
+        }
+        if !visiting.insert(node) {
+            return Some(node);
+        }
+        if let Some(callees) = edges.get(&node) {
+            for callee in callees {
+                if let Some(function_id) = visit(*callee, edges, visiting, visited) {
+                    return Some(function_id);
+                }
+            }
+        }
+        visiting.remove(&node);
+        visited.insert(node);
+        None
+    }
+
+    let mut visiting = HashSet::<UnofficialFunctionId>::new();
+    let mut visited = HashSet::<UnofficialFunctionId>::new();
+    for function_id in edges.keys() {
+        if let Some(function_id) = visit(*function_id, &edges, &mut visiting, &mut visited) {
+            return Some(function_id);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unofficial_function::UnofficialFunction;
+    use num_bigint::BigInt;
+    use num_traits::ToPrimitive;
+
+    fn testdata_dir() -> PathBuf {
+        let e = env!("CARGO_MANIFEST_DIR");
+        PathBuf::from(e).join("tests/scripted_functions")
+    }
+
+    fn run(registry: &UnofficialFunctionRegistry, id: u32, inputs: u8, outputs: u8, input: Vec<i64>) -> Vec<i64> {
+        let key = UnofficialFunctionId::InputOutput { id, inputs, outputs };
+        let f: Arc<Box<dyn UnofficialFunction>> = registry.lookup(key).expect("unofficial_function");
+        let input_vec: Vec<BigInt> = input.iter().map(|value| BigInt::from(*value)).collect();
+        let output_vec: Vec<BigInt> = f.run(input_vec).expect("output");
+        output_vec.iter().map(|value| value.to_i64().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_10000_parse() {
+        // Arrange
+        let toml_content = r#"
+        [[function]]
+        id = 5000
+        file = "a.asm"
+        inputs = 2
+        outputs = 1
+
+        [[function]]
+        id = 5000
+        file = "b.asm"
+        inputs = 2
+        outputs = 2
+        name = "Hello"
+        output_registers = [5, 6]
+        "#;
+
+        // Act
+        let manifest = ScriptedFunctionManifest::parse(toml_content, PathBuf::from("non-existing-dir")).expect("manifest");
+
+        // Assert
+        assert_eq!(manifest.definitions().len(), 2);
+        let definition: &ScriptedFunctionDefinition = &manifest.definitions()[1];
+        assert_eq!(definition.resolved_name(), "Hello");
+        assert_eq!(definition.resolved_output_registers(), vec![5, 6]);
+        assert_eq!(manifest.definitions()[0].resolved_name(), "Scripted function a.asm");
+        assert_eq!(manifest.definitions()[0].resolved_output_registers(), vec![0]);
+    }
+
+    #[test]
+    fn test_10001_parse_error() {
+        let basedir = PathBuf::from("non-existing-dir");
+        // Same function defined twice
+        let toml_content = "[[function]]\nid = 1\nfile = \"a.asm\"\ninputs = 1\noutputs = 1\n[[function]]\nid = 1\nfile = \"b.asm\"\ninputs = 1\noutputs = 1";
+        assert_eq!(ScriptedFunctionManifest::parse(toml_content, basedir.clone()).is_err(), true);
+        // Too many inputs
+        let toml_content = "[[function]]\nid = 1\nfile = \"a.asm\"\ninputs = 10\noutputs = 1";
+        assert_eq!(ScriptedFunctionManifest::parse(toml_content, basedir.clone()).is_err(), true);
+        // Mismatch between outputs and output_registers
+        let toml_content = "[[function]]\nid = 1\nfile = \"a.asm\"\ninputs = 1\noutputs = 2\noutput_registers = [0]";
+        assert_eq!(ScriptedFunctionManifest::parse(toml_content, basedir.clone()).is_err(), true);
+        // Missing file
+        let toml_content = "[[function]]\nid = 1\ninputs = 1\noutputs = 1";
+        assert_eq!(ScriptedFunctionManifest::parse(toml_content, basedir.clone()).is_err(), true);
+    }
+
+    #[test]
+    fn test_20000_register() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        let manifest = ScriptedFunctionManifest::load(&testdata_dir().join("functions/manifest.toml")).expect("manifest");
+
+        // Act
+        let count: usize = manifest.register(&registry, &testdata_dir()).expect("register");
+
+        // Assert
+        assert_eq!(count, 3);
+        assert_eq!(run(&registry, 5000, 2, 2, vec![12, 18]), vec![6, 36]);
+        assert_eq!(run(&registry, 5001, 1, 1, vec![5]), vec![31]);
+        assert_eq!(run(&registry, 5002, 2, 1, vec![6, 9]), vec![7]);
+    }
+
+    #[test]
+    fn test_20001_register_cycle() {
+        let registry = UnofficialFunctionRegistry::new();
+        let manifest = ScriptedFunctionManifest::load(&testdata_dir().join("functions/cycle.toml")).expect("manifest");
+        let error = manifest.register(&registry, &testdata_dir()).expect_err("cycle");
+        assert_eq!(error.to_string().contains("cycle"), true);
+    }
+
+    #[test]
+    fn test_20002_register_missing_dependency() {
+        // The `seq` instruction cannot be resolved, since the A000079 program cannot be found.
+        let registry = UnofficialFunctionRegistry::new();
+        let manifest = ScriptedFunctionManifest::load(&testdata_dir().join("functions/manifest.toml")).expect("manifest");
+        let result = manifest.register(&registry, &PathBuf::from("non-existing-dir"));
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn test_20003_register_already_registered() {
+        let registry = UnofficialFunctionRegistry::new();
+        let manifest = ScriptedFunctionManifest::load(&testdata_dir().join("functions/manifest.toml")).expect("manifest");
+        manifest.register(&registry, &testdata_dir()).expect("register");
+        let result = manifest.register(&registry, &testdata_dir());
+        assert_eq!(result.is_err(), true);
+    }
+}
@@ -0,0 +1,90 @@
+use super::{UnofficialFunction, UnofficialFunctionId};
+use super::number_theory_util::{factorize, input_u64, FACTORIZE_MAX};
+use num_bigint::BigInt;
+use num_traits::{One, Pow};
+
+/// Divisor function `sigma_k(n)`, the sum of the k-th powers of the divisors of `n`.
+///
+/// The input `n` must be in the range `[1..2^40]`, and `k` must be in the range `[0..32]`.
+pub struct SigmaFunction {
+    id: u32,
+}
+
+impl SigmaFunction {
+    pub const K_MAX: u64 = 32;
+
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+        }
+    }
+}
+
+impl UnofficialFunction for SigmaFunction {
+    fn id(&self) -> UnofficialFunctionId {
+        UnofficialFunctionId::InputOutput { id: self.id, inputs: 2, outputs: 1 }
+    }
+
+    fn name(&self) -> String {
+        "Divisor function sigma_k(n), where input[0] is n and input[1] is k.".to_string()
+    }
+
+    fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>> {
+        if input.len() != 2 {
+            return Err(anyhow::anyhow!("Wrong number of inputs"));
+        }
+        let n: u64 = input_u64(&input[0], 1, FACTORIZE_MAX, "Sigma n")?;
+        let k: u32 = input_u64(&input[1], 0, Self::K_MAX, "Sigma k")? as u32;
+        // sigma_k is multiplicative, sigma_k(p^e) = 1 + p^k + p^(2k) + ... + p^(ek)
+        let mut result = BigInt::one();
+        for (prime, exponent) in factorize(n) {
+            let prime_pow_k: BigInt = BigInt::from(prime).pow(k);
+            let mut sum = BigInt::one();
+            let mut term = BigInt::one();
+            for _ in 0..exponent {
+                term *= &prime_pow_k;
+                sum += &term;
+            }
+            result *= sum;
+        }
+        Ok(vec![result])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::perform_run;
+
+    fn run_terms(k: i32, count: i32) -> Vec<i32> {
+        (1..=count).map(|n| {
+            let output: Vec<i32> = perform_run(Box::new(SigmaFunction::new(0)), vec![n, k]).expect("output");
+            output[0]
+        }).collect()
+    }
+
+    #[test]
+    fn test_10000_sigma0() {
+        // A000005
+        assert_eq!(run_terms(0, 12), vec![1, 2, 2, 3, 2, 4, 2, 4, 3, 4, 2, 6]);
+    }
+
+    #[test]
+    fn test_10001_sigma1() {
+        // A000203
+        assert_eq!(run_terms(1, 12), vec![1, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28]);
+    }
+
+    #[test]
+    fn test_10002_sigma2() {
+        // A001157
+        assert_eq!(run_terms(2, 8), vec![1, 5, 10, 21, 26, 50, 50, 85]);
+    }
+
+    #[test]
+    fn test_20000_out_of_range() {
+        assert_eq!(perform_run(Box::new(SigmaFunction::new(0)), vec![0, 1]).is_err(), true);
+        assert_eq!(perform_run(Box::new(SigmaFunction::new(0)), vec![5, -1]).is_err(), true);
+        assert_eq!(perform_run(Box::new(SigmaFunction::new(0)), vec![5, 33]).is_err(), true);
+    }
+}
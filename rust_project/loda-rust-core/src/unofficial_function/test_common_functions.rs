@@ -221,8 +221,71 @@ mod tests {
         _ = run(program, 0).expect_err("should fail");
     }
 
+    #[test]
+    fn test_40000_is_prime() {
+        let program = "
+        f11 $0,1100 ; Is prime, 1 if prime, otherwise 0.
+        ";
+        let terms: Vec<i64> = (0..10).map(|input| run(program, input).expect("output")).collect();
+        assert_eq!(terms, vec![0, 0, 1, 1, 0, 1, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_40001_nth_prime() {
+        let program = "
+        add $0,1
+        f11 $0,1101 ; The n-th prime number, prime(1) = 2.
+        ";
+        let terms: Vec<i64> = (0..10).map(|input| run(program, input).expect("output")).collect();
+        assert_eq!(terms, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    #[test]
+    fn test_40002_sigma() {
+        let program = "
+        add $0,1
+        mov $1,1
+        f21 $0,1104 ; Divisor function sigma_k(n), where input[0] is n and input[1] is k.
+        ";
+        let terms: Vec<i64> = (0..10).map(|input| run(program, input).expect("output")).collect();
+        assert_eq!(terms, vec![1, 3, 4, 7, 6, 12, 8, 15, 13, 18]);
+    }
+
+    #[test]
+    fn test_40003_modular_power() {
+        let program = "
+        mov $1,$0
+        mov $0,2
+        mov $2,1000
+        f31 $0,1113 ; Modular power, input[0] raised to input[1] modulo input[2].
+        ";
+        let v: i64 = run(program, 10).expect("output");
+        assert_eq!(v, 24);
+    }
+
+    #[test]
+    fn test_50000_limit_bits_output_ok() {
+        let program = "
+        f11 $0,1110 ; Fibonacci number F(n).
+        ";
+        let v: i64 = run_advanced(program, 40, NodeRegisterLimit::LimitBits(32)).expect("output");
+        assert_eq!(v, 102334155);
+    }
+
+    #[test]
+    fn test_50001_limit_bits_output_out_of_range() {
+        let program = "
+        f11 $0,1110 ; Fibonacci number F(n).
+        ";
+        _ = run_advanced(program, 50, NodeRegisterLimit::LimitBits(32)).expect_err("should fail");
+    }
+
     /// Run program with 1 input and 1 output
     fn run<S: AsRef<str>>(program: S, input: i64) -> anyhow::Result<i64> {
+        run_advanced(program, input, NodeRegisterLimit::Unlimited)
+    }
+
+    fn run_advanced<S: AsRef<str>>(program: S, input: i64, node_register_limit: NodeRegisterLimit) -> anyhow::Result<i64> {
         let program_str: &str = program.as_ref();
 
         let registry = UnofficialFunctionRegistry::new();
@@ -256,7 +319,7 @@ mod tests {
             RunMode::Silent, 
             &mut step_count, 
             step_count_limit,
-            NodeRegisterLimit::Unlimited,
+            node_register_limit,
             NodeLoopLimit::Unlimited,
             &mut cache
         );
@@ -1,8 +1,31 @@
 use super::UnofficialFunctionId;
+use crate::execute::ProgramState;
 use num_bigint::BigInt;
 
 pub trait UnofficialFunction: Send + Sync {
     fn id(&self) -> UnofficialFunctionId;
     fn name(&self) -> String;
     fn run(&self, input: Vec<BigInt>) -> anyhow::Result<Vec<BigInt>>;
+
+    /// Same as `run()`, and with access to the state of the calling program.
+    ///
+    /// A function that runs a program, must charge its steps to the `state`,
+    /// so that calling the function inside a loop cannot get around the step count limit or the cancellation.
+    fn run_with_state(&self, input: Vec<BigInt>, _state: &mut ProgramState) -> anyhow::Result<Vec<BigInt>> {
+        self.run(input)
+    }
+
+    /// Returns `false` for functions that makes no sense to insert while mining,
+    /// such as functions that prints debug info or asserts.
+    fn is_minable(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` for functions that do something besides computing the outputs,
+    /// such as functions that prints debug info or asserts.
+    ///
+    /// The static analysis never reports these as having no effect on the output.
+    fn has_side_effect(&self) -> bool {
+        false
+    }
 }
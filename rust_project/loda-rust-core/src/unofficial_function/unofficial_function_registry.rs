@@ -1,5 +1,5 @@
 use super::{UnofficialFunction, UnofficialFunctionId};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, Weak};
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -44,6 +44,37 @@ impl UnofficialFunctionRegistry {
         }
         None
     }
+
+    /// The ids of all the registered functions, in ascending order.
+    pub fn function_ids(&self) -> Vec<UnofficialFunctionId> {
+        let inner = self.inner.read().expect("UnofficialFunctionRegistry.function_ids() RwLock poisoned");
+        let mut function_ids: Vec<UnofficialFunctionId> = inner.plugin_dict.keys().cloned().collect();
+        function_ids.sort();
+        function_ids
+    }
+
+    /// Reference to the registry, that doesn't keep the registry alive.
+    /// 
+    /// Functions that are stored inside the registry, and needs access to the registry,
+    /// must use a weak reference, otherwise the registry is never deallocated.
+    pub fn downgrade(&self) -> WeakUnofficialFunctionRegistry {
+        WeakUnofficialFunctionRegistry {
+            inner: Arc::downgrade(&self.inner)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WeakUnofficialFunctionRegistry {
+    inner: Weak<RwLock<RegistryInner>>,
+}
+
+impl WeakUnofficialFunctionRegistry {
+    /// Returns `None` when the registry has been deallocated.
+    pub fn upgrade(&self) -> Option<UnofficialFunctionRegistry> {
+        let inner = self.inner.upgrade()?;
+        Some(UnofficialFunctionRegistry { inner })
+    }
 }
 
 #[cfg(test)]
@@ -97,4 +128,56 @@ mod tests {
         let expected_output_vec: Vec<BigInt> = vec![111.to_bigint().unwrap()];
         assert_eq!(output_vec, expected_output_vec);
     }
+
+    #[test]
+    fn test_20001_function_ids() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        registry.register(Arc::new(Box::new(SumFunction::new(1234, 3))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1000, 2))));
+        registry.register(Arc::new(Box::new(SumFunction::new(1234, 2))));
+
+        // Act
+        let function_ids: Vec<UnofficialFunctionId> = registry.function_ids();
+
+        // Assert
+        let expected: Vec<UnofficialFunctionId> = vec![
+            UnofficialFunctionId::InputOutput { id: 1000, inputs: 2, outputs: 1 },
+            UnofficialFunctionId::InputOutput { id: 1234, inputs: 2, outputs: 1 },
+            UnofficialFunctionId::InputOutput { id: 1234, inputs: 3, outputs: 1 },
+        ];
+        assert_eq!(function_ids, expected);
+    }
+
+    #[test]
+    fn test_30000_weak_upgrade() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        let weak: WeakUnofficialFunctionRegistry = registry.downgrade();
+        registry.register(Arc::new(Box::new(SumFunction::new(1234, 2))));
+        let key = UnofficialFunctionId::InputOutput { 
+            id: 1234, 
+            inputs: 2, 
+            outputs: 1 
+        };
+
+        // Act
+        let registry_upgraded: UnofficialFunctionRegistry = weak.upgrade().expect("registry");
+
+        // Assert
+        assert_eq!(registry_upgraded.lookup(key).is_some(), true);
+    }
+
+    #[test]
+    fn test_30001_weak_upgrade_after_deallocation() {
+        // Arrange
+        let registry = UnofficialFunctionRegistry::new();
+        let weak: WeakUnofficialFunctionRegistry = registry.downgrade();
+
+        // Act
+        drop(registry);
+
+        // Assert
+        assert_eq!(weak.upgrade().is_none(), true);
+    }
 }
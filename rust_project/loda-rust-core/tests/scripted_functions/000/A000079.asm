@@ -0,0 +1,6 @@
+; A000079: Powers of 2: a(n) = 2^n.
+; 1,2,4,8,16,32,64,128,256,512
+
+mov $1,2
+pow $1,$0
+mov $0,$1
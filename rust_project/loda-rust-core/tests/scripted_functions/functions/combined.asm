@@ -0,0 +1,4 @@
+; gcd(2^a-1, 2^b-1) = 2^gcd(a,b)-1
+f11 $0,5001
+f11 $1,5001
+f22 $0,5000
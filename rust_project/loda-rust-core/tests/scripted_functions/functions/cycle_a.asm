@@ -0,0 +1 @@
+f11 $0,6001
@@ -0,0 +1,2 @@
+add $0,1
+f11 $0,6000
@@ -0,0 +1,7 @@
+; Input: $0 and $1
+; Output: $2 = gcd($0,$1), $3 = lcm($0,$1)
+mov $2,$0
+gcd $2,$1
+mov $3,$0
+mul $3,$1
+div $3,$2
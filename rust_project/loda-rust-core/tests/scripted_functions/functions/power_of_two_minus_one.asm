@@ -0,0 +1,3 @@
+; Depends on the A000079 program in the loda-programs repo
+seq $0,79
+sub $0,1
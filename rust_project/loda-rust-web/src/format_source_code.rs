@@ -0,0 +1,27 @@
+use wasm_bindgen::prelude::*;
+use loda_rust_core::parser::{self, ParseProgramError};
+
+/// Reformat the source code, the same way as the programs in the `loda-programs` repository.
+///
+/// The sequence names are not looked up, since the OEIS 'names' file is not available in the browser.
+///
+/// Returns `Err` when the source code cannot be parsed.
+pub fn formatted_source_code(source_code: &str) -> Result<String, ParseProgramError> {
+    parser::formatted_source_code(source_code, None)
+}
+
+/// Reformat the source code, so it's indented and has consistent spacing.
+///
+/// Returns the formatted source code.
+///
+/// Returns an error message when the source code cannot be parsed.
+#[wasm_bindgen]
+pub fn format_source_code(source_code: String) -> Result<String, JsValue> {
+    match formatted_source_code(&source_code) {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            let s = format!("Unable to format program: {}", error);
+            Err(JsValue::from_str(&s))
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use wasm_bindgen::prelude::*;
+
+/// Helper for building plain javascript objects, such as `{ line: 3, message: "..." }`.
+pub struct JsObjectBuilder {
+    object: js_sys::Object,
+}
+
+impl JsObjectBuilder {
+    pub fn new() -> Self {
+        Self {
+            object: js_sys::Object::new(),
+        }
+    }
+
+    pub fn set(self, key: &str, value: JsValue) -> Self {
+        let key_jsvalue = JsValue::from_str(key);
+        if let Err(error) = js_sys::Reflect::set(&self.object, &key_jsvalue, &value) {
+            error!("Unable to set property {:?} on object. error: {:?}", key, error);
+        }
+        self
+    }
+
+    pub fn set_str(self, key: &str, value: &str) -> Self {
+        self.set(key, JsValue::from_str(value))
+    }
+
+    pub fn set_u64(self, key: &str, value: u64) -> Self {
+        self.set(key, JsValue::from_f64(value as f64))
+    }
+
+    pub fn set_bool(self, key: &str, value: bool) -> Self {
+        self.set(key, JsValue::from_bool(value))
+    }
+
+    pub fn build(self) -> JsValue {
+        self.object.into()
+    }
+}
@@ -18,12 +18,21 @@ use std::rc::Rc;
 use core::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use loda_rust_core::control::{DependencyManager,DependencyManagerFileSystemMode};
+use loda_rust_core::control::{DependencyManager,DependencyManagerFileSystemMode,ProgramBundle};
 use loda_rust_core::execute::{NodeLoopLimit, ProgramCache, ProgramId, ProgramRunner, RegisterValue, RunMode};
 use loda_rust_core::execute::NodeRegisterLimit;
 use loda_rust_core::unofficial_function::UnofficialFunctionRegistry;
 use loda_rust_core::parser::ParsedProgram;
 
+mod format_source_code;
+mod js_object;
+mod parse_diagnostics;
+mod web_evaluator;
+
+pub use format_source_code::{format_source_code, formatted_source_code};
+pub use parse_diagnostics::{parse_with_diagnostics, diagnostics_for_source_code, Diagnostic};
+pub use web_evaluator::{EvaluateResult, WebEvaluator};
+
 
 #[derive(Clone)]
 struct MyCustomLog {
@@ -97,6 +106,27 @@ fn url_from_program_id(program_id: u64) -> String {
     format!("{}/{}/{}", baseurl, dir_index_string, filename_string)
 }
 
+/// Fetch a text file, such as a program bundle.
+async fn fetch_text(url: &str) -> Result<String, JsValue> {
+    let global = js_sys::global().unchecked_into::<WorkerGlobalScope>();
+    let mut opts = RequestInit::new();
+    opts.method("GET");
+    opts.mode(RequestMode::Cors);
+    let request = Request::new_with_str_and_init(url, &opts)?;
+    let resp_value = JsFuture::from(global.fetch_with_request(&request)).await?;
+    let resp: Response = resp_value.dyn_into()?;
+    let status: u16 = resp.status();
+    if !resp.ok() {
+        let s = format!("Expected status 2xx, but got {:?}. Cannot fetch url: {:?}", status, url);
+        return Err(JsValue::from_str(&s));
+    }
+    let text_jsvalue: JsValue = JsFuture::from(resp.text()?).await?;
+    match text_jsvalue.as_string() {
+        Some(value) => Ok(value),
+        None => Err(JsValue::from_str("Unable to obtain convert JsValue to Rust String"))
+    }
+}
+
 #[wasm_bindgen]
 pub fn perform_selfcheck() {
     let program: &str = r#"        
@@ -166,6 +196,7 @@ struct WebDependencyManagerInner {
     step_count: u64,
     outputted_byte_count: u64,
     program_runner: Rc::<ProgramRunner>,
+    offline: bool,
 }
 
 impl WebDependencyManagerInner {
@@ -188,6 +219,7 @@ impl WebDependencyManagerInner {
             step_count: 0,
             outputted_byte_count: 0,
             program_runner: program_runner,
+            offline: false,
         }
     }
 
@@ -233,6 +265,36 @@ impl WebDependencyManagerInner {
                 continue;
             }
 
+            // Use the program from the bundle, when available, instead of fetching it.
+            let bundled_dependencies: Option<Vec<u64>> = match self.dependency_manager.virtual_filesystem_get(program_id) {
+                Some(file_content) => {
+                    match ParsedProgram::parse_program(file_content) {
+                        Ok(parsed_program) => Some(parsed_program.direct_dependencies()),
+                        Err(error) => {
+                            error!("Problem with bundled dependency program_id: {}. Unable to parse program: {}", program_id, error);
+                            let s = format!("Problem with bundled dependency program_id: {}. Unable to parse program: {}", program_id, error);
+                            let err = JsValue::from_str(&s);
+                            return Err(err);
+                        }
+                    }
+                },
+                None => None
+            };
+            if let Some(dependencies) = bundled_dependencies {
+                debug!("bundled program: {:?} has these dependencies: {:?}", program_id, dependencies);
+                pending_program_ids.extend(dependencies);
+                already_fetched_program_ids.insert(program_id);
+                continue;
+            }
+
+            // Stop if the program isn't in the bundle, and fetching is not allowed
+            if self.offline {
+                error!("Dependency not found in bundle. program_id: {:?}", program_id);
+                let s = format!("Dependency not found in bundle, and offline mode is enabled. program_id: {:?}", program_id);
+                let err = JsValue::from_str(&s);
+                return Err(err);
+            }
+
             let url = url_from_program_id(program_id);
 
             let mut opts = RequestInit::new();
@@ -362,6 +424,60 @@ impl WebDependencyManagerInner {
         Ok(JsValue::from_str(&term_string))
     }
 
+    fn load_bundle(&mut self, content: &str) -> Result<JsValue, JsValue> {
+        let bundle: ProgramBundle = match ProgramBundle::parse(content) {
+            Ok(value) => value,
+            Err(error) => {
+                error!("Unable to parse bundle: {:?}", error);
+                let s = format!("Unable to parse bundle: {}", error);
+                let err = JsValue::from_str(&s);
+                return Err(err);
+            }
+        };
+        self.dependency_manager.virtual_filesystem_insert_bundle(&bundle);
+        debug!("Loaded bundle with {} programs", bundle.len());
+        Ok(JsValue::from_f64(bundle.len() as f64))
+    }
+
+    fn evaluate_terms(&self, js_start_index: i32, js_count: i32) -> Result<JsValue, JsValue> {
+        let mut evaluator: WebEvaluator = self.create_evaluator(js_start_index, js_count, js_count)?;
+        Ok(evaluator.evaluate_chunk().to_jsvalue())
+    }
+
+    fn create_evaluator(&self, js_start_index: i32, js_count: i32, js_chunk_size: i32) -> Result<WebEvaluator, JsValue> {
+        if js_start_index < 0 {
+            let err = JsValue::from_str("Expecting non-negative index");
+            return Err(err);
+        }
+        if js_count < 0 {
+            let err = JsValue::from_str("Expecting non-negative count");
+            return Err(err);
+        }
+        if js_chunk_size < 1 {
+            let err = JsValue::from_str("Expecting chunk size to be 1 or greater");
+            return Err(err);
+        }
+        let evaluator = WebEvaluator::new(
+            self.program_runner.clone(), 
+            js_start_index as i64, 
+            js_count as u32, 
+            js_chunk_size as u32
+        );
+        Ok(evaluator)
+    }
+
+    fn trace_term(&self, js_index: i32, js_max_steps: i32) -> Result<JsValue, JsValue> {
+        if js_index < 0 {
+            let err = JsValue::from_str("Expecting non-negative index");
+            return Err(err);
+        }
+        if js_max_steps < 1 {
+            let err = JsValue::from_str("Expecting max steps to be 1 or greater");
+            return Err(err);
+        }
+        Ok(web_evaluator::trace_term(&self.program_runner, js_index as i64, js_max_steps as u32))
+    }
+
     fn print_stats(&self) {
         debug!("steps: {}", self.step_count);
         debug!("cache: {}", self.cache.hit_miss_info());    
@@ -406,6 +522,51 @@ impl WebDependencyManager {
             .execute_current_program(js_index)
     }
 
+    /// Load a bundle with many programs, created by the `loda-rust bundle-programs` subcommand.
+    ///
+    /// The dependencies that are in the bundle are not fetched over the network.
+    ///
+    /// Returns the number of programs in the bundle.
+    pub fn load_bundle(self, content: String) -> Result<JsValue, JsValue> {
+        self.inner.borrow_mut()
+            .load_bundle(&content)
+    }
+
+    /// Fetch a bundle with a single request, and load it.
+    ///
+    /// Returns the number of programs in the bundle.
+    pub async fn fetch_bundle(self, url: String) -> Result<JsValue, JsValue> {
+        debug!("WebDependencyManager.fetch_bundle url: {:?}", url);
+        let content: String = fetch_text(&url).await?;
+        self.inner.borrow_mut()
+            .load_bundle(&content)
+    }
+
+    /// When offline, then dependencies that are not in the bundle are reported as errors, instead of being fetched.
+    pub fn set_offline(self, offline: bool) {
+        self.inner.borrow_mut().offline = offline;
+    }
+
+    /// Compute `count` terms starting at `start_index`, and the number of steps used for each term.
+    ///
+    /// Returns an object like this: `{ terms: ["0", "1", "1"], steps: [1, 3, 7], error: null }`
+    pub fn evaluate_terms(self, js_start_index: i32, js_count: i32) -> Result<JsValue, JsValue> {
+        self.inner.borrow()
+            .evaluate_terms(js_start_index, js_count)
+    }
+
+    /// Create an evaluator for the current program, that computes the terms in chunks and can be cancelled.
+    pub fn create_evaluator(self, js_start_index: i32, js_count: i32, js_chunk_size: i32) -> Result<WebEvaluator, JsValue> {
+        self.inner.borrow()
+            .create_evaluator(js_start_index, js_count, js_chunk_size)
+    }
+
+    /// Compute a single term, with a snapshot of the registers after every instruction, for use in a debugger.
+    pub fn trace_term(self, js_index: i32, js_max_steps: i32) -> Result<JsValue, JsValue> {
+        self.inner.borrow()
+            .trace_term(js_index, js_max_steps)
+    }
+
     pub fn print_stats(self) {
         self.inner.borrow_mut()
             .print_stats();
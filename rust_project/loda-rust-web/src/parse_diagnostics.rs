@@ -0,0 +1,49 @@
+use wasm_bindgen::prelude::*;
+use crate::js_object::JsObjectBuilder;
+use loda_rust_core::parser::ParsedProgram;
+
+pub use loda_rust_core::parser::Diagnostic;
+
+fn diagnostic_to_jsvalue(diagnostic: &Diagnostic) -> JsValue {
+    let suggestion: JsValue = match &diagnostic.suggestion {
+        Some(value) => JsValue::from_str(value),
+        None => JsValue::NULL,
+    };
+    JsObjectBuilder::new()
+        .set_u64("line", diagnostic.line as u64)
+        .set_u64("column", diagnostic.column as u64)
+        .set_u64("endColumn", diagnostic.end_column as u64)
+        .set_str("category", &diagnostic.category.to_string())
+        .set_str("message", &diagnostic.message)
+        .set("suggestion", suggestion)
+        .build()
+}
+
+/// Check the source code for problems, without fetching any dependencies.
+///
+/// Returns an empty vector when the program is ok.
+///
+/// All the problems in the source code are returned, sorted by line and column.
+pub fn diagnostics_for_source_code(source_code: &str) -> Vec<Diagnostic> {
+    match ParsedProgram::parse_with_diagnostics(source_code) {
+        Ok(_) => vec!(),
+        Err(diagnostics) => diagnostics,
+    }
+}
+
+/// Parse the source code and return an array with problems, for highlighting in the editor.
+///
+/// Each problem is an object like this:
+/// `{ line: 3, column: 5, endColumn: 9, category: "unknown mnemonic", message: "...", suggestion: "add" }`.
+///
+/// The `suggestion` is `null` when there is no suggestion.
+///
+/// An empty array is returned when the program is ok.
+#[wasm_bindgen]
+pub fn parse_with_diagnostics(source_code: String) -> js_sys::Array {
+    let array = js_sys::Array::new();
+    for diagnostic in diagnostics_for_source_code(&source_code) {
+        array.push(&diagnostic_to_jsvalue(&diagnostic));
+    }
+    array
+}
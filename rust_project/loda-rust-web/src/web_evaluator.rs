@@ -0,0 +1,253 @@
+use wasm_bindgen::prelude::*;
+use crate::js_object::JsObjectBuilder;
+use loda_rust_core::execute::{CancelCheck, EvalError, NodeLoopLimit, ProgramCache, ProgramRunner, ProgramState, RegisterValue, RunMode, StepTrace, StepTraceItem};
+use loda_rust_core::execute::NodeRegisterLimit;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The max number of steps per term.
+const STEP_COUNT_LIMIT_PER_TERM: u64 = 1000000000;
+
+/// The computed terms and the number of steps used for computing each term.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvaluateResult {
+    pub terms: Vec<String>,
+    pub step_counts: Vec<u64>,
+    pub error: Option<String>,
+}
+
+impl EvaluateResult {
+    /// Converts to a javascript object like this:
+    /// `{ terms: ["0", "1", "1", "2"], steps: [1, 3, 7, 11], error: null }`
+    ///
+    /// The terms are strings, since they may exceed the range of javascript numbers.
+    pub fn to_jsvalue(&self) -> JsValue {
+        let terms = js_sys::Array::new();
+        for term in &self.terms {
+            terms.push(&JsValue::from_str(term));
+        }
+        let steps = js_sys::Array::new();
+        for step_count in &self.step_counts {
+            steps.push(&JsValue::from_f64(*step_count as f64));
+        }
+        let error: JsValue = match &self.error {
+            Some(message) => JsValue::from_str(message),
+            None => JsValue::NULL,
+        };
+        JsObjectBuilder::new()
+            .set("terms", terms.into())
+            .set("steps", steps.into())
+            .set("error", error)
+            .build()
+    }
+}
+
+/// Computes the terms of a program in chunks, so that the browser stays responsive.
+///
+/// Usecase:
+/// Programs that are slow to compute. The javascript code invokes `run_chunk()` repeatedly,
+/// from a `setTimeout` callback or from a worker, and shows the terms as they arrive.
+/// When the user edits the program or presses stop, then `cancel()` is invoked,
+/// and the following calls to `run_chunk()` returns no more terms.
+///
+/// The cancel flag and the `set_term_time_limit()` are also checked while a term is being computed,
+/// every `CancelCheck::INTERVAL` steps, so a single slow term doesn't freeze the browser.
+#[wasm_bindgen]
+pub struct WebEvaluator {
+    program_runner: Rc<ProgramRunner>,
+    cache: ProgramCache,
+    next_index: i64,
+    end_index: i64,
+    chunk_size: u32,
+    step_count: u64,
+    cancelled: Arc<AtomicBool>,
+    term_time_limit_milliseconds: Option<f64>,
+    failed: bool,
+}
+
+impl WebEvaluator {
+    pub fn new(program_runner: Rc<ProgramRunner>, start_index: i64, term_count: u32, chunk_size: u32) -> Self {
+        Self {
+            program_runner: program_runner,
+            cache: ProgramCache::new(),
+            next_index: start_index,
+            end_index: start_index + (term_count as i64),
+            chunk_size: chunk_size.max(1),
+            step_count: 0,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            term_time_limit_milliseconds: None,
+            failed: false,
+        }
+    }
+
+    /// Stop when the cancel flag is set, or when the time limit for the term is exceeded.
+    fn create_cancel_check(&self) -> CancelCheck {
+        let cancelled: Arc<AtomicBool> = self.cancelled.clone();
+        let deadline: Option<f64> = self.term_time_limit_milliseconds.map(|milliseconds| js_sys::Date::now() + milliseconds);
+        CancelCheck::new(move || {
+            if cancelled.load(Ordering::Relaxed) {
+                return true;
+            }
+            match deadline {
+                Some(deadline) => js_sys::Date::now() >= deadline,
+                None => false
+            }
+        })
+    }
+
+    /// Compute up to `chunk_size` terms.
+    ///
+    /// Stops at the first term that cannot be computed, and no further terms are computed after that.
+    pub fn evaluate_chunk(&mut self) -> EvaluateResult {
+        let mut result = EvaluateResult::default();
+        for _ in 0..self.chunk_size {
+            if self.is_done() {
+                break;
+            }
+            let index: i64 = self.next_index;
+            let step_count_before: u64 = self.step_count;
+            let mut state = ProgramState::new(
+                RunMode::Silent,
+                step_count_before + STEP_COUNT_LIMIT_PER_TERM,
+                NodeRegisterLimit::Unlimited,
+                NodeLoopLimit::Unlimited,
+            );
+            state.set_cancel_check(Some(self.create_cancel_check()));
+            let input = RegisterValue::from_i64(index + self.program_runner.first_index());
+            let run_result = self.program_runner.run_without_offset_with_state(
+                input,
+                state,
+                &mut self.step_count,
+                &mut self.cache
+            );
+            let output: RegisterValue = match run_result {
+                Ok(value) => value,
+                Err(error) => {
+                    let is_cancelled: bool = matches!(error.root_cause().downcast_ref::<EvalError>(), Some(EvalError::Cancelled));
+                    if is_cancelled && self.is_cancelled() {
+                        debug!("WebEvaluator cancelled while computing term {}", index);
+                        break;
+                    }
+                    if is_cancelled {
+                        let milliseconds: f64 = self.term_time_limit_milliseconds.unwrap_or_default();
+                        result.error = Some(format!("Exceeded the time limit of {} ms while computing term {}", milliseconds, index));
+                        self.failed = true;
+                        break;
+                    }
+                    error!("Failure while computing term {}, error: {:?}", index, error);
+                    result.error = Some(format!("Failure while computing term {}, error: {:?}", index, error));
+                    self.failed = true;
+                    break;
+                }
+            };
+            result.terms.push(output.0.to_str_radix(10));
+            result.step_counts.push(self.step_count - step_count_before);
+            self.next_index += 1;
+        }
+        result
+    }
+}
+
+#[wasm_bindgen]
+impl WebEvaluator {
+    /// Compute the next chunk of terms.
+    ///
+    /// Returns an object like this: `{ terms: ["0", "1"], steps: [1, 3], error: null }`
+    pub fn run_chunk(&mut self) -> JsValue {
+        self.evaluate_chunk().to_jsvalue()
+    }
+
+    /// Stop computing terms. The following calls to `run_chunk()` returns no terms.
+    pub fn cancel(&mut self) {
+        debug!("WebEvaluator.cancel at index: {}", self.next_index);
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Fail with an error, when a single term takes longer than `milliseconds` to compute.
+    pub fn set_term_time_limit(&mut self, milliseconds: f64) {
+        self.term_time_limit_milliseconds = Some(milliseconds);
+    }
+
+    /// Returns `true` when all the terms have been computed, or when cancelled, or when an error occurred.
+    pub fn is_done(&self) -> bool {
+        self.is_cancelled() || self.failed || self.next_index >= self.end_index
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The index of the next term to be computed.
+    pub fn next_index(&self) -> f64 {
+        self.next_index as f64
+    }
+
+    /// The total number of steps used so far.
+    pub fn step_count(&self) -> f64 {
+        self.step_count as f64
+    }
+}
+
+/// Evaluate a single term, and take a snapshot of the registers after every instruction.
+///
+/// Returns an object like this:
+/// ```javascript
+/// {
+///   output: "5",
+///   error: null,
+///   truncated: false,
+///   steps: [
+///     { instruction: "mov $1,2", stepCount: 1, registers: [[0, "3"], [1, "2"]] },
+///     ...
+///   ]
+/// }
+/// ```
+///
+/// When the evaluation fails, then the `output` is `null` and the `error` contains the error message,
+/// the `steps` contains the snapshots up to the failing instruction.
+pub fn trace_term(program_runner: &ProgramRunner, index: i64, max_steps: u32) -> JsValue {
+    let step_trace = StepTrace::new(max_steps as usize);
+    let mut cache = ProgramCache::new();
+    let mut step_count: u64 = 0;
+    let run_result = program_runner.run_with_step_trace(
+        RegisterValue::from_i64(index),
+        &mut step_count,
+        STEP_COUNT_LIMIT_PER_TERM,
+        &step_trace,
+        &mut cache
+    );
+    let (output, error) = match run_result {
+        Ok(value) => (JsValue::from_str(&value.0.to_str_radix(10)), JsValue::NULL),
+        Err(error) => {
+            let s = format!("Failure while computing term {}, error: {:?}", index, error);
+            (JsValue::NULL, JsValue::from_str(&s))
+        }
+    };
+
+    let steps = js_sys::Array::new();
+    for item in step_trace.items() {
+        steps.push(&step_trace_item_to_jsvalue(&item));
+    }
+    JsObjectBuilder::new()
+        .set("output", output)
+        .set("error", error)
+        .set_bool("truncated", step_trace.is_truncated())
+        .set("steps", steps.into())
+        .build()
+}
+
+fn step_trace_item_to_jsvalue(item: &StepTraceItem) -> JsValue {
+    let registers = js_sys::Array::new();
+    for (register_index, value) in &item.registers {
+        let pair = js_sys::Array::new();
+        pair.push(&JsValue::from_f64(*register_index as f64));
+        pair.push(&JsValue::from_str(&value.to_str_radix(10)));
+        registers.push(&pair);
+    }
+    JsObjectBuilder::new()
+        .set_str("instruction", &item.instruction)
+        .set_u64("stepCount", item.step_count)
+        .set("registers", registers.into())
+        .build()
+}